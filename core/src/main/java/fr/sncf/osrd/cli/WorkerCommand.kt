@@ -146,7 +146,8 @@ class WorkerCommand : CliCommand {
         connection.use { connection ->
             connection.createChannel().use { channel -> reportActivity(channel, "started") }
 
-            if (!ALL_INFRA) {
+            // Workers spawned for the "all" key handle the requests bound to no infra
+            if (!ALL_INFRA && infraId != "all") {
                 try {
                     infraManager.load(infraId, null, diagnosticRecorder)
                 } catch (e: OSRDError) {
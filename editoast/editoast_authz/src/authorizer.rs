@@ -3,6 +3,10 @@ use std::{collections::HashSet, future::Future};
 use tracing::debug;
 use tracing::Level;
 
+use crate::privilege::Grant;
+use crate::privilege::Privilege;
+use crate::privilege::Resource;
+use crate::privilege::ResourceType;
 use crate::roles::BuiltinRoleSet;
 
 pub type UserIdentity = String;
@@ -25,7 +29,6 @@ pub struct Authorizer<S: StorageDriver> {
     user: UserInfo,
     user_id: i64,
     user_roles: HashSet<S::BuiltinRole>,
-    #[allow(unused)] // will be used soon
     storage: S,
 }
 
@@ -67,6 +70,40 @@ pub trait StorageDriver: Clone {
         subject_id: i64,
         roles: HashSet<Self::BuiltinRole>,
     ) -> impl Future<Output = Result<HashSet<Self::BuiltinRole>, Self::Error>> + Send;
+
+    /// Returns the highest privilege granted to a subject, directly or through its groups,
+    /// over any of the given resources
+    fn fetch_subject_privilege(
+        &self,
+        subject_id: i64,
+        resources: &[Resource],
+    ) -> impl Future<Output = Result<Option<Privilege>, Self::Error>> + Send;
+
+    /// Returns whether some privilege has been granted over any of the given resources
+    fn is_restricted(
+        &self,
+        resources: &[Resource],
+    ) -> impl Future<Output = Result<bool, Self::Error>> + Send;
+
+    fn fetch_resource_grants(
+        &self,
+        resource: Resource,
+    ) -> impl Future<Output = Result<Vec<Grant>, Self::Error>> + Send;
+
+    /// Grants a privilege over a resource, replacing the one previously granted to the subject
+    fn ensure_subject_privilege(
+        &self,
+        subject_id: i64,
+        resource: Resource,
+        privilege: Privilege,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Returns whether a privilege was granted to the subject
+    fn remove_subject_privilege(
+        &self,
+        subject_id: i64,
+        resource: Resource,
+    ) -> impl Future<Output = Result<bool, Self::Error>> + Send;
 }
 
 impl<S: StorageDriver> Authorizer<S> {
@@ -95,6 +132,10 @@ impl<S: StorageDriver> Authorizer<S> {
         }
     }
 
+    pub fn user(&self) -> &UserInfo {
+        &self.user
+    }
+
     pub fn user_id(&self) -> i64 {
         self.user_id
     }
@@ -167,6 +208,91 @@ impl<S: StorageDriver> Authorizer<S> {
         self.user_roles.retain(|r| !roles.contains(r));
         Ok(())
     }
+
+    /// Returns whether a user or a group with some id exists
+    #[tracing::instrument(skip_all, fields(subject_id = %subject_id), ret(level = Level::DEBUG), err)]
+    pub async fn subject_exists(&self, subject_id: i64) -> Result<bool, S::Error> {
+        if self.storage.get_user_info(subject_id).await?.is_some() {
+            return Ok(true);
+        }
+        Ok(self.storage.get_group_info(subject_id).await?.is_some())
+    }
+
+    /// Returns the privilege the roles of the user give over the resources of some type on which
+    /// no privilege was ever granted, see [BuiltinRoleSet::unrestricted_privilege]
+    pub fn unrestricted_privilege(&self, resource_type: ResourceType) -> Option<Privilege> {
+        if self.is_superuser() {
+            return Some(Privilege::Owner);
+        }
+        self.user_roles
+            .iter()
+            .filter_map(|role| role.unrestricted_privilege(resource_type))
+            .max()
+    }
+
+    /// Returns the privilege of the user over a resource
+    ///
+    /// `resources` lists the resource followed by its ancestors (e.g. a study then its project),
+    /// as privileges granted over a resource apply to its descendants.
+    /// When no privilege was ever granted over the resource nor its ancestors, the privilege
+    /// of the user is given by its roles, see [Self::unrestricted_privilege].
+    #[tracing::instrument(skip_all, fields(user = %self.user, ?resources), ret(level = Level::DEBUG), err)]
+    pub async fn resource_privilege(
+        &self,
+        resources: &[Resource],
+    ) -> Result<Option<Privilege>, S::Error> {
+        if self.is_superuser() {
+            tracing::debug!("privilege checking skipped for superuser");
+            return Ok(Some(Privilege::Owner));
+        }
+        let Some(resource) = resources.first() else {
+            return Ok(None);
+        };
+        if !self.storage.is_restricted(resources).await? {
+            return Ok(self.unrestricted_privilege(resource.resource_type));
+        }
+        self.storage
+            .fetch_subject_privilege(self.user_id, resources)
+            .await
+    }
+
+    /// Check that the user has at least some privilege over a resource, see [Self::resource_privilege]
+    pub async fn check_privilege(
+        &self,
+        resources: &[Resource],
+        required_privilege: Privilege,
+    ) -> Result<bool, S::Error> {
+        let privilege = self.resource_privilege(resources).await?;
+        Ok(privilege.is_some_and(|privilege| privilege >= required_privilege))
+    }
+
+    #[tracing::instrument(skip_all, fields(%resource), ret(level = Level::DEBUG), err)]
+    pub async fn resource_grants(&self, resource: Resource) -> Result<Vec<Grant>, S::Error> {
+        self.storage.fetch_resource_grants(resource).await
+    }
+
+    #[tracing::instrument(skip_all, fields(subject_id, auth_user = %self.user, %resource, %privilege), err)]
+    pub async fn grant_privilege(
+        &self,
+        subject_id: i64,
+        resource: Resource,
+        privilege: Privilege,
+    ) -> Result<(), S::Error> {
+        self.storage
+            .ensure_subject_privilege(subject_id, resource, privilege)
+            .await
+    }
+
+    #[tracing::instrument(skip_all, fields(subject_id, auth_user = %self.user, %resource), ret(level = Level::DEBUG), err)]
+    pub async fn revoke_privilege(
+        &self,
+        subject_id: i64,
+        resource: Resource,
+    ) -> Result<bool, S::Error> {
+        self.storage
+            .remove_subject_privilege(subject_id, resource)
+            .await
+    }
 }
 
 impl<S: StorageDriver> std::fmt::Debug for Authorizer<S> {
@@ -200,6 +326,7 @@ mod tests {
     struct MockStorageDriver {
         users: Arc<Mutex<HashMap<UserIdentity, i64>>>,
         user_roles: Arc<Mutex<HashMap<i64, HashSet<TestBuiltinRole>>>>,
+        grants: Arc<Mutex<HashMap<Resource, HashMap<i64, Privilege>>>>,
     }
 
     #[tokio::test]
@@ -287,6 +414,114 @@ mod tests {
         assert!(authorizer.check_roles(HashSet::from([])).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn check_privilege() {
+        let storage = MockStorageDriver::default();
+        let project = Resource::new(ResourceType::Project, 1);
+        let study = Resource::new(ResourceType::Study, 2);
+        let other_project = Resource::new(ResourceType::Project, 3);
+        storage.user_roles.lock().unwrap().insert(0, HashSet::new());
+        let authorizer = Authorizer::try_initialize(
+            UserInfo {
+                identity: "toto".to_owned(),
+                name: "Toto".to_owned(),
+            },
+            storage.clone(),
+        )
+        .await
+        .unwrap();
+        let user_id = authorizer.user_id();
+
+        // Nothing was granted, and the user has no role giving privileges
+        assert_eq!(
+            authorizer.resource_privilege(&[study, project]).await,
+            Ok(None)
+        );
+
+        // Grants on the project apply to its studies
+        authorizer
+            .grant_privilege(user_id, project, Privilege::Writer)
+            .await
+            .unwrap();
+        assert!(authorizer
+            .check_privilege(&[study, project], Privilege::Writer)
+            .await
+            .unwrap());
+        assert!(!authorizer
+            .check_privilege(&[study, project], Privilege::Owner)
+            .await
+            .unwrap());
+
+        // Grants to others restrict the resource
+        authorizer
+            .grant_privilege(42, other_project, Privilege::Owner)
+            .await
+            .unwrap();
+        assert!(!authorizer
+            .check_privilege(&[other_project], Privilege::Reader)
+            .await
+            .unwrap());
+        assert_eq!(
+            authorizer.resource_grants(other_project).await.unwrap(),
+            vec![Grant {
+                subject_id: 42,
+                privilege: Privilege::Owner
+            }]
+        );
+
+        assert!(authorizer.revoke_privilege(user_id, project).await.unwrap());
+        assert!(!authorizer.revoke_privilege(user_id, project).await.unwrap());
+        assert!(!authorizer
+            .check_privilege(&[study, project], Privilege::Reader)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn unrestricted_resources_follow_roles() {
+        let storage = MockStorageDriver::default();
+        let infra = Resource::new(ResourceType::Infra, 1);
+        storage
+            .user_roles
+            .lock()
+            .unwrap()
+            .insert(0, HashSet::from([TestBuiltinRole::DocEdit]));
+        let authorizer = Authorizer::try_initialize(
+            UserInfo {
+                identity: "toto".to_owned(),
+                name: "Toto".to_owned(),
+            },
+            storage,
+        )
+        .await
+        .unwrap();
+
+        // Roles never make a user the owner of a resource
+        assert_eq!(
+            authorizer.resource_privilege(&[infra]).await,
+            Ok(Some(Privilege::Writer))
+        );
+        assert!(!authorizer
+            .check_privilege(&[infra], Privilege::Owner)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn superuser_privileges() {
+        let storage = MockStorageDriver::default();
+        let infra = Resource::new(ResourceType::Infra, 1);
+        storage
+            .ensure_subject_privilege(42, infra, Privilege::Reader)
+            .await
+            .unwrap();
+        let authorizer = Authorizer::new_superuser(storage);
+        assert_eq!(
+            authorizer.resource_privilege(&[infra]).await,
+            Ok(Some(Privilege::Owner))
+        );
+    }
+
     impl StorageDriver for MockStorageDriver {
         type BuiltinRole = TestBuiltinRole;
         type Error = Infallible;
@@ -354,5 +589,69 @@ mod tests {
         async fn get_group_info(&self, _group_id: i64) -> Result<Option<GroupInfo>, Self::Error> {
             Ok(None)
         }
+
+        async fn fetch_subject_privilege(
+            &self,
+            subject_id: i64,
+            resources: &[Resource],
+        ) -> Result<Option<Privilege>, Self::Error> {
+            let grants = self.grants.lock().unwrap();
+            let privilege = resources
+                .iter()
+                .filter_map(|resource| grants.get(resource)?.get(&subject_id))
+                .max()
+                .copied();
+            Ok(privilege)
+        }
+
+        async fn is_restricted(&self, resources: &[Resource]) -> Result<bool, Self::Error> {
+            let grants = self.grants.lock().unwrap();
+            Ok(resources
+                .iter()
+                .any(|resource| grants.get(resource).is_some_and(|g| !g.is_empty())))
+        }
+
+        async fn fetch_resource_grants(
+            &self,
+            resource: Resource,
+        ) -> Result<Vec<Grant>, Self::Error> {
+            let grants = self.grants.lock().unwrap();
+            let grants = grants
+                .get(&resource)
+                .into_iter()
+                .flatten()
+                .map(|(subject_id, privilege)| Grant {
+                    subject_id: *subject_id,
+                    privilege: *privilege,
+                })
+                .collect();
+            Ok(grants)
+        }
+
+        async fn ensure_subject_privilege(
+            &self,
+            subject_id: i64,
+            resource: Resource,
+            privilege: Privilege,
+        ) -> Result<(), Self::Error> {
+            let mut grants = self.grants.lock().unwrap();
+            grants
+                .entry(resource)
+                .or_default()
+                .insert(subject_id, privilege);
+            Ok(())
+        }
+
+        async fn remove_subject_privilege(
+            &self,
+            subject_id: i64,
+            resource: Resource,
+        ) -> Result<bool, Self::Error> {
+            let mut grants = self.grants.lock().unwrap();
+            let removed = grants
+                .get_mut(&resource)
+                .is_some_and(|grants| grants.remove(&subject_id).is_some());
+            Ok(removed)
+        }
     }
 }
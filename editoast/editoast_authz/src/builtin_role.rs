@@ -6,6 +6,8 @@ use strum::EnumIter;
 use strum::EnumString;
 use utoipa::ToSchema;
 
+use crate::privilege::Privilege;
+use crate::privilege::ResourceType;
 use crate::roles::BuiltinRoleSet;
 
 #[derive(
@@ -59,10 +61,32 @@ pub enum BuiltinRole {
 
     RoleRead,
     RoleWrite,
+
+    AuditRead,
 }
 
 impl BuiltinRoleSet for BuiltinRole {
     fn superuser() -> Self {
         Self::Superuser
     }
+
+    /// Read and write roles give the reader and writer privileges over unrestricted resources,
+    /// and STDCM users can read the infras, timetables and rolling stocks they search paths in.
+    /// No role makes a user the owner of a resource.
+    fn unrestricted_privilege(&self, resource_type: ResourceType) -> Option<Privilege> {
+        use BuiltinRole::*;
+        use ResourceType::*;
+        match (self, resource_type) {
+            (InfraRead, Infra)
+            | (OpsRead, Project | Study | Scenario)
+            | (TimetableRead, Timetable)
+            | (RollingStockCollectionRead, RollingStock)
+            | (Stdcm, Infra | Timetable | RollingStock) => Some(Privilege::Reader),
+            (InfraWrite, Infra)
+            | (OpsWrite, Project | Study | Scenario)
+            | (TimetableWrite, Timetable)
+            | (RollingStockCollectionWrite, RollingStock) => Some(Privilege::Writer),
+            _ => None,
+        }
+    }
 }
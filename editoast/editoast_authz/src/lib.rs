@@ -1,13 +1,19 @@
 pub mod authorizer;
 pub mod builtin_role;
+pub mod privilege;
 pub mod roles;
 
 pub use builtin_role::BuiltinRole;
+pub use privilege::Privilege;
+pub use privilege::Resource;
+pub use privilege::ResourceType;
 
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures {
     use strum::{AsRefStr, EnumString};
 
+    use crate::privilege::Privilege;
+    use crate::privilege::ResourceType;
     use crate::roles::BuiltinRoleSet;
 
     #[derive(Debug, Clone, PartialEq, Eq, Hash, AsRefStr, EnumString)]
@@ -25,5 +31,13 @@ pub mod fixtures {
         fn superuser() -> Self {
             Self::Superuser
         }
+
+        fn unrestricted_privilege(&self, _resource_type: ResourceType) -> Option<Privilege> {
+            match self {
+                Self::DocRead => Some(Privilege::Reader),
+                Self::DocEdit => Some(Privilege::Writer),
+                _ => None,
+            }
+        }
     }
 }
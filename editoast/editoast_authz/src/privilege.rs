@@ -0,0 +1,119 @@
+use serde::Deserialize;
+use serde::Serialize;
+use strum::AsRefStr;
+use strum::Display;
+use strum::EnumIter;
+use strum::EnumString;
+use utoipa::ToSchema;
+
+/// The kinds of resources on which privileges can be granted
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumString,
+    AsRefStr,
+    EnumIter,
+    Display,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ResourceType {
+    Infra,
+    Project,
+    Study,
+    Scenario,
+    Timetable,
+    RollingStock,
+}
+
+/// The privilege of a subject over a resource
+///
+/// Privileges are ordered: each one includes the ones below it.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumString,
+    AsRefStr,
+    EnumIter,
+    Display,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Privilege {
+    /// Can read the resource
+    Reader,
+    /// Can read and modify the resource
+    Writer,
+    /// Can read, modify and delete the resource, and grant privileges over it
+    Owner,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Resource {
+    pub resource_type: ResourceType,
+    pub id: i64,
+}
+
+impl Resource {
+    pub fn new(resource_type: ResourceType, id: i64) -> Self {
+        Self { resource_type, id }
+    }
+}
+
+impl std::fmt::Display for Resource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.resource_type, self.id)
+    }
+}
+
+/// A privilege granted to a subject (user or group) over a resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grant {
+    pub subject_id: i64,
+    pub privilege: Privilege,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn privileges_are_ordered() {
+        assert!(Privilege::Owner > Privilege::Writer);
+        assert!(Privilege::Writer > Privilege::Reader);
+        assert_eq!(
+            [Privilege::Reader, Privilege::Owner, Privilege::Writer]
+                .into_iter()
+                .max(),
+            Some(Privilege::Owner)
+        );
+    }
+
+    #[test]
+    fn resource_type_tags() {
+        assert_eq!(ResourceType::RollingStock.as_ref(), "rolling_stock");
+        assert_eq!(
+            ResourceType::from_str("scenario"),
+            Ok(ResourceType::Scenario)
+        );
+        assert_eq!(Privilege::from_str("writer"), Ok(Privilege::Writer));
+    }
+}
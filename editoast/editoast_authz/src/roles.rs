@@ -1,11 +1,23 @@
 use std::str::FromStr;
 
+use crate::privilege::Privilege;
+use crate::privilege::ResourceType;
+
 pub trait BuiltinRoleSet:
     FromStr + AsRef<str> + Sized + Clone + std::hash::Hash + std::cmp::Eq + std::fmt::Debug
 {
     /// Returns the builtin role that short-circuits all role and permission checks.
     fn superuser() -> Self;
 
+    /// Returns the privilege this role gives over the resources of some type on which no
+    /// privilege was ever granted, if any
+    ///
+    /// Resources are created with an owner, so this only concerns the ones created before
+    /// privileges existed, or by trusted services. By default, roles give no privilege.
+    fn unrestricted_privilege(&self, _resource_type: ResourceType) -> Option<Privilege> {
+        None
+    }
+
     fn as_str(&self) -> &str {
         self.as_ref()
     }
@@ -92,7 +92,8 @@ pub fn error(input: TokenStream) -> TokenStream {
 /// - **name** (optional): the name of the search object (defaults to the struct name lowercase-d)
 /// - **column** (0-*): a description of each search table column
 ///     - **name** (required): the column name in the database
-///     - **data_type** (required): the SQL column type in the database
+///     - **data_type** (required): the SQL column type in the database. Geometry columns, such as
+///       `geometry(Point, 4326)`, are typed `geometry` in queries and indexed with GiST
 ///     - **sql** (optional, required if **migration** is provided): the SQL query to perform to retrieve the data for the column in the search table
 ///     - **index** (optional): whether to create an index for the column in the search table (defaults to `true`)
 ///     - **textual_search** (optional): whether to create a textual search index for the column in the search table (defaults to `false`)
@@ -77,6 +77,7 @@ enum ColumnType {
     TextualSearchString,
     Boolean,
     Null,
+    Geometry,
     Sequence(Box<ColumnType>),
 }
 
@@ -112,6 +113,7 @@ impl ColumnType {
             }
             "boolean" | "bool" => Some(ColumnType::Boolean),
             "null" => Some(ColumnType::Null),
+            "geometry" => Some(ColumnType::Geometry),
             // handles VARCHAR(240), NUMERIC(4, 2), etc.
             prefix if prefix.contains('(') => {
                 let (prefix, _) = prefix.split_once('(').unwrap();
@@ -144,6 +146,9 @@ impl ColumnType {
             ColumnType::Null => {
                 quote! { editoast_search::TypeSpec::Type(editoast_search::AstType::Null) }
             }
+            ColumnType::Geometry => {
+                quote! { editoast_search::TypeSpec::Type(editoast_search::AstType::Geometry) }
+            }
             ColumnType::Sequence(ct) => {
                 let ts = ct.to_type_spec();
                 quote! { editoast_search::TypeSpec::Sequence(Box::new(#ts)) }
@@ -154,6 +159,7 @@ impl ColumnType {
     fn index(&self) -> TokenStream {
         match self {
             ColumnType::TextualSearchString => quote! { editoast_search::Index::GinTrgm },
+            ColumnType::Geometry => quote! { editoast_search::Index::Gist },
             _ => quote! { editoast_search::Index::Default },
         }
     }
@@ -1,5 +1,24 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    audit_log (id) {
+        id -> Int8,
+        created -> Timestamptz,
+        user_id -> Nullable<Int8>,
+        user_identity -> Text,
+        user_name -> Text,
+        #[max_length = 255]
+        action -> Varchar,
+        #[max_length = 255]
+        resource_type -> Varchar,
+        resource_id -> Int8,
+        summary -> Text,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use postgis_diesel::sql_types::*;
@@ -42,6 +61,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    authz_grant (id) {
+        id -> Int8,
+        subject -> Int8,
+        #[max_length = 255]
+        resource_type -> Varchar,
+        resource_id -> Int8,
+        #[max_length = 255]
+        privilege -> Varchar,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use postgis_diesel::sql_types::*;
@@ -99,6 +133,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    infra_edition (id) {
+        id -> Int8,
+        infra_id -> Int8,
+        operations -> Jsonb,
+        inverse_operations -> Jsonb,
+        undone -> Bool,
+        created -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use postgis_diesel::sql_types::*;
@@ -491,6 +539,23 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    saved_search (id) {
+        id -> Int8,
+        #[max_length = 255]
+        name -> Varchar,
+        owner_identity -> Text,
+        #[max_length = 255]
+        object -> Varchar,
+        query -> Jsonb,
+        order_by -> Jsonb,
+        created -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use postgis_diesel::sql_types::*;
@@ -526,6 +591,7 @@ diesel::table! {
         ci -> Nullable<Int4>,
         ch -> Nullable<Text>,
         name -> Nullable<Text>,
+        geographic -> Nullable<Geometry>,
     }
 }
 
@@ -568,6 +634,7 @@ diesel::table! {
         signaling_systems -> Nullable<Array<Nullable<Text>>>,
         settings -> Nullable<Array<Nullable<Text>>>,
         line_code -> Nullable<Int4>,
+        geographic -> Nullable<Geometry>,
     }
 }
 
@@ -594,6 +661,7 @@ diesel::table! {
         line_name -> Text,
         unprocessed_line_name -> Text,
         id -> Int8,
+        geographic -> Nullable<Geometry>,
     }
 }
 
@@ -719,6 +787,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use postgis_diesel::sql_types::*;
+
+    train_pattern (id) {
+        id -> Int8,
+        timetable_id -> Int8,
+        template -> Jsonb,
+        recurrence -> Jsonb,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use postgis_diesel::sql_types::*;
@@ -742,6 +822,7 @@ diesel::table! {
         speed_limit_tag -> Nullable<Varchar>,
         power_restrictions -> Jsonb,
         options -> Jsonb,
+        train_pattern_id -> Nullable<Int8>,
     }
 }
 
@@ -773,11 +854,13 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(audit_log -> authn_user (user_id));
 diesel::joinable!(authn_group -> authn_subject (id));
 diesel::joinable!(authn_group_membership -> authn_group (group));
 diesel::joinable!(authn_group_membership -> authn_user (user));
 diesel::joinable!(authn_user -> authn_subject (id));
 diesel::joinable!(authz_role -> authn_subject (subject));
+diesel::joinable!(infra_edition -> infra (infra_id));
 diesel::joinable!(infra_layer_buffer_stop -> infra (infra_id));
 diesel::joinable!(infra_layer_detector -> infra (infra_id));
 diesel::joinable!(infra_layer_electrification -> infra (infra_id));
@@ -823,18 +906,23 @@ diesel::joinable!(stdcm_search_environment -> timetable (timetable_id));
 diesel::joinable!(stdcm_search_environment -> work_schedule_group (work_schedule_group_id));
 diesel::joinable!(study -> project (project_id));
 diesel::joinable!(temporary_speed_limit -> temporary_speed_limit_group (temporary_speed_limit_group_id));
+diesel::joinable!(train_pattern -> timetable (timetable_id));
 diesel::joinable!(train_schedule -> timetable (timetable_id));
+diesel::joinable!(train_schedule -> train_pattern (train_pattern_id));
 diesel::joinable!(work_schedule -> work_schedule_group (work_schedule_group_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    audit_log,
     authn_group,
     authn_group_membership,
     authn_subject,
     authn_user,
+    authz_grant,
     authz_role,
     document,
     electrical_profile_set,
     infra,
+    infra_edition,
     infra_layer_buffer_stop,
     infra_layer_detector,
     infra_layer_electrification,
@@ -862,6 +950,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     rolling_stock,
     rolling_stock_livery,
     rolling_stock_separate_image,
+    saved_search,
     scenario,
     search_operational_point,
     search_project,
@@ -876,6 +965,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     temporary_speed_limit_group,
     timetable,
     towed_rolling_stock,
+    train_pattern,
     train_schedule,
     work_schedule,
     work_schedule_group,
@@ -13,6 +13,7 @@ mod loading_gauge_limit;
 mod neutral_section;
 mod operational_point;
 mod railjson;
+mod railjson_migration;
 mod route;
 mod side;
 mod sign;
@@ -53,6 +54,12 @@ pub use operational_point::OperationalPointIdentifierExtension;
 pub use operational_point::OperationalPointPart;
 pub use railjson::RailJson;
 pub use railjson::RAILJSON_VERSION;
+pub use railjson_migration::migrate_railjson;
+pub use railjson_migration::RailJsonMigrationError;
+pub use railjson_migration::RailJsonMigrationReport;
+pub use railjson_migration::RailJsonMigrationStep;
+pub use railjson_migration::RailJsonMigrationStepReport;
+pub use railjson_migration::RAILJSON_MIGRATION_STEPS;
 pub use route::Route;
 pub use route::RoutePath;
 pub use side::Side;
@@ -98,6 +105,7 @@ editoast_common::schemas! {
     neutral_section::schemas(),
     operational_point::schemas(),
     railjson::schemas(),
+    railjson_migration::schemas(),
     route::schemas(),
     side::schemas(),
     sign::schemas(),
@@ -0,0 +1,629 @@
+//! Upgrade of RailJSON documents written with an older version of the format
+//!
+//! Each [RailJsonMigrationStep] upgrades a raw JSON document from one version to the next one.
+//! Steps mirror the database migrations that were applied to the stored infrastructures when
+//! the format changed, so an archived export can be imported as if it had never left the database.
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::builtin_node_types_list;
+use super::RailJson;
+use super::RAILJSON_VERSION;
+
+editoast_common::schemas! {
+    RailJsonMigrationReport,
+    RailJsonMigrationStepReport,
+}
+
+#[derive(Debug, Error)]
+pub enum RailJsonMigrationError {
+    #[error("the railjson document is not a JSON object")]
+    NotAnObject,
+    #[error("the railjson document has no version")]
+    MissingVersion,
+    #[error("railjson version '{version}' cannot be migrated to '{RAILJSON_VERSION}'")]
+    UnsupportedVersion { version: String },
+    #[error("the migrated railjson document is invalid: {0}")]
+    InvalidDocument(#[from] serde_json::Error),
+}
+
+/// Summary of the changes applied by one migration step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RailJsonMigrationStepReport {
+    pub from: String,
+    pub to: String,
+    pub description: String,
+    /// The number of infrastructure objects modified by this step
+    pub modified_objects: usize,
+}
+
+/// Summary of the migration of a RailJSON document to the current version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RailJsonMigrationReport {
+    /// The version of the provided document
+    pub from_version: String,
+    /// The version of the resulting document, always the current one
+    pub to_version: String,
+    /// The applied steps, in order. Empty if the document was already up to date.
+    pub steps: Vec<RailJsonMigrationStepReport>,
+}
+
+impl RailJsonMigrationReport {
+    /// Whether the document needed any migration
+    pub fn is_migrated(&self) -> bool {
+        !self.steps.is_empty()
+    }
+}
+
+/// A single upgrade of a RailJSON document from `from` to `to`
+pub struct RailJsonMigrationStep {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub description: &'static str,
+    /// Upgrades the document in place and returns the number of modified objects
+    migrate: fn(&mut Map<String, Value>) -> usize,
+}
+
+/// The ordered chain of upgrades, the last step must target [RAILJSON_VERSION]
+pub const RAILJSON_MIGRATION_STEPS: &[RailJsonMigrationStep] = &[
+    RailJsonMigrationStep {
+        from: "3.4.4",
+        to: "3.4.5",
+        description: "Remove legacy signal fields",
+        migrate: remove_signal_legacy_fields,
+    },
+    RailJsonMigrationStep {
+        from: "3.4.5",
+        to: "3.4.6",
+        description: "Remove builtin switch types from extended switch types",
+        migrate: remove_builtin_switch_types,
+    },
+    RailJsonMigrationStep {
+        from: "3.4.6",
+        to: "3.4.7",
+        description: "Rename catenaries to electrifications and remove applicable directions from detectors",
+        migrate: catenaries_to_electrifications_and_remove_detector_directions,
+    },
+    RailJsonMigrationStep {
+        from: "3.4.7",
+        to: "3.4.8",
+        description: "Split TVM signaling system into TVM300 and TVM430",
+        migrate: split_tvm,
+    },
+    RailJsonMigrationStep {
+        from: "3.4.8",
+        to: "3.4.9",
+        description: "Add units to electrification voltages, remove zero speed limits and replace sign angles by a direction",
+        migrate: voltage_units_zero_speed_limits_and_sign_directions,
+    },
+    RailJsonMigrationStep {
+        from: "3.4.9",
+        to: "3.4.10",
+        description: "Add optional routes to speed sections",
+        migrate: no_change,
+    },
+    RailJsonMigrationStep {
+        from: "3.4.10",
+        to: "3.4.11",
+        description: "Add signal default and conditional parameters",
+        migrate: add_signal_parameters,
+    },
+    RailJsonMigrationStep {
+        from: "3.4.11",
+        to: "3.4.12",
+        description: "Remove schematic geometry from track sections and TVM signals sight distance",
+        migrate: remove_schematic_and_tvm_sight_distance,
+    },
+    RailJsonMigrationStep {
+        from: "3.4.12",
+        to: "3.4.13",
+        description: "No change to the stored objects",
+        migrate: no_change,
+    },
+];
+
+/// Upgrades a raw RailJSON document to the current [RailJson] format
+///
+/// Documents already at [RAILJSON_VERSION] are only deserialized.
+pub fn migrate_railjson(
+    railjson: Value,
+) -> Result<(RailJson, RailJsonMigrationReport), RailJsonMigrationError> {
+    let Value::Object(mut document) = railjson else {
+        return Err(RailJsonMigrationError::NotAnObject);
+    };
+    let from_version = document
+        .get("version")
+        .and_then(Value::as_str)
+        .ok_or(RailJsonMigrationError::MissingVersion)?
+        .to_owned();
+
+    let first_step = if from_version == RAILJSON_VERSION {
+        RAILJSON_MIGRATION_STEPS.len()
+    } else {
+        RAILJSON_MIGRATION_STEPS
+            .iter()
+            .position(|step| step.from == from_version)
+            .ok_or_else(|| RailJsonMigrationError::UnsupportedVersion {
+                version: from_version.clone(),
+            })?
+    };
+
+    let steps = RAILJSON_MIGRATION_STEPS[first_step..]
+        .iter()
+        .map(|step| {
+            let modified_objects = (step.migrate)(&mut document);
+            document.insert("version".to_owned(), Value::String(step.to.to_owned()));
+            RailJsonMigrationStepReport {
+                from: step.from.to_owned(),
+                to: step.to.to_owned(),
+                description: step.description.to_owned(),
+                modified_objects,
+            }
+        })
+        .collect();
+
+    let railjson: RailJson = serde_json::from_value(Value::Object(document))?;
+    let report = RailJsonMigrationReport {
+        from_version,
+        to_version: railjson.version.clone(),
+        steps,
+    };
+    Ok((railjson, report))
+}
+
+/// Applies `f` on every object of the `collection` array, returns how many objects were modified
+fn update_objects<F>(document: &mut Map<String, Value>, collection: &str, mut f: F) -> usize
+where
+    F: FnMut(&mut Map<String, Value>) -> bool,
+{
+    let Some(Value::Array(objects)) = document.get_mut(collection) else {
+        return 0;
+    };
+    objects
+        .iter_mut()
+        .filter_map(Value::as_object_mut)
+        .map(&mut f)
+        .filter(|modified| *modified)
+        .count()
+}
+
+/// Retrieves the nested object at `path`, if every part of it exists
+fn get_object_mut<'a>(
+    object: &'a mut Map<String, Value>,
+    path: &[&str],
+) -> Option<&'a mut Map<String, Value>> {
+    path.iter()
+        .try_fold(object, |object, key| object.get_mut(*key)?.as_object_mut())
+}
+
+fn no_change(_: &mut Map<String, Value>) -> usize {
+    0
+}
+
+fn remove_signal_legacy_fields(document: &mut Map<String, Value>) -> usize {
+    const LEGACY_SNCF_FIELDS: [&str; 10] = [
+        "aspects",
+        "comment",
+        "default_aspect",
+        "installation_type",
+        "is_in_service",
+        "is_lightable",
+        "is_operational",
+        "support_type",
+        "type_code",
+        "value",
+    ];
+    update_objects(document, "signals", |signal| {
+        let mut modified = signal.remove("linked_detector").is_some();
+        if let Some(sncf) = get_object_mut(signal, &["extensions", "sncf"]) {
+            for field in LEGACY_SNCF_FIELDS {
+                modified |= sncf.remove(field).is_some();
+            }
+        }
+        modified
+    })
+}
+
+fn remove_builtin_switch_types(document: &mut Map<String, Value>) -> usize {
+    let builtin_ids: Vec<_> = builtin_node_types_list()
+        .into_iter()
+        .map(|switch_type| switch_type.id.0)
+        .collect();
+    let Some(Value::Array(switch_types)) = document.get_mut("extended_switch_types") else {
+        return 0;
+    };
+    let count = switch_types.len();
+    switch_types.retain(|switch_type| {
+        let id = switch_type.get("id").and_then(Value::as_str);
+        !builtin_ids
+            .iter()
+            .any(|builtin| Some(builtin.as_str()) == id)
+    });
+    count - switch_types.len()
+}
+
+fn catenaries_to_electrifications_and_remove_detector_directions(
+    document: &mut Map<String, Value>,
+) -> usize {
+    let electrifications = match document.remove("catenaries") {
+        Some(Value::Array(catenaries)) => {
+            let count = catenaries.len();
+            document.insert("electrifications".to_owned(), Value::Array(catenaries));
+            count
+        }
+        Some(catenaries) => {
+            document.insert("electrifications".to_owned(), catenaries);
+            0
+        }
+        None => 0,
+    };
+    let detectors = update_objects(document, "detectors", |detector| {
+        detector.remove("applicable_directions").is_some()
+    });
+    electrifications + detectors
+}
+
+fn split_tvm(document: &mut Map<String, Value>) -> usize {
+    update_objects(document, "signals", |signal| {
+        let Some(Value::Array(logical_signals)) = signal.get_mut("logical_signals") else {
+            return false;
+        };
+        let mut modified = false;
+        for logical_signal in logical_signals.iter_mut() {
+            if logical_signal
+                .get("signaling_system")
+                .and_then(Value::as_str)
+                != Some("TVM")
+            {
+                continue;
+            }
+            let is_430 = logical_signal
+                .pointer("/settings/is_430")
+                .and_then(Value::as_str)
+                == Some("true");
+            *logical_signal = serde_json::json!({
+                "signaling_system": if is_430 { "TVM430" } else { "TVM300" },
+                "settings": { "Nf": "true" },
+                "next_signaling_systems": [],
+            });
+            modified = true;
+        }
+        modified
+    })
+}
+
+fn voltage_units_zero_speed_limits_and_sign_directions(document: &mut Map<String, Value>) -> usize {
+    add_voltage_units(document)
+        + remove_zero_speed_limits(document)
+        + sign_angles_to_direction(document)
+}
+
+/// Voltages made of digits only are suffixed with `V`, e.g. `"1500;25000V"` becomes `"1500V;25000V"`
+fn add_voltage_units(document: &mut Map<String, Value>) -> usize {
+    update_objects(document, "electrifications", |electrification| {
+        let Some(Value::String(voltage)) = electrification.get_mut("voltage") else {
+            return false;
+        };
+        let with_units = voltage
+            .split(';')
+            .map(|level| {
+                if !level.is_empty() && level.chars().all(|c| c.is_ascii_digit()) {
+                    format!("{level}V")
+                } else {
+                    level.to_owned()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        if *voltage == with_units {
+            return false;
+        }
+        *voltage = with_units;
+        true
+    })
+}
+
+/// A speed limit of zero used to mean "no limit", it is now expressed by its absence
+fn remove_zero_speed_limits(document: &mut Map<String, Value>) -> usize {
+    update_objects(document, "speed_sections", |speed_section| {
+        let mut modified = false;
+        if let Some(speed_limit) = speed_section.get_mut("speed_limit") {
+            if speed_limit.as_f64() == Some(0.0) {
+                *speed_limit = Value::Null;
+                modified = true;
+            }
+        }
+        if let Some(Value::Object(speed_limit_by_tag)) = speed_section.get_mut("speed_limit_by_tag")
+        {
+            let count = speed_limit_by_tag.len();
+            speed_limit_by_tag.retain(|_, speed| speed.as_f64().is_none_or(|speed| speed > 0.0));
+            modified |= speed_limit_by_tag.len() != count;
+        }
+        modified
+    })
+}
+
+fn sign_angles_to_direction(document: &mut Map<String, Value>) -> usize {
+    fn update_sign(sign: &mut Value) -> bool {
+        let Some(sign) = sign.as_object_mut() else {
+            return false;
+        };
+        let mut modified = sign.remove("angle_geo").is_some();
+        modified |= sign.remove("angle_sch").is_some();
+        let direction = Value::from("START_TO_STOP");
+        if sign.get("direction") != Some(&direction) {
+            sign.insert("direction".to_owned(), direction);
+            modified = true;
+        }
+        modified
+    }
+    update_objects(document, "speed_sections", |speed_section| {
+        let Some(psl) = get_object_mut(speed_section, &["extensions", "psl_sncf"]) else {
+            return false;
+        };
+        let mut modified = psl.get_mut("z").is_some_and(update_sign);
+        for signs in ["r", "announcement"] {
+            if let Some(Value::Array(signs)) = psl.get_mut(signs) {
+                for sign in signs.iter_mut() {
+                    modified |= update_sign(sign);
+                }
+            }
+        }
+        modified
+    })
+}
+
+fn add_signal_parameters(document: &mut Map<String, Value>) -> usize {
+    update_objects(document, "signals", |signal| {
+        let Some(Value::Array(logical_signals)) = signal.get_mut("logical_signals") else {
+            return false;
+        };
+        let mut modified = false;
+        for logical_signal in logical_signals.iter_mut().filter_map(Value::as_object_mut) {
+            if !logical_signal.contains_key("default_parameters") {
+                let default_parameters = if logical_signal
+                    .get("signaling_system")
+                    .and_then(Value::as_str)
+                    == Some("BAL")
+                {
+                    serde_json::json!({ "jaune_cli": "false" })
+                } else {
+                    serde_json::json!({})
+                };
+                logical_signal.insert("default_parameters".to_owned(), default_parameters);
+                modified = true;
+            }
+            if !logical_signal.contains_key("conditional_parameters") {
+                logical_signal.insert("conditional_parameters".to_owned(), Value::Array(vec![]));
+                modified = true;
+            }
+        }
+        modified
+    })
+}
+
+fn remove_schematic_and_tvm_sight_distance(document: &mut Map<String, Value>) -> usize {
+    let tracks = update_objects(document, "track_sections", |track| {
+        track.remove("sch").is_some()
+    });
+    // TVM signals are not seen by the driver, their sight distance is set to zero
+    let signals = update_objects(document, "signals", |signal| {
+        let is_tvm = signal
+            .get("logical_signals")
+            .and_then(Value::as_array)
+            .is_some_and(|logical_signals| {
+                logical_signals.iter().any(|logical_signal| {
+                    matches!(
+                        logical_signal
+                            .get("signaling_system")
+                            .and_then(Value::as_str),
+                        Some("TVM300" | "TVM430")
+                    )
+                })
+            });
+        let sight_distance = Value::from(0.0);
+        if !is_tvm || signal.get("sight_distance") == Some(&sight_distance) {
+            return false;
+        }
+        signal.insert("sight_distance".to_owned(), sight_distance);
+        true
+    });
+    tracks + signals
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn empty_railjson(version: &str) -> Value {
+        let mut railjson = serde_json::to_value(RailJson::default()).unwrap();
+        railjson["version"] = version.into();
+        railjson
+    }
+
+    #[test]
+    fn migration_chain_ends_at_current_version() {
+        let last_step = RAILJSON_MIGRATION_STEPS.last().unwrap();
+        assert_eq!(last_step.to, RAILJSON_VERSION);
+        for steps in RAILJSON_MIGRATION_STEPS.windows(2) {
+            assert_eq!(steps[0].to, steps[1].from);
+        }
+    }
+
+    #[test]
+    fn current_version_is_not_migrated() {
+        let (railjson, report) = migrate_railjson(empty_railjson(RAILJSON_VERSION)).unwrap();
+        assert_eq!(railjson.version, RAILJSON_VERSION);
+        assert!(!report.is_migrated());
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        assert!(matches!(
+            migrate_railjson(empty_railjson("2.0.0")),
+            Err(RailJsonMigrationError::UnsupportedVersion { .. })
+        ));
+        assert!(matches!(
+            migrate_railjson(json!({ "track_sections": [] })),
+            Err(RailJsonMigrationError::MissingVersion)
+        ));
+    }
+
+    #[test]
+    fn migrate_old_signals_and_tracks() {
+        let mut railjson = empty_railjson("3.4.7");
+        railjson["signals"] = json!([{
+            "id": "signal",
+            "track": "track",
+            "position": 10.0,
+            "direction": "START_TO_STOP",
+            "sight_distance": 400.0,
+            "logical_signals": [
+                {
+                    "signaling_system": "TVM",
+                    "next_signaling_systems": [],
+                    "settings": { "is_430": "true" }
+                },
+                {
+                    "signaling_system": "BAL",
+                    "next_signaling_systems": [],
+                    "settings": { "Nf": "true" }
+                }
+            ]
+        }]);
+        railjson["track_sections"] = json!([{
+            "id": "track",
+            "length": 100.0,
+            "slopes": [],
+            "curves": [],
+            "geo": { "type": "LineString", "coordinates": [[0.0, 0.0], [1.0, 1.0]] },
+            "sch": { "type": "LineString", "coordinates": [[0.0, 0.0], [1.0, 1.0]] }
+        }]);
+
+        let (railjson, report) = migrate_railjson(railjson).unwrap();
+
+        assert_eq!(report.from_version, "3.4.7");
+        assert_eq!(report.to_version, RAILJSON_VERSION);
+        assert_eq!(report.steps.len(), 6);
+        let logical_signals = &railjson.signals[0].logical_signals;
+        assert_eq!(logical_signals[0].signaling_system, "TVM430");
+        assert!(logical_signals[0].default_parameters.is_empty());
+        assert_eq!(logical_signals[1].default_parameters.len(), 1);
+        assert_eq!(railjson.track_sections.len(), 1);
+        assert_eq!(railjson.signals[0].sight_distance, 0.0);
+    }
+
+    #[test]
+    fn migrate_catenaries_and_zero_speed_limits() {
+        let mut railjson = empty_railjson("3.4.6");
+        railjson.as_object_mut().unwrap().remove("electrifications");
+        railjson["catenaries"] = json!([{
+            "id": "catenary",
+            "voltage": "1500;25000V",
+            "track_ranges": []
+        }]);
+        railjson["speed_sections"] = json!([{
+            "id": "speed_section",
+            "speed_limit": 0.0,
+            "speed_limit_by_tag": { "MA100": 0.0, "HLP": 27.0 },
+            "track_ranges": []
+        }]);
+
+        let (railjson, report) = migrate_railjson(railjson).unwrap();
+
+        assert_eq!(report.steps[0].modified_objects, 1);
+        assert_eq!(report.steps[2].modified_objects, 2);
+        assert_eq!(railjson.electrifications.len(), 1);
+        assert_eq!(railjson.electrifications[0].voltage.0, "1500V;25000V");
+        let speed_section = &railjson.speed_sections[0];
+        assert_eq!(speed_section.speed_limit, None);
+        assert_eq!(speed_section.speed_limit_by_tag.len(), 1);
+    }
+
+    #[test]
+    fn existing_signal_parameters_are_kept() {
+        let mut railjson = empty_railjson("3.4.10");
+        railjson["signals"] = json!([
+            {
+                "id": "with_parameters",
+                "track": "track",
+                "position": 10.0,
+                "direction": "START_TO_STOP",
+                "sight_distance": 400.0,
+                "logical_signals": [{
+                    "signaling_system": "BAL",
+                    "next_signaling_systems": [],
+                    "settings": { "Nf": "true" },
+                    "default_parameters": { "jaune_cli": "true" },
+                    "conditional_parameters": []
+                }]
+            },
+            {
+                "id": "without_parameters",
+                "track": "track",
+                "position": 20.0,
+                "direction": "START_TO_STOP",
+                "sight_distance": 400.0,
+                "logical_signals": [{
+                    "signaling_system": "BAL",
+                    "next_signaling_systems": [],
+                    "settings": { "Nf": "true" }
+                }]
+            }
+        ]);
+
+        let (railjson, report) = migrate_railjson(railjson).unwrap();
+
+        assert_eq!(report.steps[0].modified_objects, 1);
+        let jaune_cli = |signal: usize| {
+            let default_parameters =
+                &railjson.signals[signal].logical_signals[0].default_parameters;
+            default_parameters
+                .iter()
+                .map(|(key, value)| (key.0.as_str(), value.0.as_str()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(jaune_cli(0), vec![("jaune_cli", "true")]);
+        assert_eq!(jaune_cli(1), vec![("jaune_cli", "false")]);
+    }
+
+    #[test]
+    fn unchanged_signs_are_not_counted() {
+        let mut railjson = empty_railjson("3.4.8");
+        railjson["speed_sections"] = json!([
+            {
+                "id": "with_angles",
+                "speed_limit_by_tag": {},
+                "track_ranges": [],
+                "extensions": { "psl_sncf": {
+                    "z": { "type": "Z", "value": "", "kp": "", "side": "LEFT", "track": "track",
+                           "position": 0.0, "angle_geo": 0.0, "angle_sch": 0.0 },
+                    "r": [],
+                    "announcement": []
+                } }
+            },
+            {
+                "id": "up_to_date",
+                "speed_limit_by_tag": {},
+                "track_ranges": [],
+                "extensions": { "psl_sncf": {
+                    "z": { "type": "Z", "value": "", "kp": "", "side": "LEFT", "track": "track",
+                           "position": 0.0, "direction": "START_TO_STOP" },
+                    "r": [],
+                    "announcement": []
+                } }
+            }
+        ]);
+
+        let (_, report) = migrate_railjson(railjson).unwrap();
+
+        assert_eq!(report.steps[0].modified_objects, 1);
+    }
+}
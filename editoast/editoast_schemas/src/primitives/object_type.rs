@@ -3,6 +3,7 @@ use serde::Deserialize;
 use serde::Serialize;
 use strum::Display;
 use strum::EnumIter;
+use strum::EnumString;
 use utoipa::ToSchema;
 
 editoast_common::schemas! {
@@ -20,6 +21,7 @@ editoast_common::schemas! {
     Serialize,
     Enum,
     EnumIter,
+    EnumString,
     Display,
     ToSchema,
 )]
@@ -28,6 +28,11 @@ pub use comfort::Comfort;
 mod train_schedule_base;
 pub use train_schedule_base::TrainScheduleBase;
 
+mod train_pattern;
+pub use train_pattern::DayOfWeek;
+pub use train_pattern::TrainPatternBase;
+pub use train_pattern::TrainPatternRecurrence;
+
 mod allowance;
 pub use allowance::Allowance;
 pub use allowance::AllowanceDistribution;
@@ -41,6 +46,7 @@ pub use rjs_power_restriction_range::RjsPowerRestrictionRange;
 
 editoast_common::schemas! {
     train_schedule_base::schemas(),
+    train_pattern::schemas(),
     margins::schemas(),
     schedule_item::schemas(),
     path_item::schemas(),
@@ -0,0 +1,218 @@
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Timelike;
+use chrono::Utc;
+use chrono::Weekday;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::TrainScheduleBase;
+use crate::primitives::PositiveDuration;
+
+editoast_common::schemas! {
+    TrainPatternBase,
+    TrainPatternRecurrence,
+    DayOfWeek,
+}
+
+/// A train schedule repeated at regular intervals
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TrainPatternBase {
+    /// The first train of the pattern.
+    /// Its name is a template, in which `{index}` is replaced by the number of the train in the
+    /// pattern (starting at 1), `{date}` by its departure date and `{time}` by its departure time.
+    pub template: TrainScheduleBase,
+    pub recurrence: TrainPatternRecurrence,
+}
+
+/// When the trains of a pattern depart
+///
+/// The first train departs at the start time of the template. Then a train departs every
+/// `interval`, until `end_time`, on the days listed in `days`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TrainPatternRecurrence {
+    /// Duration between two departures
+    #[schema(value_type = chrono::Duration)]
+    pub interval: PositiveDuration,
+    /// No train of the pattern departs after this time
+    pub end_time: DateTime<Utc>,
+    /// The days of the week (in UTC) on which trains depart, every day if empty
+    #[serde(default)]
+    pub days: Vec<DayOfWeek>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DayOfWeek {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<Weekday> for DayOfWeek {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Mon => DayOfWeek::Monday,
+            Weekday::Tue => DayOfWeek::Tuesday,
+            Weekday::Wed => DayOfWeek::Wednesday,
+            Weekday::Thu => DayOfWeek::Thursday,
+            Weekday::Fri => DayOfWeek::Friday,
+            Weekday::Sat => DayOfWeek::Saturday,
+            Weekday::Sun => DayOfWeek::Sunday,
+        }
+    }
+}
+
+impl TrainPatternRecurrence {
+    /// The departure times of the trains, the first train departing at `first_departure`
+    ///
+    /// The interval must not be zero, otherwise the departures never end.
+    /// The departures also end when the next one would overflow the representable dates.
+    pub fn departures(
+        &self,
+        first_departure: DateTime<Utc>,
+    ) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        let interval = *self.interval;
+        std::iter::successors(Some(first_departure), move |departure| {
+            departure.checked_add_signed(interval)
+        })
+        .take_while(|departure| *departure <= self.end_time)
+        .filter(|departure| self.days.is_empty() || self.days.contains(&departure.weekday().into()))
+    }
+}
+
+impl TrainPatternBase {
+    /// The train schedules of the pattern, in departure order
+    pub fn train_schedules(&self) -> impl Iterator<Item = TrainScheduleBase> + '_ {
+        self.recurrence
+            .departures(self.template.start_time)
+            .enumerate()
+            .map(|(index, start_time)| TrainScheduleBase {
+                train_name: self
+                    .template
+                    .train_name
+                    .replace("{index}", &(index + 1).to_string())
+                    .replace("{date}", &start_time.date_naive().to_string())
+                    .replace(
+                        "{time}",
+                        &format!("{:02}:{:02}", start_time.hour(), start_time.minute()),
+                    ),
+                start_time,
+                ..self.template.clone()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use serde_json::from_str;
+
+    use super::DayOfWeek;
+    use super::TrainPatternBase;
+    use super::TrainPatternRecurrence;
+    use crate::train_schedule::TrainScheduleBase;
+
+    fn recurrence(
+        interval: Duration,
+        end_time: &str,
+        days: Vec<DayOfWeek>,
+    ) -> TrainPatternRecurrence {
+        TrainPatternRecurrence {
+            interval: interval.try_into().unwrap(),
+            end_time: end_time.parse().unwrap(),
+            days,
+        }
+    }
+
+    #[test]
+    fn departures_are_spaced_by_the_interval() {
+        let recurrence = recurrence(Duration::minutes(30), "2024-12-30T09:15:00Z", vec![]);
+
+        let departures = recurrence
+            .departures("2024-12-30T08:00:00Z".parse().unwrap())
+            .map(|departure| departure.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            departures,
+            vec![
+                "2024-12-30 08:00:00 UTC",
+                "2024-12-30 08:30:00 UTC",
+                "2024-12-30 09:00:00 UTC",
+            ]
+        );
+    }
+
+    #[test]
+    fn departures_end_before_overflowing() {
+        let recurrence = recurrence(
+            Duration::days(365 * 300_000),
+            "9999-12-31T00:00:00Z",
+            vec![],
+        );
+
+        let departures = recurrence
+            .departures("2024-12-30T08:00:00Z".parse().unwrap())
+            .map(|departure| departure.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(departures, vec!["2024-12-30 08:00:00 UTC"]);
+    }
+
+    #[test]
+    fn departures_only_happen_on_the_given_days() {
+        // 2024-12-30 is a monday
+        let recurrence = recurrence(
+            Duration::days(1),
+            "2025-01-05T08:00:00Z",
+            vec![DayOfWeek::Monday, DayOfWeek::Saturday],
+        );
+
+        let departures = recurrence
+            .departures("2024-12-30T08:00:00Z".parse().unwrap())
+            .map(|departure| departure.date_naive().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(departures, vec!["2024-12-30", "2025-01-04"]);
+    }
+
+    #[test]
+    fn train_names_are_templated() {
+        let pattern = TrainPatternBase {
+            template: TrainScheduleBase {
+                train_name: "Line 4 #{index} ({date} {time})".into(),
+                start_time: "2024-12-30T23:30:00Z".parse().unwrap(),
+                ..Default::default()
+            },
+            recurrence: recurrence(Duration::hours(1), "2024-12-31T00:30:00Z", vec![]),
+        };
+
+        let train_names = pattern
+            .train_schedules()
+            .map(|train_schedule| train_schedule.train_name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            train_names,
+            vec![
+                "Line 4 #1 (2024-12-30 23:30)",
+                "Line 4 #2 (2024-12-31 00:30)"
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_days_are_rejected() {
+        let recurrence = r#"{"interval":"PT1H","end_time":"2024-12-30T08:00:00Z","days":["mon"]}"#;
+
+        assert!(from_str::<TrainPatternRecurrence>(recurrence).is_err());
+    }
+}
@@ -15,12 +15,20 @@ pub use searchast::*;
 pub use sqlquery::*;
 pub use typing::*;
 
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
 #[derive(Debug, thiserror::Error)]
 pub enum SearchError {
     #[error("object type '{object_type}' is invalid")]
     ObjectType { object_type: String },
     #[error("query has type '{query_type}' but Boolean is expected")]
     QueryAst { query_type: String },
+    #[error("sort key has type '{key_type}' but a boolean, a number or a string is expected")]
+    SortKey { key_type: String },
+    #[error("score has type '{score_type}' but a number is expected")]
+    Score { score_type: String },
     #[error(transparent)]
     TypeCheckError(#[from] TypeCheckError),
     #[error(transparent)]
@@ -54,12 +62,149 @@ impl SearchConfig {
     }
 }
 
+/// Options of a search, besides the query constraining its results
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// The keys results are sorted by, most significant first
+    ///
+    /// Each key is a query expression, such as `["rank", ["name"], "mich st"]`, which may be
+    /// wrapped in `["asc", key]` or `["desc", key]`. Keys are sorted in ascending order by default.
+    pub order_by: Vec<serde_json::Value>,
+    /// Whether to add to each result a `highlights` object, mapping the columns
+    /// searched with `search` to their value with the matched words enclosed in `<b></b>`
+    pub highlight: bool,
+    /// An expression whose value is added to each result as `score`, such as `["rank", ["name"], "mich st"]`
+    pub score: Option<serde_json::Value>,
+    /// An SQL condition the results must also satisfy, which may refer to the table and its joins
+    ///
+    /// The condition is written in the query as is: it must never contain user input.
+    pub sql_constraint: Option<String>,
+}
+
+struct SortKey {
+    expression: SqlQuery,
+    descending: bool,
+}
+
+impl SortKey {
+    fn build(key: serde_json::Value, context: &QueryContext) -> Result<Self, SearchError> {
+        let (key, descending) = match key {
+            serde_json::Value::Array(mut items)
+                if items.len() == 2 && (items[0] == "asc" || items[0] == "desc") =>
+            {
+                let key = items.pop().unwrap();
+                (key, items[0] == "desc")
+            }
+            key => (key, false),
+        };
+        let ast = SearchAst::build_ast(key)?;
+        let key_type = context.typecheck_search_query(&ast)?;
+        let scalar = TypeSpec::or(
+            AstType::Null,
+            TypeSpec::or(
+                AstType::Boolean,
+                TypeSpec::or(
+                    AstType::Integer,
+                    TypeSpec::or(AstType::Float, AstType::String),
+                ),
+            ),
+        );
+        if !scalar.is_supertype_spec(&key_type) {
+            return Err(SearchError::SortKey {
+                key_type: key_type.to_string(),
+            });
+        }
+        Ok(Self {
+            expression: context.search_ast_to_sql(&ast)?,
+            descending,
+        })
+    }
+
+    fn to_sql(&self, bindings: &mut Vec<String>) -> String {
+        let direction = if self.descending { "DESC" } else { "ASC" };
+        format!(
+            "({}) {direction} NULLS LAST",
+            self.expression.to_sql(bindings)
+        )
+    }
+}
+
+/// Returns the patterns the query looks for in each column with the `search` function
+fn searched_patterns(ast: &SearchAst, patterns: &mut BTreeMap<String, Vec<String>>) {
+    let SearchAst::Call(function, args) = ast else {
+        return;
+    };
+    if let ("search", [SearchAst::Column(column), SearchAst::String(pattern)]) =
+        (function.as_str(), args.as_slice())
+    {
+        patterns
+            .entry(column.to_owned())
+            .or_default()
+            .push(pattern.to_owned());
+    }
+    for arg in args {
+        searched_patterns(arg, patterns);
+    }
+}
+
+/// Builds the `score` result column
+fn score_column(
+    score: serde_json::Value,
+    context: &QueryContext,
+    bindings: &mut Vec<String>,
+) -> Result<String, SearchError> {
+    let ast = SearchAst::build_ast(score)?;
+    let score_type = context.typecheck_search_query(&ast)?;
+    let number = TypeSpec::or(
+        AstType::Null,
+        TypeSpec::or(AstType::Integer, AstType::Float),
+    );
+    if !number.is_supertype_spec(&score_type) {
+        return Err(SearchError::Score {
+            score_type: score_type.to_string(),
+        });
+    }
+    let score = context.search_ast_to_sql(&ast)?;
+    Ok(format!("({}) AS \"score\"", score.to_sql(bindings)))
+}
+
+impl SearchConfig {
+    /// Builds the `highlights` result column, only result properties named after a searched column are highlighted
+    fn highlights_column(&self, ast: &SearchAst, bindings: &mut Vec<String>) -> String {
+        let mut patterns = BTreeMap::new();
+        searched_patterns(ast, &mut patterns);
+        let highlights = patterns
+            .into_iter()
+            .filter_map(|(column, patterns)| {
+                let Property { sql, .. } = self
+                    .properties
+                    .iter()
+                    .find(|property| property.name == column)?;
+                bindings.push(patterns.join(" "));
+                // Every word of the patterns is matched as a prefix, as with `search`
+                let query = format!(
+                    "to_tsquery('simple', array_to_string(ARRAY(
+                        SELECT quote_literal(lexeme) || ':*'
+                        FROM unnest(to_tsvector('simple', osrd_prepare_for_search(${})))
+                    ), ' | '))",
+                    bindings.len()
+                );
+                Some(format!(
+                    "'{column}', ts_headline('simple', ({sql})::text, {query})"
+                ))
+            })
+            .join(", ");
+        format!("jsonb_build_object({highlights}) AS \"highlights\"")
+    }
+}
+
 pub fn query_into_sql(
     query: serde_json::Value,
     search_config: &SearchConfig,
     limit: i64,
     offset: i64,
     column_name: &'static str,
+    options: &SearchOptions,
 ) -> Result<(String, Vec<String>), SearchError> {
     let ast = SearchAst::build_ast(query)?;
     let context = search_config.create_context();
@@ -70,13 +215,30 @@ pub fn query_into_sql(
         });
     }
     let where_expression = context.search_ast_to_sql(&ast)?;
+    let sort_keys = options
+        .order_by
+        .iter()
+        .map(|key| SortKey::build(key.clone(), &context))
+        .collect::<Result<Vec<_>, _>>()?;
     let table = &search_config.table;
     let joins = search_config.joins.as_ref().cloned().unwrap_or_default();
-    let result_columns = search_config.result_columns();
+    let mut result_columns = search_config.result_columns();
     let mut bindings = Default::default();
-    let constraints = where_expression.to_sql(&mut bindings);
-    let sql_code = format!(
-        "WITH _RESULT AS (
+    let mut constraints = where_expression.to_sql(&mut bindings);
+    if let Some(sql_constraint) = &options.sql_constraint {
+        constraints = format!("({constraints}) AND ({sql_constraint})");
+    }
+    if options.highlight {
+        let highlights = search_config.highlights_column(&ast, &mut bindings);
+        result_columns = format!("{result_columns}, {highlights}");
+    }
+    if let Some(score) = &options.score {
+        let score = score_column(score.clone(), &context, &mut bindings)?;
+        result_columns = format!("{result_columns}, {score}");
+    }
+    if sort_keys.is_empty() {
+        let sql_code = format!(
+            "WITH _RESULT AS (
             SELECT {result_columns}
             FROM {table}
             {joins}
@@ -85,6 +247,174 @@ pub fn query_into_sql(
         )
         SELECT to_jsonb(_RESULT) AS {column_name}
         FROM _RESULT"
+        );
+        return Ok((sql_code, bindings));
+    }
+    // The id breaks ties so that pages don't overlap
+    let order = sort_keys
+        .iter()
+        .map(|key| key.to_sql(&mut bindings))
+        .chain([format!("\"{table}\".id")])
+        .join(", ");
+    let sql_code = format!(
+        "WITH _RESULT AS (
+            SELECT {result_columns}, ROW_NUMBER() OVER (ORDER BY {order}) AS \"_position\"
+            FROM {table}
+            {joins}
+            WHERE {constraints}
+            ORDER BY \"_position\"
+            LIMIT {limit} OFFSET {offset}
+        )
+        SELECT to_jsonb(_RESULT) - '_position' AS {column_name}
+        FROM _RESULT
+        ORDER BY \"_position\""
     );
     Ok((sql_code, bindings))
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn search_config() -> SearchConfig {
+        SearchConfig {
+            table: "search_operational_point".to_owned(),
+            criterias: vec![
+                Criteria {
+                    name: "name".to_owned(),
+                    data_type: AstType::String.into(),
+                    migration: None,
+                },
+                Criteria {
+                    name: "infra_id".to_owned(),
+                    data_type: AstType::Integer.into(),
+                    migration: None,
+                },
+            ],
+            properties: vec![Property {
+                name: "name".to_owned(),
+                sql: "OP.name".to_owned(),
+                data_type: None,
+            }],
+            joins: None,
+            migration: None,
+        }
+    }
+
+    #[test]
+    fn sort_keys_share_bindings_with_constraints() {
+        let options = SearchOptions {
+            order_by: vec![
+                json!(["desc", ["rank", ["name"], "mich"]]),
+                json!(["infra_id"]),
+            ],
+            ..Default::default()
+        };
+        let (sql, bindings) = query_into_sql(
+            json!(["search", ["name"], "mich"]),
+            &search_config(),
+            10,
+            0,
+            "result",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(bindings, vec!["mich", "mich"]);
+        assert!(sql.contains(
+            "ROW_NUMBER() OVER (ORDER BY (word_similarity((osrd_prepare_for_search(($2))), \
+             (\"search_operational_point\".\"name\"))) DESC NULLS LAST, \
+             (\"search_operational_point\".\"infra_id\") ASC NULLS LAST, \
+             \"search_operational_point\".id)"
+        ));
+        assert!(sql.contains("to_jsonb(_RESULT) - '_position'"));
+    }
+
+    #[test]
+    fn sort_keys_are_scalars() {
+        let options = SearchOptions {
+            order_by: vec![json!(["list", "a", "b"])],
+            ..Default::default()
+        };
+        let result = query_into_sql(json!(true), &search_config(), 10, 0, "result", &options);
+        assert!(matches!(result, Err(SearchError::SortKey { .. })));
+    }
+
+    #[test]
+    fn searched_columns_are_highlighted() {
+        let options = SearchOptions {
+            highlight: true,
+            ..Default::default()
+        };
+        let (sql, bindings) = query_into_sql(
+            json!([
+                "and",
+                ["search", ["name"], "mich"],
+                ["search", ["name"], "st"]
+            ]),
+            &search_config(),
+            10,
+            0,
+            "result",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(bindings, vec!["mich", "st", "mich st"]);
+        assert!(sql.contains("jsonb_build_object('name', ts_headline('simple', (OP.name)::text,"));
+        assert!(sql.contains("osrd_prepare_for_search($3)"));
+
+        let (sql, bindings) =
+            query_into_sql(json!(true), &search_config(), 10, 0, "result", &options).unwrap();
+        assert!(bindings.is_empty());
+        assert!(sql.contains("jsonb_build_object() AS \"highlights\""));
+    }
+
+    #[test]
+    fn score_is_a_result_column() {
+        let options = SearchOptions {
+            score: Some(json!(["rank", ["name"], "mich"])),
+            ..Default::default()
+        };
+        let (sql, bindings) = query_into_sql(
+            json!(["search", ["name"], "mich"]),
+            &search_config(),
+            10,
+            0,
+            "result",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(bindings, vec!["mich", "mich"]);
+        assert!(sql.contains(
+            "(word_similarity((osrd_prepare_for_search(($2))), \
+             (\"search_operational_point\".\"name\"))) AS \"score\""
+        ));
+
+        let options = SearchOptions {
+            score: Some(json!(["name"])),
+            ..Default::default()
+        };
+        let result = query_into_sql(json!(true), &search_config(), 10, 0, "result", &options);
+        assert!(matches!(result, Err(SearchError::Score { .. })));
+    }
+
+    #[test]
+    fn sql_constraint_restricts_results() {
+        let options = SearchOptions {
+            sql_constraint: Some("OP.infra_id = 1".to_owned()),
+            ..Default::default()
+        };
+        let (sql, bindings) = query_into_sql(
+            json!(["search", ["name"], "mich"]),
+            &search_config(),
+            10,
+            0,
+            "result",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(bindings, vec!["mich"]);
+        assert!(sql.contains(") AND (OP.infra_id = 1)"));
+    }
+}
@@ -97,12 +97,21 @@ impl QueryContext {
 /// - like : string -> (string | null) -> (bool | null)
 /// - ilike : string -> (string | null) -> (bool | null)
 /// - search : string -> (string | null) -> bool
+/// - rank : string -> (string | null) -> float
 /// - =i : string -> string -> bool
 /// - to_string : (int | null) -> string
 /// - to_string : (float | null) -> string
 /// - to_string : (string | null) -> string
 /// - list : variadic string -> string list
 /// - contains : string list -> string list -> bool
+/// - within_bbox : geometry -> number -> number -> number -> number -> bool
+/// - within_distance : geometry -> number -> number -> number -> bool
+/// - intersects : geometry -> string -> bool
+///
+/// Where `number` stands for `(int | float)`. The spatial functions take WGS84 coordinates:
+/// `within_bbox` takes the min longitude, min latitude, max longitude and max latitude of the box,
+/// `within_distance` the longitude and latitude of a point then a distance in meters,
+/// and `intersects` a GeoJSON geometry.
 pub fn create_processing_context() -> QueryContext {
     let mut context = QueryContext::default();
     context.def_function_1::<dsl::Nullable<dsl::Ersatz<dsl::Boolean>>, dsl::Sql<dsl::Boolean>>(
@@ -176,6 +185,22 @@ pub fn create_processing_context() -> QueryContext {
             })
         }),
     );
+    context.def_function_2::<dsl::Ersatz<dsl::String>, dsl::Nullable<dsl::String>, dsl::Sql<dsl::Float>>(
+        "rank",
+        Rc::new(|value, pattern| {
+            // Between 0 and 1, 1 meaning that every word of the pattern is found in the value
+            Ok(match pattern {
+                Some(pattern) => SqlQuery::call(
+                    "word_similarity",
+                    vec![
+                        SqlQuery::call("osrd_prepare_for_search", vec![pattern]),
+                        value.into(),
+                    ],
+                ),
+                None => SqlQuery::Value(TypedAst::Float(0.0)),
+            })
+        }),
+    );
     context.def_function_2::<dsl::Ersatz<dsl::String>, dsl::Nullable<dsl::String>, dsl::Sql<dsl::Boolean>>(
         "=i",
         Rc::new(|left, right| {
@@ -209,6 +234,65 @@ pub fn create_processing_context() -> QueryContext {
         "contains",
         Rc::new(|sub, array| Ok(SqlQuery::infix("<@", sub, array))),
     );
+    let number = || TypeSpec::or(AstType::Integer, AstType::Float);
+    context.def_function(
+        "within_bbox",
+        AstType::Geometry >> number() >> number() >> number() >> number() >> AstType::Boolean,
+        Rc::new(|mut args| {
+            let geometry = args.remove(0);
+            let mut envelope = args;
+            envelope.push(TypedAst::Integer(4326));
+            Ok(SqlQuery::call(
+                "ST_Intersects",
+                vec![geometry.into(), SqlQuery::call("ST_MakeEnvelope", envelope)],
+            )
+            .into_typed_ast(AstType::Boolean.into()))
+        }),
+    );
+    context.def_function(
+        "within_distance",
+        AstType::Geometry >> number() >> number() >> number() >> AstType::Boolean,
+        Rc::new(|args| {
+            let [geometry, longitude, latitude, distance]: [TypedAst; 4] =
+                args.try_into().expect("arity is checked by the signature");
+            let point = SqlQuery::call(
+                "ST_SetSRID",
+                vec![
+                    SqlQuery::call("ST_MakePoint", vec![longitude, latitude]),
+                    TypedAst::Integer(4326).into(),
+                ],
+            );
+            // Distances between geographies are in meters
+            Ok(SqlQuery::call(
+                "ST_DWithin",
+                vec![
+                    SqlQuery::cast(geometry, "geography"),
+                    SqlQuery::cast(point, "geography"),
+                    distance.into(),
+                ],
+            )
+            .into_typed_ast(AstType::Boolean.into()))
+        }),
+    );
+    context.def_function(
+        "intersects",
+        AstType::Geometry >> AstType::String >> AstType::Boolean,
+        Rc::new(|args| {
+            let [geometry, geojson]: [TypedAst; 2] =
+                args.try_into().expect("arity is checked by the signature");
+            let shape = SqlQuery::call(
+                "ST_SetSRID",
+                vec![
+                    SqlQuery::call("ST_GeomFromGeoJSON", vec![geojson]),
+                    TypedAst::Integer(4326).into(),
+                ],
+            );
+            Ok(
+                SqlQuery::call("ST_Intersects", vec![geometry.into(), shape])
+                    .into_typed_ast(AstType::Boolean.into()),
+            )
+        }),
+    );
     context
 }
 
@@ -229,6 +313,8 @@ mod tests {
             .insert("trigram".into(), AstType::String.into());
         env.columns_type
             .insert("infra_id".into(), AstType::Integer.into());
+        env.columns_type
+            .insert("geographic".into(), AstType::Geometry.into());
         // + : int -> int -> int
         env.def_function_2::<dsl::Integer, dsl::Integer, dsl::Integer>(
             "+",
@@ -443,6 +529,61 @@ mod tests {
         assert_eq!(eval(req), expected);
     }
 
+    #[test]
+    fn eval_rank() {
+        assert_eq!(
+            typecheck(json!(["rank", ["name"], "mich st"])).unwrap(),
+            AstType::Float.into()
+        );
+        assert_eq!(
+            eval(json!(["rank", ["name"], "mich st"])),
+            TypedAst::Sql(
+                Box::new(SqlQuery::call(
+                    "word_similarity",
+                    vec![
+                        SqlQuery::call(
+                            "osrd_prepare_for_search",
+                            vec![TypedAst::String("mich st".to_owned())]
+                        ),
+                        SqlQuery::Value(TypedAst::Column {
+                            name: "name".to_owned(),
+                            table: None,
+                            spec: AstType::String.into(),
+                        }),
+                    ]
+                )),
+                AstType::Float.into()
+            )
+        );
+        assert_eq!(eval(json!(["rank", ["name"], null])), TypedAst::Float(0.0));
+        assert!(try_eval(json!(["rank", ["infra_id"], "mich"])).is_err());
+    }
+
+    #[test]
+    fn eval_spatial_functions() {
+        let to_sql = |query| SqlQuery::from(eval(query)).to_string();
+        assert_eq!(
+            to_sql(json!(["within_bbox", ["geographic"], -1.6, 48.5, -1.4, 48.7])),
+            "ST_Intersects((\"geographic\"), (ST_MakeEnvelope((-1.6), (48.5), (-1.4), (48.7), (4326))))"
+        );
+        assert_eq!(
+            to_sql(json!(["within_distance", ["geographic"], 2, 48.8, 500])),
+            "ST_DWithin(((\"geographic\")::geography), \
+             ((ST_SetSRID((ST_MakePoint((2), (48.8))), (4326)))::geography), (500))"
+        );
+        assert_eq!(
+            to_sql(json!([
+                "intersects",
+                ["geographic"],
+                "{\"type\": \"Point\"}"
+            ])),
+            "ST_Intersects((\"geographic\"), (ST_SetSRID((ST_GeomFromGeoJSON(($1))), (4326))))"
+        );
+        assert!(typecheck(json!(["within_bbox", ["geographic"], 0, 0, 1])).is_err());
+        assert!(typecheck(json!(["within_distance", ["name"], 0, 0, 1])).is_err());
+        assert!(typecheck(json!(["intersects", ["geographic"], 12])).is_err());
+    }
+
     #[test]
     fn test_null_keeping() {
         assert_eq!(
@@ -42,6 +42,7 @@ pub struct CriteriaMigration {
 pub enum Index {
     Default,
     GinTrgm,
+    Gist,
 }
 
 pub enum SearchType {
@@ -238,6 +239,9 @@ impl Index {
                     "CREATE INDEX \"{name}\" ON \"{table}\" USING gin (\"{column}\" gin_trgm_ops);"
                 )
             }
+            Index::Gist => {
+                format!("CREATE INDEX \"{name}\" ON \"{table}\" USING gist (\"{column}\");")
+            }
         }
     }
 }
@@ -39,6 +39,8 @@ pub enum AstType {
     Integer,
     Float,
     String,
+    /// A PostGIS geometry, only found in columns
+    Geometry,
 }
 
 /// Allows combining [AstType]s in order to express more complex types
@@ -0,0 +1,9 @@
+CREATE TABLE infra_edition (
+    id int8 PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    infra_id int8 NOT NULL REFERENCES infra(id) ON DELETE CASCADE,
+    operations jsonb NOT NULL,
+    inverse_operations jsonb NOT NULL,
+    undone boolean NOT NULL DEFAULT FALSE,
+    created timestamptz NOT NULL DEFAULT NOW()
+);
+CREATE INDEX infra_edition_infra_id_idx ON infra_edition (infra_id);
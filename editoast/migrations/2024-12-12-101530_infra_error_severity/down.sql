@@ -0,0 +1,2 @@
+UPDATE infra_layer_error
+SET information = information - 'severity';
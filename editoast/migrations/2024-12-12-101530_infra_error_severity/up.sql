@@ -0,0 +1,8 @@
+UPDATE infra_layer_error
+SET information = information || jsonb_build_object(
+        'severity',
+        CASE
+            WHEN (information->>'is_warning')::boolean THEN 'warning'
+            ELSE 'error'
+        END
+    );
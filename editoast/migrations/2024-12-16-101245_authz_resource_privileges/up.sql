@@ -0,0 +1,15 @@
+-- Privileges over resources (infras, projects, timetables...)
+-- Resources are referenced by type and id, grants outlive deleted resources as ids are never reused.
+-- Grants also outlive deleted subjects: a resource owned by a deleted user stays restricted
+-- until a privilege over it is granted to someone else.
+
+create table authz_grant(
+    id             bigserial primary key,
+    subject        bigint not null,
+    resource_type  varchar(255) not null,
+    resource_id    bigint not null,
+    privilege      varchar(255) not null,
+    unique (subject, resource_type, resource_id)
+);
+
+create index authz_grant_resource_idx on authz_grant (resource_type, resource_id);
@@ -0,0 +1,15 @@
+-- Entries are kept when their author is deleted, the identity and name of the author are copied for that purpose.
+CREATE TABLE audit_log (
+    id int8 PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    created timestamptz NOT NULL DEFAULT NOW(),
+    user_id bigint REFERENCES authn_user ON DELETE SET NULL,
+    user_identity text NOT NULL,
+    user_name text NOT NULL,
+    action varchar(255) NOT NULL,
+    resource_type varchar(255) NOT NULL,
+    resource_id bigint NOT NULL,
+    summary text NOT NULL
+);
+
+CREATE INDEX audit_log_created_idx ON audit_log (created);
+CREATE INDEX audit_log_resource_idx ON audit_log (resource_type, resource_id);
@@ -0,0 +1,10 @@
+CREATE TABLE saved_search (
+    id int8 PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    name varchar(255) NOT NULL,
+    owner_identity text NOT NULL,
+    object varchar(255) NOT NULL,
+    query jsonb NOT NULL,
+    order_by jsonb NOT NULL DEFAULT '[]',
+    created timestamptz NOT NULL DEFAULT NOW(),
+    CONSTRAINT saved_search_owner_name_key UNIQUE (owner_identity, name)
+);
@@ -0,0 +1,218 @@
+-- DO NOT EDIT THIS FILE MANUALLY!
+-- To change the migration's content, use `editoast search make-migration`.
+-- To add custom SQL code, check out `#[derive(Search)]` attributes `prepend_sql` and `append_sql`.
+
+DROP TABLE IF EXISTS "search_operational_point";
+
+CREATE TABLE "search_operational_point" (
+    id BIGINT PRIMARY KEY REFERENCES "infra_object_operational_point"("id") ON UPDATE CASCADE ON DELETE CASCADE,
+    "obj_id" varchar(255),
+    "infra_id" integer,
+    "uic" integer,
+    "trigram" varchar(3),
+    "ci" integer,
+    "ch" text,
+    "name" text,
+    "geographic" geometry(Geometry, 4326)
+);
+
+CREATE INDEX "search_operational_point_obj_id" ON "search_operational_point" ("obj_id");
+CREATE INDEX "search_operational_point_infra_id" ON "search_operational_point" ("infra_id");
+CREATE INDEX "search_operational_point_uic" ON "search_operational_point" ("uic");
+CREATE INDEX "search_operational_point_trigram" ON "search_operational_point" ("trigram");
+CREATE INDEX "search_operational_point_ci" ON "search_operational_point" ("ci");
+CREATE INDEX "search_operational_point_ch" ON "search_operational_point" ("ch");
+CREATE INDEX "search_operational_point_name" ON "search_operational_point" USING gin ("name" gin_trgm_ops);
+CREATE INDEX "search_operational_point_geographic" ON "search_operational_point" USING gist ("geographic");
+
+CREATE OR REPLACE FUNCTION search_operational_point__ins_trig_fun()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+AS $$
+BEGIN
+    INSERT INTO "search_operational_point" (id, obj_id, infra_id, uic, trigram, ci, ch, name, geographic)
+        SELECT "infra_object_operational_point".id AS id, (infra_object_operational_point.obj_id) AS obj_id,
+    (infra_object_operational_point.infra_id) AS infra_id,
+    ((infra_object_operational_point.data->'extensions'->'identifier'->>'uic')::integer) AS uic,
+    (infra_object_operational_point.data->'extensions'->'sncf'->>'trigram') AS trigram,
+    ((infra_object_operational_point.data->'extensions'->'sncf'->>'ci')::integer) AS ci,
+    (infra_object_operational_point.data->'extensions'->'sncf'->>'ch') AS ch,
+    osrd_prepare_for_search(infra_object_operational_point.data->'extensions'->'identifier'->>'name') AS name,
+    (SELECT ST_Collect(ST_LineInterpolatePoint(
+                ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+                LEAST(GREATEST((part->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+            ))
+            FROM jsonb_array_elements(infra_object_operational_point.data->'parts') AS part
+            INNER JOIN infra_object_track_section AS track_section
+                ON track_section.infra_id = infra_object_operational_point.infra_id
+                AND track_section.obj_id = part->>'track') AS geographic
+        FROM (SELECT NEW.*) AS "infra_object_operational_point"
+        ;
+    RETURN NEW;
+END;
+$$;
+CREATE OR REPLACE TRIGGER search_operational_point__ins_trig
+AFTER INSERT ON "infra_object_operational_point"
+FOR EACH ROW EXECUTE FUNCTION search_operational_point__ins_trig_fun();
+
+
+CREATE OR REPLACE FUNCTION search_operational_point__upd_trig_fun()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+AS $$
+BEGIN
+    UPDATE "search_operational_point"
+        SET "obj_id" = (infra_object_operational_point.obj_id),
+        "infra_id" = (infra_object_operational_point.infra_id),
+        "uic" = ((infra_object_operational_point.data->'extensions'->'identifier'->>'uic')::integer),
+        "trigram" = (infra_object_operational_point.data->'extensions'->'sncf'->>'trigram'),
+        "ci" = ((infra_object_operational_point.data->'extensions'->'sncf'->>'ci')::integer),
+        "ch" = (infra_object_operational_point.data->'extensions'->'sncf'->>'ch'),
+        "name" = osrd_prepare_for_search(infra_object_operational_point.data->'extensions'->'identifier'->>'name'),
+        "geographic" = (SELECT ST_Collect(ST_LineInterpolatePoint(
+                ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+                LEAST(GREATEST((part->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+            ))
+            FROM jsonb_array_elements(infra_object_operational_point.data->'parts') AS part
+            INNER JOIN infra_object_track_section AS track_section
+                ON track_section.infra_id = infra_object_operational_point.infra_id
+                AND track_section.obj_id = part->>'track')
+        FROM (SELECT NEW.*) AS "infra_object_operational_point"
+        
+        WHERE "infra_object_operational_point".id = "search_operational_point".id;
+    RETURN NEW;
+END;
+$$;
+CREATE OR REPLACE TRIGGER search_operational_point__upd_trig
+AFTER UPDATE ON "infra_object_operational_point"
+FOR EACH ROW EXECUTE FUNCTION search_operational_point__upd_trig_fun();
+
+
+
+INSERT INTO "search_operational_point" (id, "obj_id", "infra_id", "uic", "trigram", "ci", "ch", "name", "geographic")
+SELECT
+    "infra_object_operational_point"."id" AS id,
+    (infra_object_operational_point.obj_id) AS obj_id
+,    (infra_object_operational_point.infra_id) AS infra_id
+,    ((infra_object_operational_point.data->'extensions'->'identifier'->>'uic')::integer) AS uic
+,    (infra_object_operational_point.data->'extensions'->'sncf'->>'trigram') AS trigram
+,    ((infra_object_operational_point.data->'extensions'->'sncf'->>'ci')::integer) AS ci
+,    (infra_object_operational_point.data->'extensions'->'sncf'->>'ch') AS ch
+,    osrd_prepare_for_search(infra_object_operational_point.data->'extensions'->'identifier'->>'name') AS name
+,    (SELECT ST_Collect(ST_LineInterpolatePoint(
+                ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+                LEAST(GREATEST((part->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+            ))
+            FROM jsonb_array_elements(infra_object_operational_point.data->'parts') AS part
+            INNER JOIN infra_object_track_section AS track_section
+                ON track_section.infra_id = infra_object_operational_point.infra_id
+                AND track_section.obj_id = part->>'track') AS geographic
+FROM "infra_object_operational_point"
+    ;
+
+
+DROP TABLE IF EXISTS "search_signal";
+
+CREATE TABLE "search_signal" (
+    id BIGINT PRIMARY KEY REFERENCES "infra_object_signal"("id") ON UPDATE CASCADE ON DELETE CASCADE,
+    "label" text,
+    "line_name" text,
+    "infra_id" integer,
+    "obj_id" VARCHAR(255),
+    "signaling_systems" TEXT[],
+    "settings" TEXT[],
+    "line_code" integer,
+    "geographic" geometry(Point, 4326)
+);
+
+CREATE INDEX "search_signal_label" ON "search_signal" USING gin ("label" gin_trgm_ops);
+CREATE INDEX "search_signal_line_name" ON "search_signal" USING gin ("line_name" gin_trgm_ops);
+CREATE INDEX "search_signal_infra_id" ON "search_signal" ("infra_id");
+CREATE INDEX "search_signal_obj_id" ON "search_signal" ("obj_id");
+CREATE INDEX "search_signal_signaling_systems" ON "search_signal" ("signaling_systems");
+CREATE INDEX "search_signal_settings" ON "search_signal" ("settings");
+CREATE INDEX "search_signal_line_code" ON "search_signal" ("line_code");
+CREATE INDEX "search_signal_geographic" ON "search_signal" USING gist ("geographic");
+
+CREATE OR REPLACE FUNCTION search_signal__ins_trig_fun()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+AS $$
+BEGIN
+    INSERT INTO "search_signal" (id, label, line_name, infra_id, obj_id, signaling_systems, settings, line_code, geographic)
+        SELECT "infra_object_signal".id AS id, osrd_prepare_for_search(infra_object_signal.data->'extensions'->'sncf'->>'label') AS label,
+    osrd_prepare_for_search(track_section.data->'extensions'->'sncf'->>'line_name') AS line_name,
+    (infra_object_signal.infra_id) AS infra_id,
+    (infra_object_signal.obj_id) AS obj_id,
+    (ARRAY(SELECT jsonb_path_query(infra_object_signal.data, '$.logical_signals[*].signaling_system')->>0)) AS signaling_systems,
+    (ARRAY(SELECT jsonb_path_query(infra_object_signal.data, '$.logical_signals[*].settings.keyvalue().key')->>0)) AS settings,
+    ((track_section.data->'extensions'->'sncf'->>'line_code')::integer) AS line_code,
+    (ST_LineInterpolatePoint(
+            ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+            LEAST(GREATEST((infra_object_signal.data->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+        )) AS geographic
+        FROM (SELECT NEW.*) AS "infra_object_signal"
+        
+            INNER JOIN infra_object_track_section AS track_section
+            ON track_section.infra_id = infra_object_signal.infra_id
+                AND track_section.obj_id = infra_object_signal.data->>'track';
+    RETURN NEW;
+END;
+$$;
+CREATE OR REPLACE TRIGGER search_signal__ins_trig
+AFTER INSERT ON "infra_object_signal"
+FOR EACH ROW EXECUTE FUNCTION search_signal__ins_trig_fun();
+
+
+CREATE OR REPLACE FUNCTION search_signal__upd_trig_fun()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+AS $$
+BEGIN
+    UPDATE "search_signal"
+        SET "label" = osrd_prepare_for_search(infra_object_signal.data->'extensions'->'sncf'->>'label'),
+        "line_name" = osrd_prepare_for_search(track_section.data->'extensions'->'sncf'->>'line_name'),
+        "infra_id" = (infra_object_signal.infra_id),
+        "obj_id" = (infra_object_signal.obj_id),
+        "signaling_systems" = (ARRAY(SELECT jsonb_path_query(infra_object_signal.data, '$.logical_signals[*].signaling_system')->>0)),
+        "settings" = (ARRAY(SELECT jsonb_path_query(infra_object_signal.data, '$.logical_signals[*].settings.keyvalue().key')->>0)),
+        "line_code" = ((track_section.data->'extensions'->'sncf'->>'line_code')::integer),
+        "geographic" = (ST_LineInterpolatePoint(
+            ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+            LEAST(GREATEST((infra_object_signal.data->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+        ))
+        FROM (SELECT NEW.*) AS "infra_object_signal"
+        
+            INNER JOIN infra_object_track_section AS track_section
+            ON track_section.infra_id = infra_object_signal.infra_id
+                AND track_section.obj_id = infra_object_signal.data->>'track'
+        WHERE "infra_object_signal".id = "search_signal".id;
+    RETURN NEW;
+END;
+$$;
+CREATE OR REPLACE TRIGGER search_signal__upd_trig
+AFTER UPDATE ON "infra_object_signal"
+FOR EACH ROW EXECUTE FUNCTION search_signal__upd_trig_fun();
+
+
+
+INSERT INTO "search_signal" (id, "label", "line_name", "infra_id", "obj_id", "signaling_systems", "settings", "line_code", "geographic")
+SELECT
+    "infra_object_signal"."id" AS id,
+    osrd_prepare_for_search(infra_object_signal.data->'extensions'->'sncf'->>'label') AS label
+,    osrd_prepare_for_search(track_section.data->'extensions'->'sncf'->>'line_name') AS line_name
+,    (infra_object_signal.infra_id) AS infra_id
+,    (infra_object_signal.obj_id) AS obj_id
+,    (ARRAY(SELECT jsonb_path_query(infra_object_signal.data, '$.logical_signals[*].signaling_system')->>0)) AS signaling_systems
+,    (ARRAY(SELECT jsonb_path_query(infra_object_signal.data, '$.logical_signals[*].settings.keyvalue().key')->>0)) AS settings
+,    ((track_section.data->'extensions'->'sncf'->>'line_code')::integer) AS line_code
+,    (ST_LineInterpolatePoint(
+            ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+            LEAST(GREATEST((infra_object_signal.data->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+        )) AS geographic
+FROM "infra_object_signal"
+    
+            INNER JOIN infra_object_track_section AS track_section
+            ON track_section.infra_id = infra_object_signal.infra_id
+                AND track_section.obj_id = infra_object_signal.data->>'track';
+
@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS search_track__geo_ins_trig ON infra_object_track_section;
+DROP TRIGGER IF EXISTS search_track__geo_upd_trig ON infra_object_track_section;
+DROP TRIGGER IF EXISTS search_track__geo_del_trig ON infra_object_track_section;
+DROP FUNCTION IF EXISTS search_track__geo_trig_fun;
+
+ALTER TABLE search_track DROP COLUMN IF EXISTS geographic;
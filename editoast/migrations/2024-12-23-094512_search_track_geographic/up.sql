@@ -0,0 +1,74 @@
+-- search_track rows aggregate every track section of a line, so their geometry
+-- is maintained by a statement-level trigger which runs after the row triggers
+-- creating and deleting the rows themselves.
+
+ALTER TABLE search_track
+ADD COLUMN geographic geometry(Geometry, 4326);
+
+CREATE INDEX search_track_geographic ON search_track USING gist (geographic);
+
+UPDATE search_track
+SET geographic = line_geo.geographic
+FROM (
+        SELECT infra_id,
+            (data#>>'{extensions,sncf,line_code}')::integer AS line_code,
+            ST_Collect(ST_SetSRID(ST_GeomFromGeoJSON(data->>'geo'), 4326)) AS geographic
+        FROM infra_object_track_section
+        WHERE data#>>'{extensions,sncf,line_code}' IS NOT NULL
+        GROUP BY infra_id,
+            data#>>'{extensions,sncf,line_code}'
+    ) AS line_geo
+WHERE search_track.infra_id = line_geo.infra_id
+    AND search_track.line_code = line_geo.line_code;
+
+CREATE OR REPLACE FUNCTION search_track__geo_trig_fun()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+AS $$
+DECLARE
+    changed_lines text[];
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        SELECT array_agg(infra_id || ':' || (data#>>'{extensions,sncf,line_code}'))
+        INTO changed_lines
+        FROM new_track_section;
+    ELSIF TG_OP = 'DELETE' THEN
+        SELECT array_agg(infra_id || ':' || (data#>>'{extensions,sncf,line_code}'))
+        INTO changed_lines
+        FROM old_track_section;
+    ELSE
+        SELECT array_agg(infra_id || ':' || (data#>>'{extensions,sncf,line_code}'))
+        INTO changed_lines
+        FROM (
+                SELECT infra_id, data FROM new_track_section
+                UNION ALL
+                SELECT infra_id, data FROM old_track_section
+            ) AS track_section;
+    END IF;
+
+    UPDATE search_track
+    SET geographic = (
+            SELECT ST_Collect(ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326))
+            FROM infra_object_track_section AS track_section
+            WHERE track_section.infra_id = search_track.infra_id
+                AND track_section.data#>>'{extensions,sncf,line_code}' = search_track.line_code::text
+        )
+    WHERE search_track.infra_id || ':' || search_track.line_code = ANY(changed_lines);
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER search_track__geo_ins_trig
+AFTER INSERT ON infra_object_track_section
+REFERENCING NEW TABLE AS new_track_section
+FOR EACH STATEMENT EXECUTE FUNCTION search_track__geo_trig_fun();
+
+CREATE TRIGGER search_track__geo_upd_trig
+AFTER UPDATE ON infra_object_track_section
+REFERENCING OLD TABLE AS old_track_section NEW TABLE AS new_track_section
+FOR EACH STATEMENT EXECUTE FUNCTION search_track__geo_trig_fun();
+
+CREATE TRIGGER search_track__geo_del_trig
+AFTER DELETE ON infra_object_track_section
+REFERENCING OLD TABLE AS old_track_section
+FOR EACH STATEMENT EXECUTE FUNCTION search_track__geo_trig_fun();
@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS search_located_object__geo_ins_trig ON infra_object_track_section;
+DROP TRIGGER IF EXISTS search_located_object__geo_upd_trig ON infra_object_track_section;
+DROP TRIGGER IF EXISTS search_located_object__geo_del_trig ON infra_object_track_section;
+DROP FUNCTION IF EXISTS search_located_object__geo_trig_fun;
@@ -0,0 +1,95 @@
+-- The geometry of operational points and signals is interpolated on their track sections,
+-- which their own row triggers read. It is refreshed by statement-level triggers when
+-- track sections change, as well as the line of signals.
+
+CREATE OR REPLACE FUNCTION search_located_object__geo_trig_fun()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+AS $$
+DECLARE
+    changed_infra_ids bigint[];
+    changed_track_ids text[];
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        SELECT array_agg(infra_id), array_agg(obj_id)
+        INTO changed_infra_ids, changed_track_ids
+        FROM new_track_section;
+    ELSIF TG_OP = 'DELETE' THEN
+        SELECT array_agg(infra_id), array_agg(obj_id)
+        INTO changed_infra_ids, changed_track_ids
+        FROM old_track_section;
+    ELSE
+        SELECT array_agg(infra_id), array_agg(obj_id)
+        INTO changed_infra_ids, changed_track_ids
+        FROM (
+                SELECT infra_id, obj_id FROM new_track_section
+                UNION
+                SELECT infra_id, obj_id FROM old_track_section
+            ) AS track_section;
+    END IF;
+
+    WITH changed_track AS (
+        SELECT DISTINCT infra_id, obj_id
+        FROM unnest(changed_infra_ids, changed_track_ids) AS changed_track(infra_id, obj_id)
+    ),
+    changed_operational_point AS (
+        SELECT DISTINCT operational_point.id
+        FROM changed_track
+            INNER JOIN infra_object_operational_point AS operational_point
+                ON operational_point.infra_id = changed_track.infra_id
+            INNER JOIN LATERAL jsonb_array_elements(operational_point.data->'parts') AS part
+                ON part->>'track' = changed_track.obj_id
+    )
+    UPDATE search_operational_point
+    SET geographic = (
+            SELECT ST_Collect(ST_LineInterpolatePoint(
+                ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+                LEAST(GREATEST((part->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+            ))
+            FROM jsonb_array_elements(operational_point.data->'parts') AS part
+            INNER JOIN infra_object_track_section AS track_section
+                ON track_section.infra_id = operational_point.infra_id
+                AND track_section.obj_id = part->>'track'
+        )
+    FROM changed_operational_point
+        INNER JOIN infra_object_operational_point AS operational_point
+            ON operational_point.id = changed_operational_point.id
+    WHERE search_operational_point.id = operational_point.id;
+
+    WITH changed_track AS (
+        SELECT DISTINCT infra_id, obj_id
+        FROM unnest(changed_infra_ids, changed_track_ids) AS changed_track(infra_id, obj_id)
+    )
+    UPDATE search_signal
+    SET line_name = osrd_prepare_for_search(track_section.data->'extensions'->'sncf'->>'line_name'),
+        line_code = (track_section.data->'extensions'->'sncf'->>'line_code')::integer,
+        geographic = ST_LineInterpolatePoint(
+            ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+            LEAST(GREATEST((signal.data->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+        )
+    FROM changed_track
+        INNER JOIN infra_object_signal AS signal
+            ON signal.infra_id = changed_track.infra_id
+            AND signal.data->>'track' = changed_track.obj_id
+        LEFT JOIN infra_object_track_section AS track_section
+            ON track_section.infra_id = signal.infra_id
+            AND track_section.obj_id = signal.data->>'track'
+    WHERE search_signal.id = signal.id;
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER search_located_object__geo_ins_trig
+AFTER INSERT ON infra_object_track_section
+REFERENCING NEW TABLE AS new_track_section
+FOR EACH STATEMENT EXECUTE FUNCTION search_located_object__geo_trig_fun();
+
+CREATE TRIGGER search_located_object__geo_upd_trig
+AFTER UPDATE ON infra_object_track_section
+REFERENCING OLD TABLE AS old_track_section NEW TABLE AS new_track_section
+FOR EACH STATEMENT EXECUTE FUNCTION search_located_object__geo_trig_fun();
+
+CREATE TRIGGER search_located_object__geo_del_trig
+AFTER DELETE ON infra_object_track_section
+REFERENCING OLD TABLE AS old_track_section
+FOR EACH STATEMENT EXECUTE FUNCTION search_located_object__geo_trig_fun();
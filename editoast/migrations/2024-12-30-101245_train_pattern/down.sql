@@ -0,0 +1,3 @@
+ALTER TABLE train_schedule DROP COLUMN IF EXISTS train_pattern_id;
+
+DROP TABLE IF EXISTS train_pattern;
@@ -0,0 +1,11 @@
+CREATE TABLE train_pattern (
+    id int8 PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    timetable_id int8 NOT NULL REFERENCES timetable(id) ON DELETE CASCADE,
+    template jsonb NOT NULL,
+    recurrence jsonb NOT NULL
+);
+
+ALTER TABLE train_schedule
+ADD COLUMN train_pattern_id int8 NULL REFERENCES train_pattern(id) ON DELETE CASCADE;
+
+CREATE INDEX train_schedule_train_pattern_id_idx ON train_schedule(train_pattern_id);
@@ -0,0 +1,405 @@
+//! Restricts a conversion to an area, given as a bounding box or a GeoJSON polygon
+//!
+//! Tracks are clipped at the boundary of the area. The track ends created by the clipping
+//! are not buffer stops: they are listed in a [CropReport] instead.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::str::FromStr;
+
+use editoast_schemas::infra::Endpoint;
+use editoast_schemas::infra::TrackEndpoint;
+use geo_types::Coord;
+use geo_types::LineString;
+use geo_types::MultiPolygon;
+use geo_types::Polygon;
+use geo_types::Rect;
+use geos::geojson;
+use osm4routing::Edge;
+use osm4routing::NodeId;
+use serde::Serialize;
+
+/// The area a conversion is restricted to, in WGS84 coordinates
+#[derive(Debug, Clone)]
+pub struct CropArea(MultiPolygon<f64>);
+
+impl CropArea {
+    pub fn from_bbox(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Self {
+        let rect = Rect::new(
+            Coord {
+                x: min_lon,
+                y: min_lat,
+            },
+            Coord {
+                x: max_lon,
+                y: max_lat,
+            },
+        );
+        Self(MultiPolygon(vec![rect.to_polygon()]))
+    }
+
+    /// Reads the polygons of a GeoJSON geometry, feature or feature collection
+    pub fn from_geojson(raw_geojson: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let geometries: Vec<geojson::Geometry> = match raw_geojson.parse::<geojson::GeoJson>()? {
+            geojson::GeoJson::Geometry(geometry) => vec![geometry],
+            geojson::GeoJson::Feature(feature) => feature.geometry.into_iter().collect(),
+            geojson::GeoJson::FeatureCollection(collection) => collection
+                .features
+                .into_iter()
+                .filter_map(|feature| feature.geometry)
+                .collect(),
+        };
+
+        let mut polygons = vec![];
+        for geometry in geometries {
+            match geometry.value {
+                geojson::Value::Polygon(rings) => polygons.push(polygon(rings)),
+                geojson::Value::MultiPolygon(multi_rings) => {
+                    polygons.extend(multi_rings.into_iter().map(polygon))
+                }
+                _ => return Err("the crop area must only contain polygons".into()),
+            }
+        }
+        if polygons.is_empty() {
+            return Err("the crop area doesn't contain any polygon".into());
+        }
+        Ok(Self(MultiPolygon(polygons)))
+    }
+
+    fn rings(&self) -> impl Iterator<Item = &LineString<f64>> {
+        self.0
+            .iter()
+            .flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors()))
+    }
+
+    /// Whether a point is inside the area, using the even-odd rule
+    fn contains(&self, point: Coord) -> bool {
+        self.0.iter().any(|polygon| {
+            std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .flat_map(|ring| ring.lines())
+                .filter(|line| {
+                    let (a, b) = (line.start, line.end);
+                    (a.y > point.y) != (b.y > point.y)
+                        && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+                })
+                .count()
+                % 2
+                == 1
+        })
+    }
+
+    /// Lists where the segment `[a, b]` crosses the boundary of the area,
+    /// as sorted fractions of its length
+    fn crossings(&self, a: Coord, b: Coord) -> Vec<f64> {
+        let direction = b - a;
+        let mut crossings: Vec<f64> = self
+            .rings()
+            .flat_map(|ring| ring.lines())
+            .filter_map(|line| {
+                let edge = line.end - line.start;
+                let denominator = cross(direction, edge);
+                if denominator == 0. {
+                    return None;
+                }
+                let t = cross(line.start - a, edge) / denominator;
+                let u = cross(line.start - a, direction) / denominator;
+                ((0. ..=1.).contains(&u) && 0. < t && t < 1.).then_some(t)
+            })
+            .collect();
+        crossings.sort_by(f64::total_cmp);
+        crossings.dedup();
+        crossings
+    }
+}
+
+/// Parses a bounding box given as `min_lon,min_lat,max_lon,max_lat`
+impl FromStr for CropArea {
+    type Err = String;
+
+    fn from_str(bbox: &str) -> Result<Self, Self::Err> {
+        let bounds = bbox
+            .split(',')
+            .map(|bound| bound.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("invalid bounding box '{bbox}': {err}"))?;
+        let [min_lon, min_lat, max_lon, max_lat] = bounds[..] else {
+            return Err(format!(
+                "invalid bounding box '{bbox}': expected min_lon,min_lat,max_lon,max_lat"
+            ));
+        };
+        if min_lon >= max_lon || min_lat >= max_lat {
+            return Err(format!("invalid bounding box '{bbox}': empty area"));
+        }
+        Ok(Self::from_bbox(min_lon, min_lat, max_lon, max_lat))
+    }
+}
+
+fn polygon(rings: Vec<Vec<Vec<f64>>>) -> Polygon<f64> {
+    let mut rings = rings.into_iter().map(|ring| {
+        LineString::from(
+            ring.into_iter()
+                .map(|position| (position[0], position[1]))
+                .collect::<Vec<_>>(),
+        )
+    });
+    let exterior = rings.next().unwrap_or_else(|| LineString(vec![]));
+    Polygon::new(exterior, rings.collect())
+}
+
+fn cross(a: Coord, b: Coord) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn lerp(a: Coord, b: Coord, t: f64) -> Coord {
+    a + (b - a) * t
+}
+
+/// A track end created by clipping a track at the boundary of the crop area
+#[derive(Debug, Clone, Serialize)]
+pub struct CutEnd {
+    pub track: TrackEndpoint,
+    /// The location of the cut, as `[longitude, latitude]`
+    pub location: [f64; 2],
+}
+
+/// Describes how the converted network was cropped
+#[derive(Debug, Default, Serialize)]
+pub struct CropReport {
+    /// The number of tracks entirely outside the crop area
+    pub removed_tracks: usize,
+    /// The number of tracks crossing the boundary of the crop area
+    pub cut_tracks: usize,
+    pub cut_ends: Vec<CutEnd>,
+    /// The nodes created at the cuts, which don't exist in OpenStreetMap
+    #[serde(skip)]
+    pub(crate) cut_nodes: HashSet<NodeId>,
+}
+
+/// A part of a track inside the crop area, with its nodes
+/// Nodes created by the clipping are `None`
+struct Piece {
+    geometry: Vec<Coord>,
+    nodes: Vec<Option<NodeId>>,
+}
+
+/// Splits the geometry of an edge into the parts inside the crop area
+fn clip(edge: &Edge, area: &CropArea) -> Vec<Piece> {
+    let mut pieces = vec![];
+    let mut current: Option<Piece> = None;
+    for (i, segment) in edge.geometry.windows(2).enumerate() {
+        let (a, b) = (segment[0], segment[1]);
+        let mut bounds = vec![0.];
+        bounds.extend(area.crossings(a, b));
+        bounds.push(1.);
+
+        for sub_segment in bounds.windows(2) {
+            let (t0, t1) = (sub_segment[0], sub_segment[1]);
+            if !area.contains(lerp(a, b, (t0 + t1) / 2.)) {
+                if let Some(piece) = current.take() {
+                    pieces.push(piece);
+                }
+                continue;
+            }
+
+            let piece = current.get_or_insert_with(|| {
+                let node = (t0 == 0.).then_some(edge.nodes[i]);
+                Piece {
+                    geometry: vec![lerp(a, b, t0)],
+                    nodes: vec![node],
+                }
+            });
+            if t1 == 1. {
+                piece.geometry.push(b);
+                piece.nodes.push(Some(edge.nodes[i + 1]));
+            } else {
+                piece.geometry.push(lerp(a, b, t1));
+                piece.nodes.push(None);
+                pieces.push(current.take().unwrap());
+            }
+        }
+    }
+    pieces.extend(current);
+    pieces
+}
+
+/// Clips the edges to the crop area, dropping the ones outside of it
+pub(crate) fn crop_edges(edges: Vec<Edge>, area: &CropArea) -> (Vec<Edge>, CropReport) {
+    let mut report = CropReport::default();
+    let mut cropped_edges = vec![];
+    // Nodes created by the clipping get ids that can't be found in OpenStreetMap data
+    let mut next_cut_node = i64::MIN;
+
+    for edge in edges {
+        if edge.geometry.len() < 2 || edge.nodes.len() != edge.geometry.len() {
+            cropped_edges.push(edge);
+            continue;
+        }
+        let pieces = clip(&edge, area);
+        let untouched = pieces.len() == 1 && pieces[0].nodes.iter().all(Option::is_some);
+        if untouched {
+            cropped_edges.push(edge);
+            continue;
+        }
+        if pieces.is_empty() {
+            report.removed_tracks += 1;
+            continue;
+        }
+
+        report.cut_tracks += 1;
+        let piece_count = pieces.len();
+        for (index, piece) in pieces.into_iter().enumerate() {
+            let id = if piece_count == 1 {
+                edge.id.clone()
+            } else {
+                format!("{}-{index}", edge.id)
+            };
+            let last = piece.nodes.len() - 1;
+            let nodes: Vec<NodeId> = piece
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(position, node)| {
+                    node.unwrap_or_else(|| {
+                        let node = NodeId(next_cut_node);
+                        next_cut_node += 1;
+                        report.cut_nodes.insert(node);
+                        let endpoint = if position == last {
+                            Endpoint::End
+                        } else {
+                            Endpoint::Begin
+                        };
+                        let location = piece.geometry[position];
+                        report.cut_ends.push(CutEnd {
+                            track: TrackEndpoint::new(&id, endpoint),
+                            location: [location.x, location.y],
+                        });
+                        node
+                    })
+                })
+                .collect();
+            cropped_edges.push(Edge {
+                id,
+                source: nodes[0],
+                target: nodes[last],
+                geometry: piece.geometry,
+                nodes,
+                ..edge.clone()
+            });
+        }
+    }
+    (cropped_edges, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(coords: &[(f64, f64)]) -> Edge {
+        Edge {
+            id: "1-0".into(),
+            source: NodeId(1),
+            target: NodeId(coords.len() as i64),
+            geometry: coords.iter().map(|&(x, y)| Coord { x, y }).collect(),
+            nodes: (1..=coords.len() as i64).map(NodeId).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_bbox() {
+        let area: CropArea = "0,0,2,1".parse().unwrap();
+        assert!(area.contains(Coord { x: 1.5, y: 0.5 }));
+        assert!(!area.contains(Coord { x: 2.5, y: 0.5 }));
+
+        assert!("0,0,2".parse::<CropArea>().is_err());
+        assert!("2,0,0,1".parse::<CropArea>().is_err());
+        assert!("0,0,a,1".parse::<CropArea>().is_err());
+    }
+
+    #[test]
+    fn parse_geojson_polygon_with_hole() {
+        let area = CropArea::from_geojson(
+            r#"{
+                "type": "Feature",
+                "properties": {},
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [
+                        [[0, 0], [4, 0], [4, 4], [0, 4], [0, 0]],
+                        [[1, 1], [3, 1], [3, 3], [1, 3], [1, 1]]
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(area.contains(Coord { x: 0.5, y: 2. }));
+        assert!(!area.contains(Coord { x: 2., y: 2. }));
+
+        assert!(CropArea::from_geojson(r#"{"type": "Point", "coordinates": [0, 0]}"#).is_err());
+    }
+
+    #[test]
+    fn keep_edges_inside() {
+        let area = CropArea::from_bbox(0., 0., 10., 10.);
+        let (edges, report) = crop_edges(vec![edge(&[(1., 1.), (2., 2.)])], &area);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].id, "1-0");
+        assert_eq!(report.cut_tracks, 0);
+        assert!(report.cut_ends.is_empty());
+
+        let (edges, report) = crop_edges(vec![edge(&[(11., 1.), (12., 2.)])], &area);
+        assert!(edges.is_empty());
+        assert_eq!(report.removed_tracks, 1);
+    }
+
+    #[test]
+    fn cut_edges_at_the_boundary() {
+        let area = CropArea::from_bbox(0., 0., 10., 10.);
+        // Leaves the area, and comes back
+        let (edges, report) = crop_edges(
+            vec![edge(&[(5., 5.), (15., 5.), (15., 8.), (5., 8.)])],
+            &area,
+        );
+        assert_eq!(edges.len(), 2);
+        assert_eq!(report.cut_tracks, 1);
+        assert_eq!(report.cut_ends.len(), 2);
+        assert_eq!(report.cut_nodes.len(), 2);
+
+        assert_eq!(edges[0].id, "1-0-0");
+        assert_eq!(edges[0].source, NodeId(1));
+        assert_eq!(edges[0].geometry[1], Coord { x: 10., y: 5. });
+        assert!(report.cut_nodes.contains(&edges[0].target));
+        assert_eq!(
+            report.cut_ends[0].track,
+            TrackEndpoint::new("1-0-0", Endpoint::End)
+        );
+
+        assert_eq!(edges[1].id, "1-0-1");
+        assert_eq!(edges[1].target, NodeId(4));
+        assert_eq!(edges[1].geometry[0], Coord { x: 10., y: 8. });
+        assert_eq!(edges[1].nodes.len(), edges[1].geometry.len());
+        assert_eq!(report.cut_ends[1].location, [10., 8.]);
+    }
+
+    #[test]
+    fn cut_single_segment_edges_leaving_the_area() {
+        let area = CropArea::from_bbox(0., 0., 10., 10.);
+        let (edges, report) = crop_edges(vec![edge(&[(5., 5.), (15., 5.)])], &area);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(report.cut_tracks, 1);
+        assert_eq!(report.cut_ends.len(), 1);
+
+        assert_eq!(edges[0].id, "1-0");
+        assert_eq!(edges[0].source, NodeId(1));
+        assert_eq!(
+            edges[0].geometry,
+            vec![Coord { x: 5., y: 5. }, Coord { x: 10., y: 5. }]
+        );
+        assert!(report.cut_nodes.contains(&edges[0].target));
+        assert_eq!(
+            report.cut_ends[0].track,
+            TrackEndpoint::new("1-0", Endpoint::End)
+        );
+    }
+}
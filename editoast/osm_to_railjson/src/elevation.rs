@@ -0,0 +1,146 @@
+//! Elevation sampled from a digital elevation model (DEM)
+//!
+//! The model is read from a single band GeoTIFF file, whose coordinates must be WGS84
+//! longitudes and latitudes (EPSG:4326), as OpenStreetMap data.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use geo_types::Coord;
+use tiff::decoder::Decoder;
+use tiff::decoder::DecodingResult;
+use tiff::tags::Tag;
+
+#[derive(Debug)]
+pub struct ElevationModel {
+    width: usize,
+    height: usize,
+    /// The coordinates of the center of the top left pixel
+    origin: Coord,
+    /// The size of a pixel, in degrees (latitudes decrease with rows)
+    pixel_size: Coord,
+    /// Elevations in meters, row by row
+    elevations: Vec<f64>,
+    nodata: Option<f64>,
+}
+
+impl ElevationModel {
+    pub fn from_geotiff(path: &Path) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut decoder = Decoder::new(BufReader::new(File::open(path)?))?;
+        let (width, height) = decoder.dimensions()?;
+
+        let scale = decoder.get_tag_f64_vec(Tag::ModelPixelScaleTag)?;
+        let tiepoint = decoder.get_tag_f64_vec(Tag::ModelTiepointTag)?;
+        let (&[scale_x, scale_y, ..], &[raster_x, raster_y, _, model_x, model_y, ..]) =
+            (&scale[..], &tiepoint[..])
+        else {
+            return Err("the elevation model isn't georeferenced".into());
+        };
+        let nodata = match decoder.find_tag(Tag::GdalNodata)? {
+            Some(nodata) => nodata.into_string()?.trim_end_matches('\0').parse().ok(),
+            None => None,
+        };
+
+        let elevations: Vec<f64> = match decoder.read_image()? {
+            DecodingResult::U8(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::U16(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::U32(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::I8(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::I16(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::I32(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::F32(values) => values.into_iter().map(f64::from).collect(),
+            DecodingResult::F64(values) => values,
+            _ => return Err("unsupported elevation model sample format".into()),
+        };
+        if elevations.len() != width as usize * height as usize {
+            return Err("the elevation model must have a single band".into());
+        }
+
+        // The tie point is the corner of the raster pixel it references
+        let origin = Coord {
+            x: model_x + (0.5 - raster_x) * scale_x,
+            y: model_y - (0.5 - raster_y) * scale_y,
+        };
+        Ok(Self {
+            width: width as usize,
+            height: height as usize,
+            origin,
+            pixel_size: Coord {
+                x: scale_x,
+                y: scale_y,
+            },
+            elevations,
+            nodata,
+        })
+    }
+
+    fn pixel(&self, column: usize, row: usize) -> Option<f64> {
+        let elevation = self.elevations[row * self.width + column];
+        if elevation.is_nan() || self.nodata == Some(elevation) {
+            return None;
+        }
+        Some(elevation)
+    }
+
+    /// The elevation at a location, interpolated between the nearest pixels
+    pub fn elevation(&self, location: Coord) -> Option<f64> {
+        let column = (location.x - self.origin.x) / self.pixel_size.x;
+        let row = (self.origin.y - location.y) / self.pixel_size.y;
+        let max_column = (self.width - 1) as f64;
+        let max_row = (self.height - 1) as f64;
+        if !(0. ..=max_column).contains(&column) || !(0. ..=max_row).contains(&row) {
+            return None;
+        }
+
+        let (left, top) = (column.floor() as usize, row.floor() as usize);
+        let (right, bottom) = (column.ceil() as usize, row.ceil() as usize);
+        let (dx, dy) = (column.fract(), row.fract());
+        let top_elevation = self.pixel(left, top)? * (1. - dx) + self.pixel(right, top)? * dx;
+        let bottom_elevation =
+            self.pixel(left, bottom)? * (1. - dx) + self.pixel(right, bottom)? * dx;
+        Some(top_elevation * (1. - dy) + bottom_elevation * dy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> ElevationModel {
+        ElevationModel {
+            width: 2,
+            height: 2,
+            origin: Coord { x: 2., y: 49. },
+            pixel_size: Coord { x: 0.01, y: 0.01 },
+            elevations: vec![100., 200., 300., -9999.],
+            nodata: Some(-9999.),
+        }
+    }
+
+    #[test]
+    fn interpolate_elevation() {
+        let model = model();
+        assert_eq!(model.elevation(Coord { x: 2., y: 49. }), Some(100.));
+        let elevation = model.elevation(Coord { x: 2.005, y: 49. }).unwrap();
+        assert!((elevation - 150.).abs() < 1e-6);
+        let elevation = model.elevation(Coord { x: 2., y: 48.995 }).unwrap();
+        assert!((elevation - 200.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn missing_elevation() {
+        let model = model();
+        // Outside the model
+        assert_eq!(model.elevation(Coord { x: 1., y: 49. }), None);
+        // Next to a pixel without data
+        assert_eq!(
+            model.elevation(Coord {
+                x: 2.005,
+                y: 48.995
+            }),
+            None
+        );
+    }
+}
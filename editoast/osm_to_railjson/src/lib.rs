@@ -1,5 +1,18 @@
+mod crop;
+mod elevation;
 mod generate_routes;
 mod osm_to_railjson;
+mod profile;
+mod track_profile;
 mod utils;
 
+pub use crop::CropArea;
+pub use crop::CropReport;
+pub use crop::CutEnd;
+pub use elevation::ElevationModel;
 pub use osm_to_railjson::osm_to_railjson;
+pub use osm_to_railjson::parse_osm;
+pub use osm_to_railjson::parse_osm_cropped;
+pub use osm_to_railjson::parse_osm_with_options;
+pub use osm_to_railjson::ConversionOptions;
+pub use profile::Profile;
@@ -7,44 +7,109 @@ use tracing::error;
 use tracing::info;
 
 use super::utils::*;
+use crate::crop::crop_edges;
+use crate::crop::CropArea;
+use crate::crop::CropReport;
+use crate::elevation::ElevationModel;
 use crate::generate_routes;
+use crate::profile::Profile;
+use crate::track_profile::curves;
+use crate::track_profile::slopes;
 use editoast_schemas::infra::RailJson;
 use editoast_schemas::infra::TrackSection;
+use editoast_schemas::infra::RAILJSON_VERSION;
+
+/// Settings of a conversion
+#[derive(Debug, Default)]
+pub struct ConversionOptions {
+    /// Only the part of the network inside this area is converted
+    pub crop_area: Option<CropArea>,
+    /// Slopes of the tracks without `incline` tag are computed from this elevation model
+    pub elevation_model: Option<ElevationModel>,
+    /// Selects the converted objects
+    pub profile: Profile,
+}
+
 /// Run the osm-to-railjson subcommand
 /// Converts OpenStreetMap pbf file to railjson
 pub fn osm_to_railjson(
     osm_pbf_in: PathBuf,
     railjson_out: PathBuf,
+    options: ConversionOptions,
+    crop_report_out: Option<PathBuf>,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     info!(
-        "🗺️ Converting {} to {}",
+        "🗺️ Converting {} to {} (railjson version {RAILJSON_VERSION})",
         osm_pbf_in.display(),
         railjson_out.display()
     );
-    let railjson = parse_osm(osm_pbf_in)?;
+    let (railjson, crop_report) = parse_osm_with_options(osm_pbf_in, &options)?;
     let file = std::fs::File::create(railjson_out)?;
     serde_json::to_writer(file, &railjson)?;
+
+    if let Some(crop_report) = crop_report {
+        info!(
+            "✂️ Cropped the network: {} tracks cut, {} tracks removed, {} cut ends",
+            crop_report.cut_tracks,
+            crop_report.removed_tracks,
+            crop_report.cut_ends.len()
+        );
+        if let Some(crop_report_out) = crop_report_out {
+            let file = std::fs::File::create(crop_report_out)?;
+            serde_json::to_writer_pretty(file, &crop_report)?;
+        }
+    }
     Ok(())
 }
 
 pub fn parse_osm(osm_pbf_in: PathBuf) -> Result<RailJson, Box<dyn Error + Send + Sync>> {
-    let (nodes, edges) = osm4routing::Reader::new()
-        .require("railway", "rail")
-        .reject("service", "yard")
-        .reject("service", "siding")
-        .reject("service", "spur")
-        .reject("building", "*")
-        .reject("area", "yes")
-        .reject("gauge", "600")
-        .reject("roller_coaster", "*")
-        .reject("construction", "*")
+    let (railjson, _) = parse_osm_with_options(osm_pbf_in, &ConversionOptions::default())?;
+    Ok(railjson)
+}
+
+/// Converts the part of the network inside the crop area
+/// Tracks crossing its boundary are cut, and the cut ends are listed in the report
+pub fn parse_osm_cropped(
+    osm_pbf_in: PathBuf,
+    crop_area: &CropArea,
+) -> Result<(RailJson, CropReport), Box<dyn Error + Send + Sync>> {
+    let options = ConversionOptions {
+        crop_area: Some(crop_area.clone()),
+        ..Default::default()
+    };
+    let (railjson, crop_report) = parse_osm_with_options(osm_pbf_in, &options)?;
+    Ok((railjson, crop_report.unwrap_or_default()))
+}
+
+/// Converts OpenStreetMap pbf file to railjson
+/// When the conversion is cropped, tracks crossing the boundary of the crop area are cut,
+/// and the cut ends are listed in the returned report
+pub fn parse_osm_with_options(
+    osm_pbf_in: PathBuf,
+    options: &ConversionOptions,
+) -> Result<(RailJson, Option<CropReport>), Box<dyn Error + Send + Sync>> {
+    let (nodes, edges) = options
+        .profile
+        .reader()
         .read_tag("maxspeed")
         .read_tag("maxspeed:forward")
         .read_tag("maxspeed:backward")
         .read_tag("voltage")
+        .read_tag("incline")
+        .read_tag("gauge")
+        .read_tag("loading_gauge")
+        .read_tag("railway:track_class")
         .read(&osm_pbf_in)?;
     info!("🗺️ We have {} nodes and {} edges", nodes.len(), edges.len());
 
+    let (edges, crop_report) = match &options.crop_area {
+        Some(crop_area) => {
+            let (edges, crop_report) = crop_edges(edges, crop_area);
+            (edges, Some(crop_report))
+        }
+        None => (edges, None),
+    };
+
     let rail_edges = edges
         .iter()
         .filter(|e| e.properties.train == osm4routing::TrainAccessibility::Allowed)
@@ -57,14 +122,18 @@ pub fn parse_osm(osm_pbf_in: PathBuf) -> Result<RailJson, Box<dyn Error + Send +
     }
 
     let nodes_tracks = NodeToTrack::from_edges(&edges);
-    let signals = signals(&osm_pbf_in, &nodes_tracks, &adjacencies);
+    let signals = signals(&osm_pbf_in, &nodes_tracks, &adjacencies, &options.profile);
     let mut railjson = RailJson {
         extended_switch_types: vec![],
         detectors: signals.iter().map(detector).collect(),
         signals,
         speed_sections: rail_edges.clone().flat_map(speed_sections).collect(),
         electrifications: rail_edges.clone().flat_map(electrifications).collect(),
-        operational_points: operational_points(&osm_pbf_in, &nodes_tracks),
+        operational_points: operational_points(
+            &osm_pbf_in,
+            &nodes_tracks,
+            &options.profile.operational_points,
+        ),
         ..Default::default()
     };
 
@@ -76,6 +145,9 @@ pub fn parse_osm(osm_pbf_in: PathBuf) -> Result<RailJson, Box<dyn Error + Send +
             TrackSection {
                 id: e.id.as_str().into(),
                 length: e.length(),
+                slopes: slopes(e, options.elevation_model.as_ref()),
+                curves: curves(e),
+                loading_gauge_limits: loading_gauge_limits(e),
                 geo: geo.clone(),
                 ..Default::default()
             }
@@ -83,6 +155,14 @@ pub fn parse_osm(osm_pbf_in: PathBuf) -> Result<RailJson, Box<dyn Error + Send +
         .collect();
 
     for (node, mut adj) in adjacencies {
+        // Cut ends are listed in the crop report, they are not buffer stops
+        if crop_report
+            .as_ref()
+            .is_some_and(|crop_report| crop_report.cut_nodes.contains(&node))
+        {
+            continue;
+        }
+
         for e1 in &adj.edges {
             for e2 in &adj.edges {
                 if e1.id < e2.id {
@@ -122,7 +202,7 @@ pub fn parse_osm(osm_pbf_in: PathBuf) -> Result<RailJson, Box<dyn Error + Send +
     debug!("Start generating routes");
     railjson.routes = generate_routes::routes(&railjson);
     debug!("Done, got {} routes", railjson.routes.len());
-    Ok(railjson)
+    Ok((railjson, crop_report))
 }
 
 #[cfg(test)]
@@ -140,7 +220,9 @@ mod tests {
         let output = tempfile::NamedTempFile::new().unwrap();
         assert!(osm_to_railjson(
             "src/tests/minimal_rail.osm.pbf".into(),
-            output.path().into()
+            output.path().into(),
+            Default::default(),
+            None
         )
         .is_ok());
 
@@ -255,4 +337,42 @@ mod tests {
         assert_eq!("atlantis", ext.name);
         assert_eq!(1234, ext.uic);
     }
+
+    #[test]
+    fn parse_stations_without_stop_areas() {
+        let profile: Profile = serde_yaml::from_str(
+            r#"
+            operational_points:
+              stop_areas: false
+            "#,
+        )
+        .unwrap();
+        let options = ConversionOptions {
+            profile,
+            ..Default::default()
+        };
+        let (rj, _) = parse_osm_with_options("src/tests/station.osm.pbf".into(), &options).unwrap();
+        assert!(rj.operational_points.is_empty());
+    }
+
+    #[test]
+    fn parse_signals_with_profile() {
+        let profile: Profile = serde_yaml::from_str(
+            r#"
+            signals:
+              - tags: [railway:signal:main, railway:signal:combined]
+                signaling_system: BAPR
+                sight_distance: 200
+            "#,
+        )
+        .unwrap();
+        let options = ConversionOptions {
+            profile,
+            ..Default::default()
+        };
+        let (rj, _) = parse_osm_with_options("src/tests/signals.osm.pbf".into(), &options).unwrap();
+        assert_eq!(1, rj.signals.len());
+        assert_eq!(200., rj.signals[0].sight_distance);
+        assert_eq!("BAPR", rj.signals[0].logical_signals[0].signaling_system);
+    }
 }
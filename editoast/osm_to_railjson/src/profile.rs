@@ -0,0 +1,313 @@
+//! Conversion profiles, describing which OpenStreetMap objects are converted and how
+//!
+//! Profiles are read from YAML files. Tags are written `key=value`, or `key` to match any value.
+//! Omitted sections keep their default value, which is the behavior without profile.
+//!
+//! ```yaml
+//! tracks:
+//!   require: [railway=rail, railway=narrow_gauge]
+//!   reject: [building, area=yes, construction]
+//! signals:
+//!   - tags: [railway:signal:main, railway:signal:combined]
+//!     signaling_system: BAL
+//!     settings:
+//!       Nf: "true"
+//!     default_parameters:
+//!       jaune_cli: "false"
+//! operational_points:
+//!   stop_areas: true
+//!   nodes:
+//!     - tag: railway=station
+//!       weight: 2
+//!     - tag: railway=halt
+//!       weight: 1
+//!   name_tag: name
+//!   uic_tag: uic_ref
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use editoast_schemas::primitives::NonBlankString;
+use serde::Deserialize;
+
+/// A tag to look for in OpenStreetMap objects
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct TagRule {
+    pub key: String,
+    /// `*` matches any value
+    pub value: String,
+}
+
+impl TagRule {
+    pub fn new(key: &str, value: &str) -> Self {
+        Self {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    pub fn matches(&self, tags: &osmpbfreader::Tags) -> bool {
+        tags.get(self.key.as_str())
+            .is_some_and(|value| self.value == "*" || value.as_str() == self.value)
+    }
+}
+
+impl TryFrom<String> for TagRule {
+    type Error = String;
+
+    fn try_from(tag: String) -> Result<Self, Self::Error> {
+        let (key, value) = tag.split_once('=').unwrap_or((&tag, "*"));
+        if key.trim().is_empty() || value.trim().is_empty() {
+            return Err(format!(
+                "invalid tag '{tag}', expected 'key=value' or 'key'"
+            ));
+        }
+        Ok(Self::new(key.trim(), value.trim()))
+    }
+}
+
+impl fmt::Display for TagRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct Profile {
+    pub tracks: TrackRules,
+    /// Nodes become signals using the first rule they match
+    pub signals: Vec<SignalRule>,
+    pub operational_points: OperationalPointRules,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            tracks: TrackRules::default(),
+            signals: vec![SignalRule::default()],
+            operational_points: OperationalPointRules::default(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let raw_profile = std::fs::read_to_string(path)?;
+        let profile: Profile = serde_yaml::from_str(&raw_profile)
+            .map_err(|err| format!("invalid profile '{}': {err}", path.display()))?;
+        Ok(profile)
+    }
+
+    /// Builds the reader of the ways converted into tracks
+    pub fn reader(&self) -> osm4routing::Reader {
+        let reader = self
+            .tracks
+            .require
+            .iter()
+            .fold(osm4routing::Reader::new(), |reader, tag| {
+                reader.require(&tag.key, &tag.value)
+            });
+        self.tracks
+            .reject
+            .iter()
+            .fold(reader, |reader, tag| reader.reject(&tag.key, &tag.value))
+    }
+
+    /// The rule to convert a node into a signal, if any
+    pub fn signal_rule(&self, tags: &osmpbfreader::Tags) -> Option<&SignalRule> {
+        self.signals
+            .iter()
+            .find(|rule| rule.tags.iter().any(|tag| tag.matches(tags)))
+    }
+}
+
+/// Selects the ways converted into tracks
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct TrackRules {
+    /// Ways must have one of these tags
+    pub require: Vec<TagRule>,
+    /// Ways with one of these tags are ignored
+    pub reject: Vec<TagRule>,
+}
+
+impl Default for TrackRules {
+    fn default() -> Self {
+        Self {
+            require: vec![TagRule::new("railway", "rail")],
+            reject: vec![
+                TagRule::new("service", "yard"),
+                TagRule::new("service", "siding"),
+                TagRule::new("service", "spur"),
+                TagRule::new("building", "*"),
+                TagRule::new("area", "yes"),
+                TagRule::new("gauge", "600"),
+                TagRule::new("roller_coaster", "*"),
+                TagRule::new("construction", "*"),
+            ],
+        }
+    }
+}
+
+/// Converts nodes with one of the given tags into signals
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignalRule {
+    pub tags: Vec<TagRule>,
+    /// The signaling system of the logical signal of the signals
+    pub signaling_system: String,
+    #[serde(default)]
+    pub settings: HashMap<NonBlankString, NonBlankString>,
+    #[serde(default)]
+    pub default_parameters: HashMap<NonBlankString, NonBlankString>,
+    #[serde(default = "default_sight_distance")]
+    pub sight_distance: f64,
+}
+
+fn default_sight_distance() -> f64 {
+    400.
+}
+
+impl Default for SignalRule {
+    fn default() -> Self {
+        Self {
+            tags: vec![
+                TagRule::new("railway:signal:main", "*"),
+                TagRule::new("railway:signal:combined", "*"),
+            ],
+            signaling_system: "BAL".to_owned(),
+            settings: HashMap::from([("Nf".into(), "true".into())]),
+            default_parameters: HashMap::from([("jaune_cli".into(), "false".into())]),
+            sight_distance: default_sight_distance(),
+        }
+    }
+}
+
+/// Selects the objects converted into operational points
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct OperationalPointRules {
+    /// Whether `public_transport=stop_area` relations are converted, using their `stop` members
+    pub stop_areas: bool,
+    /// Nodes located on a track with one of these tags are converted,
+    /// unless they are a stop of a converted stop area
+    pub nodes: Vec<NodeOperationalPointRule>,
+    /// The tag holding the name of the operational point
+    pub name_tag: String,
+    /// The tag holding the UIC code of the operational point
+    pub uic_tag: String,
+}
+
+impl Default for OperationalPointRules {
+    fn default() -> Self {
+        Self {
+            stop_areas: true,
+            nodes: vec![],
+            name_tag: "name".to_owned(),
+            uic_tag: "uic_ref".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeOperationalPointRule {
+    pub tag: TagRule,
+    /// The weight of the operational points, used to select the ones displayed first
+    pub weight: Option<u8>,
+}
+
+impl OperationalPointRules {
+    pub fn node_rule(&self, tags: &osmpbfreader::Tags) -> Option<&NodeOperationalPointRule> {
+        self.nodes.iter().find(|rule| rule.tag.matches(tags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[(&str, &str)]) -> osmpbfreader::Tags {
+        let mut osm_tags = osmpbfreader::Tags::new();
+        for (key, value) in tags {
+            osm_tags.insert((*key).into(), (*value).into());
+        }
+        osm_tags
+    }
+
+    #[test]
+    fn parse_tag_rules() {
+        assert_eq!(
+            TagRule::try_from("railway=rail".to_owned()),
+            Ok(TagRule::new("railway", "rail"))
+        );
+        assert_eq!(
+            TagRule::try_from("building".to_owned()),
+            Ok(TagRule::new("building", "*"))
+        );
+        assert!(TagRule::try_from("=yard".to_owned()).is_err());
+
+        let rule = TagRule::new("service", "*");
+        assert!(rule.matches(&tags(&[("service", "yard")])));
+        assert!(!rule.matches(&tags(&[("railway", "rail")])));
+    }
+
+    #[test]
+    fn default_profile() {
+        let profile: Profile = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(
+            profile.tracks.require,
+            vec![TagRule::new("railway", "rail")]
+        );
+        assert_eq!(profile.tracks.reject.len(), 8);
+        assert_eq!(profile.signals.len(), 1);
+        assert!(profile.operational_points.stop_areas);
+    }
+
+    #[test]
+    fn parse_profile() {
+        let profile: Profile = serde_yaml::from_str(
+            r#"
+            tracks:
+              require: [railway=rail, railway=narrow_gauge]
+              reject: [construction]
+            signals:
+              - tags: [railway:signal:main=ETCS]
+                signaling_system: ETCS_LEVEL2
+            operational_points:
+              stop_areas: false
+              nodes:
+                - tag: railway=halt
+                  weight: 1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(profile.tracks.require.len(), 2);
+        assert_eq!(
+            profile.tracks.reject,
+            vec![TagRule::new("construction", "*")]
+        );
+
+        let rule = profile
+            .signal_rule(&tags(&[("railway:signal:main", "ETCS")]))
+            .unwrap();
+        assert_eq!(rule.signaling_system, "ETCS_LEVEL2");
+        assert_eq!(rule.sight_distance, 400.);
+        assert!(profile
+            .signal_rule(&tags(&[("railway:signal:main", "FR:CARRE")]))
+            .is_none());
+
+        let operational_points = &profile.operational_points;
+        assert!(!operational_points.stop_areas);
+        assert_eq!(operational_points.name_tag, "name");
+        let rule = operational_points
+            .node_rule(&tags(&[("railway", "halt")]))
+            .unwrap();
+        assert_eq!(rule.weight, Some(1));
+    }
+}
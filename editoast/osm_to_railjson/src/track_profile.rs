@@ -0,0 +1,287 @@
+//! Derives the curves and slopes of tracks
+//!
+//! Curves are computed from the geometry of the ways. Slopes are read from the `incline` tag,
+//! or computed from an [elevation model](crate::elevation) when the tag is missing.
+
+use editoast_schemas::infra::Curve;
+use editoast_schemas::infra::Slope;
+use geo_types::Coord;
+use osm4routing::Distance;
+use osm4routing::Edge;
+use tracing::warn;
+
+use crate::elevation::ElevationModel;
+
+/// Larger radii are considered straight tracks
+const MAX_CURVE_RADIUS: f64 = 10_000.;
+
+/// Radii are rounded to this precision, so that consecutive similar curves are merged
+const CURVE_RADIUS_STEP: f64 = 10.;
+
+/// Elevations are sampled along the tracks every this distance, in meters.
+/// Shorter distances make gradients follow the noise of the elevation model.
+const ELEVATION_SAMPLING_DISTANCE: f64 = 200.;
+
+/// Gradients are rounded to this precision, in ‰, so that consecutive similar slopes are merged
+const GRADIENT_STEP: f64 = 0.5;
+
+/// Projects a coordinate in meters, in a plane tangent to the earth around `reference`
+fn local_projection(reference: Coord, coord: Coord) -> Coord {
+    let meters_per_degree = 6_378_100_f64.to_radians();
+    Coord {
+        x: (coord.x - reference.x) * meters_per_degree * reference.y.to_radians().cos(),
+        y: (coord.y - reference.y) * meters_per_degree,
+    }
+}
+
+/// Positions of the points of the geometry along the edge, in meters
+fn positions(geometry: &[Coord]) -> Vec<f64> {
+    let mut position = 0.;
+    let mut positions = vec![0.];
+    for segment in geometry.windows(2) {
+        position += segment[0].distance_to(segment[1]);
+        positions.push(position);
+    }
+    positions
+}
+
+/// Merges consecutive ranges with the same value
+fn merge_ranges(ranges: Vec<(f64, f64, f64)>) -> Vec<(f64, f64, f64)> {
+    let mut merged: Vec<(f64, f64, f64)> = vec![];
+    for (value, begin, end) in ranges {
+        match merged.last_mut() {
+            Some((last_value, _, last_end)) if *last_value == value && *last_end == begin => {
+                *last_end = end
+            }
+            _ => merged.push((value, begin, end)),
+        }
+    }
+    merged
+}
+
+/// Computes the curves of an edge from its geometry
+///
+/// At each point of the geometry, the track turns by some angle. The curve around it spans
+/// from the middle of the previous segment to the middle of the next one.
+/// Radii are positive when the track turns right, and negative when it turns left.
+pub fn curves(edge: &Edge) -> Vec<Curve> {
+    let geometry = &edge.geometry;
+    if geometry.len() < 3 {
+        return vec![];
+    }
+    let positions = positions(geometry);
+
+    let ranges = (1..geometry.len() - 1)
+        .filter_map(|i| {
+            let previous = local_projection(geometry[i], geometry[i - 1]);
+            let next = local_projection(geometry[i], geometry[i + 1]);
+            let angle = (-previous.y).atan2(-previous.x) - next.y.atan2(next.x);
+            // Normalize the angle to [-π, π], positive when turning right
+            let angle = angle.sin().atan2(angle.cos());
+
+            let begin = (positions[i - 1] + positions[i]) / 2.;
+            let end = (positions[i] + positions[i + 1]) / 2.;
+            if angle == 0. || end <= begin {
+                return None;
+            }
+            let radius = (end - begin) / angle;
+            if radius.abs() > MAX_CURVE_RADIUS {
+                return None;
+            }
+            let radius = radius.signum()
+                * (radius.abs() / CURVE_RADIUS_STEP).round().max(1.)
+                * CURVE_RADIUS_STEP;
+            Some((radius, begin, end))
+        })
+        .collect();
+
+    merge_ranges(ranges)
+        .into_iter()
+        .map(|(radius, begin, end)| Curve { radius, begin, end })
+        .collect()
+}
+
+/// Parses the `incline` tag, as a gradient in ‰ (positive when going uphill towards the end of the way)
+///
+/// Values are in % by default, and can be in ‰ or °.
+/// Values without a number, such as `up` or `down`, can't be used.
+fn parse_incline(incline: &str) -> Option<f64> {
+    let incline = incline.trim();
+    let (value, factor) = if let Some(value) = incline.strip_suffix('‰') {
+        (value, 1.)
+    } else if let Some(value) = incline.strip_suffix('%') {
+        (value, 10.)
+    } else if let Some(value) = incline.strip_suffix('°') {
+        return value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|degrees| degrees.to_radians().tan() * 1000.);
+    } else {
+        (incline, 10.)
+    };
+    value.trim().parse::<f64>().ok().map(|value| value * factor)
+}
+
+/// Computes the slopes of an edge, from its `incline` tag or from the elevation model
+pub fn slopes(edge: &Edge, elevation_model: Option<&ElevationModel>) -> Vec<Slope> {
+    let length = edge.length();
+    if length <= 0. {
+        return vec![];
+    }
+
+    if let Some(incline) = edge.tags.get("incline") {
+        match parse_incline(incline) {
+            Some(gradient) => {
+                return vec![Slope {
+                    gradient,
+                    begin: 0.,
+                    end: length,
+                }]
+            }
+            None => warn!("Invalid incline '{incline}' for way {}", edge.osm_id.0),
+        }
+    }
+
+    match elevation_model {
+        Some(elevation_model) => sampled_slopes(edge, length, elevation_model),
+        None => vec![],
+    }
+}
+
+/// Computes slopes from elevations sampled along the edge
+/// Parts of the edge outside the elevation model have no slope
+fn sampled_slopes(edge: &Edge, length: f64, elevation_model: &ElevationModel) -> Vec<Slope> {
+    let positions = positions(&edge.geometry);
+    let sample_count = (length / ELEVATION_SAMPLING_DISTANCE).ceil().max(1.) as usize;
+    let samples: Vec<(f64, Option<f64>)> = (0..=sample_count)
+        .map(|sample| {
+            let position = length * sample as f64 / sample_count as f64;
+            let location = location_at(&edge.geometry, &positions, position);
+            (position, elevation_model.elevation(location))
+        })
+        .collect();
+
+    let ranges = samples
+        .windows(2)
+        .filter_map(|window| match window {
+            [(begin, Some(begin_elevation)), (end, Some(end_elevation))] => {
+                let gradient = (end_elevation - begin_elevation) / (end - begin) * 1000.;
+                let gradient = (gradient / GRADIENT_STEP).round() * GRADIENT_STEP;
+                Some((gradient, *begin, *end))
+            }
+            _ => None,
+        })
+        .collect();
+
+    merge_ranges(ranges)
+        .into_iter()
+        .filter(|(gradient, _, _)| *gradient != 0.)
+        .map(|(gradient, begin, end)| Slope {
+            gradient,
+            begin,
+            end,
+        })
+        .collect()
+}
+
+/// Interpolates the location at some position along the geometry
+fn location_at(geometry: &[Coord], positions: &[f64], position: f64) -> Coord {
+    let segment = positions
+        .windows(2)
+        .position(|window| position <= window[1])
+        .unwrap_or(positions.len().saturating_sub(2));
+    let (begin, end) = (positions[segment], positions[segment + 1]);
+    if end <= begin {
+        return geometry[segment];
+    }
+    let ratio = ((position - begin) / (end - begin)).clamp(0., 1.);
+    geometry[segment] + (geometry[segment + 1] - geometry[segment]) * ratio
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    fn edge(coords: &[(f64, f64)]) -> Edge {
+        Edge {
+            id: "1".into(),
+            geometry: coords.iter().map(|&(x, y)| Coord { x, y }).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn straight_track_has_no_curve() {
+        let edge = edge(&[(2., 48.), (2.001, 48.), (2.002, 48.)]);
+        assert!(curves(&edge).is_empty());
+    }
+
+    #[test]
+    fn curve_radius() {
+        // Points on a circle of 1000m radius, every 10°, turning left
+        let center: Coord = Coord { x: 2., y: 48. };
+        let meters_per_degree = 6_378_100_f64.to_radians();
+        let coords: Vec<(f64, f64)> = (0..4)
+            .map(|i| {
+                let angle = (i as f64 * 10.).to_radians();
+                (
+                    center.x
+                        + 1000. * angle.cos() / (meters_per_degree * center.y.to_radians().cos()),
+                    center.y + 1000. * angle.sin() / meters_per_degree,
+                )
+            })
+            .collect();
+
+        let left_curves = curves(&edge(&coords));
+        assert_eq!(left_curves.len(), 1);
+        assert!((left_curves[0].radius + 1000.).abs() <= CURVE_RADIUS_STEP);
+        let length = edge(&coords).length();
+        assert!(left_curves[0].begin > 0.);
+        assert!(left_curves[0].end < length);
+
+        // The same track, the other way around, turns right
+        let reversed: Vec<(f64, f64)> = coords.into_iter().rev().collect();
+        let right_curves = curves(&edge(&reversed));
+        assert_eq!(right_curves.len(), 1);
+        assert_eq!(right_curves[0].radius, -left_curves[0].radius);
+    }
+
+    #[rstest]
+    #[case("5%", Some(50.))]
+    #[case("-2.5 %", Some(-25.))]
+    #[case("12‰", Some(12.))]
+    #[case("3", Some(30.))]
+    #[case("up", None)]
+    fn parse_inclines(#[case] incline: &str, #[case] expected: Option<f64>) {
+        assert_eq!(parse_incline(incline), expected);
+    }
+
+    #[test]
+    fn slope_from_incline() {
+        let mut edge = edge(&[(2., 48.), (2.01, 48.)]);
+        edge.tags = HashMap::from([("incline".into(), "-8‰".into())]);
+        let slopes = slopes(&edge, None);
+        assert_eq!(slopes.len(), 1);
+        assert_eq!(slopes[0].gradient, -8.);
+        assert_eq!(slopes[0].end, edge.length());
+    }
+
+    #[test]
+    fn location_along_geometry() {
+        let geometry = [Coord { x: 0., y: 0. }, Coord { x: 2., y: 0. }];
+        let positions = [0., 100.];
+        assert_eq!(
+            location_at(&geometry, &positions, 50.),
+            Coord { x: 1., y: 0. }
+        );
+        assert_eq!(
+            location_at(&geometry, &positions, 100.),
+            Coord { x: 2., y: 0. }
+        );
+    }
+}
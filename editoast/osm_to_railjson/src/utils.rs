@@ -5,6 +5,7 @@ use editoast_schemas::infra::Detector;
 use editoast_schemas::infra::Direction;
 use editoast_schemas::infra::Electrification;
 use editoast_schemas::infra::Endpoint;
+use editoast_schemas::infra::LoadingGaugeLimit;
 use editoast_schemas::infra::LogicalSignal;
 use editoast_schemas::infra::OperationalPoint;
 use editoast_schemas::infra::OperationalPointExtensions;
@@ -19,16 +20,21 @@ use editoast_schemas::infra::SpeedSection;
 use editoast_schemas::infra::Switch;
 use editoast_schemas::infra::TrackEndpoint;
 use editoast_schemas::primitives::Identifier;
+use editoast_schemas::rolling_stock::LoadingGaugeType;
 use geo_types::Coord;
 use osm4routing::Distance;
 use osm4routing::Edge;
 use osm4routing::NodeId;
 use osmpbfreader::Node;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::str::FromStr;
 use tracing::error;
 use tracing::warn;
 
+use crate::profile::OperationalPointRules;
+use crate::profile::Profile;
+
 // Given an edge and a coordinate, returns the coordinates used to compute the angle
 // It uses the nearest OpenStreetMap node, and the other as the the rails might do a loop
 // that would result in a bad angle
@@ -198,11 +204,6 @@ fn direction(node: &osmpbfreader::Node) -> Direction {
     }
 }
 
-fn main_signal(node: &osmpbfreader::OsmObj) -> bool {
-    node.tags().contains_key("railway:signal:main")
-        || node.tags().contains_key("railway:signal:combined")
-}
-
 /// When reading OpenStreetMap data, we sometimes need to match a Node to a Track and position
 /// This struct maps the nodes to the Edges (a Way from OpenStreetMap that might have been split)
 pub struct NodeToTrack<'a> {
@@ -240,44 +241,36 @@ pub fn signals(
     osm_pbf_in: &std::path::PathBuf,
     nodes_to_tracks: &NodeToTrack,
     adjacencies: &HashMap<osm4routing::NodeId, NodeAdjacencies>,
+    profile: &Profile,
 ) -> Vec<Signal> {
     let file = std::fs::File::open(osm_pbf_in).unwrap();
     let mut pbf = osmpbfreader::OsmPbfReader::new(file);
     pbf.iter()
         .flatten()
-        .filter(main_signal)
         .flat_map(|obj| match obj {
             osmpbfreader::OsmObj::Node(node) => Some(node),
             _ => None,
         })
         .filter(|node| adjacencies.get(&node.id).map_or(0, |adj| adj.edges.len()) != 1) // Ignore all the nodes that are at the end of a track, as it will be buffer stops
         .flat_map(|node| {
-            if let Some((track, position)) = nodes_to_tracks.track_and_position(node.id) {
-                let mut settings = HashMap::new();
-                settings.insert("Nf".into(), "true".into());
-
-                let mut default_parameters = HashMap::new();
-                default_parameters.insert("jaune_cli".into(), "false".into());
-
-                Some(Signal {
-                    id: node.id.0.to_string().into(),
-                    direction: direction(&node),
-                    track,
-                    position,
-                    sight_distance: 400.,
-                    logical_signals: vec![LogicalSignal {
-                        signaling_system: "BAL".to_string(),
-                        settings,
-                        default_parameters,
-                        ..Default::default()
-                    }],
-                    extensions: SignalExtensions {
-                        sncf: Some(sncf_extensions(&node)),
-                    },
-                })
-            } else {
-                None
-            }
+            let rule = profile.signal_rule(&node.tags)?;
+            let (track, position) = nodes_to_tracks.track_and_position(node.id)?;
+            Some(Signal {
+                id: node.id.0.to_string().into(),
+                direction: direction(&node),
+                track,
+                position,
+                sight_distance: rule.sight_distance,
+                logical_signals: vec![LogicalSignal {
+                    signaling_system: rule.signaling_system.clone(),
+                    settings: rule.settings.clone(),
+                    default_parameters: rule.default_parameters.clone(),
+                    ..Default::default()
+                }],
+                extensions: SignalExtensions {
+                    sncf: Some(sncf_extensions(&node)),
+                },
+            })
         })
         .collect()
 }
@@ -434,19 +427,127 @@ pub fn electrifications(edge: &Edge) -> Option<Electrification> {
     })
 }
 
+/// Parses a value of the `loading_gauge` tag, returning the loading gauges it includes
+fn loading_gauges(value: &str) -> Vec<LoadingGaugeType> {
+    use LoadingGaugeType::*;
+    let normalized = value
+        .trim()
+        .to_uppercase()
+        .trim_start_matches("UIC")
+        .trim_start_matches("FR")
+        .trim_start_matches([' ', '-', '_'])
+        .to_owned();
+    match normalized.as_str() {
+        "G1" => vec![G1],
+        "G2" => vec![G1, G2],
+        "GA" => vec![G1, GA],
+        "GB" => vec![G1, GA, GB],
+        "GB1" => vec![G1, GA, GB, GB1],
+        "GC" => vec![G1, GA, GB, GB1, GC],
+        "3.3" => vec![Fr3_3],
+        "GLOTT" => vec![Glott],
+        _ => {
+            warn!("Unknown loading gauge '{value}'");
+            vec![]
+        }
+    }
+}
+
+/// Whether a value of the `railway:track_class` tag is a UIC line category (A, B1, B2, C2… E5)
+fn is_track_class(value: &str) -> bool {
+    let mut chars = value.trim().chars();
+    let Some(class) = chars.next() else {
+        return false;
+    };
+    ('A'..='E').contains(&class.to_ascii_uppercase()) && chars.all(|c| c.is_ascii_digit())
+}
+
+/// Reads the loading gauges allowed on an edge from its `loading_gauge` tag
+/// A track allowing a loading gauge also allows the smaller ones it includes
+///
+/// The `railway:track_class` tag gives the UIC line category, which describes allowed axle
+/// loads rather than a loading gauge. Lines with such a category are open to international
+/// traffic, so they allow at least the G1 gauge: it is used when `loading_gauge` is missing.
+pub fn loading_gauge_limits(edge: &Edge) -> Vec<LoadingGaugeLimit> {
+    // These loading gauges are only defined for standard gauge tracks
+    let standard_gauge = edge
+        .tags
+        .get("gauge")
+        .is_none_or(|gauge| gauge.split(';').any(|gauge| gauge.trim() == "1435"));
+    if !standard_gauge {
+        return vec![];
+    }
+    let tag = match edge.tags.get("loading_gauge") {
+        Some(tag) => tag.as_str(),
+        None if edge
+            .tags
+            .get("railway:track_class")
+            .map(String::as_str)
+            .is_some_and(is_track_class) =>
+        {
+            "G1"
+        }
+        None => return vec![],
+    };
+
+    let mut categories: Vec<LoadingGaugeType> = vec![];
+    for category in tag.split(';').flat_map(loading_gauges) {
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    let combined = [
+        LoadingGaugeType::Fr3_3,
+        LoadingGaugeType::GB,
+        LoadingGaugeType::G2,
+    ];
+    if combined
+        .iter()
+        .all(|category| categories.contains(category))
+    {
+        categories.push(LoadingGaugeType::Fr3_3GbG2);
+    }
+
+    let length = edge.length();
+    categories
+        .into_iter()
+        .map(|category| LoadingGaugeLimit {
+            category,
+            begin: 0.,
+            end: length,
+        })
+        .collect()
+}
+
 pub fn operational_points(
     osm_pbf_in: &std::path::PathBuf,
     nodes_to_tracks: &NodeToTrack,
+    rules: &OperationalPointRules,
 ) -> Vec<OperationalPoint> {
     let file = std::fs::File::open(osm_pbf_in).unwrap();
     let mut pbf = osmpbfreader::OsmPbfReader::new(file);
-    pbf.iter()
-        .flatten()
-        .filter(|obj| obj.tags().contains("public_transport", "stop_area")) // https://wiki.openstreetmap.org/wiki/Tag:public_transport%3Dstop_area
-        .flat_map(|obj| match obj {
-            osmpbfreader::OsmObj::Relation(rel) => Some(rel), // Only consider OSM relations
-            _ => None,                                        // Discard Nodes and Ways
-        })
+    let mut stop_areas = vec![];
+    let mut nodes = vec![];
+    for obj in pbf.iter().flatten() {
+        match obj {
+            // https://wiki.openstreetmap.org/wiki/Tag:public_transport%3Dstop_area
+            osmpbfreader::OsmObj::Relation(rel)
+                if rules.stop_areas && rel.tags.contains("public_transport", "stop_area") =>
+            {
+                stop_areas.push(rel)
+            }
+            osmpbfreader::OsmObj::Node(node) => {
+                if let Some(rule) = rules.node_rule(&node.tags) {
+                    nodes.push((node, rule.weight))
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let mut stops = HashSet::new();
+    let mut operational_points: Vec<_> = stop_areas
+        .into_iter()
         .flat_map(|rel| {
             let parts: Vec<_> = rel
                 .refs
@@ -460,9 +561,9 @@ pub fn operational_points(
                     },
                 })
                 .flat_map(|node| {
-                    nodes_to_tracks
-                        .track_and_position(node)
-                        .map(|(track, position)| OperationalPointPart { track, position, extensions: Default::default() })
+                    let (track, position) = nodes_to_tracks.track_and_position(node)?;
+                    stops.insert(node);
+                    Some(OperationalPointPart { track, position, extensions: Default::default() })
                 })
                 .collect();
             // Parts can be empty when the stop_area references stops that are not railway (e.g. bus station)
@@ -473,19 +574,46 @@ pub fn operational_points(
                     id: rel.id.0.to_string().into(),
                     parts,
                     extensions: OperationalPointExtensions {
-                        identifier: identifier(&rel.tags),
+                        identifier: identifier(&rel.tags, rules),
                         sncf: None,
                     },
                     weight: None,
                 })
             }
         })
-        .collect()
+        .collect();
+
+    // Nodes already converted as a stop of a stop area are skipped
+    operational_points.extend(
+        nodes
+            .into_iter()
+            .filter(|(node, _)| !stops.contains(&node.id))
+            .flat_map(|(node, weight)| {
+                let (track, position) = nodes_to_tracks.track_and_position(node.id)?;
+                Some(OperationalPoint {
+                    id: format!("node-{}", node.id.0).into(),
+                    parts: vec![OperationalPointPart {
+                        track,
+                        position,
+                        extensions: Default::default(),
+                    }],
+                    extensions: OperationalPointExtensions {
+                        identifier: identifier(&node.tags, rules),
+                        sncf: None,
+                    },
+                    weight,
+                })
+            }),
+    );
+    operational_points
 }
 
-fn identifier(tags: &osmpbfreader::Tags) -> Option<OperationalPointIdentifierExtension> {
+fn identifier(
+    tags: &osmpbfreader::Tags,
+    rules: &OperationalPointRules,
+) -> Option<OperationalPointIdentifierExtension> {
     let uic = tags
-        .get("uic_ref")
+        .get(rules.uic_tag.as_str())
         .and_then(|uic| match i64::from_str(uic.as_str()) {
             Ok(uic) => Some(uic),
             Err(_) => {
@@ -495,7 +623,7 @@ fn identifier(tags: &osmpbfreader::Tags) -> Option<OperationalPointIdentifierExt
         })
         .unwrap_or_default();
 
-    tags.get("name")
+    tags.get(rules.name_tag.as_str())
         .map(|name| OperationalPointIdentifierExtension {
             name: name.as_str().into(),
             uic,
@@ -571,6 +699,50 @@ mod tests {
         assert_eq!(electrification.voltage, expected.into());
     }
 
+    #[rstest]
+    #[case("GC", None, 5)]
+    #[case("UIC GB", None, 3)]
+    #[case("FR3.3;GB;G2", Some("1435"), 6)]
+    #[case("GC", Some("1000"), 0)]
+    #[case("W6a", None, 0)]
+    fn test_loading_gauges(
+        #[case] loading_gauge: &str,
+        #[case] gauge: Option<&str>,
+        #[case] expected_count: usize,
+    ) {
+        let mut tags = HashMap::from([("loading_gauge".into(), loading_gauge.into())]);
+        if let Some(gauge) = gauge {
+            tags.insert("gauge".into(), gauge.into());
+        }
+        let edge = Edge {
+            id: "1".into(),
+            tags,
+            ..Default::default()
+        };
+
+        assert_eq!(loading_gauge_limits(&edge).len(), expected_count);
+    }
+
+    #[rstest]
+    #[case("D4", Some(vec![LoadingGaugeType::G1]))]
+    #[case("c2", Some(vec![LoadingGaugeType::G1]))]
+    #[case("heavy", None)]
+    fn test_track_class(
+        #[case] track_class: &str,
+        #[case] expected: Option<Vec<LoadingGaugeType>>,
+    ) {
+        let edge = Edge {
+            id: "1".into(),
+            tags: HashMap::from([("railway:track_class".into(), track_class.into())]),
+            ..Default::default()
+        };
+        let categories: Vec<_> = loading_gauge_limits(&edge)
+            .into_iter()
+            .map(|limit| limit.category)
+            .collect();
+        assert_eq!(categories, expected.unwrap_or_default());
+    }
+
     #[test]
     fn test_no_voltage() {
         let edge = Edge {
@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use chrono::Utc;
+use clap::Args;
+use clap::Subcommand;
+use editoast_models::DbConnectionPoolV2;
+
+use crate::models::audit_log::AuditLog;
+use crate::models::audit_log::AuditLogFilter;
+use crate::models::prelude::*;
+
+#[derive(Subcommand, Debug)]
+pub enum AuditCommands {
+    Export(ExportAuditArgs),
+}
+
+#[derive(Args, Debug)]
+#[command(
+    about,
+    long_about = "Export the audit log as JSON lines, oldest entry first"
+)]
+pub struct ExportAuditArgs {
+    /// The output file path
+    path: PathBuf,
+    /// Only export the changes made by this user
+    #[arg(long)]
+    user_id: Option<i64>,
+    /// Only export the changes of this type of resource (`subject` for users and groups)
+    #[arg(long)]
+    resource_type: Option<String>,
+    #[arg(long)]
+    resource_id: Option<i64>,
+    /// Only export the changes made at or after this time (RFC 3339)
+    #[arg(long)]
+    since: Option<DateTime<Utc>>,
+}
+
+pub async fn export_audit_log(
+    args: ExportAuditArgs,
+    db_pool: Arc<DbConnectionPoolV2>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let filter = AuditLogFilter {
+        user_id: args.user_id,
+        resource_type: args.resource_type,
+        resource_id: args.resource_id,
+        since: args.since,
+    };
+    let settings = filter.apply(SelectionSettings::new().order_by(|| AuditLog::ID.asc()));
+    let entries = AuditLog::list(&mut db_pool.get().await?, settings).await?;
+
+    let mut writer = BufWriter::new(File::create(&args.path)?);
+    for entry in &entries {
+        serde_json::to_writer(&mut writer, entry)?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+
+    println!(
+        "✅ {} audit log entries exported to {}",
+        entries.len(),
+        args.path.to_string_lossy()
+    );
+    Ok(())
+}
@@ -0,0 +1,1028 @@
+//! GTFS import and export of timetables
+//!
+//! A GTFS feed is read from and written to a directory holding its text files, which can then be
+//! zipped to be published. Only the files describing when trains stop where are handled.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Utc;
+use chrono::Weekday;
+use chrono_tz::Tz;
+use clap::Args;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use editoast_models::DbConnection;
+use editoast_models::DbConnectionPoolV2;
+use editoast_schemas::infra::OperationalPoint;
+use editoast_schemas::primitives::ObjectType;
+use editoast_schemas::primitives::PositiveDuration;
+use editoast_schemas::train_schedule::OperationalPointIdentifier;
+use editoast_schemas::train_schedule::OperationalPointReference;
+use editoast_schemas::train_schedule::PathItem;
+use editoast_schemas::train_schedule::PathItemLocation;
+use editoast_schemas::train_schedule::ScheduleItem;
+use editoast_schemas::train_schedule::TrainScheduleBase;
+use geos::geojson::Value as GeoJsonValue;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::runserver::CoreArgs;
+use super::ValkeyConfig;
+use crate::core::simulation::SimulationResponse;
+use crate::models::infra_objects::SchemaModel as _;
+use crate::models::prelude::*;
+use crate::models::timetable::Timetable;
+use crate::models::timetable::TimetableWithTrains;
+use crate::models::train_schedule::TrainSchedule;
+use crate::models::Infra;
+use crate::models::OperationalPointModel;
+use crate::views::train_schedule::train_simulation_batch;
+use crate::views::train_schedule::TrainScheduleForm;
+use crate::views::train_schedule::TrainScheduleResult;
+use crate::CliError;
+use crate::ValkeyClient;
+
+/// The GTFS route type of railway services
+const RAIL_ROUTE_TYPE: u8 = 2;
+/// The GTFS pickup and drop off type of stops where passengers can't get on or off the train
+const NO_PICKUP_OR_DROP_OFF: u8 = 1;
+
+#[derive(Args, Debug)]
+#[command(
+    about,
+    long_about = "Export the train schedules of a timetable as a GTFS feed, with their simulated passing times"
+)]
+pub struct ExportGtfsArgs {
+    /// The timetable to export
+    id: i64,
+    /// The directory in which the GTFS files are written
+    path: PathBuf,
+    /// The infra on which the trains are simulated
+    #[arg(long)]
+    infra: i64,
+    /// The timezone of the times of the feed, as an IANA name
+    #[arg(long, default_value = "Etc/UTC")]
+    timezone: Tz,
+    /// The name of the agency running the trains
+    #[arg(long, default_value = "OSRD")]
+    agency_name: String,
+    /// The website of the agency running the trains
+    #[arg(long, default_value = "https://osrd.fr")]
+    agency_url: String,
+    #[command(flatten)]
+    core: CoreArgs,
+}
+
+#[derive(Args, Debug)]
+#[command(
+    about,
+    long_about = "Import the trips of a day of a GTFS feed as train schedules, matching its stops with operational points by UIC code or name. The times of the feed are read in the timezone of its agencies."
+)]
+pub struct ImportGtfsArgs {
+    /// The directory holding the GTFS files
+    path: PathBuf,
+    /// The infra whose operational points are matched with the stops of the feed
+    #[arg(long)]
+    infra: i64,
+    /// The service day whose trips are imported
+    #[arg(long)]
+    date: NaiveDate,
+    /// The rolling stock of the imported trains
+    #[arg(long)]
+    rolling_stock_name: String,
+    /// The timetable to add the trains to, a new one is created if not given
+    #[arg(long)]
+    id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Agency {
+    #[serde(default)]
+    agency_id: String,
+    agency_name: String,
+    agency_url: String,
+    agency_timezone: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Stop {
+    stop_id: String,
+    #[serde(default)]
+    stop_code: Option<String>,
+    #[serde(default)]
+    stop_name: Option<String>,
+    #[serde(default)]
+    stop_lat: Option<f64>,
+    #[serde(default)]
+    stop_lon: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Route {
+    route_id: String,
+    agency_id: String,
+    route_short_name: String,
+    route_type: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Trip {
+    route_id: String,
+    service_id: String,
+    trip_id: String,
+    #[serde(default)]
+    trip_short_name: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct StopTime {
+    trip_id: String,
+    #[serde(default)]
+    arrival_time: Option<String>,
+    #[serde(default)]
+    departure_time: Option<String>,
+    stop_id: String,
+    stop_sequence: u32,
+    #[serde(default)]
+    pickup_type: Option<u8>,
+    #[serde(default)]
+    drop_off_type: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Calendar {
+    service_id: String,
+    monday: u8,
+    tuesday: u8,
+    wednesday: u8,
+    thursday: u8,
+    friday: u8,
+    saturday: u8,
+    sunday: u8,
+    start_date: String,
+    end_date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CalendarDate {
+    service_id: String,
+    date: String,
+    exception_type: u8,
+}
+
+/// Parses a GTFS time in seconds since the start of the service day, which exceeds 24:00:00
+/// for trips running past midnight
+fn parse_time(time: &str) -> Option<i64> {
+    let mut fields = time
+        .trim()
+        .split(':')
+        .map(|field| field.parse::<i64>().ok());
+    match (fields.next(), fields.next(), fields.next(), fields.next()) {
+        (Some(Some(hours)), Some(Some(minutes)), Some(Some(seconds)), None) => {
+            Some(hours * 3600 + minutes * 60 + seconds)
+        }
+        _ => None,
+    }
+}
+
+/// Formats seconds since the start of the service day as a GTFS time
+fn format_time(seconds: i64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y%m%d").ok()
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+/// The time the GTFS times of a service day are measured from: noon minus 12 hours in the
+/// timezone of the feed, which is midnight except on the days the clocks change
+fn service_day_start(date: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    let noon = date
+        .and_hms_opt(12, 0, 0)
+        .expect("noon is a valid time")
+        .and_local_timezone(timezone)
+        .earliest()
+        .expect("the clocks do not change at noon");
+    noon.with_timezone(&Utc) - Duration::hours(12)
+}
+
+fn read_records<T: DeserializeOwned>(
+    directory: &Path,
+    file_name: &str,
+) -> Result<Vec<T>, Box<dyn Error + Send + Sync>> {
+    let path = directory.join(file_name);
+    let mut reader = csv::Reader::from_path(&path)
+        .map_err(|e| CliError::new(1, format!("❌ Could not read GTFS file {path:?} ({e})")))?;
+    Ok(reader.deserialize().collect::<Result<_, _>>()?)
+}
+
+/// Like [read_records], but for the optional files of a feed
+fn read_optional_records<T: DeserializeOwned>(
+    directory: &Path,
+    file_name: &str,
+) -> Result<Option<Vec<T>>, Box<dyn Error + Send + Sync>> {
+    if !directory.join(file_name).exists() {
+        return Ok(None);
+    }
+    read_records(directory, file_name).map(Some)
+}
+
+fn write_records<T: Serialize>(
+    directory: &Path,
+    file_name: &str,
+    records: impl IntoIterator<Item = T>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut writer = csv::Writer::from_path(directory.join(file_name))?;
+    for record in records {
+        writer.serialize(record)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+async fn retrieve_infra(
+    conn: &mut DbConnection,
+    infra_id: i64,
+) -> Result<Infra, Box<dyn Error + Send + Sync>> {
+    Infra::retrieve(conn, infra_id).await?.ok_or_else(|| {
+        CliError::new(1, format!("❌ Infrastructure not found, id: {infra_id}")).into()
+    })
+}
+
+/// Finds the operational points referred to by path items, by id, UIC code or trigram
+struct OperationalPointIndex {
+    operational_points: Vec<OperationalPoint>,
+    by_id: HashMap<String, usize>,
+    by_uic: HashMap<i64, Vec<usize>>,
+    by_trigram: HashMap<String, Vec<usize>>,
+    by_name: HashMap<String, usize>,
+}
+
+impl OperationalPointIndex {
+    fn new(operational_points: Vec<OperationalPoint>) -> Self {
+        let mut index = Self {
+            by_id: HashMap::new(),
+            by_uic: HashMap::new(),
+            by_trigram: HashMap::new(),
+            by_name: HashMap::new(),
+            operational_points: Vec::new(),
+        };
+        for (position, operational_point) in operational_points.iter().enumerate() {
+            index.by_id.insert(operational_point.id.0.clone(), position);
+            if let Some(identifier) = &operational_point.extensions.identifier {
+                index
+                    .by_uic
+                    .entry(identifier.uic)
+                    .or_default()
+                    .push(position);
+                index
+                    .by_name
+                    .entry(identifier.name.0.to_lowercase())
+                    .or_insert(position);
+            }
+            if let Some(sncf) = &operational_point.extensions.sncf {
+                index
+                    .by_trigram
+                    .entry(sncf.trigram.clone())
+                    .or_default()
+                    .push(position);
+                index
+                    .by_name
+                    .entry(sncf.ch_long_label.0.to_lowercase())
+                    .or_insert(position);
+            }
+        }
+        index.operational_points = operational_points;
+        index
+    }
+
+    fn find(&self, location: &PathItemLocation) -> Option<&OperationalPoint> {
+        let PathItemLocation::OperationalPointReference(OperationalPointReference {
+            reference,
+            ..
+        }) = location
+        else {
+            return None;
+        };
+        let (candidates, secondary_code) = match reference {
+            OperationalPointIdentifier::OperationalPointId { operational_point } => {
+                return self
+                    .by_id
+                    .get(&operational_point.0)
+                    .map(|&position| &self.operational_points[position]);
+            }
+            OperationalPointIdentifier::OperationalPointUic {
+                uic,
+                secondary_code,
+            } => (self.by_uic.get(&(*uic as i64)), secondary_code),
+            OperationalPointIdentifier::OperationalPointDescription {
+                trigram,
+                secondary_code,
+            } => (self.by_trigram.get(&trigram.0), secondary_code),
+        };
+        candidates?
+            .iter()
+            .map(|&position| &self.operational_points[position])
+            .find(|operational_point| {
+                secondary_code.is_none()
+                    || operational_point
+                        .extensions
+                        .sncf
+                        .as_ref()
+                        .map(|sncf| &sncf.ch)
+                        == secondary_code.as_ref()
+            })
+    }
+
+    /// Matches a GTFS stop with an operational point, by the UIC code in its code or id,
+    /// then by name
+    fn match_stop(&self, stop: &Stop) -> Option<PathItemLocation> {
+        let uic = [stop.stop_code.as_deref(), Some(stop.stop_id.as_str())]
+            .into_iter()
+            .flatten()
+            .filter_map(|code| code.trim().parse::<u32>().ok())
+            .find(|uic| self.by_uic.contains_key(&(*uic as i64)));
+        let reference = if let Some(uic) = uic {
+            OperationalPointIdentifier::OperationalPointUic {
+                uic,
+                secondary_code: None,
+            }
+        } else {
+            let position = self
+                .by_name
+                .get(&stop.stop_name.as_ref()?.trim().to_lowercase())?;
+            OperationalPointIdentifier::OperationalPointId {
+                operational_point: self.operational_points[*position].id.clone(),
+            }
+        };
+        Some(PathItemLocation::OperationalPointReference(
+            OperationalPointReference {
+                reference,
+                track_reference: None,
+            },
+        ))
+    }
+}
+
+fn stop_from_operational_point(
+    operational_point: &OperationalPoint,
+    coordinates: Option<&(f64, f64)>,
+) -> Stop {
+    let identifier = operational_point.extensions.identifier.as_ref();
+    Stop {
+        stop_id: operational_point.id.0.clone(),
+        stop_code: identifier.map(|identifier| identifier.uic.to_string()),
+        stop_name: Some(
+            identifier
+                .map(|identifier| identifier.name.0.clone())
+                .unwrap_or_else(|| operational_point.id.0.clone()),
+        ),
+        stop_lat: coordinates.map(|(_, latitude)| *latitude),
+        stop_lon: coordinates.map(|(longitude, _)| *longitude),
+    }
+}
+
+/// Builds the stop times of a simulated train, at the operational points of its path
+///
+/// Returns the service day of the trip along with its stop times.
+fn trip_stop_times(
+    trip_id: &str,
+    train_schedule: &TrainScheduleBase,
+    operational_point_ids: &[Option<String>],
+    path_item_times: &[u64],
+    timezone: Tz,
+) -> (NaiveDate, Vec<StopTime>) {
+    let service_date = train_schedule
+        .start_time
+        .with_timezone(&timezone)
+        .date_naive();
+    let departure_seconds =
+        (train_schedule.start_time - service_day_start(service_date, timezone)).num_seconds();
+    let stop_durations: HashMap<&str, i64> = train_schedule
+        .schedule
+        .iter()
+        .filter_map(|schedule_item| {
+            let stop_for = schedule_item.stop_for.as_ref()?;
+            Some((schedule_item.at.0.as_str(), stop_for.num_seconds()))
+        })
+        .collect();
+    let last_index = train_schedule.path.len() - 1;
+    let stop_times = train_schedule
+        .path
+        .iter()
+        .zip(operational_point_ids)
+        .zip(path_item_times)
+        .enumerate()
+        .filter_map(|(index, ((path_item, operational_point_id), time))| {
+            let operational_point_id = operational_point_id.as_ref()?;
+            let arrival = departure_seconds + (*time as i64 + 500) / 1000;
+            // The train leaves its origin at its start time, whatever its stop duration there
+            let stop_duration = stop_durations
+                .get(path_item.id.0.as_str())
+                .copied()
+                .filter(|_| index != 0);
+            let pickup_type = (stop_duration.is_none() && index != 0 && index != last_index)
+                .then_some(NO_PICKUP_OR_DROP_OFF);
+            Some(StopTime {
+                trip_id: trip_id.to_owned(),
+                arrival_time: Some(format_time(arrival)),
+                departure_time: Some(format_time(arrival + stop_duration.unwrap_or(0))),
+                stop_id: operational_point_id.clone(),
+                stop_sequence: index as u32,
+                pickup_type,
+                drop_off_type: pickup_type,
+            })
+        })
+        .collect();
+    (service_date, stop_times)
+}
+
+pub async fn gtfs_export(
+    args: ExportGtfsArgs,
+    db_pool: Arc<DbConnectionPoolV2>,
+    valkey_config: ValkeyConfig,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let conn = &mut db_pool.get().await?;
+    let timetable = TimetableWithTrains::retrieve(conn, args.id)
+        .await?
+        .ok_or_else(|| CliError::new(1, format!("❌ Timetable not found, id: {}", args.id)))?;
+    let infra = retrieve_infra(conn, args.infra).await?;
+    let (train_schedules, _): (Vec<_>, _) =
+        TrainSchedule::retrieve_batch(conn, timetable.train_ids).await?;
+
+    let valkey = ValkeyClient::new(valkey_config.into())?;
+    let core_client = args.core.core_client().await?;
+    let simulations = train_simulation_batch(
+        conn,
+        valkey.into(),
+        core_client.into(),
+        &train_schedules,
+        &infra,
+        None,
+    )
+    .await?;
+
+    let operational_points: Vec<OperationalPointModel> =
+        OperationalPointModel::find_all(conn, infra.id).await?;
+    let index = OperationalPointIndex::new(
+        operational_points
+            .into_iter()
+            .map(|operational_point| operational_point.schema)
+            .collect(),
+    );
+
+    let mut stop_ids = BTreeSet::new();
+    let mut service_dates = BTreeSet::new();
+    let mut trips = Vec::new();
+    let mut stop_times = Vec::new();
+    let mut failed_simulations = 0;
+    for (train_schedule, (simulation, _)) in train_schedules.iter().zip(simulations) {
+        let SimulationResponse::Success { final_output, .. } = simulation else {
+            failed_simulations += 1;
+            continue;
+        };
+        let operational_point_ids = train_schedule
+            .path
+            .iter()
+            .map(|path_item| {
+                index
+                    .find(&path_item.location)
+                    .map(|operational_point| operational_point.id.0.clone())
+            })
+            .collect::<Vec<_>>();
+        let trip_id = train_schedule.id.to_string();
+        let train_schedule = TrainScheduleResult::from(train_schedule.clone()).train_schedule;
+        let (service_date, trip_times) = trip_stop_times(
+            &trip_id,
+            &train_schedule,
+            &operational_point_ids,
+            &final_output.report_train.path_item_times,
+            args.timezone,
+        );
+        stop_ids.extend(trip_times.iter().map(|stop| stop.stop_id.clone()));
+        service_dates.insert(service_date);
+        trips.push(Trip {
+            route_id: timetable.id.to_string(),
+            service_id: format_date(service_date),
+            trip_id,
+            trip_short_name: Some(train_schedule.train_name),
+        });
+        stop_times.extend(trip_times);
+    }
+
+    let stop_ids = stop_ids.into_iter().collect::<Vec<_>>();
+    let coordinates: HashMap<String, (f64, f64)> = infra
+        .get_objects(conn, ObjectType::OperationalPoint, &stop_ids)
+        .await?
+        .into_iter()
+        .filter_map(|object| {
+            let coordinates = match object.geographic?.0.value {
+                GeoJsonValue::Point(point) => point,
+                GeoJsonValue::MultiPoint(points) => points.into_iter().next()?,
+                _ => return None,
+            };
+            Some((object.obj_id, (coordinates[0], coordinates[1])))
+        })
+        .collect();
+    let stops = stop_ids.iter().filter_map(|stop_id| {
+        let operational_point = &index.operational_points[*index.by_id.get(stop_id)?];
+        Some(stop_from_operational_point(
+            operational_point,
+            coordinates.get(stop_id),
+        ))
+    });
+
+    std::fs::create_dir_all(&args.path)?;
+    write_records(
+        &args.path,
+        "agency.txt",
+        [Agency {
+            agency_id: args.agency_name.clone(),
+            agency_name: args.agency_name.clone(),
+            agency_url: args.agency_url,
+            agency_timezone: args.timezone.name().to_owned(),
+        }],
+    )?;
+    write_records(
+        &args.path,
+        "routes.txt",
+        [Route {
+            route_id: timetable.id.to_string(),
+            agency_id: args.agency_name,
+            route_short_name: format!("Timetable {}", timetable.id),
+            route_type: RAIL_ROUTE_TYPE,
+        }],
+    )?;
+    write_records(
+        &args.path,
+        "calendar_dates.txt",
+        service_dates.into_iter().map(|date| CalendarDate {
+            service_id: format_date(date),
+            date: format_date(date),
+            exception_type: 1,
+        }),
+    )?;
+    write_records(&args.path, "stops.txt", stops)?;
+    write_records(&args.path, "trips.txt", &trips)?;
+    write_records(&args.path, "stop_times.txt", stop_times)?;
+
+    println!(
+        "✅ {} trips exported to {}",
+        trips.len(),
+        args.path.to_string_lossy()
+    );
+    if failed_simulations > 0 {
+        println!("⚠️ {failed_simulations} train schedules could not be simulated and were skipped");
+    }
+    Ok(())
+}
+
+/// Returns the services running on a day, or `None` if the feed has no calendar
+fn running_services(
+    calendars: Option<Vec<Calendar>>,
+    calendar_dates: Option<Vec<CalendarDate>>,
+    date: NaiveDate,
+) -> Option<HashSet<String>> {
+    if calendars.is_none() && calendar_dates.is_none() {
+        return None;
+    }
+    let mut services: HashSet<String> = calendars
+        .into_iter()
+        .flatten()
+        .filter(|calendar| {
+            let runs_on_weekday = match date.weekday() {
+                Weekday::Mon => calendar.monday,
+                Weekday::Tue => calendar.tuesday,
+                Weekday::Wed => calendar.wednesday,
+                Weekday::Thu => calendar.thursday,
+                Weekday::Fri => calendar.friday,
+                Weekday::Sat => calendar.saturday,
+                Weekday::Sun => calendar.sunday,
+            } == 1;
+            let in_range = parse_date(&calendar.start_date).is_some_and(|start| start <= date)
+                && parse_date(&calendar.end_date).is_some_and(|end| date <= end);
+            runs_on_weekday && in_range
+        })
+        .map(|calendar| calendar.service_id)
+        .collect();
+    for calendar_date in calendar_dates.into_iter().flatten() {
+        if parse_date(&calendar_date.date) != Some(date) {
+            continue;
+        }
+        // 1 adds the service for the day, 2 removes it
+        match calendar_date.exception_type {
+            1 => services.insert(calendar_date.service_id),
+            2 => services.remove(&calendar_date.service_id),
+            _ => false,
+        };
+    }
+    Some(services)
+}
+
+/// Builds the train schedule of a GTFS trip, whose stop times are sorted and matched with
+/// operational points
+fn trip_train_schedule(
+    trip: &Trip,
+    stop_times: &[(&StopTime, PathItemLocation)],
+    service_day_start: DateTime<Utc>,
+    rolling_stock_name: &str,
+) -> Option<TrainScheduleBase> {
+    let times = stop_times
+        .iter()
+        .map(|(stop_time, _)| {
+            let arrival = stop_time.arrival_time.as_deref().and_then(parse_time);
+            let departure = stop_time.departure_time.as_deref().and_then(parse_time);
+            (arrival.or(departure), departure.or(arrival))
+        })
+        .collect::<Vec<_>>();
+    let (_, first_departure) = times.first()?;
+    let first_departure = (*first_departure)?;
+    let duration = |seconds: i64| PositiveDuration::try_from(Duration::seconds(seconds)).ok();
+
+    let path = stop_times
+        .iter()
+        .map(|(stop_time, location)| PathItem {
+            id: stop_time.stop_sequence.to_string().into(),
+            deleted: false,
+            location: location.clone(),
+        })
+        .collect::<Vec<_>>();
+    let last_index = stop_times.len() - 1;
+    let schedule = stop_times
+        .iter()
+        .zip(&times)
+        .enumerate()
+        .skip(1)
+        .map(|(index, ((stop_time, _), (arrival, departure)))| {
+            let passengers_stop = stop_time.pickup_type != Some(NO_PICKUP_OR_DROP_OFF)
+                || stop_time.drop_off_type != Some(NO_PICKUP_OR_DROP_OFF);
+            let stop_for = match (arrival, departure) {
+                _ if index == last_index => duration(0),
+                (Some(arrival), Some(departure)) if passengers_stop => {
+                    duration(departure - arrival)
+                }
+                _ => None,
+            };
+            ScheduleItem {
+                at: stop_time.stop_sequence.to_string().into(),
+                arrival: arrival.and_then(|arrival| duration(arrival - first_departure)),
+                stop_for,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Some(TrainScheduleBase {
+        train_name: trip
+            .trip_short_name
+            .clone()
+            .unwrap_or_else(|| trip.trip_id.clone()),
+        rolling_stock_name: rolling_stock_name.to_owned(),
+        start_time: service_day_start + Duration::seconds(first_departure),
+        path,
+        schedule,
+        ..Default::default()
+    })
+}
+
+/// Returns the timezone of the times of a feed, which all its agencies share
+fn feed_timezone(agencies: &[Agency]) -> Result<Tz, Box<dyn Error + Send + Sync>> {
+    let timezone = agencies
+        .first()
+        .map(|agency| agency.agency_timezone.trim())
+        .ok_or_else(|| CliError::new(1, "❌ The GTFS feed has no agency"))?;
+    timezone.parse().map_err(|e| {
+        CliError::new(1, format!("❌ Unknown agency timezone {timezone:?} ({e})")).into()
+    })
+}
+
+pub async fn gtfs_import(
+    args: ImportGtfsArgs,
+    db_pool: Arc<DbConnectionPoolV2>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let agencies: Vec<Agency> = read_records(&args.path, "agency.txt")?;
+    let timezone = feed_timezone(&agencies)?;
+    let stops: Vec<Stop> = read_records(&args.path, "stops.txt")?;
+    let trips: Vec<Trip> = read_records(&args.path, "trips.txt")?;
+    let all_stop_times: Vec<StopTime> = read_records(&args.path, "stop_times.txt")?;
+    let services = running_services(
+        read_optional_records(&args.path, "calendar.txt")?,
+        read_optional_records(&args.path, "calendar_dates.txt")?,
+        args.date,
+    );
+
+    let conn = &mut db_pool.get().await?;
+    let infra = retrieve_infra(conn, args.infra).await?;
+    let operational_points: Vec<OperationalPointModel> =
+        OperationalPointModel::find_all(conn, infra.id).await?;
+    let index = OperationalPointIndex::new(
+        operational_points
+            .into_iter()
+            .map(|operational_point| operational_point.schema)
+            .collect(),
+    );
+    let stop_locations: HashMap<&str, Option<PathItemLocation>> = stops
+        .iter()
+        .map(|stop| (stop.stop_id.as_str(), index.match_stop(stop)))
+        .collect();
+    let mut stop_times_by_trip: HashMap<&str, Vec<&StopTime>> = HashMap::new();
+    for stop_time in &all_stop_times {
+        stop_times_by_trip
+            .entry(stop_time.trip_id.as_str())
+            .or_default()
+            .push(stop_time);
+    }
+
+    let service_day_start = service_day_start(args.date, timezone);
+    let mut train_schedules = Vec::new();
+    let mut unmatched_stops = BTreeSet::new();
+    let mut skipped_trips = 0;
+    for trip in &trips {
+        if services
+            .as_ref()
+            .is_some_and(|services| !services.contains(&trip.service_id))
+        {
+            continue;
+        }
+        let mut stop_times = stop_times_by_trip
+            .get(trip.trip_id.as_str())
+            .cloned()
+            .unwrap_or_default();
+        stop_times.sort_by_key(|stop_time| stop_time.stop_sequence);
+        let mut located_stop_times = Vec::with_capacity(stop_times.len());
+        for stop_time in stop_times {
+            match stop_locations.get(stop_time.stop_id.as_str()) {
+                Some(Some(location)) => located_stop_times.push((stop_time, location.clone())),
+                _ => {
+                    unmatched_stops.insert(stop_time.stop_id.as_str());
+                }
+            }
+        }
+        let train_schedule = trip_train_schedule(
+            trip,
+            &located_stop_times,
+            service_day_start,
+            &args.rolling_stock_name,
+        );
+        match train_schedule {
+            Some(train_schedule) if train_schedule.path.len() >= 2 => {
+                train_schedules.push(train_schedule)
+            }
+            _ => skipped_trips += 1,
+        }
+    }
+
+    // A new timetable is only kept if its trains are inserted
+    let (timetable_id, inserted_count) = conn
+        .transaction::<_, Box<dyn Error + Send + Sync>, _>(|conn| {
+            async move {
+                let conn = &mut conn.clone();
+                let timetable = match args.id {
+                    Some(timetable_id) => Timetable::retrieve(conn, timetable_id)
+                        .await?
+                        .ok_or_else(|| {
+                            CliError::new(1, format!("❌ Timetable not found, id: {timetable_id}"))
+                        })?,
+                    None => Timetable::create(conn).await?,
+                };
+                let changesets: Vec<Changeset<TrainSchedule>> = train_schedules
+                    .into_iter()
+                    .map(|train_schedule| {
+                        TrainScheduleForm {
+                            timetable_id: Some(timetable.id),
+                            train_schedule,
+                        }
+                        .into()
+                    })
+                    .collect();
+                let inserted: Vec<_> = TrainSchedule::create_batch(conn, changesets).await?;
+                Ok((timetable.id, inserted.len()))
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+    println!("✅ {inserted_count} train schedules created for timetable with id {timetable_id}");
+    if !unmatched_stops.is_empty() {
+        println!(
+            "⚠️ {} stops could not be matched with an operational point and were left out: {}",
+            unmatched_stops.len(),
+            unmatched_stops.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+    if skipped_trips > 0 {
+        println!("⚠️ {skipped_trips} trips have less than two matched stops and were skipped");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::models::fixtures::create_small_infra;
+    use crate::models::fixtures::simple_train_schedule_base;
+
+    #[rstest]
+    #[case::morning("08:05:30", Some(29130))]
+    #[case::past_midnight("25:10:00", Some(90600))]
+    #[case::single_digit_hour("7:00:00", Some(25200))]
+    #[case::missing_seconds("08:05", None)]
+    fn gtfs_times_are_parsed(#[case] time: &str, #[case] expected: Option<i64>) {
+        assert_eq!(parse_time(time), expected);
+    }
+
+    #[rstest]
+    fn gtfs_times_past_midnight_are_formatted() {
+        assert_eq!(format_time(90600), "25:10:00");
+    }
+
+    #[rstest]
+    #[case::winter("2024-12-30", "2024-12-29T23:00:00Z")]
+    #[case::clocks_going_forward("2025-03-30", "2025-03-29T22:00:00Z")]
+    fn gtfs_times_are_measured_from_noon_minus_12_hours(
+        #[case] date: NaiveDate,
+        #[case] expected: DateTime<Utc>,
+    ) {
+        assert_eq!(service_day_start(date, chrono_tz::Europe::Paris), expected);
+    }
+
+    #[rstest]
+    fn services_follow_calendar_exceptions() {
+        let calendar = Calendar {
+            service_id: "weekdays".to_owned(),
+            monday: 1,
+            tuesday: 1,
+            wednesday: 1,
+            thursday: 1,
+            friday: 1,
+            saturday: 0,
+            sunday: 0,
+            start_date: "20241201".to_owned(),
+            end_date: "20241231".to_owned(),
+        };
+        let exceptions = vec![
+            CalendarDate {
+                service_id: "weekdays".to_owned(),
+                date: "20241225".to_owned(),
+                exception_type: 2,
+            },
+            CalendarDate {
+                service_id: "christmas".to_owned(),
+                date: "20241225".to_owned(),
+                exception_type: 1,
+            },
+        ];
+
+        let services = running_services(
+            Some(vec![calendar]),
+            Some(exceptions),
+            NaiveDate::from_ymd_opt(2024, 12, 25).unwrap(),
+        );
+
+        assert_eq!(services, Some(HashSet::from(["christmas".to_owned()])));
+    }
+
+    #[rstest]
+    fn stop_times_follow_the_simulation() {
+        let train_schedule = TrainScheduleBase {
+            start_time: "2024-12-30T23:50:00Z".parse().unwrap(),
+            ..simple_train_schedule_base()
+        };
+        let operational_point_ids = train_schedule
+            .path
+            .iter()
+            .map(|path_item| Some(path_item.id.0.clone()))
+            .collect::<Vec<_>>();
+        let path_item_times = [0, 600_000, 1_200_000, 1_800_000];
+
+        let (service_date, stop_times) = trip_stop_times(
+            "42",
+            &train_schedule,
+            &operational_point_ids,
+            &path_item_times,
+            chrono_tz::Europe::Paris,
+        );
+
+        assert_eq!(service_date, NaiveDate::from_ymd_opt(2024, 12, 31).unwrap());
+        let times = stop_times
+            .iter()
+            .map(|stop_time| {
+                (
+                    stop_time.arrival_time.as_deref().unwrap(),
+                    stop_time.departure_time.as_deref().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            vec![
+                ("00:50:00", "00:50:00"),
+                ("01:00:00", "01:05:00"),
+                ("01:10:00", "01:15:00"),
+                ("01:20:00", "01:20:00"),
+            ]
+        );
+    }
+
+    #[rstest]
+    async fn gtfs_trips_are_imported_on_matched_stops() {
+        let db_pool = DbConnectionPoolV2::for_tests();
+        let infra = create_small_infra(&mut db_pool.get_ok()).await;
+        let timetable = Timetable::create(&mut db_pool.get_ok()).await.unwrap();
+        let feed = tempfile::tempdir().unwrap();
+        fs::write(
+            feed.path().join("agency.txt"),
+            "agency_id,agency_name,agency_url,agency_timezone\n\
+             osrd,OSRD,https://osrd.fr,Europe/Paris\n",
+        )
+        .unwrap();
+        fs::write(
+            feed.path().join("stops.txt"),
+            "stop_id,stop_code,stop_name\n\
+             west,2,West\n\
+             mid_west,,Mid_West_station\n\
+             nowhere,,Nowhere\n",
+        )
+        .unwrap();
+        fs::write(
+            feed.path().join("trips.txt"),
+            "route_id,service_id,trip_id,trip_short_name\n\
+             line,daily,trip_1,Train 1\n\
+             line,sundays,trip_2,Train 2\n",
+        )
+        .unwrap();
+        fs::write(
+            feed.path().join("stop_times.txt"),
+            "trip_id,arrival_time,departure_time,stop_id,stop_sequence\n\
+             trip_1,08:00:00,08:00:00,west,1\n\
+             trip_1,08:10:00,08:12:00,nowhere,2\n\
+             trip_1,08:20:00,08:20:00,mid_west,3\n\
+             trip_2,09:00:00,09:00:00,west,1\n\
+             trip_2,09:20:00,09:20:00,mid_west,2\n",
+        )
+        .unwrap();
+        fs::write(
+            feed.path().join("calendar_dates.txt"),
+            "service_id,date,exception_type\n\
+             daily,20241230,1\n",
+        )
+        .unwrap();
+
+        let args = ImportGtfsArgs {
+            path: feed.path().into(),
+            infra: infra.id,
+            date: NaiveDate::from_ymd_opt(2024, 12, 30).unwrap(),
+            rolling_stock_name: "fast_rolling_stock".to_owned(),
+            id: Some(timetable.id),
+        };
+        let result = gtfs_import(args, db_pool.clone().into()).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let timetable = TimetableWithTrains::retrieve(&mut db_pool.get_ok(), timetable.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(timetable.train_ids.len(), 1);
+        let train_schedule = TrainSchedule::retrieve(&mut db_pool.get_ok(), timetable.train_ids[0])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(train_schedule.train_name, "Train 1");
+        assert_eq!(
+            train_schedule.start_time,
+            "2024-12-30T07:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(train_schedule.path.len(), 2);
+        assert_eq!(
+            train_schedule.schedule[0]
+                .arrival
+                .as_ref()
+                .map(|arrival| arrival.num_minutes()),
+            Some(20)
+        );
+    }
+}
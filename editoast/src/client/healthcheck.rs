@@ -3,11 +3,7 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use editoast_models::DbConnectionPoolV2;
 
-use crate::{
-    core::{mq_client, CoreClient},
-    views::check_health,
-    ValkeyClient,
-};
+use crate::{views::check_health, ValkeyClient};
 
 use super::{runserver::CoreArgs, ValkeyConfig};
 
@@ -17,14 +13,7 @@ pub async fn healthcheck_cmd(
     core_config: CoreArgs,
 ) -> anyhow::Result<()> {
     let valkey = ValkeyClient::new(valkey_config.into()).unwrap();
-    let core_client = CoreClient::new_mq(mq_client::Options {
-        uri: core_config.mq_url,
-        worker_pool_identifier: String::from("core"),
-        timeout: core_config.core_timeout,
-        single_worker: core_config.core_single_worker,
-        num_channels: core_config.core_client_channels_size,
-    })
-    .await?;
+    let core_client = core_config.core_client().await?;
     check_health(db_pool, valkey.into(), core_client.into())
         .await
         .map_err(|e| anyhow!("❌ healthcheck failed: {e}"))?;
@@ -1,12 +1,17 @@
-use std::{error::Error, fs::File, io::BufReader, path::PathBuf, sync::Arc};
+use std::{error::Error, fs::File, io::BufReader, path::PathBuf, str::FromStr as _, sync::Arc};
 
 use clap::{Args, Subcommand};
 use colored::Colorize as _;
 use editoast_models::{DbConnection, DbConnectionPoolV2};
-use editoast_schemas::infra::RailJson;
+use editoast_schemas::infra::migrate_railjson;
+use editoast_schemas::primitives::ObjectType;
 
+use crate::generated_data::infra_error::InfraErrorTypeLabel;
+use crate::infra_cache::diff::diff_operations;
 use crate::map::MapLayers;
 use crate::models::prelude::*;
+use crate::views::infra::auto_fixes::fix_and_apply;
+use crate::views::infra::auto_fixes::AutoFixesFilter;
 use crate::{infra_cache::InfraCache, models::Infra, views::infra::InfraApiError, CliError};
 use crate::{map, ValkeyClient};
 
@@ -18,6 +23,8 @@ pub enum InfraCommands {
     Clear(ClearArgs),
     Generate(GenerateArgs),
     ImportRailjson(ImportRailjsonArgs),
+    Diff(DiffArgs),
+    AutoFix(AutoFixArgs),
 }
 
 #[derive(Args, Debug, Clone)]
@@ -58,6 +65,39 @@ pub struct ImportRailjsonArgs {
     generate: bool,
 }
 
+#[derive(Args, Debug, Clone)]
+#[command(
+    about,
+    long_about = "Export the edition operations turning an infrastructure into another one"
+)]
+pub struct DiffArgs {
+    /// Infrastructure ID
+    infra_id: u64,
+    /// ID of the infrastructure to compare with
+    other_infra_id: u64,
+    /// The output file path
+    path: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+#[command(
+    about,
+    long_about = "Apply the automatic fixes of an infrastructure errors"
+)]
+pub struct AutoFixArgs {
+    /// Infrastructure ID
+    infra_id: u64,
+    /// Only fix errors of these types (comma-separated, e.g. `invalid_reference,empty_object`)
+    #[arg(long, value_delimiter = ',', value_parser = InfraErrorTypeLabel::from_str)]
+    error_types: Vec<InfraErrorTypeLabel>,
+    /// Only fix errors on objects of these types (comma-separated, e.g. `Signal,Detector`)
+    #[arg(long, value_delimiter = ',', value_parser = ObjectType::from_str)]
+    object_types: Vec<ObjectType>,
+    /// Only print the fixes, without applying them
+    #[arg(long)]
+    dry_run: bool,
+}
+
 pub async fn clone_infra(
     infra_args: InfraCloneArgs,
     db_pool: Arc<DbConnectionPoolV2>,
@@ -84,6 +124,87 @@ pub async fn clone_infra(
     Ok(())
 }
 
+pub async fn diff_infra(
+    args: DiffArgs,
+    db_pool: Arc<DbConnectionPoolV2>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let infra = retrieve_infra(&mut db_pool.get().await?, args.infra_id).await?;
+    let other_infra = retrieve_infra(&mut db_pool.get().await?, args.other_infra_id).await?;
+
+    let infra_cache = InfraCache::load(&mut db_pool.get().await?, &infra).await?;
+    let other_infra_cache = InfraCache::load(&mut db_pool.get().await?, &other_infra).await?;
+    let cache_diff = infra_cache.diff(&other_infra_cache);
+    let operations =
+        diff_operations(&mut db_pool.get().await?, &infra, &other_infra, cache_diff).await?;
+
+    let file = File::create(args.path.clone())?;
+    serde_json::to_writer_pretty(file, &operations)?;
+    println!(
+        "✅ {} operations from infra {}[{}] to infra {}[{}] exported to {}",
+        operations.len(),
+        infra.name.bold(),
+        infra.id,
+        other_infra.name.bold(),
+        other_infra.id,
+        args.path.to_string_lossy()
+    );
+    Ok(())
+}
+
+pub async fn auto_fix_infra(
+    args: AutoFixArgs,
+    db_pool: Arc<DbConnectionPoolV2>,
+    valkey_config: ValkeyConfig,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut infra = retrieve_infra(&mut db_pool.get().await?, args.infra_id).await?;
+    let mut infra_cache = InfraCache::load(&mut db_pool.get().await?, &infra).await?;
+    let filter = AutoFixesFilter {
+        error_types: args.error_types,
+        object_types: args.object_types,
+    };
+
+    println!(
+        "🍞 Fixing infra {}[{}]",
+        infra.name.clone().bold(),
+        infra.id
+    );
+    let iterations = fix_and_apply(
+        &mut db_pool.get().await?,
+        &mut infra,
+        &mut infra_cache,
+        &filter,
+        args.dry_run,
+    )
+    .await?;
+    for (index, iteration) in iterations.iter().enumerate() {
+        println!(
+            "🔧 Iteration {}: {} operations, {} errors before, {} errors after",
+            index + 1,
+            iteration.operations.len(),
+            iteration.errors_before.len(),
+            iteration.errors_after.len()
+        );
+    }
+
+    let operation_count: usize = iterations
+        .iter()
+        .map(|iteration| iteration.operations.len())
+        .sum();
+    if args.dry_run {
+        println!("✅ {operation_count} operations would be applied (dry run)");
+    } else {
+        if operation_count > 0 {
+            build_valkey_pool_and_invalidate_all_cache(valkey_config, infra.id).await?;
+        }
+        println!(
+            "✅ Infra {}[{}] fixed with {operation_count} operations",
+            infra.name.bold(),
+            infra.id
+        );
+    }
+    Ok(())
+}
+
 pub async fn import_railjson(
     args: ImportRailjsonArgs,
     db_pool: Arc<DbConnectionPoolV2>,
@@ -107,7 +228,14 @@ pub async fn import_railjson(
     let infra = Infra::changeset()
         .name(args.infra_name)
         .last_railjson_version();
-    let railjson: RailJson = serde_json::from_reader(BufReader::new(railjson_file))?;
+    let railjson: serde_json::Value = serde_json::from_reader(BufReader::new(railjson_file))?;
+    let (railjson, migration) = migrate_railjson(railjson)?;
+    for step in &migration.steps {
+        println!(
+            "⬆️  Railjson migrated from {} to {}: {} ({} objects modified)",
+            step.from, step.to, step.description, step.modified_objects
+        );
+    }
 
     println!("🍞 Importing infra {infra_name}");
     let mut infra = infra.persist(railjson, &mut db_pool.get().await?).await?;
@@ -226,6 +354,15 @@ async fn build_valkey_pool_and_invalidate_all_cache(
     })?)
 }
 
+async fn retrieve_infra(
+    conn: &mut DbConnection,
+    id: u64,
+) -> Result<Infra, Box<dyn Error + Send + Sync>> {
+    Infra::retrieve(conn, id as i64)
+        .await?
+        .ok_or_else(|| CliError::new(1, format!("❌ Infrastructure not found, ID: {id}")).into())
+}
+
 async fn batch_retrieve_infras(
     conn: &mut DbConnection,
     ids: &[u64],
@@ -253,6 +390,7 @@ mod tests {
     use rand::{distributions::Alphanumeric, thread_rng, Rng as _};
 
     use crate::client::generate_temp_file;
+    use editoast_schemas::infra::RailJson;
 
     use super::*;
 
@@ -305,4 +443,25 @@ mod tests {
         // THEN
         assert!(result.is_ok());
     }
+
+    #[rstest::rstest]
+    async fn import_railjson_ko_unsupported_version() {
+        // GIVEN
+        let railjson = RailJson {
+            version: "1.0.0".to_owned(),
+            ..Default::default()
+        };
+        let file = generate_temp_file::<RailJson>(&railjson);
+        let args: ImportRailjsonArgs = ImportRailjsonArgs {
+            infra_name: "test".into(),
+            railjson_path: file.path().into(),
+            generate: false,
+        };
+
+        // WHEN
+        let result = import_railjson(args, DbConnectionPoolV2::for_tests().into()).await;
+
+        // THEN
+        assert!(result.is_err());
+    }
 }
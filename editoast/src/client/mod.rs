@@ -1,5 +1,7 @@
+pub mod audit_commands;
 pub mod electrical_profiles_commands;
 pub mod group;
+pub mod gtfs_commands;
 pub mod healthcheck;
 pub mod import_rolling_stock;
 pub mod infra_commands;
@@ -7,6 +9,7 @@ mod postgres_config;
 pub mod roles;
 pub mod runserver;
 pub mod search_commands;
+pub mod stdcm_commands;
 pub mod stdcm_search_env_commands;
 mod telemetry_config;
 pub mod timetables_commands;
@@ -16,6 +19,7 @@ mod valkey_config;
 use std::env;
 use std::path::PathBuf;
 
+use audit_commands::AuditCommands;
 use clap::Args;
 use clap::Parser;
 use clap::Subcommand;
@@ -30,6 +34,7 @@ use roles::RolesCommand;
 use runserver::CoreArgs;
 use runserver::RunserverArgs;
 use search_commands::SearchCommands;
+use stdcm_commands::StdcmCommands;
 use stdcm_search_env_commands::StdcmSearchEnvCommands;
 pub use telemetry_config::TelemetryConfig;
 pub use telemetry_config::TelemetryKind;
@@ -53,6 +58,10 @@ pub struct Client {
     pub telemetry_config: TelemetryConfig,
     #[arg(long, env, value_enum, default_value_t = Color::Auto)]
     pub color: Color,
+    /// YAML file of custom validation rules, checked along with the built-in infra errors.
+    /// It can also override error severities and make blocking errors prevent train simulations.
+    #[arg(long, env = "EDITOAST_VALIDATION_RULES")]
+    pub validation_rules: Option<PathBuf>,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -92,12 +101,16 @@ pub enum Commands {
         long_about = "STDCM search environment management commands"
     )]
     STDCMSearchEnv(StdcmSearchEnvCommands),
+    #[command(subcommand, about, long_about = "STDCM related commands")]
+    Stdcm(StdcmCommands),
     #[command(subcommand, about, long_about = "Roles related commands")]
     Roles(RolesCommand),
     #[command(subcommand, about, long_about = "Group related commands")]
     Group(GroupCommand),
     #[command(subcommand, about, long_about = "User related commands")]
     User(UserCommand),
+    #[command(subcommand, about, long_about = "Audit log related commands")]
+    Audit(AuditCommands),
     #[command(about, long_about = "Healthcheck")]
     Healthcheck(CoreArgs),
 }
@@ -109,6 +122,22 @@ pub struct OsmToRailjsonArgs {
     pub osm_pbf_in: PathBuf,
     /// Output file in Railjson format
     pub railjson_out: PathBuf,
+    /// Only convert the network inside this bounding box, given as `min_lon,min_lat,max_lon,max_lat`
+    #[arg(long, conflicts_with = "polygon")]
+    pub bbox: Option<osm_to_railjson::CropArea>,
+    /// Only convert the network inside the polygons of this GeoJSON file
+    #[arg(long)]
+    pub polygon: Option<PathBuf>,
+    /// Output file listing the tracks cut at the boundary of the bounding box or polygon
+    #[arg(long)]
+    pub crop_report: Option<PathBuf>,
+    /// Compute the slopes of the tracks without `incline` tag from this elevation model,
+    /// given as a GeoTIFF file in WGS84 coordinates
+    #[arg(long)]
+    pub elevation_model: Option<PathBuf>,
+    /// YAML profile selecting the converted tracks, signals and operational points
+    #[arg(long)]
+    pub profile: Option<PathBuf>,
 }
 
 /// Prints the OpenApi to stdout
@@ -2,6 +2,9 @@ use chrono::Duration;
 use clap::Args;
 use url::Url;
 
+use crate::core::mq_client;
+use crate::core::CoreClient;
+use crate::error::Result;
 use crate::views;
 
 use super::{PostgresConfig, ValkeyConfig};
@@ -23,6 +26,54 @@ pub struct CoreArgs {
     pub(super) core_single_worker: bool,
     #[clap(long, env = "CORE_CLIENT_CHANNELS_SIZE", default_value_t = 8)]
     pub(super) core_client_channels_size: usize,
+    /// Number of times idempotent core requests are sent again after a transient failure
+    #[clap(long, env = "EDITOAST_CORE_MAX_RETRIES", default_value_t = 3)]
+    pub(super) core_max_retries: u32,
+    /// Delay before the first retry of a core request in milliseconds, doubled for each following one
+    #[clap(long, env = "EDITOAST_CORE_RETRY_BACKOFF_MS", default_value_t = 200)]
+    pub(super) core_retry_backoff_ms: u64,
+    /// Number of consecutive core failures after which requests fail fast, 0 disabling the circuit breaker
+    #[clap(
+        long,
+        env = "EDITOAST_CORE_CIRCUIT_BREAKER_THRESHOLD",
+        default_value_t = 5
+    )]
+    pub(super) core_circuit_breaker_threshold: u32,
+    /// How long core requests fail fast once the circuit breaker opened, in seconds
+    #[clap(
+        long,
+        env = "EDITOAST_CORE_CIRCUIT_BREAKER_COOLDOWN",
+        default_value_t = 30
+    )]
+    pub(super) core_circuit_breaker_cooldown: u64,
+    /// If set, calls the core worker listening at this URL over HTTP instead of going through RabbitMQ
+    ///
+    /// Meant for local development, with a core started in `api` mode.
+    #[clap(long, env = "EDITOAST_CORE_URL")]
+    pub(super) core_url: Option<Url>,
+}
+
+impl CoreArgs {
+    /// Builds a client for the core configured by these arguments
+    pub(super) async fn core_client(self) -> Result<CoreClient> {
+        if let Some(core_url) = self.core_url {
+            return CoreClient::new_http(core_url, self.core_timeout);
+        }
+        CoreClient::new_mq(mq_client::Options {
+            uri: self.mq_url,
+            worker_pool_identifier: String::from("core"),
+            timeout: self.core_timeout,
+            single_worker: self.core_single_worker,
+            num_channels: self.core_client_channels_size,
+            max_retries: self.core_max_retries,
+            retry_backoff: std::time::Duration::from_millis(self.core_retry_backoff_ms),
+            circuit_breaker_threshold: self.core_circuit_breaker_threshold,
+            circuit_breaker_cooldown: std::time::Duration::from_secs(
+                self.core_circuit_breaker_cooldown,
+            ),
+        })
+        .await
+    }
 }
 
 #[derive(Args, Debug)]
@@ -62,6 +113,11 @@ pub async fn runserver(
                 core_timeout,
                 core_single_worker,
                 core_client_channels_size,
+                core_max_retries,
+                core_retry_backoff_ms,
+                core_circuit_breaker_threshold,
+                core_circuit_breaker_cooldown,
+                core_url,
             },
         disable_authorization,
         osrdyne_api_url,
@@ -84,6 +140,11 @@ pub async fn runserver(
                 timeout: Duration::seconds(core_timeout as i64),
                 single_worker: core_single_worker,
                 num_channels: core_client_channels_size,
+                max_retries: core_max_retries,
+                retry_backoff: Duration::milliseconds(core_retry_backoff_ms as i64),
+                circuit_breaker_threshold: core_circuit_breaker_threshold,
+                circuit_breaker_cooldown: Duration::seconds(core_circuit_breaker_cooldown as i64),
+                url: core_url,
             },
         },
         valkey_config: valkey.into(),
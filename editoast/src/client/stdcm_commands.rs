@@ -0,0 +1,176 @@
+use std::error::Error;
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::Args;
+use clap::Subcommand;
+use colored::Colorize as _;
+use editoast_models::DbConnection;
+use editoast_models::DbConnectionPoolV2;
+use json_patch::Patch;
+use json_patch::PatchOperation;
+
+use super::runserver::CoreArgs;
+use crate::core::stdcm::Response;
+use crate::core::AsCoreRequest as _;
+use crate::core::CoreClient;
+use crate::models::prelude::*;
+use crate::models::stdcm_log::StdcmLog;
+use crate::models::stdcm_log::StdcmLogOutcome;
+use crate::CliError;
+
+#[derive(Subcommand, Debug)]
+pub enum StdcmCommands {
+    Replay(ReplayStdcmArgs),
+}
+
+#[derive(Args, Debug)]
+#[command(
+    about,
+    long_about = "Send a logged STDCM request to core again and compare its response with the logged one"
+)]
+pub struct ReplayStdcmArgs {
+    /// The id of the STDCM log to replay
+    log_id: i64,
+    /// Write the differences from the logged response to the new one to this file, as a JSON patch
+    #[arg(long)]
+    diff_path: Option<PathBuf>,
+    #[command(flatten)]
+    core: CoreArgs,
+}
+
+/// How many differences are printed, the others being only counted
+const PRINTED_DIFFERENCES: usize = 20;
+
+pub async fn replay_stdcm_log(
+    args: ReplayStdcmArgs,
+    db_pool: Arc<DbConnectionPoolV2>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let core_client = args.core.core_client().await?;
+    let Replay {
+        log,
+        response,
+        diff,
+    } = replay(&mut db_pool.get().await?, &core_client, args.log_id).await?;
+
+    println!(
+        "🔁 STDCM log {} (trace {}, {}) replayed on infra {}",
+        log.id,
+        log.trace_id.bold(),
+        log.created,
+        log.request.infra
+    );
+    println!(
+        "Logged outcome: {}, new outcome: {}",
+        StdcmLogOutcome::from(&log.response).as_ref(),
+        StdcmLogOutcome::from(&response).as_ref().bold()
+    );
+    if let Some(diff_path) = &args.diff_path {
+        serde_json::to_writer_pretty(File::create(diff_path)?, &diff)?;
+    }
+    if diff.0.is_empty() {
+        println!("✅ The response did not change");
+        return Ok(());
+    }
+
+    println!("❌ {} differences in the response:", diff.0.len());
+    for operation in diff.0.iter().take(PRINTED_DIFFERENCES) {
+        let change = match operation {
+            PatchOperation::Add(_) => "+".green(),
+            PatchOperation::Remove(_) => "-".red(),
+            _ => "~".yellow(),
+        };
+        println!("  {change} {}", operation.path());
+    }
+    if diff.0.len() > PRINTED_DIFFERENCES {
+        println!("  … and {} more", diff.0.len() - PRINTED_DIFFERENCES);
+    }
+    Ok(())
+}
+
+/// A logged STDCM request sent to core again
+struct Replay {
+    log: StdcmLog,
+    response: Response,
+    /// The changes from the logged response to the new one
+    diff: Patch,
+}
+
+async fn replay(
+    conn: &mut DbConnection,
+    core_client: &CoreClient,
+    log_id: i64,
+) -> Result<Replay, Box<dyn Error + Send + Sync>> {
+    let log = StdcmLog::retrieve(conn, log_id)
+        .await?
+        .ok_or_else(|| CliError::new(1, format!("❌ STDCM log not found, ID: {log_id}")))?;
+    let response = log.request.fetch(core_client).await?;
+    let diff = json_patch::diff(
+        &serde_json::to_value(&log.response)?,
+        &serde_json::to_value(&response)?,
+    );
+    Ok(Replay {
+        log,
+        response,
+        diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::core::mocking::MockingClient;
+    use crate::core::simulation::SimulationResponse;
+    use crate::models::fixtures::create_stdcm_core_request;
+
+    #[rstest]
+    async fn replayed_response_is_compared_with_the_logged_one() {
+        let db_pool = DbConnectionPoolV2::for_tests();
+        let log = StdcmLog::changeset()
+            .trace_id("trace".to_owned())
+            .request(create_stdcm_core_request(1))
+            .response(Response::PathNotFound)
+            .create(&mut db_pool.get_ok())
+            .await
+            .expect("Failed to create STDCM log");
+        let mut core = MockingClient::new();
+        core.stub("/v2/stdcm")
+            .method(reqwest::Method::POST)
+            .response(StatusCode::OK)
+            .json(Response::PreprocessingSimulationError {
+                error: SimulationResponse::default(),
+            })
+            .finish();
+
+        let replay = replay(&mut db_pool.get_ok(), &core.into(), log.id)
+            .await
+            .expect("Failed to replay STDCM log");
+
+        assert_eq!(
+            StdcmLogOutcome::from(&replay.response),
+            StdcmLogOutcome::PreprocessingSimulationError
+        );
+        let paths = replay
+            .diff
+            .0
+            .iter()
+            .map(|operation| operation.path().to_string())
+            .collect::<Vec<_>>();
+        assert!(paths.contains(&"/status".to_owned()), "{paths:?}");
+        assert!(paths.contains(&"/error".to_owned()), "{paths:?}");
+    }
+
+    #[rstest]
+    async fn missing_log_cannot_be_replayed() {
+        let db_pool = DbConnectionPoolV2::for_tests();
+
+        let result = replay(&mut db_pool.get_ok(), &MockingClient::new().into(), -1).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -15,6 +15,10 @@ pub struct TelemetryConfig {
     #[derivative(Default(value = r#"Url::parse("http://localhost:4317").unwrap()"#))]
     #[arg(long, env, default_value = "http://localhost:4317")]
     pub telemetry_endpoint: Url,
+    /// Also export metrics (like core request retries) to the telemetry endpoint
+    #[derivative(Default(value = "false"))]
+    #[clap(long, env, default_value_t = false)]
+    pub telemetry_metrics: bool,
 }
 
 #[derive(Default, ValueEnum, Debug, Derivative, Clone, strum::Display)]
@@ -5,6 +5,8 @@ use clap::{Args, Subcommand};
 use editoast_models::DbConnectionPoolV2;
 use editoast_schemas::train_schedule::TrainScheduleBase;
 
+use super::gtfs_commands::ExportGtfsArgs;
+use super::gtfs_commands::ImportGtfsArgs;
 use crate::models::prelude::*;
 use crate::models::train_schedule::TrainSchedule;
 use crate::views::train_schedule::TrainScheduleForm;
@@ -18,6 +20,8 @@ use crate::{
 pub enum TimetablesCommands {
     Import(ImportTimetableArgs),
     Export(ExportTimetableArgs),
+    ImportGtfs(ImportGtfsArgs),
+    ExportGtfs(ExportGtfsArgs),
 }
 
 #[derive(Args, Debug)]
@@ -0,0 +1,157 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Stops sending requests to core once it failed to answer several times in a row
+///
+/// After `cooldown`, a single request is let through to probe core: its success closes
+/// the breaker again, while other requests keep failing fast for another `cooldown`.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent
+    Closed,
+    /// Requests fail fast
+    Open,
+    /// A single request is sent to check whether core answers again
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// The value reported by the breaker state metric
+    pub fn as_metric(&self) -> u64 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::Open => 1,
+            CircuitState::HalfOpen => 2,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// A `failure_threshold` of 0 disables the breaker
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let state = self.state.lock().unwrap();
+        match state.opened_at {
+            None => CircuitState::Closed,
+            Some(_) if state.probing => CircuitState::HalfOpen,
+            Some(opened_at) if opened_at.elapsed() >= self.cooldown => CircuitState::HalfOpen,
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    /// Returns whether a request may be sent, or how long to wait before the next attempt
+    pub fn acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+        let elapsed = opened_at.elapsed();
+        if elapsed < self.cooldown {
+            return Err(self.cooldown - elapsed);
+        }
+        // Restarting the cooldown lets another request probe core if this one never completes
+        state.opened_at = Some(Instant::now());
+        state.probing = true;
+        Ok(())
+    }
+
+    pub fn record_success(&self) {
+        *self.state.lock().unwrap() = BreakerState::default();
+    }
+
+    /// Returns whether this failure opened the breaker
+    pub fn record_failure(&self) -> bool {
+        if self.failure_threshold == 0 {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        if state.probing {
+            state.probing = false;
+            state.opened_at = Some(Instant::now());
+            return false;
+        }
+        if state.opened_at.is_none() && state.consecutive_failures >= self.failure_threshold {
+            state.opened_at = Some(Instant::now());
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        assert!(!breaker.record_failure());
+        breaker.record_success();
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.acquire().is_ok());
+
+        assert!(breaker.record_failure());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.acquire().is_err());
+    }
+
+    #[test]
+    fn probes_core_after_the_cooldown() {
+        let cooldown = Duration::from_millis(20);
+        let breaker = CircuitBreaker::new(1, cooldown);
+        assert!(breaker.record_failure());
+        std::thread::sleep(cooldown);
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        // Only one request probes core at a time
+        assert!(breaker.acquire().is_ok());
+        assert!(breaker.acquire().is_err());
+
+        // A failed probe opens the breaker again
+        assert!(!breaker.record_failure());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        std::thread::sleep(cooldown);
+        assert!(breaker.acquire().is_ok());
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.acquire().is_ok());
+        assert!(breaker.acquire().is_ok());
+    }
+
+    #[test]
+    fn disabled_breaker_never_opens() {
+        let breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            assert!(!breaker.record_failure());
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
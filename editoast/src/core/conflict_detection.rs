@@ -15,6 +15,7 @@ editoast_common::schemas! {
     ConflictDetectionResponse,
     Conflict,
     ConflictRequirement,
+    TrainRequirements,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,7 +30,7 @@ pub struct ConflictDetectionRequest {
     pub work_schedules: Option<WorkSchedulesRequest>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TrainRequirements {
     pub start_time: DateTime<Utc>,
     pub spacing_requirements: Vec<SpacingRequirement>,
@@ -113,6 +114,7 @@ pub enum ConflictType {
 impl AsCoreRequest<Json<ConflictDetectionResponse>> for ConflictDetectionRequest {
     const METHOD: reqwest::Method = reqwest::Method::POST;
     const URL_PATH: &'static str = "/v2/conflict_detection";
+    const IDEMPOTENT: bool = true;
 
     fn infra_id(&self) -> Option<i64> {
         Some(self.infra)
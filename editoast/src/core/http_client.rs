@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use url::Url;
+
+use super::CoreError;
+
+/// Calls a single core worker through its HTTP API, without going through RabbitMQ and osrdyne
+///
+/// The worker loads infrastructures on demand, so requests need no routing.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    base_url: Url,
+    /// The default response timeout in seconds
+    timeout: u64,
+}
+
+#[derive(Debug)]
+pub struct HttpResponse {
+    pub payload: Vec<u8>,
+    pub success: bool,
+}
+
+impl HttpClient {
+    pub fn new(mut base_url: Url, timeout: u64) -> Result<Self, CoreError> {
+        // Without a trailing slash, joining a path would replace the last segment of the base URL
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout))
+            .build()?;
+        Ok(Self {
+            client,
+            base_url,
+            timeout,
+        })
+    }
+
+    fn url(&self, path: &str) -> Result<Url, CoreError> {
+        self.base_url
+            .join(path.trim_start_matches('/'))
+            .map_err(|err| CoreError::GenericCoreError {
+                status: None,
+                url: path.to_owned(),
+                raw_error: err.to_string(),
+            })
+    }
+
+    pub async fn ping(&self) -> Result<bool, CoreError> {
+        let response = self.client.get(self.url("/health")?).send().await?;
+        Ok(response.status().is_success())
+    }
+
+    pub async fn call<B: Serialize>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&B>,
+        timeout: Option<u64>,
+    ) -> Result<HttpResponse, CoreError> {
+        let mut request = self.client.request(method, self.url(path)?);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        if let Some(timeout) = timeout.filter(|timeout| *timeout > self.timeout) {
+            request = request.timeout(Duration::from_secs(timeout));
+        }
+        let response = request.send().await?;
+        let success = response.status().is_success();
+        let payload = response.bytes().await?.to_vec();
+        Ok(HttpResponse { payload, success })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn paths_are_joined_to_the_base_url() {
+        let client = HttpClient::new(Url::parse("http://localhost:8000").unwrap(), 10).unwrap();
+        assert_eq!(
+            client.url("/v2/stdcm").unwrap().as_str(),
+            "http://localhost:8000/v2/stdcm"
+        );
+
+        let client =
+            HttpClient::new(Url::parse("http://localhost:8000/core").unwrap(), 10).unwrap();
+        assert_eq!(
+            client.url("/v2/pathfinding/blocks").unwrap().as_str(),
+            "http://localhost:8000/core/v2/pathfinding/blocks"
+        );
+        assert_eq!(
+            client.url("version").unwrap().as_str(),
+            "http://localhost:8000/core/version"
+        );
+    }
+}
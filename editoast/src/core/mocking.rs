@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use axum::http::StatusCode;
 use reqwest::Body;
@@ -13,6 +14,27 @@ use super::CoreResponse;
 #[derive(Debug, Default)]
 pub struct MockingClient {
     stubs: Vec<StubRequest>,
+    received_requests: ReceivedRequests,
+}
+
+/// The requests received by a mocking client and its clones, in the order they were received
+///
+/// See [MockingClient::received_requests]
+#[derive(Debug, Default, Clone)]
+pub struct ReceivedRequests(Arc<Mutex<Vec<(String, Option<String>)>>>);
+
+impl ReceivedRequests {
+    /// Returns the JSON bodies of the requests received at a path
+    pub fn bodies<P: AsRef<str>>(&self, path: P) -> Vec<serde_json::Value> {
+        self.0
+            .lock()
+            .expect("received requests lock should not be poisoned")
+            .iter()
+            .filter(|(req_path, _)| req_path == path.as_ref())
+            .filter_map(|(_, body)| body.as_ref())
+            .map(|body| serde_json::from_str(body).expect("request body should be valid JSON"))
+            .collect()
+    }
 }
 
 impl From<MockingClient> for CoreClient {
@@ -39,6 +61,12 @@ impl MockingClient {
         StubRequestBuilder::new(path.as_ref().into(), self)
     }
 
+    /// Returns a handle on the requests received by the client, which stays valid after the
+    /// client is moved into a [CoreClient]
+    pub fn received_requests(&self) -> ReceivedRequests {
+        self.received_requests.clone()
+    }
+
     pub(super) fn fetch_mocked<P: AsRef<str>, B: Serialize, R: CoreResponse>(
         &self,
         method: reqwest::Method,
@@ -65,8 +93,15 @@ impl MockingClient {
         let Some(stub) = stub else {
             panic!("could not find stub for {method} resquest at PATH {req_path}")
         };
+        let body =
+            body.map(|b| serde_json::to_string(b).expect("could not serialize request body"));
+        self.received_requests
+            .0
+            .lock()
+            .expect("received requests lock should not be poisoned")
+            .push((req_path.clone(), body.clone()));
         match (
-            body.map(|b| serde_json::to_string(b).expect("could not serialize request body")),
+            body,
             stub.body.as_ref().map(|b| {
                 String::from_utf8(
                     b.as_bytes()
@@ -112,7 +147,10 @@ impl MockingClient {
 
 impl Clone for MockingClient {
     fn clone(&self) -> Self {
-        let mut cli = Self::default();
+        let mut cli = Self {
+            received_requests: self.received_requests.clone(),
+            ..Default::default()
+        };
         cli.stubs.clone_from_slice(self.stubs.as_slice());
         cli
     }
@@ -1,4 +1,6 @@
+pub mod circuit_breaker;
 pub mod conflict_detection;
+pub mod http_client;
 pub mod infra_loading;
 #[cfg(test)]
 pub mod mocking;
@@ -24,12 +26,14 @@ use serde_json::Value;
 use thiserror::Error;
 use tracing::error;
 use tracing::trace;
+use url::Url;
 
 #[cfg(test)]
 use crate::core::mocking::MockingError;
 use crate::error::InternalError;
 use crate::error::Result;
 
+pub use http_client::HttpClient;
 pub use mq_client::RabbitMQClient;
 
 editoast_common::schemas! {
@@ -42,6 +46,7 @@ editoast_common::schemas! {
 #[derive(Debug, Clone)]
 pub enum CoreClient {
     MessageQueue(RabbitMQClient),
+    Http(HttpClient),
     #[cfg(test)]
     Mocked(mocking::MockingClient),
 }
@@ -53,6 +58,13 @@ impl CoreClient {
         Ok(Self::MessageQueue(client))
     }
 
+    /// Builds a client calling a single core worker at `base_url`, with a timeout in seconds
+    pub fn new_http(base_url: Url, timeout: u64) -> Result<Self> {
+        let client = HttpClient::new(base_url, timeout)?;
+
+        Ok(Self::Http(client))
+    }
+
     fn handle_error(&self, bytes: &[u8], url: String) -> InternalError {
         // We try to deserialize the response as an StandardCoreError in order to retain the context of the core error
         if let Ok(mut core_error) = <Json<StandardCoreError>>::from_bytes(bytes) {
@@ -77,6 +89,7 @@ impl CoreClient {
             CoreClient::MessageQueue(mq_client) => {
                 mq_client.ping().await.map_err(|_| CoreError::BrokenPipe)
             }
+            CoreClient::Http(http_client) => http_client.ping().await,
             #[cfg(test)]
             CoreClient::Mocked(_) => Ok(true),
         }
@@ -94,6 +107,8 @@ impl CoreClient {
         path: &str,
         body: Option<&B>,
         infra_id: Option<i64>,
+        timeout: Option<u64>,
+        idempotent: bool,
     ) -> Result<R::Response> {
         trace!(
             target: "editoast::coreclient",
@@ -101,13 +116,15 @@ impl CoreClient {
             "Request content");
         match self {
             CoreClient::MessageQueue(client) => {
-                // TODO: maybe implement retry?
-                let infra_id = infra_id.unwrap_or(1); // FIXME: don't do that!!!
-                                                      //expect("FIXME: allow empty infra id in the amqp protocol"); // FIXME: allow empty infra id in the amqp protocol
-                                                      // TODO: tracing: use correlation id
+                // TODO: tracing: use correlation id
+
+                // Requests bound to no infra are handled by the workers able to load any infra
+                let routing_key = infra_id
+                    .map(|infra_id| infra_id.to_string())
+                    .unwrap_or_else(|| mq_client::ALL_INFRA_KEY.to_owned());
 
                 let response = client
-                    .call_with_response(infra_id.to_string(), path, &body, true, None)
+                    .call_with_retry(routing_key, path, &body, timeout, idempotent)
                     .await?;
 
                 if response.status == b"ok" {
@@ -118,7 +135,19 @@ impl CoreClient {
                     return Err(self.handle_error(&response.payload, path.to_string()));
                 }
 
-                todo!("TODO: handle protocol errors")
+                Err(CoreError::UnknownResponseStatus {
+                    status: String::from_utf8_lossy(&response.status).into_owned(),
+                }
+                .into())
+            }
+            CoreClient::Http(client) => {
+                let response = client.call(method, path, body, timeout).await?;
+
+                if response.success {
+                    return R::from_bytes(&response.payload);
+                }
+
+                Err(self.handle_error(&response.payload, path.to_string()))
             }
             #[cfg(test)]
             CoreClient::Mocked(client) => {
@@ -170,6 +199,12 @@ where
     const METHOD: reqwest::Method;
     /// A shorthand for [Self::url]
     const URL_PATH: &'static str;
+    /// Whether sending this request several times is harmless, letting it be retried after transient failures
+    const IDEMPOTENT: bool = false;
+    /// The response timeout in seconds for requests known to take longer than usual
+    ///
+    /// It never lowers the default timeout of the client, configured by `EDITOAST_CORE_TIMEOUT`.
+    const TIMEOUT: Option<u64> = None;
 
     /// Returns the HTTP method for this request, by default returns [Self::METHOD]
     fn method(&self) -> reqwest::Method {
@@ -181,7 +216,7 @@ where
         Self::URL_PATH
     }
 
-    /// Returns the infra id used for the request. Must be provided.
+    /// Returns the infra id used for the request, or `None` if the request isn't bound to an infra
     fn infra_id(&self) -> Option<i64>;
 
     /// Returns whether or not `self` should be serialized as JSON and used as
@@ -207,6 +242,8 @@ where
             self.url(),
             if self.has_body() { Some(self) } else { None },
             self.infra_id(),
+            Self::TIMEOUT,
+            Self::IDEMPOTENT,
         )
         .await
     }
@@ -284,6 +321,12 @@ pub enum CoreError {
     #[error("Core connection broken. Should retry.")]
     #[editoast_error(status = 500)]
     BrokenPipe,
+    #[error("Core is unavailable, its queue has been unresponsive. Retry in {retry_in} seconds.")]
+    #[editoast_error(status = 503)]
+    Unavailable { retry_in: u64 },
+    #[error("Core replied with an unknown status '{status}'")]
+    #[editoast_error(status = 500)]
+    UnknownResponseStatus { status: String },
 
     #[cfg(test)]
     #[error("The mocked response had no body configured - check out StubResponseBuilder::body if this is unexpected")]
@@ -8,6 +8,7 @@ use lapin::{
     types::{ByteArray, FieldTable, ShortString},
     BasicProperties, Channel, Connection, ConnectionProperties,
 };
+use opentelemetry::{metrics::Counter, KeyValue};
 use serde::Serialize;
 use serde_json::to_vec;
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
@@ -20,12 +21,49 @@ use tokio::{
 use url::Url;
 use uuid::Uuid;
 
+use super::circuit_breaker::CircuitBreaker;
+use super::CoreError;
+use crate::error::InternalError;
+
 #[derive(Debug, Clone)]
 pub struct RabbitMQClient {
     pool: Pool<ChannelManager>,
     exchange: String,
     timeout: u64,
     single_worker: bool,
+    max_retries: u32,
+    retry_backoff: Duration,
+    circuit_breaker: Arc<CircuitBreaker>,
+    metrics: MqClientMetrics,
+}
+
+#[derive(Debug, Clone)]
+struct MqClientMetrics {
+    retries: Counter<u64>,
+    rejections: Counter<u64>,
+}
+
+impl MqClientMetrics {
+    fn new(circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        let meter = opentelemetry::global::meter("editoast");
+        meter
+            .u64_observable_gauge("editoast.core.circuit_breaker.state")
+            .with_description("State of the core circuit breaker: 0 closed, 1 open, 2 half-open")
+            .with_callback(move |observer| {
+                observer.observe(circuit_breaker.state().as_metric(), &[])
+            })
+            .build();
+        Self {
+            retries: meter
+                .u64_counter("editoast.core.retries")
+                .with_description("Core requests sent again after a transient failure")
+                .build(),
+            rejections: meter
+                .u64_counter("editoast.core.circuit_breaker.rejections")
+                .with_description("Core requests failed fast by the open circuit breaker")
+                .build(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -159,6 +197,14 @@ pub struct Options {
     pub timeout: u64,
     pub single_worker: bool,
     pub num_channels: usize,
+    /// Number of times idempotent requests are sent again after a transient failure
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each following one
+    pub retry_backoff: Duration,
+    /// Number of consecutive failures after which requests fail fast, 0 disabling the circuit breaker
+    pub circuit_breaker_threshold: u32,
+    /// How long requests fail fast before core is probed again
+    pub circuit_breaker_cooldown: Duration,
 }
 
 #[derive(Debug, Error, EditoastError)]
@@ -184,13 +230,28 @@ pub enum MqClientError {
     PoolChannelFail,
 }
 
+impl MqClientError {
+    /// Whether the request or its response got lost in the queue, which may succeed when sent again
+    fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            MqClientError::Lapin(_)
+                | MqClientError::ConnectionDoesNotExist
+                | MqClientError::PoolChannelFail
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct MQResponse {
     pub payload: Vec<u8>,
     pub status: Vec<u8>,
 }
 
-const SINGLE_WORKER_KEY: &str = "all";
+/// The routing key of the workers able to load any infra
+///
+/// Used for every request in single worker mode, and for the requests bound to no infra.
+pub const ALL_INFRA_KEY: &str = "all";
 
 impl RabbitMQClient {
     pub async fn new(options: Options) -> Result<Self, MqClientError> {
@@ -230,11 +291,20 @@ impl RabbitMQClient {
             .build()
             .map_err(|_| MqClientError::ConnectionDoesNotExist)?;
 
+        let circuit_breaker = Arc::new(CircuitBreaker::new(
+            options.circuit_breaker_threshold,
+            options.circuit_breaker_cooldown,
+        ));
+
         Ok(RabbitMQClient {
             pool,
             exchange: format!("{}-req-xchg", options.worker_pool_identifier),
             timeout: options.timeout,
             single_worker: options.single_worker,
+            max_retries: options.max_retries,
+            retry_backoff: options.retry_backoff,
+            metrics: MqClientMetrics::new(circuit_breaker.clone()),
+            circuit_breaker,
         })
     }
 
@@ -344,7 +414,7 @@ impl RabbitMQClient {
             .basic_publish(
                 self.exchange.as_str(),
                 if self.single_worker {
-                    SINGLE_WORKER_KEY
+                    ALL_INFRA_KEY
                 } else {
                     routing_key.as_str()
                 },
@@ -408,7 +478,7 @@ impl RabbitMQClient {
             .basic_publish(
                 self.exchange.as_str(),
                 if self.single_worker {
-                    SINGLE_WORKER_KEY
+                    ALL_INFRA_KEY
                 } else {
                     routing_key.as_str()
                 },
@@ -423,7 +493,9 @@ impl RabbitMQClient {
         drop(channel_worker);
 
         match timeout(
-            Duration::from_secs(override_timeout.unwrap_or(self.timeout)),
+            Duration::from_secs(
+                override_timeout.map_or(self.timeout, |timeout| timeout.max(self.timeout)),
+            ),
             rx,
         )
         .await
@@ -446,6 +518,75 @@ impl RabbitMQClient {
             Ok(Err(_)) | Err(_) => Err(MqClientError::ResponseTimeout),
         }
     }
+
+    /// Sends a request and waits for its response, failing fast while the circuit breaker is open
+    ///
+    /// Idempotent requests are sent again after transient failures, with an exponential backoff.
+    /// Timeouts are never retried since core may still be processing the request.
+    pub async fn call_with_retry<T>(
+        &self,
+        routing_key: String,
+        path: &str,
+        published_payload: &Option<T>,
+        override_timeout: Option<u64>,
+        idempotent: bool,
+    ) -> Result<MQResponse, InternalError>
+    where
+        T: Serialize,
+    {
+        let path_attribute = [KeyValue::new("path", path.to_owned())];
+        let mut attempt = 0;
+        loop {
+            if let Err(retry_in) = self.circuit_breaker.acquire() {
+                self.metrics.rejections.add(1, &path_attribute);
+                return Err(CoreError::Unavailable {
+                    retry_in: retry_in.as_secs().max(1),
+                }
+                .into());
+            }
+
+            let err = match self
+                .call_with_response(
+                    routing_key.clone(),
+                    path,
+                    published_payload,
+                    true,
+                    override_timeout,
+                )
+                .await
+            {
+                Ok(response) => {
+                    self.circuit_breaker.record_success();
+                    return Ok(response);
+                }
+                Err(err) => err,
+            };
+
+            if !err.is_transient() && !matches!(err, MqClientError::ResponseTimeout) {
+                return Err(err.into());
+            }
+            if self.circuit_breaker.record_failure() {
+                tracing::warn!(
+                    path,
+                    "Core is unresponsive, failing fast until it answers again"
+                );
+            }
+            if !idempotent || !err.is_transient() || attempt >= self.max_retries {
+                return Err(err.into());
+            }
+
+            let backoff = self.retry_backoff * 2u32.saturating_pow(attempt);
+            attempt += 1;
+            self.metrics.retries.add(1, &path_attribute);
+            tracing::warn!(
+                path,
+                attempt,
+                ?backoff,
+                "Core request failed, retrying: {err}"
+            );
+            tokio::time::sleep(backoff).await;
+        }
+    }
 }
 
 fn attach_tracing_info(headers: &mut FieldTable) {
@@ -93,6 +93,7 @@ pub struct PropertyZoneValues {
 impl AsCoreRequest<Json<PathPropertiesResponse>> for PathPropertiesRequest<'_> {
     const METHOD: reqwest::Method = reqwest::Method::POST;
     const URL_PATH: &'static str = "/v2/path_properties";
+    const IDEMPOTENT: bool = true;
 
     fn infra_id(&self) -> Option<i64> {
         Some(self.infra)
@@ -64,6 +64,7 @@ pub struct SignalUpdatesResponse {
 impl AsCoreRequest<Json<SignalUpdatesResponse>> for SignalUpdatesRequest<'_> {
     const METHOD: reqwest::Method = reqwest::Method::POST;
     const URL_PATH: &'static str = "/v2/signal_projection";
+    const IDEMPOTENT: bool = true;
 
     fn infra_id(&self) -> Option<i64> {
         Some(self.infra)
@@ -31,9 +31,10 @@ editoast_common::schemas! {
     ZoneUpdate,
     ReportTrain,
     SimulationResponse,
+    PhysicsConsist,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Derivative)]
+#[derive(Debug, Clone, Serialize, Deserialize, Derivative, ToSchema)]
 #[derivative(Hash)]
 pub struct PhysicsConsist {
     pub effort_curves: EffortCurves,
@@ -489,6 +490,7 @@ impl Default for SimulationResponse {
 impl AsCoreRequest<Json<SimulationResponse>> for SimulationRequest {
     const METHOD: reqwest::Method = reqwest::Method::POST;
     const URL_PATH: &'static str = "/v2/standalone_simulation";
+    const IDEMPOTENT: bool = true;
 
     fn infra_id(&self) -> Option<i64> {
         Some(self.infra)
@@ -508,6 +510,15 @@ impl SimulationResponse {
             None
         }
     }
+
+    /// Returns when the train passes a path item, in milliseconds from its departure
+    pub fn path_item_time(&self, path_item_index: usize) -> Option<u64> {
+        if let SimulationResponse::Success { provisional, .. } = self {
+            provisional.path_item_times.get(path_item_index).copied()
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -20,10 +20,18 @@ use crate::core::AsCoreRequest;
 use crate::core::Json;
 
 editoast_common::schemas! {
+    Request,
+    PathItem,
+    StepTimingData,
+    WorkSchedule,
+    TemporarySpeedLimit,
+    UndirectedTrackRange,
     Response,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An STDCM request, as sent to core
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = CoreStdcmRequest)]
 pub struct Request {
     /// Infrastructure id
     pub infra: i64,
@@ -58,6 +66,7 @@ pub struct Request {
     /// Gap between the created train and following trains in milliseconds
     pub time_gap_after: u64,
     /// Margin to apply to the whole train
+    #[schema(value_type = Option<String>)]
     pub margin: Option<MarginValue>,
     /// List of planned work schedules
     pub work_schedules: Vec<WorkSchedule>,
@@ -65,7 +74,8 @@ pub struct Request {
     pub temporary_speed_limits: Vec<TemporarySpeedLimit>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = CoreStdcmPathItem)]
 pub struct PathItem {
     /// The track offsets of the path item
     pub locations: Vec<TrackOffset>,
@@ -76,7 +86,8 @@ pub struct PathItem {
 }
 
 /// Contains the data of a step timing, when it is specified
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = CoreStdcmStepTimingData)]
 pub struct StepTimingData {
     /// Time the train should arrive at this point
     pub arrival_time: DateTime<Utc>,
@@ -87,7 +98,8 @@ pub struct StepTimingData {
 }
 
 /// Lighter description of a work schedule, with only the relevant information for core
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = CoreStdcmWorkSchedule)]
 pub struct WorkSchedule {
     /// Start time as a time delta from the stdcm start time in ms
     pub start_time: u64,
@@ -97,8 +109,9 @@ pub struct WorkSchedule {
     pub track_ranges: Vec<UndirectedTrackRange>,
 }
 
-/// Lighter description of a work schedule with only the relevant information for core
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Lighter description of a temporary speed limit, with only the relevant information for core
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = CoreStdcmTemporarySpeedLimit)]
 pub struct TemporarySpeedLimit {
     /// Speed limitation in m/s
     pub speed_limit: f64,
@@ -138,6 +151,8 @@ pub enum Response {
 impl AsCoreRequest<Json<Response>> for Request {
     const METHOD: reqwest::Method = reqwest::Method::POST;
     const URL_PATH: &'static str = "/v2/stdcm";
+    // The search explores many paths and can take much longer than other requests
+    const TIMEOUT: Option<u64> = Some(600);
 
     fn infra_id(&self) -> Option<i64> {
         Some(self.infra)
@@ -13,6 +13,7 @@ pub struct CoreVersionRequest {}
 impl AsCoreRequest<Json<Version>> for CoreVersionRequest {
     const METHOD: reqwest::Method = reqwest::Method::POST;
     const URL_PATH: &'static str = "/version";
+    const IDEMPOTENT: bool = true;
 
     fn infra_id(&self) -> Option<i64> {
         None
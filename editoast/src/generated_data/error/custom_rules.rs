@@ -0,0 +1,537 @@
+//! Declarative validation rules loaded at startup from a YAML file
+//!
+//! They complement the built-in error generators with checks specific to a railway network,
+//! such as national regulations. Each rule emits its own errors in the error layer, with the
+//! severity of the rule.
+//!
+//! The same file can also override the severity of built-in error types, and prevent trains
+//! from being simulated on infras with blocking errors.
+//!
+//! ```yaml
+//! rules:
+//!   - id: signal_switch_distance
+//!     severity: warning
+//!     check:
+//!       type: min_distance_to_switch
+//!       object_type: Signal
+//!       min_distance: 50.0
+//!   - id: max_slope_gc
+//!     check:
+//!       type: max_slope
+//!       max_gradient: 25.0
+//!       loading_gauges: [GC]
+//! severities:
+//!   missing_route: info
+//!   invalid_reference: blocking
+//! refuse_simulation_on_blocking_errors: true
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use editoast_schemas::primitives::OSRDIdentified;
+use editoast_schemas::primitives::ObjectType;
+use editoast_schemas::rolling_stock::LoadingGaugeType;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::generated_data::infra_error::InfraError;
+use crate::generated_data::infra_error::InfraErrorSeverity;
+use crate::generated_data::infra_error::InfraErrorTypeLabel;
+use crate::infra_cache::object_cache::TrackSectionCache;
+use crate::infra_cache::Graph;
+use crate::infra_cache::InfraCache;
+use crate::infra_cache::ObjectCache;
+
+/// Rules installed for the whole process, see [CustomRules::install]
+static CUSTOM_RULES: OnceLock<CustomRules> = OnceLock::new();
+
+#[derive(Debug, Error)]
+pub enum CustomRulesError {
+    #[error("Could not read validation rules file '{path}': {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("Invalid validation rules file '{path}': {source}")]
+    Parse {
+        path: String,
+        source: serde_yaml::Error,
+    },
+    #[error("Validation rule '{rule}' can't apply to '{object_type}' objects")]
+    UnsupportedObjectType {
+        rule: String,
+        object_type: ObjectType,
+    },
+    #[error("Validation rule id '{rule}' is used more than once")]
+    DuplicatedRule { rule: String },
+    #[error("Custom validation rules are already installed")]
+    AlreadyInstalled,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRules {
+    #[serde(default)]
+    pub rules: Vec<CustomRule>,
+    /// Severity of the errors of the given types, overriding the default one
+    #[serde(default)]
+    pub severities: HashMap<InfraErrorTypeLabel, InfraErrorSeverity>,
+    /// Whether train simulations must be refused on infras with blocking errors
+    #[serde(default)]
+    pub refuse_simulation_on_blocking_errors: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRule {
+    /// Identifies the rule in the errors it emits
+    pub id: String,
+    #[serde(default)]
+    pub severity: InfraErrorSeverity,
+    pub check: CustomRuleCheck,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CustomRuleCheck {
+    /// Objects located on a track must be at least `min_distance` meters away from the switches
+    /// connected to the extremities of this track
+    MinDistanceToSwitch {
+        object_type: ObjectType,
+        min_distance: f64,
+    },
+    /// Slopes must not exceed `max_gradient` (in ‰, uphill or downhill).
+    /// When `loading_gauges` is set, only the parts of the tracks allowing one of them are checked.
+    MaxSlope {
+        max_gradient: f64,
+        #[serde(default)]
+        loading_gauges: Vec<LoadingGaugeType>,
+    },
+}
+
+/// Object types located on a single track, whose distance to switches can be checked
+const LOCATED_OBJECT_TYPES: [ObjectType; 3] = [
+    ObjectType::Signal,
+    ObjectType::Detector,
+    ObjectType::BufferStop,
+];
+
+impl CustomRules {
+    /// Loads and validates rules from a YAML file
+    pub fn load(path: &Path) -> Result<Self, CustomRulesError> {
+        let display_path = path.display().to_string();
+        let raw_rules = std::fs::read_to_string(path).map_err(|source| CustomRulesError::Io {
+            path: display_path.clone(),
+            source,
+        })?;
+        let rules: CustomRules =
+            serde_yaml::from_str(&raw_rules).map_err(|source| CustomRulesError::Parse {
+                path: display_path,
+                source,
+            })?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    fn validate(&self) -> Result<(), CustomRulesError> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if self.rules[..index].iter().any(|other| other.id == rule.id) {
+                return Err(CustomRulesError::DuplicatedRule {
+                    rule: rule.id.clone(),
+                });
+            }
+            rule.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Makes these rules the ones checked by [crate::generated_data::generate_infra_errors]
+    ///
+    /// Rules can only be installed once, before any infra is checked.
+    pub fn install(self) -> Result<(), CustomRulesError> {
+        CUSTOM_RULES
+            .set(self)
+            .map_err(|_| CustomRulesError::AlreadyInstalled)
+    }
+
+    /// The rules installed for the process, if any
+    pub fn installed() -> Option<&'static CustomRules> {
+        CUSTOM_RULES.get()
+    }
+
+    /// Runs every rule against an infra
+    pub fn check(&self, infra_cache: &InfraCache, graph: &Graph) -> Vec<InfraError> {
+        self.rules
+            .iter()
+            .flat_map(|rule| rule.check(infra_cache, graph))
+            .collect()
+    }
+
+    /// Overrides the severity of the errors whose type is configured
+    ///
+    /// The severity of custom rule errors is set by their rule, unless overridden here too.
+    pub fn apply_severities(&self, errors: &mut [InfraError]) {
+        for error in errors {
+            let label = InfraErrorTypeLabel::from(&error.sub_type);
+            if let Some(severity) = self.severities.get(&label) {
+                error.set_severity(*severity);
+            }
+        }
+    }
+}
+
+impl CustomRule {
+    pub fn validate(&self) -> Result<(), CustomRulesError> {
+        if let CustomRuleCheck::MinDistanceToSwitch { object_type, .. } = self.check {
+            if !LOCATED_OBJECT_TYPES.contains(&object_type) {
+                return Err(CustomRulesError::UnsupportedObjectType {
+                    rule: self.id.clone(),
+                    object_type,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check(&self, infra_cache: &InfraCache, graph: &Graph) -> Vec<InfraError> {
+        match &self.check {
+            CustomRuleCheck::MinDistanceToSwitch {
+                object_type,
+                min_distance,
+            } => infra_cache
+                .get_objects_by_type(*object_type)
+                .values()
+                .flat_map(|object| {
+                    switches_too_close(object, *min_distance, infra_cache, graph)
+                        .into_iter()
+                        .map(move |(switch_id, distance)| {
+                            InfraError::new_switch_too_close(
+                                object,
+                                &self.id,
+                                self.severity,
+                                switch_id,
+                                distance,
+                                *min_distance,
+                            )
+                        })
+                })
+                .collect(),
+            CustomRuleCheck::MaxSlope {
+                max_gradient,
+                loading_gauges,
+            } => infra_cache
+                .track_sections()
+                .values()
+                .map(ObjectCache::unwrap_track_section)
+                .flat_map(|track| {
+                    steep_slopes(track, *max_gradient, loading_gauges)
+                        .into_iter()
+                        .map(move |(index, gradient)| {
+                            InfraError::new_slope_too_steep(
+                                track,
+                                format!("slopes.{index}"),
+                                &self.id,
+                                self.severity,
+                                gradient,
+                                *max_gradient,
+                            )
+                        })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Lists the switches at the extremities of the object's track that are too close to it,
+/// along with their distance to the object
+fn switches_too_close<'a>(
+    object: &ObjectCache,
+    min_distance: f64,
+    infra_cache: &InfraCache,
+    graph: &'a Graph,
+) -> Vec<(&'a String, f64)> {
+    let (track_id, position) = match object {
+        ObjectCache::Signal(signal) => (&signal.track, signal.position),
+        ObjectCache::Detector(detector) => (&detector.track, detector.position),
+        ObjectCache::BufferStop(buffer_stop) => (&buffer_stop.track, buffer_stop.position),
+        _ => return vec![],
+    };
+    // Invalid references and positions are reported by the built-in generators
+    let Some(track) = infra_cache.track_sections().get(track_id) else {
+        return vec![];
+    };
+    let track = track.unwrap_track_section();
+    if !(0.0..=track.length).contains(&position) {
+        return vec![];
+    }
+
+    [
+        (track.get_begin(), position),
+        (track.get_end(), track.length - position),
+    ]
+    .into_iter()
+    .filter(|(_, distance)| *distance < min_distance)
+    .filter_map(|(endpoint, distance)| {
+        graph
+            .get_switch(&endpoint)
+            .map(|switch| (switch.get_id(), distance))
+    })
+    .collect()
+}
+
+/// Lists the slopes of a track steeper than `max_gradient`, with their index
+fn steep_slopes(
+    track: &TrackSectionCache,
+    max_gradient: f64,
+    loading_gauges: &[LoadingGaugeType],
+) -> Vec<(usize, f64)> {
+    track
+        .slopes
+        .iter()
+        .enumerate()
+        .filter(|(_, slope)| slope.gradient.abs() > max_gradient)
+        .filter(|(_, slope)| {
+            loading_gauges.is_empty()
+                || track.loading_gauge_limits.iter().any(|limit| {
+                    loading_gauges.contains(&limit.category)
+                        && limit.begin < slope.end
+                        && slope.begin < limit.end
+                })
+        })
+        .map(|(index, slope)| (index, slope.gradient))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use editoast_schemas::infra::LoadingGaugeLimit;
+    use editoast_schemas::infra::Slope;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::generated_data::infra_error::InfraErrorType;
+    use crate::infra_cache::tests::create_small_infra_cache;
+    use crate::infra_cache::tests::create_track_section_cache;
+
+    fn parse_rule(raw_rule: &str) -> CustomRule {
+        let rule: CustomRule = serde_yaml::from_str(raw_rule).unwrap();
+        rule.validate().unwrap();
+        rule
+    }
+
+    #[test]
+    fn reject_unsupported_object_type() {
+        let rule: CustomRule = serde_yaml::from_str(
+            r#"
+            id: route_switch_distance
+            check:
+              type: min_distance_to_switch
+              object_type: Route
+              min_distance: 10.0
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            rule.validate(),
+            Err(CustomRulesError::UnsupportedObjectType { .. })
+        ));
+    }
+
+    #[test]
+    fn detector_too_close_to_switches() {
+        let infra_cache = create_small_infra_cache();
+        let graph = Graph::load(&infra_cache);
+        let rule = parse_rule(
+            r#"
+            id: detector_switch_distance
+            severity: warning
+            check:
+              type: min_distance_to_switch
+              object_type: Detector
+              min_distance: 300.0
+            "#,
+        );
+
+        // The detector D1 is in the middle of track B, between the switches "link" and "switch"
+        let errors = rule.check(&infra_cache, &graph);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|error| error.is_warning));
+        let InfraErrorType::SwitchTooClose {
+            rule,
+            distance,
+            min_distance,
+            ..
+        } = &errors[0].sub_type
+        else {
+            panic!("expected a switch too close error, got {:?}", errors[0]);
+        };
+        assert_eq!(rule, "detector_switch_distance");
+        assert_eq!(*distance, 250.);
+        assert_eq!(*min_distance, 300.);
+
+        let rule = parse_rule(
+            r#"
+            id: detector_switch_distance
+            check:
+              type: min_distance_to_switch
+              object_type: Detector
+              min_distance: 200.0
+            "#,
+        );
+        assert!(rule.check(&infra_cache, &graph).is_empty());
+    }
+
+    #[test]
+    fn slope_too_steep() {
+        let mut infra_cache = InfraCache::default();
+        let mut track = create_track_section_cache("A", 500.);
+        track.slopes = vec![
+            Slope {
+                gradient: 10.,
+                begin: 0.,
+                end: 100.,
+            },
+            Slope {
+                gradient: -30.,
+                begin: 100.,
+                end: 200.,
+            },
+            Slope {
+                gradient: 40.,
+                begin: 300.,
+                end: 400.,
+            },
+        ];
+        track.loading_gauge_limits = vec![LoadingGaugeLimit {
+            category: LoadingGaugeType::GC,
+            begin: 0.,
+            end: 250.,
+        }];
+        infra_cache.add(track).unwrap();
+        let graph = Graph::load(&infra_cache);
+
+        let rule = parse_rule(
+            r#"
+            id: max_slope
+            check:
+              type: max_slope
+              max_gradient: 25.0
+            "#,
+        );
+        let errors = rule.check(&infra_cache, &graph);
+        assert_eq!(errors.len(), 2);
+
+        let rule = parse_rule(
+            r#"
+            id: max_slope_gc
+            severity: blocking
+            check:
+              type: max_slope
+              max_gradient: 25.0
+              loading_gauges: [GC]
+            "#,
+        );
+        let errors = rule.check(&infra_cache, &graph);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field.as_deref(), Some("slopes.1"));
+        assert_eq!(errors[0].severity, InfraErrorSeverity::Blocking);
+        assert!(!errors[0].is_warning);
+        assert_eq!(
+            errors[0].sub_type,
+            InfraErrorType::SlopeTooSteep {
+                rule: "max_slope_gc".into(),
+                gradient: -30.,
+                max_gradient: 25.,
+            }
+        );
+    }
+
+    fn parse_rules(raw_rules: &str) -> CustomRules {
+        let rules: CustomRules = serde_yaml::from_str(raw_rules).unwrap();
+        rules.validate().unwrap();
+        rules
+    }
+
+    #[test]
+    fn parse_custom_rules() {
+        let rules = parse_rules(
+            r#"
+            severities:
+              missing_route: info
+              invalid_reference: blocking
+            refuse_simulation_on_blocking_errors: true
+            rules:
+              - id: detector_switch_distance
+                severity: warning
+                check:
+                  type: min_distance_to_switch
+                  object_type: Detector
+                  min_distance: 300.0
+              - id: max_slope
+                check:
+                  type: max_slope
+                  max_gradient: 25.0
+                  loading_gauges: [GC]
+            "#,
+        );
+        assert!(rules.refuse_simulation_on_blocking_errors);
+        assert_eq!(
+            rules.severities[&InfraErrorTypeLabel::MissingRoute],
+            InfraErrorSeverity::Info
+        );
+        assert_eq!(rules.rules.len(), 2);
+        assert_eq!(rules.rules[0].severity, InfraErrorSeverity::Warning);
+        assert_eq!(rules.rules[1].severity, InfraErrorSeverity::Error);
+    }
+
+    #[test]
+    fn reject_duplicated_rules() {
+        let rules: CustomRules = serde_yaml::from_str(
+            r#"
+            rules:
+              - id: max_slope
+                check:
+                  type: max_slope
+                  max_gradient: 25.0
+              - id: max_slope
+                check:
+                  type: max_slope
+                  max_gradient: 30.0
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            rules.validate(),
+            Err(CustomRulesError::DuplicatedRule { .. })
+        ));
+    }
+
+    #[test]
+    fn override_severities() {
+        let rules = parse_rules(
+            r#"
+            severities:
+              switch_too_close: info
+            rules:
+              - id: detector_switch_distance
+                severity: blocking
+                check:
+                  type: min_distance_to_switch
+                  object_type: Detector
+                  min_distance: 300.0
+            "#,
+        );
+        let infra_cache = create_small_infra_cache();
+        let mut errors = rules.check(&infra_cache, &Graph::load(&infra_cache));
+        assert!(errors
+            .iter()
+            .all(|error| error.severity == InfraErrorSeverity::Blocking));
+
+        rules.apply_severities(&mut errors);
+        assert!(errors
+            .iter()
+            .all(|error| error.severity == InfraErrorSeverity::Info && error.is_warning));
+    }
+}
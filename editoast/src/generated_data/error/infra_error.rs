@@ -10,8 +10,11 @@ use strum::EnumDiscriminants;
 use strum::EnumString;
 use utoipa::ToSchema;
 
+use crate::infra_cache::object_cache::TrackSectionCache;
+
 editoast_common::schemas! {
      InfraError,
+     InfraErrorSeverity,
      InfraErrorType,
      InfraErrorTypeLabel,
 }
@@ -22,14 +25,51 @@ pub struct InfraError {
     pub obj_type: ObjectType,
     #[schema(required)]
     pub field: Option<String>,
+    /// Whether the severity of the error is at most [InfraErrorSeverity::Warning]
     pub is_warning: bool,
+    pub severity: InfraErrorSeverity,
     #[serde(flatten)]
     pub sub_type: InfraErrorType,
 }
 
+/// How much an infra error matters, from a mere notice to an error preventing simulations
+///
+/// Each error type has a default severity, which can be overridden in the custom validation rules file.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum InfraErrorSeverity {
+    Info,
+    Warning,
+    #[default]
+    Error,
+    Blocking,
+}
+
+impl InfraErrorSeverity {
+    pub fn is_warning(self) -> bool {
+        self <= InfraErrorSeverity::Warning
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, EnumDiscriminants, Clone, ToSchema)]
 #[strum(serialize_all = "snake_case")]
-#[strum_discriminants(derive(ToSchema, Deserialize, EnumString, AsRefStr))]
+#[strum_discriminants(derive(Hash, ToSchema, Serialize, Deserialize, EnumString, AsRefStr))]
 #[strum_discriminants(name(InfraErrorTypeLabel))]
 #[strum_discriminants(serde(rename_all = "snake_case", deny_unknown_fields))]
 #[strum_discriminants(strum(serialize_all = "snake_case"))]
@@ -70,6 +110,19 @@ pub enum InfraErrorType {
     OverlappingSwitches {
         reference: ObjectRef,
     },
+    /// Emitted by a custom validation rule
+    SlopeTooSteep {
+        rule: String,
+        gradient: f64,
+        max_gradient: f64,
+    },
+    /// Emitted by a custom validation rule
+    SwitchTooClose {
+        rule: String,
+        reference: ObjectRef,
+        distance: f64,
+        min_distance: f64,
+    },
     UnknownPortName {
         port_name: String,
     },
@@ -89,6 +142,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::InvalidReference { reference },
         }
     }
@@ -104,6 +158,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::OutOfRange {
                 position,
                 expected_range,
@@ -117,6 +172,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: None, // This error concern the whole object consistency
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::InvalidRoute,
         }
     }
@@ -127,6 +183,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: true,
+            severity: InfraErrorSeverity::Warning,
             sub_type: InfraErrorType::EmptyObject,
         }
     }
@@ -141,6 +198,7 @@ impl InfraError {
             obj_type: route.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::ObjectOutOfPath { reference },
         }
     }
@@ -151,6 +209,7 @@ impl InfraError {
             obj_type: ObjectType::TrackSection,
             field: Default::default(),
             is_warning: true,
+            severity: InfraErrorSeverity::Warning,
             sub_type: InfraErrorType::MissingRoute,
         }
     }
@@ -161,6 +220,7 @@ impl InfraError {
             obj_type: ObjectType::Switch,
             field: Default::default(),
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::NodeEndpointsNotUnique,
         }
     }
@@ -176,6 +236,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::UnknownPortName { port_name },
         }
     }
@@ -186,6 +247,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::InvalidSwitchPorts,
         }
     }
@@ -200,6 +262,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: true,
+            severity: InfraErrorSeverity::Warning,
             sub_type: InfraErrorType::UnusedPort {
                 port_name: port_name.as_ref().into(),
             },
@@ -216,6 +279,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: true,
+            severity: InfraErrorSeverity::Warning,
             sub_type: InfraErrorType::DuplicatedGroup {
                 original_group_path,
             },
@@ -233,6 +297,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Some(field.as_ref().into()),
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::InvalidGroup {
                 group: group.as_ref().into(),
                 switch_type: switch_type.as_ref().into(),
@@ -246,6 +311,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: None,
             is_warning: true,
+            severity: InfraErrorSeverity::Warning,
             sub_type: InfraErrorType::MissingBufferStop { endpoint },
         }
     }
@@ -256,6 +322,7 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: None,
             is_warning: true,
+            severity: InfraErrorSeverity::Warning,
             sub_type: InfraErrorType::OddBufferStopLocation,
         }
     }
@@ -276,6 +343,7 @@ impl InfraError {
             obj_type: ObjectType::SpeedSection,
             field: Default::default(),
             is_warning: true,
+            severity: InfraErrorSeverity::Warning,
             sub_type: InfraErrorType::OverlappingSpeedSections { reference },
         }
     }
@@ -294,6 +362,7 @@ impl InfraError {
             obj_type: ObjectType::Electrification,
             field: Default::default(),
             is_warning: true,
+            severity: InfraErrorSeverity::Warning,
             sub_type: InfraErrorType::OverlappingElectrifications { reference },
         }
     }
@@ -305,10 +374,61 @@ impl InfraError {
             obj_type: obj.get_type(),
             field: Default::default(),
             is_warning: false,
+            severity: InfraErrorSeverity::Error,
             sub_type: InfraErrorType::OverlappingSwitches { reference },
         }
     }
 
+    pub fn new_slope_too_steep<T: AsRef<str>, R: AsRef<str>>(
+        track: &TrackSectionCache,
+        field: T,
+        rule: R,
+        severity: InfraErrorSeverity,
+        gradient: f64,
+        max_gradient: f64,
+    ) -> Self {
+        Self {
+            obj_id: track.get_id().clone(),
+            obj_type: ObjectType::TrackSection,
+            field: Some(field.as_ref().into()),
+            is_warning: severity.is_warning(),
+            severity,
+            sub_type: InfraErrorType::SlopeTooSteep {
+                rule: rule.as_ref().into(),
+                gradient,
+                max_gradient,
+            },
+        }
+    }
+
+    pub fn new_switch_too_close<O: OSRDObject, R: AsRef<str>, T: AsRef<str>>(
+        obj: &O,
+        rule: R,
+        severity: InfraErrorSeverity,
+        switch: T,
+        distance: f64,
+        min_distance: f64,
+    ) -> Self {
+        Self {
+            obj_id: obj.get_id().clone(),
+            obj_type: obj.get_type(),
+            field: Some("position".into()),
+            is_warning: severity.is_warning(),
+            severity,
+            sub_type: InfraErrorType::SwitchTooClose {
+                rule: rule.as_ref().into(),
+                reference: ObjectRef::new(ObjectType::Switch, switch),
+                distance,
+                min_distance,
+            },
+        }
+    }
+
+    pub fn set_severity(&mut self, severity: InfraErrorSeverity) {
+        self.is_warning = severity.is_warning();
+        self.severity = severity;
+    }
+
     pub fn get_sub_type(&self) -> &InfraErrorType {
         &self.sub_type
     }
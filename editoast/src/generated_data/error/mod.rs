@@ -1,4 +1,5 @@
 pub mod buffer_stops;
+pub mod custom_rules;
 pub mod detectors;
 pub mod electrifications;
 pub mod infra_error;
@@ -40,6 +41,7 @@ use crate::infra_cache::operation::CacheOperation;
 use crate::infra_cache::Graph;
 use crate::infra_cache::InfraCache;
 use crate::infra_cache::ObjectCache;
+use custom_rules::CustomRules;
 
 editoast_common::schemas! {
     infra_error::schemas(),
@@ -261,11 +263,18 @@ pub async fn generate_infra_errors(infra_cache: &InfraCache) -> Vec<InfraError>
         )),
     ];
 
-    futures::future::join_all(futures)
+    let mut errors: Vec<_> = futures::future::join_all(futures)
         .await
         .into_iter()
         .flatten()
-        .collect()
+        .collect();
+
+    // Rules loaded at startup are checked after the built-in ones
+    if let Some(custom_rules) = CustomRules::installed() {
+        errors.extend(custom_rules.check(infra_cache, &graph));
+        custom_rules.apply_severities(&mut errors);
+    }
+    errors
 }
 
 /// Get sql query that insert errors given an object type
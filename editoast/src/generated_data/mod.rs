@@ -22,6 +22,7 @@ use diesel::sql_query;
 use diesel::sql_types::BigInt;
 use diesel_async::RunQueryDsl;
 use electrification::ElectrificationLayer;
+pub use error::custom_rules;
 pub use error::generate_infra_errors;
 pub use error::infra_error;
 use error::ErrorLayer;
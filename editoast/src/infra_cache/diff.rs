@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use editoast_models::DbConnection;
+use editoast_schemas::infra::InfraObject;
+use editoast_schemas::primitives::ObjectRef;
+use editoast_schemas::primitives::ObjectType;
+use enum_map::EnumMap;
+use itertools::Itertools as _;
+use serde_json::json;
+use strum::IntoEnumIterator as _;
+
+use super::operation::DeleteOperation;
+use super::operation::Operation;
+use super::operation::UpdateOperation;
+use super::InfraCache;
+use crate::error::Result;
+use crate::models::Infra;
+
+/// References of the objects of two infra caches, sorted by id
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CacheDiff {
+    /// Objects only present in the other infra
+    pub created: Vec<ObjectRef>,
+    /// Objects only present in the base infra
+    pub deleted: Vec<ObjectRef>,
+    /// Objects present in both infras, that may have been modified
+    pub common: Vec<ObjectRef>,
+}
+
+/// Ids of the objects of an infra cache, by type
+pub type ObjectIds = EnumMap<ObjectType, HashSet<String>>;
+
+impl CacheDiff {
+    /// Compares the object ids of an infra with the ones of `other_ids`
+    pub fn new(ids: &ObjectIds, other_ids: &ObjectIds) -> Self {
+        let mut diff = CacheDiff::default();
+        for obj_type in ObjectType::iter() {
+            let objects = &ids[obj_type];
+            let other_objects = &other_ids[obj_type];
+            for obj_id in objects.iter().sorted() {
+                let obj_ref = ObjectRef::new(obj_type, obj_id);
+                if other_objects.contains(obj_id) {
+                    diff.common.push(obj_ref);
+                } else {
+                    diff.deleted.push(obj_ref);
+                }
+            }
+            diff.created.extend(
+                other_objects
+                    .difference(objects)
+                    .sorted()
+                    .map(|obj_id| ObjectRef::new(obj_type, obj_id)),
+            );
+        }
+        diff
+    }
+}
+
+impl InfraCache {
+    /// Collects the ids of the objects of this infra cache
+    ///
+    /// Unlike the cache itself, they can be kept while the cache of another infra is loaded.
+    pub fn object_ids(&self) -> ObjectIds {
+        EnumMap::from_fn(|obj_type| self.objects[obj_type].keys().cloned().collect())
+    }
+
+    /// Compares the objects of this infra cache with the ones of `other`
+    pub fn diff(&self, other: &InfraCache) -> CacheDiff {
+        CacheDiff::new(&self.object_ids(), &other.object_ids())
+    }
+}
+
+/// Builds the edition operations that turn the objects of `infra` into the ones of `other_infra`
+///
+/// Deleted objects come first, then created ones, then the JSON patches of modified objects.
+/// Applying the result to a copy of `infra` through the edition endpoint yields `other_infra`.
+pub async fn diff_operations(
+    conn: &mut DbConnection,
+    infra: &Infra,
+    other_infra: &Infra,
+    cache_diff: CacheDiff,
+) -> Result<Vec<Operation>> {
+    let CacheDiff {
+        created,
+        deleted,
+        common,
+    } = cache_diff;
+
+    let mut operations: Vec<_> = deleted
+        .into_iter()
+        .map(|obj_ref| Operation::Delete(DeleteOperation::from(obj_ref)))
+        .collect();
+
+    for (obj_type, obj_ids) in ids_by_type(created) {
+        for (_, railjson) in load_railjson(conn, other_infra, obj_type, &obj_ids).await? {
+            let infra_object: InfraObject = serde_json::from_value(json!({
+                "obj_type": obj_type,
+                "railjson": railjson,
+            }))?;
+            operations.push(Operation::Create(Box::new(infra_object)));
+        }
+    }
+
+    for (obj_type, obj_ids) in ids_by_type(common) {
+        let mut other_objects = load_railjson(conn, other_infra, obj_type, &obj_ids)
+            .await?
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        for (obj_id, railjson) in load_railjson(conn, infra, obj_type, &obj_ids).await? {
+            let Some(other_railjson) = other_objects.remove(&obj_id) else {
+                continue;
+            };
+            let railjson_patch = json_patch::diff(&railjson, &other_railjson);
+            if railjson_patch.0.is_empty() {
+                continue;
+            }
+            operations.push(Operation::Update(UpdateOperation {
+                obj_id,
+                obj_type,
+                railjson_patch,
+            }));
+        }
+    }
+    Ok(operations)
+}
+
+/// Groups the ids of consecutive references of the same type
+fn ids_by_type(obj_refs: Vec<ObjectRef>) -> Vec<(ObjectType, Vec<String>)> {
+    obj_refs
+        .into_iter()
+        .chunk_by(|obj_ref| obj_ref.obj_type)
+        .into_iter()
+        .map(|(obj_type, obj_refs)| (obj_type, obj_refs.map(|obj_ref| obj_ref.obj_id).collect()))
+        .collect()
+}
+
+/// Loads the railjson of the given objects, sorted by id
+async fn load_railjson(
+    conn: &mut DbConnection,
+    infra: &Infra,
+    obj_type: ObjectType,
+    obj_ids: &Vec<String>,
+) -> Result<Vec<(String, serde_json::Value)>> {
+    let objects = infra.get_objects(conn, obj_type, obj_ids).await?;
+    Ok(objects
+        .into_iter()
+        .map(|object| (object.obj_id, object.railjson))
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::infra_cache::tests::create_buffer_stop_cache;
+    use crate::infra_cache::tests::create_track_section_cache;
+
+    #[test]
+    fn diff_infra_caches() {
+        let mut infra_cache = InfraCache::default();
+        infra_cache
+            .add(create_track_section_cache("A", 500.))
+            .unwrap();
+        infra_cache
+            .add(create_track_section_cache("B", 500.))
+            .unwrap();
+        infra_cache
+            .add(create_buffer_stop_cache("BF", "A", 10.))
+            .unwrap();
+
+        let mut other_infra_cache = InfraCache::default();
+        other_infra_cache
+            .add(create_track_section_cache("B", 200.))
+            .unwrap();
+        other_infra_cache
+            .add(create_track_section_cache("C", 500.))
+            .unwrap();
+
+        let diff = infra_cache.diff(&other_infra_cache);
+
+        assert_eq!(
+            diff,
+            CacheDiff {
+                created: vec![ObjectRef::new(ObjectType::TrackSection, "C")],
+                deleted: vec![
+                    ObjectRef::new(ObjectType::TrackSection, "A"),
+                    ObjectRef::new(ObjectType::BufferStop, "BF"),
+                ],
+                common: vec![ObjectRef::new(ObjectType::TrackSection, "B")],
+            }
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod diff;
 mod graph;
 pub mod object_cache;
 pub mod operation;
@@ -279,6 +280,8 @@ pub struct TrackQueryable {
     #[diesel(sql_type = Text)]
     pub slopes: String,
     #[diesel(sql_type = Text)]
+    pub loading_gauge_limits: String,
+    #[diesel(sql_type = Text)]
     pub geo: String,
 }
 
@@ -291,6 +294,7 @@ impl From<TrackQueryable> for TrackSectionCache {
             length: track.length,
             curves: serde_json::from_str(&track.curves).unwrap(),
             slopes: serde_json::from_str(&track.slopes).unwrap(),
+            loading_gauge_limits: serde_json::from_str(&track.loading_gauge_limits).unwrap(),
             line_code: track.line_code,
             bbox_geo: BoundingBox::from_geometry(geo)
                 .expect("tracksections' geometry must be LineStrings"),
@@ -433,6 +437,7 @@ impl InfraCache {
                 (data->>'length')::float as length,
                 data->>'curves' as curves,
                 data->>'slopes' as slopes,
+                COALESCE(data->>'loading_gauge_limits', '[]') as loading_gauge_limits,
                 data->>'geo' as geo
             FROM infra_object_track_section WHERE infra_id = $1",
         )
@@ -1,6 +1,7 @@
 use derivative::Derivative;
 use editoast_schemas::infra::Curve;
 use editoast_schemas::infra::Endpoint;
+use editoast_schemas::infra::LoadingGaugeLimit;
 use editoast_schemas::infra::Slope;
 use editoast_schemas::infra::TrackEndpoint;
 use editoast_schemas::primitives::OSRDIdentified;
@@ -25,6 +26,8 @@ pub struct TrackSectionCache {
     #[derivative(Hash = "ignore", PartialEq = "ignore")]
     pub curves: Vec<Curve>,
     #[derivative(Hash = "ignore", PartialEq = "ignore")]
+    pub loading_gauge_limits: Vec<LoadingGaugeLimit>,
+    #[derivative(Hash = "ignore", PartialEq = "ignore")]
     pub bbox_geo: BoundingBox,
 }
 
@@ -64,6 +67,7 @@ impl From<TrackSection> for TrackSectionCache {
             length: track.length,
             curves: track.curves,
             slopes: track.slopes,
+            loading_gauge_limits: track.loading_gauge_limits,
             line_code: track.extensions.sncf.map(|sncf| sncf.line_code),
         }
     }
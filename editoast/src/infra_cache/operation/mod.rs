@@ -3,12 +3,21 @@ mod delete;
 mod update;
 
 use std::ops::Deref as _;
+use std::ops::DerefMut as _;
 
+use diesel::sql_query;
+use diesel::sql_types::BigInt;
+use diesel::sql_types::Jsonb;
+use diesel::sql_types::Text;
+use diesel::OptionalExtension as _;
+use diesel::QueryableByName;
+use diesel_async::RunQueryDsl;
 use editoast_derive::EditoastError;
 use editoast_schemas::primitives::OSRDObject as _;
 use json_patch::Patch;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::json;
 use thiserror::Error;
 pub use update::UpdateOperation;
 use utoipa::ToSchema;
@@ -16,6 +25,7 @@ use utoipa::ToSchema;
 pub use self::delete::DeleteOperation;
 use crate::error::Result;
 use crate::infra_cache::ObjectCache;
+use crate::models::get_table;
 use editoast_models::DbConnection;
 use editoast_schemas::infra::InfraObject;
 use editoast_schemas::primitives::ObjectRef;
@@ -157,6 +167,78 @@ impl Operation {
             }
         }
     }
+
+    /// Applies the operation and returns the operation that reverts it
+    ///
+    /// The inverse operation is computed from the object as stored before the edition.
+    pub async fn apply_with_inverse(
+        &self,
+        infra_id: i64,
+        conn: &mut DbConnection,
+    ) -> Result<(Option<InfraObject>, Operation)> {
+        let inverse = match self {
+            Operation::Create(railjson_object) => {
+                Operation::Delete(DeleteOperation::from(railjson_object.get_ref()))
+            }
+            Operation::Update(update) => {
+                let obj_ref = ObjectRef::new(update.obj_type, &update.obj_id);
+                let previous = load_railjson(&obj_ref, infra_id, conn).await?;
+                let mut patched = previous.clone();
+                json_patch::patch(&mut patched, &update.railjson_patch).map_err(|err| {
+                    OperationError::InvalidPatch {
+                        error: err.to_string(),
+                    }
+                })?;
+                Operation::Update(UpdateOperation {
+                    obj_id: update.obj_id.clone(),
+                    obj_type: update.obj_type,
+                    railjson_patch: json_patch::diff(&patched, &previous),
+                })
+            }
+            Operation::Delete(deletion) => {
+                let obj_ref = ObjectRef::from(deletion.clone());
+                let previous = load_railjson(&obj_ref, infra_id, conn).await?;
+                let railjson_object: InfraObject = serde_json::from_value(json!({
+                    "obj_type": deletion.obj_type,
+                    "railjson": previous,
+                }))?;
+                Operation::Create(Box::new(railjson_object))
+            }
+        };
+        let railjson = self.apply(infra_id, conn).await?;
+        Ok((railjson, inverse))
+    }
+}
+
+#[derive(QueryableByName)]
+struct DataObject {
+    #[diesel(sql_type = Jsonb)]
+    data: serde_json::Value,
+}
+
+/// Loads the RailJSON of an object as stored in the database
+async fn load_railjson(
+    obj_ref: &ObjectRef,
+    infra_id: i64,
+    conn: &mut DbConnection,
+) -> Result<serde_json::Value> {
+    let object: Option<DataObject> = sql_query(format!(
+        "SELECT data FROM {} WHERE infra_id = $1 AND obj_id = $2",
+        get_table(&obj_ref.obj_type)
+    ))
+    .bind::<BigInt, _>(infra_id)
+    .bind::<Text, _>(&obj_ref.obj_id)
+    .get_result(conn.write().await.deref_mut())
+    .await
+    .optional()?;
+    match object {
+        Some(object) => Ok(object.data),
+        None => Err(OperationError::ObjectNotFound {
+            obj_id: obj_ref.obj_id.clone(),
+            infra_id,
+        }
+        .into()),
+    }
 }
 
 pub fn patch_infra_object(infra_object: &InfraObject, json_patch: &Patch) -> Result<InfraObject> {
@@ -12,9 +12,13 @@ mod valkey_utils;
 mod views;
 
 use clap::Parser;
+use client::audit_commands::export_audit_log;
+use client::audit_commands::AuditCommands;
 use client::electrical_profiles_commands::*;
 use client::group;
 use client::group::GroupCommand;
+use client::gtfs_commands::gtfs_export;
+use client::gtfs_commands::gtfs_import;
 use client::healthcheck::healthcheck_cmd;
 use client::import_rolling_stock::*;
 use client::infra_commands::*;
@@ -23,6 +27,8 @@ use client::roles;
 use client::roles::RolesCommand;
 use client::runserver::runserver;
 use client::search_commands::*;
+use client::stdcm_commands::replay_stdcm_log;
+use client::stdcm_commands::StdcmCommands;
 use client::stdcm_search_env_commands::handle_stdcm_search_env_command;
 use client::timetables_commands::*;
 use client::user;
@@ -31,8 +37,10 @@ use client::Client;
 use client::Color;
 use client::Commands;
 use editoast_models::DbConnectionPoolV2;
+use generated_data::custom_rules::CustomRules;
 use models::RollingStockModel;
 use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::metrics::PeriodicReader;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::resource::EnvResourceDetector;
 use opentelemetry_sdk::resource::SdkProvidedResourceDetector;
@@ -71,7 +79,10 @@ enum EditoastMode {
     Cli,
 }
 
-fn init_tracing(mode: EditoastMode, telemetry_config: &client::TelemetryConfig) {
+fn init_tracing(
+    mode: EditoastMode,
+    telemetry_config: &client::TelemetryConfig,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
     let env_filter_layer = tracing_subscriber::EnvFilter::builder()
         // Set the default log level to 'info'
         .with_default_directive(tracing_subscriber::filter::LevelFilter::INFO.into())
@@ -92,8 +103,7 @@ fn init_tracing(mode: EditoastMode, telemetry_config: &client::TelemetryConfig)
             let exporter = opentelemetry_otlp::SpanExporter::builder()
                 .with_tonic()
                 .with_endpoint(telemetry_config.telemetry_endpoint.as_str())
-                .build()
-                .expect("failed to build a span exporter");
+                .build()?;
             let resource = Resource::new(vec![KeyValue::new(
                 opentelemetry_semantic_conventions::resource::SERVICE_NAME,
                 telemetry_config.service_name.clone(),
@@ -106,6 +116,20 @@ fn init_tracing(mode: EditoastMode, telemetry_config: &client::TelemetryConfig)
                     Box::new(EnvResourceDetector::new()),
                 ],
             ));
+            if telemetry_config.telemetry_metrics {
+                let metric_exporter = opentelemetry_otlp::MetricExporter::builder()
+                    .with_tonic()
+                    .with_endpoint(telemetry_config.telemetry_endpoint.as_str())
+                    .build()?;
+                let meter_provider = opentelemetry_sdk::metrics::SdkMeterProvider::builder()
+                    .with_reader(
+                        PeriodicReader::builder(metric_exporter, opentelemetry_sdk::runtime::Tokio)
+                            .build(),
+                    )
+                    .with_resource(resource.clone())
+                    .build();
+                opentelemetry::global::set_meter_provider(meter_provider);
+            }
             let otlp_tracer = opentelemetry_sdk::trace::TracerProvider::builder()
                 .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
                 .with_resource(resource)
@@ -121,6 +145,7 @@ fn init_tracing(mode: EditoastMode, telemetry_config: &client::TelemetryConfig)
         .with(env_filter_layer)
         .with(fmt_layer)
         .init();
+    Ok(())
 }
 
 impl EditoastMode {
@@ -151,7 +176,7 @@ async fn main() {
 
 async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
     let client = Client::parse();
-    init_tracing(EditoastMode::from_client(&client), &client.telemetry_config);
+    init_tracing(EditoastMode::from_client(&client), &client.telemetry_config)?;
 
     let pg_config = client.postgres_config;
     let db_pool =
@@ -166,6 +191,10 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
         Color::Auto => colored::control::set_override(std::io::stderr().is_terminal()),
     }
 
+    if let Some(validation_rules) = client.validation_rules {
+        CustomRules::load(&validation_rules)?.install()?;
+    }
+
     match client.command {
         Commands::Runserver(args) => runserver(args, pg_config, valkey_config)
             .await
@@ -175,7 +204,31 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
             import_towed_rolling_stock(args, db_pool.into()).await
         }
         Commands::OsmToRailjson(args) => {
-            osm_to_railjson::osm_to_railjson(args.osm_pbf_in, args.railjson_out)
+            let crop_area = match args.polygon {
+                Some(polygon) => Some(osm_to_railjson::CropArea::from_geojson(
+                    &std::fs::read_to_string(polygon)?,
+                )?),
+                None => args.bbox,
+            };
+            let elevation_model = args
+                .elevation_model
+                .map(|path| osm_to_railjson::ElevationModel::from_geotiff(&path))
+                .transpose()?;
+            let profile = match args.profile {
+                Some(path) => osm_to_railjson::Profile::load(&path)?,
+                None => Default::default(),
+            };
+            let options = osm_to_railjson::ConversionOptions {
+                crop_area,
+                elevation_model,
+                profile,
+            };
+            osm_to_railjson::osm_to_railjson(
+                args.osm_pbf_in,
+                args.railjson_out,
+                options,
+                args.crop_report,
+            )
         }
         Commands::Openapi => {
             print_openapi();
@@ -207,10 +260,18 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
                 generate_infra(args, db_pool.into(), valkey_config).await
             }
             InfraCommands::ImportRailjson(args) => import_railjson(args, db_pool.into()).await,
+            InfraCommands::Diff(args) => diff_infra(args, db_pool.into()).await,
+            InfraCommands::AutoFix(args) => {
+                auto_fix_infra(args, db_pool.into(), valkey_config).await
+            }
         },
         Commands::Timetables(subcommand) => match subcommand {
             TimetablesCommands::Import(args) => trains_import(args, db_pool.into()).await,
             TimetablesCommands::Export(args) => trains_export(args, db_pool.into()).await,
+            TimetablesCommands::ImportGtfs(args) => gtfs_import(args, db_pool.into()).await,
+            TimetablesCommands::ExportGtfs(args) => {
+                gtfs_export(args, db_pool.into(), valkey_config).await
+            }
         },
         Commands::STDCMSearchEnv(subcommand) => {
             handle_stdcm_search_env_command(subcommand, db_pool).await
@@ -262,6 +323,12 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
                 .await
                 .map_err(Into::into),
         },
+        Commands::Audit(subcommand) => match subcommand {
+            AuditCommands::Export(args) => export_audit_log(args, db_pool.into()).await,
+        },
+        Commands::Stdcm(subcommand) => match subcommand {
+            StdcmCommands::Replay(args) => replay_stdcm_log(args, db_pool.into()).await,
+        },
         Commands::Healthcheck(core_config) => {
             healthcheck_cmd(db_pool.into(), valkey_config, core_config)
                 .await
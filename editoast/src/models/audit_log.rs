@@ -0,0 +1,212 @@
+use chrono::DateTime;
+use chrono::Utc;
+use editoast_authz::authorizer::UserInfo;
+use editoast_authz::Resource;
+use editoast_derive::Model;
+use editoast_models::DbConnection;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::AsRefStr;
+use strum::Display;
+use strum::EnumString;
+use utoipa::ToSchema;
+
+use crate::error::Result;
+use crate::models::prelude::*;
+
+editoast_common::schemas! {
+    AuditLog,
+}
+
+/// A change made through the API, and who made it
+#[derive(Clone, Debug, Serialize, Deserialize, Model, ToSchema)]
+#[model(table = editoast_models::tables::audit_log)]
+#[model(gen(ops = cr, list))]
+pub struct AuditLog {
+    pub id: i64,
+    pub created: DateTime<Utc>,
+    /// Unset if the author has been deleted, or isn't a registered user
+    pub user_id: Option<i64>,
+    pub user_identity: String,
+    pub user_name: String,
+    /// One of `create`, `update`, `delete`, `lock`, `unlock`, `grant` or `revoke`
+    pub action: String,
+    /// The type of the changed resource, or `subject` for changes of users and groups
+    pub resource_type: String,
+    pub resource_id: i64,
+    /// A human readable description of the change
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Lock,
+    Unlock,
+    Grant,
+    Revoke,
+}
+
+/// The object of an audited change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditTarget {
+    Resource(Resource),
+    /// A user or a group, whose roles changed
+    Subject(i64),
+}
+
+impl AuditTarget {
+    pub fn resource_type(&self) -> &str {
+        match self {
+            AuditTarget::Resource(resource) => resource.resource_type.as_ref(),
+            AuditTarget::Subject(_) => "subject",
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        match self {
+            AuditTarget::Resource(resource) => resource.id,
+            AuditTarget::Subject(subject_id) => *subject_id,
+        }
+    }
+}
+
+impl From<Resource> for AuditTarget {
+    fn from(resource: Resource) -> Self {
+        AuditTarget::Resource(resource)
+    }
+}
+
+impl AuditLog {
+    /// Records a change
+    ///
+    /// This must run in the transaction making the change, so that no change goes unrecorded.
+    pub async fn log(
+        conn: &mut DbConnection,
+        author: &UserInfo,
+        user_id: Option<i64>,
+        action: AuditAction,
+        target: AuditTarget,
+        summary: String,
+    ) -> Result<()> {
+        let audit_log_changeset = AuditLog::changeset()
+            .user_id(user_id)
+            .user_identity(author.identity.clone())
+            .user_name(author.name.clone())
+            .action(action.to_string())
+            .resource_type(target.resource_type().to_owned())
+            .resource_id(target.id())
+            .summary(summary);
+        audit_log_changeset.create(conn).await?;
+        Ok(())
+    }
+}
+
+/// Restricts the listed entries, unset criteria match every entry
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub user_id: Option<i64>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<i64>,
+    /// Only keeps the entries recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl AuditLogFilter {
+    pub fn apply(self, mut settings: SelectionSettings<AuditLog>) -> SelectionSettings<AuditLog> {
+        if let Some(user_id) = self.user_id {
+            settings = settings.filter(move || AuditLog::USER_ID.eq(Some(user_id)));
+        }
+        if let Some(resource_type) = self.resource_type {
+            settings = settings.filter(move || AuditLog::RESOURCE_TYPE.eq(resource_type.clone()));
+        }
+        if let Some(resource_id) = self.resource_id {
+            settings = settings.filter(move || AuditLog::RESOURCE_ID.eq(resource_id));
+        }
+        if let Some(since) = self.since {
+            settings = settings.filter(move || {
+                use diesel::ExpressionMethods;
+                FilterSetting::new(editoast_models::tables::audit_log::created.ge(since))
+            });
+        }
+        settings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use editoast_authz::ResourceType;
+    use editoast_models::DbConnectionPoolV2;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[rstest::rstest]
+    async fn log_and_list_changes() {
+        let db_pool = DbConnectionPoolV2::for_tests();
+        let conn = &mut db_pool.get_ok();
+        let author = UserInfo {
+            identity: "jdoe".to_owned(),
+            name: "John Doe".to_owned(),
+        };
+
+        AuditLog::log(
+            conn,
+            &author,
+            None,
+            AuditAction::Delete,
+            Resource::new(ResourceType::Scenario, 42).into(),
+            "Deleted scenario 'Morning peak'".to_owned(),
+        )
+        .await
+        .expect("audit log entry should be recorded");
+        AuditLog::log(
+            conn,
+            &author,
+            None,
+            AuditAction::Grant,
+            AuditTarget::Subject(7),
+            "Granted roles InfraRead".to_owned(),
+        )
+        .await
+        .expect("audit log entry should be recorded");
+
+        let entries = AuditLog::list(
+            conn,
+            SelectionSettings::new().order_by(|| AuditLog::ID.asc()),
+        )
+        .await
+        .expect("Failed to list audit log entries");
+        let entries = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.user_identity.as_str(),
+                    entry.action.as_str(),
+                    entry.resource_type.as_str(),
+                    entry.resource_id,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                ("jdoe", "delete", "scenario", 42),
+                ("jdoe", "grant", "subject", 7),
+            ]
+        );
+
+        let filter = AuditLogFilter {
+            resource_type: Some("subject".to_owned()),
+            ..Default::default()
+        };
+        let entries = AuditLog::list(conn, filter.apply(SelectionSettings::new()))
+            .await
+            .expect("Failed to list audit log entries");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].summary, "Granted roles InfraRead");
+    }
+}
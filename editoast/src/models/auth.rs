@@ -1,18 +1,29 @@
 use std::ops::DerefMut;
+use std::str::FromStr;
 use std::{collections::HashSet, sync::Arc};
 
-use diesel::{dsl, prelude::*};
+use diesel::{
+    dsl::{self, sql},
+    prelude::*,
+    sql_types::Bool,
+    upsert::excluded,
+};
 use diesel_async::{scoped_futures::ScopedFutureExt as _, RunQueryDsl};
 use editoast_authz::{
     authorizer::{GroupInfo, StorageDriver, UserIdentity, UserInfo},
+    privilege::{Grant, Privilege, Resource, ResourceType},
     roles::BuiltinRoleSet,
 };
+use editoast_models::DbConnection;
 use editoast_models::DbConnectionPoolV2;
 
 use editoast_models::tables::*;
 use itertools::Itertools as _;
 use tracing::Level;
 
+use crate::models::prelude::FilterSetting;
+use crate::models::Model;
+
 #[derive(Clone)]
 pub struct PgAuthDriver<B: BuiltinRoleSet + Send + Sync> {
     pool: Arc<DbConnectionPoolV2>,
@@ -28,12 +39,208 @@ impl<B: BuiltinRoleSet + Send + Sync> PgAuthDriver<B> {
     }
 }
 
+/// Returns whether a grant row references one of the resources
+fn is_any_of(resources: &[Resource], resource_type: &str, resource_id: i64) -> bool {
+    resources.iter().any(|resource| {
+        resource.id == resource_id && resource.resource_type.as_ref() == resource_type
+    })
+}
+
+fn parse_privilege(privilege: &str) -> Result<Privilege, AuthDriverError> {
+    Privilege::from_str(privilege)
+        .map_err(|_| AuthDriverError::InvalidPrivilege(privilege.to_owned()))
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AuthDriverError {
     #[error(transparent)]
     DieselError(#[from] diesel::result::Error),
     #[error(transparent)]
     DbPoolError(#[from] editoast_models::db_connection_pool::DatabasePoolError),
+    #[error("invalid privilege tag '{0}'")]
+    InvalidPrivilege(String),
+}
+
+/// Grants a privilege over a resource, replacing the one previously granted to the subject
+///
+/// Unlike [PgAuthDriver::ensure_subject_privilege], this uses the given connection, so that
+/// privileges can be granted in the transaction creating the resource.
+pub async fn grant_privilege(
+    conn: &mut DbConnection,
+    subject_id: i64,
+    resource: Resource,
+    privilege: Privilege,
+) -> Result<(), AuthDriverError> {
+    dsl::insert_into(authz_grant::table)
+        .values((
+            authz_grant::subject.eq(subject_id),
+            authz_grant::resource_type.eq(resource.resource_type.as_ref()),
+            authz_grant::resource_id.eq(resource.id),
+            authz_grant::privilege.eq(privilege.as_ref()),
+        ))
+        .on_conflict((
+            authz_grant::subject,
+            authz_grant::resource_type,
+            authz_grant::resource_id,
+        ))
+        .do_update()
+        .set(authz_grant::privilege.eq(excluded(authz_grant::privilege)))
+        .execute(conn.write().await.deref_mut())
+        .await?;
+    Ok(())
+}
+
+/// Revokes the privilege granted to a subject over a resource, returns whether there was one
+pub async fn revoke_privilege(
+    conn: &mut DbConnection,
+    subject_id: i64,
+    resource: Resource,
+) -> Result<bool, AuthDriverError> {
+    let deleted = dsl::delete(
+        authz_grant::table
+            .filter(authz_grant::subject.eq(subject_id))
+            .filter(authz_grant::resource_type.eq(resource.resource_type.as_ref()))
+            .filter(authz_grant::resource_id.eq(resource.id)),
+    )
+    .execute(conn.write().await.deref_mut())
+    .await?;
+    Ok(deleted > 0)
+}
+
+/// Grants builtin roles to a subject, keeping the ones it already has
+///
+/// Like [grant_privilege], this uses the given connection to run in the transaction of a request.
+pub async fn grant_roles<B: BuiltinRoleSet>(
+    conn: &mut DbConnection,
+    subject_id: i64,
+    roles: &HashSet<B>,
+) -> Result<(), AuthDriverError> {
+    dsl::insert_into(authz_role::table)
+        .values(
+            roles
+                .iter()
+                .map(|role| {
+                    (
+                        authz_role::subject.eq(subject_id),
+                        authz_role::role.eq(role.as_str()),
+                    )
+                })
+                .collect_vec(),
+        )
+        .on_conflict((authz_role::subject, authz_role::role))
+        .do_nothing()
+        .execute(conn.write().await.deref_mut())
+        .await?;
+    Ok(())
+}
+
+/// Removes builtin roles from a subject, returns the ones it had
+pub async fn strip_roles<B: BuiltinRoleSet>(
+    conn: &mut DbConnection,
+    subject_id: i64,
+    roles: &HashSet<B>,
+) -> Result<HashSet<B>, AuthDriverError> {
+    let deleted_roles = dsl::delete(
+        authz_role::table
+            .filter(authz_role::subject.eq(subject_id))
+            .filter(authz_role::role.eq_any(roles.iter().map(|r| r.as_str()).collect::<Vec<_>>())),
+    )
+    .returning(authz_role::role)
+    .load::<String>(conn.write().await.deref_mut())
+    .await?
+    .into_iter()
+    .map(|role| {
+        B::from_str(role.as_str())
+            .ok()
+            .expect("invalid builtin role tag")
+    })
+    .collect();
+    Ok(deleted_roles)
+}
+
+/// SQL expressions of the resource stored in each row of a table, followed by its ancestors
+fn resource_columns(resource_type: ResourceType) -> &'static [(ResourceType, &'static str)] {
+    match resource_type {
+        ResourceType::Infra => &[(ResourceType::Infra, "infra.id")],
+        ResourceType::Project => &[(ResourceType::Project, "project.id")],
+        ResourceType::Study => &[
+            (ResourceType::Study, "study.id"),
+            (ResourceType::Project, "study.project_id"),
+        ],
+        ResourceType::Scenario => &[
+            (ResourceType::Scenario, "scenario.id"),
+            (ResourceType::Study, "scenario.study_id"),
+            (
+                ResourceType::Project,
+                "(SELECT study.project_id FROM study WHERE study.id = scenario.study_id)",
+            ),
+        ],
+        ResourceType::Timetable => &[(ResourceType::Timetable, "timetable.id")],
+        ResourceType::RollingStock => &[(ResourceType::RollingStock, "rolling_stock.id")],
+    }
+}
+
+/// The SQL condition selecting the resources a subject can read, see [editoast_authz::authorizer::Authorizer::resource_privilege]
+///
+/// The subject can read the resources over which a privilege was granted to it, directly or
+/// through its groups, and if `unrestricted_readable`, the ones over which nothing was granted.
+/// The condition refers to the table of the resources, and to the tables of their ancestors.
+pub fn readable_condition(
+    subject_id: i64,
+    unrestricted_readable: bool,
+    resource_type: ResourceType,
+) -> String {
+    grants_condition(
+        subject_id,
+        unrestricted_readable,
+        resource_columns(resource_type),
+    )
+}
+
+/// Like [readable_condition], for the resources referred to by a column of another table
+///
+/// The ancestors of the resources aren't considered, so it only suits resources without any.
+pub fn referenced_readable_condition(
+    subject_id: i64,
+    unrestricted_readable: bool,
+    resource_type: ResourceType,
+    column: &str,
+) -> String {
+    grants_condition(
+        subject_id,
+        unrestricted_readable,
+        &[(resource_type, column)],
+    )
+}
+
+/// See [readable_condition], the resources and their ancestors being given by their id columns
+fn grants_condition(
+    subject_id: i64,
+    unrestricted_readable: bool,
+    columns: &[(ResourceType, &str)],
+) -> String {
+    let resource_grants = columns
+        .iter()
+        .map(|(resource_type, column)| {
+            format!(
+                "(authz_grant.resource_type = '{}' AND authz_grant.resource_id = {column})",
+                resource_type.as_ref()
+            )
+        })
+        .join(" OR ");
+    format!(
+        "(EXISTS (SELECT 1 FROM authz_grant WHERE ({resource_grants}) \
+            AND (authz_grant.subject = {subject_id} OR authz_grant.subject IN \
+                (SELECT authn_group_membership.\"group\" FROM authn_group_membership \
+                WHERE authn_group_membership.\"user\" = {subject_id}))) \
+        OR ({unrestricted_readable} AND NOT EXISTS \
+            (SELECT 1 FROM authz_grant WHERE {resource_grants})))"
+    )
+}
+
+/// Selects the resources satisfying a [readable_condition]
+pub fn readable_filter<M: Model>(condition: &str) -> FilterSetting<M> {
+    FilterSetting(Box::new(sql::<Bool>(condition)))
 }
 
 impl<B: BuiltinRoleSet + Send + Sync> StorageDriver for PgAuthDriver<B> {
@@ -154,25 +361,8 @@ impl<B: BuiltinRoleSet + Send + Sync> StorageDriver for PgAuthDriver<B> {
         subject_id: i64,
         roles: HashSet<Self::BuiltinRole>,
     ) -> Result<(), Self::Error> {
-        let conn = self.pool.get().await?;
-        dsl::insert_into(authz_role::table)
-            .values(
-                roles
-                    .iter()
-                    .map(|role| {
-                        (
-                            authz_role::subject.eq(subject_id),
-                            authz_role::role.eq(role.as_str()),
-                        )
-                    })
-                    .collect_vec(),
-            )
-            .on_conflict((authz_role::subject, authz_role::role))
-            .do_nothing()
-            .execute(conn.write().await.deref_mut())
-            .await?;
-
-        Ok(())
+        let mut conn = self.pool.get().await?;
+        grant_roles(&mut conn, subject_id, &roles).await
     }
 
     #[tracing::instrument(skip_all, fields(%subject_id, ?roles), ret(level = Level::DEBUG), err)]
@@ -181,26 +371,98 @@ impl<B: BuiltinRoleSet + Send + Sync> StorageDriver for PgAuthDriver<B> {
         subject_id: i64,
         roles: HashSet<Self::BuiltinRole>,
     ) -> Result<HashSet<Self::BuiltinRole>, Self::Error> {
+        let mut conn = self.pool.get().await?;
+        strip_roles(&mut conn, subject_id, &roles).await
+    }
+
+    #[tracing::instrument(skip_all, fields(%subject_id, ?resources), ret(level = Level::DEBUG), err)]
+    async fn fetch_subject_privilege(
+        &self,
+        subject_id: i64,
+        resources: &[Resource],
+    ) -> Result<Option<Privilege>, Self::Error> {
         let conn = self.pool.get().await?;
-        let deleted_roles = dsl::delete(
-            authz_role::table
-                .filter(authz_role::subject.eq(subject_id))
-                .filter(
-                    authz_role::role.eq_any(roles.iter().map(|r| r.as_str()).collect::<Vec<_>>()),
-                ),
-        )
-        .returning(authz_role::role)
-        .load::<String>(conn.write().await.deref_mut())
-        .await?
-        .into_iter()
-        .map(|role| {
-            Self::BuiltinRole::from_str(role.as_str())
-                .ok()
-                .expect("invalid builtin role tag")
-        })
-        .collect();
+        let subject_groups = authn_group_membership::table
+            .select(authn_group_membership::group)
+            .filter(authn_group_membership::user.eq(subject_id));
+        let privilege = authz_grant::table
+            .select((
+                authz_grant::resource_type,
+                authz_grant::resource_id,
+                authz_grant::privilege,
+            ))
+            .filter(
+                authz_grant::subject
+                    .eq(subject_id)
+                    .or(authz_grant::subject.eq_any(subject_groups)),
+            )
+            .filter(authz_grant::resource_id.eq_any(resources.iter().map(|r| r.id).collect_vec()))
+            .load::<(String, i64, String)>(conn.write().await.deref_mut())
+            .await?
+            .into_iter()
+            .filter(|(resource_type, resource_id, _)| {
+                is_any_of(resources, resource_type, *resource_id)
+            })
+            .map(|(_, _, privilege)| parse_privilege(&privilege))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .max();
+        Ok(privilege)
+    }
 
-        Ok(deleted_roles)
+    #[tracing::instrument(skip_all, fields(?resources), ret(level = Level::DEBUG), err)]
+    async fn is_restricted(&self, resources: &[Resource]) -> Result<bool, Self::Error> {
+        let conn = self.pool.get().await?;
+        let restricted = authz_grant::table
+            .select((authz_grant::resource_type, authz_grant::resource_id))
+            .filter(authz_grant::resource_id.eq_any(resources.iter().map(|r| r.id).collect_vec()))
+            .load::<(String, i64)>(conn.write().await.deref_mut())
+            .await?
+            .into_iter()
+            .any(|(resource_type, resource_id)| is_any_of(resources, &resource_type, resource_id));
+        Ok(restricted)
+    }
+
+    #[tracing::instrument(skip_all, fields(%resource), err)]
+    async fn fetch_resource_grants(&self, resource: Resource) -> Result<Vec<Grant>, Self::Error> {
+        let conn = self.pool.get().await?;
+        let grants = authz_grant::table
+            .select((authz_grant::subject, authz_grant::privilege))
+            .filter(authz_grant::resource_type.eq(resource.resource_type.as_ref()))
+            .filter(authz_grant::resource_id.eq(resource.id))
+            .order_by(authz_grant::subject)
+            .load::<(i64, String)>(conn.write().await.deref_mut())
+            .await?
+            .into_iter()
+            .map(|(subject_id, privilege)| {
+                Ok(Grant {
+                    subject_id,
+                    privilege: parse_privilege(&privilege)?,
+                })
+            })
+            .collect::<Result<_, Self::Error>>()?;
+        Ok(grants)
+    }
+
+    #[tracing::instrument(skip_all, fields(%subject_id, %resource, %privilege), err)]
+    async fn ensure_subject_privilege(
+        &self,
+        subject_id: i64,
+        resource: Resource,
+        privilege: Privilege,
+    ) -> Result<(), Self::Error> {
+        let mut conn = self.pool.get().await?;
+        grant_privilege(&mut conn, subject_id, resource, privilege).await
+    }
+
+    #[tracing::instrument(skip_all, fields(%subject_id, %resource), ret(level = Level::DEBUG), err)]
+    async fn remove_subject_privilege(
+        &self,
+        subject_id: i64,
+        resource: Resource,
+    ) -> Result<bool, Self::Error> {
+        let mut conn = self.pool.get().await?;
+        revoke_privilege(&mut conn, subject_id, resource).await
     }
 }
 
@@ -254,4 +516,88 @@ mod tests {
         assert_eq!(deleted, HashSet::from([DocEdit]));
         assert_roles(&mut driver, uid, &[DocRead]).await;
     }
+
+    #[rstest::rstest]
+    async fn test_auth_driver_privileges() {
+        let pool = DbConnectionPoolV2::for_tests();
+        let driver = PgAuthDriver::<TestBuiltinRole>::new(pool.into());
+        let infra = Resource::new(ResourceType::Infra, 1);
+        let other_infra = Resource::new(ResourceType::Infra, 2);
+        let project = Resource::new(ResourceType::Project, 1);
+
+        let uid = driver
+            .ensure_user(&UserInfo {
+                identity: "toto".to_owned(),
+                name: "Sir Toto, the One and Only".to_owned(),
+            })
+            .await
+            .expect("toto should be created successfully");
+        assert!(!driver.is_restricted(&[infra]).await.unwrap());
+
+        driver
+            .ensure_subject_privilege(uid, infra, Privilege::Reader)
+            .await
+            .expect("privilege should be granted successfully");
+        driver
+            .ensure_subject_privilege(uid, infra, Privilege::Writer)
+            .await
+            .expect("privilege should be replaced successfully");
+        assert!(driver.is_restricted(&[infra]).await.unwrap());
+        assert!(!driver.is_restricted(&[other_infra, project]).await.unwrap());
+        assert_eq!(
+            driver.fetch_subject_privilege(uid, &[infra]).await.unwrap(),
+            Some(Privilege::Writer)
+        );
+        assert_eq!(
+            driver
+                .fetch_subject_privilege(uid, &[project])
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            driver.fetch_resource_grants(infra).await.unwrap(),
+            vec![Grant {
+                subject_id: uid,
+                privilege: Privilege::Writer
+            }]
+        );
+
+        assert!(driver.remove_subject_privilege(uid, infra).await.unwrap());
+        assert!(!driver.remove_subject_privilege(uid, infra).await.unwrap());
+        assert!(!driver.is_restricted(&[infra]).await.unwrap());
+    }
+
+    #[rstest::rstest]
+    async fn invalid_privilege_tags_are_errors() {
+        let pool = DbConnectionPoolV2::for_tests();
+        let driver = PgAuthDriver::<TestBuiltinRole>::new(pool.clone().into());
+        let infra = Resource::new(ResourceType::Infra, 1);
+        let uid = driver
+            .ensure_user(&UserInfo {
+                identity: "toto".to_owned(),
+                name: "Sir Toto, the One and Only".to_owned(),
+            })
+            .await
+            .expect("toto should be created successfully");
+        dsl::insert_into(authz_grant::table)
+            .values((
+                authz_grant::subject.eq(uid),
+                authz_grant::resource_type.eq(infra.resource_type.as_ref()),
+                authz_grant::resource_id.eq(infra.id),
+                authz_grant::privilege.eq("admin"),
+            ))
+            .execute(pool.get_ok().write().await.deref_mut())
+            .await
+            .expect("grant should be inserted successfully");
+
+        assert!(matches!(
+            driver.fetch_subject_privilege(uid, &[infra]).await,
+            Err(AuthDriverError::InvalidPrivilege(tag)) if tag == "admin"
+        ));
+        assert!(matches!(
+            driver.fetch_resource_grants(infra).await,
+            Err(AuthDriverError::InvalidPrivilege(_))
+        ));
+    }
 }
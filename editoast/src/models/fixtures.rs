@@ -18,10 +18,13 @@ use editoast_schemas::rolling_stock::RollingResistancePerWeight;
 use editoast_schemas::rolling_stock::RollingStock;
 use editoast_schemas::rolling_stock::RollingStockSupportedSignalingSystems;
 use editoast_schemas::rolling_stock::TowedRollingStock;
+use editoast_schemas::train_schedule::Comfort;
 use editoast_schemas::train_schedule::TrainScheduleBase;
 use postgis_diesel::types::LineString;
 use serde_json::Value;
 
+use crate::core::simulation::PhysicsConsistParameters;
+use crate::core::stdcm;
 use crate::infra_cache::operation::create::apply_create_operation;
 use crate::models::electrical_profiles::ElectricalProfileSet;
 use crate::models::prelude::*;
@@ -275,6 +278,33 @@ pub fn create_simple_rolling_stock() -> RollingStock {
     }
 }
 
+/// A core STDCM request on an empty path, only meant to be stored
+pub fn create_stdcm_core_request(infra_id: i64) -> stdcm::Request {
+    let rolling_stock = create_simple_rolling_stock();
+    stdcm::Request {
+        infra: infra_id,
+        expected_version: "1".to_owned(),
+        path_items: vec![],
+        rolling_stock_loading_gauge: rolling_stock.loading_gauge,
+        rolling_stock_supported_signaling_systems: rolling_stock
+            .supported_signaling_systems
+            .clone(),
+        comfort: Comfort::Standard,
+        speed_limit_tag: None,
+        rolling_stock: PhysicsConsistParameters::with_traction_engine(rolling_stock).into(),
+        trains_requirements: HashMap::new(),
+        time_step: Some(2000),
+        start_time: Utc::now(),
+        maximum_departure_delay: 0,
+        maximum_run_time: 0,
+        time_gap_before: 0,
+        time_gap_after: 0,
+        margin: None,
+        work_schedules: vec![],
+        temporary_speed_limits: vec![],
+    }
+}
+
 pub async fn create_document_example(conn: &mut DbConnection) -> Document {
     let img = image::open("src/tests/example_rolling_stock_image_1.gif").unwrap();
     let mut img_bytes: Vec<u8> = Vec::new();
@@ -134,6 +134,8 @@ impl Infra {
             // Disable triggers to speed up the cloning
             sql_query("ALTER TABLE infra_object_signal DISABLE TRIGGER search_signal__ins_trig").execute(conn.write().await.deref_mut()).await?;
             sql_query("ALTER TABLE infra_object_track_section DISABLE TRIGGER search_track__ins_trig").execute(conn.write().await.deref_mut()).await?;
+            sql_query("ALTER TABLE infra_object_track_section DISABLE TRIGGER search_track__geo_ins_trig").execute(conn.write().await.deref_mut()).await?;
+            sql_query("ALTER TABLE infra_object_track_section DISABLE TRIGGER search_located_object__geo_ins_trig").execute(conn.write().await.deref_mut()).await?;
             sql_query("ALTER TABLE infra_object_operational_point DISABLE TRIGGER search_operational_point__ins_trig").execute(conn.write().await.deref_mut()).await?;
 
             // Fill cloned infra with data
@@ -174,24 +176,26 @@ impl Infra {
             // Re-enable triggers to speed up the cloning
             sql_query("ALTER TABLE infra_object_signal ENABLE TRIGGER search_signal__ins_trig").execute(conn.write().await.deref_mut()).await?;
             sql_query("ALTER TABLE infra_object_track_section ENABLE TRIGGER search_track__ins_trig").execute(conn.write().await.deref_mut()).await?;
+            sql_query("ALTER TABLE infra_object_track_section ENABLE TRIGGER search_track__geo_ins_trig").execute(conn.write().await.deref_mut()).await?;
+            sql_query("ALTER TABLE infra_object_track_section ENABLE TRIGGER search_located_object__geo_ins_trig").execute(conn.write().await.deref_mut()).await?;
             sql_query("ALTER TABLE infra_object_operational_point ENABLE TRIGGER search_operational_point__ins_trig").execute(conn.write().await.deref_mut()).await?;
 
             // Fill search tables
-            sql_query("INSERT INTO search_signal(id, label, line_name, infra_id, obj_id, signaling_systems, settings, line_code)
-                        SELECT signal.id, label, line_name, $1, search_signal.obj_id, signaling_systems, settings, line_code FROM search_signal
+            sql_query("INSERT INTO search_signal(id, label, line_name, infra_id, obj_id, signaling_systems, settings, line_code, geographic)
+                        SELECT signal.id, label, line_name, $1, search_signal.obj_id, signaling_systems, settings, line_code, geographic FROM search_signal
                         JOIN infra_object_signal AS signal ON search_signal.obj_id = signal.obj_id and signal.infra_id = $1
                         WHERE search_signal.infra_id = $2")
                 .bind::<BigInt, _>(cloned_infra.id)
                 .bind::<BigInt, _>(self.id)
                 .execute(conn.write().await.deref_mut()).await?;
 
-            sql_query("INSERT INTO search_track(infra_id, line_code, line_name, unprocessed_line_name) SELECT $1, line_code, line_name, unprocessed_line_name FROM search_track WHERE infra_id = $2")
+            sql_query("INSERT INTO search_track(infra_id, line_code, line_name, unprocessed_line_name, geographic) SELECT $1, line_code, line_name, unprocessed_line_name, geographic FROM search_track WHERE infra_id = $2")
                 .bind::<BigInt, _>(cloned_infra.id)
                 .bind::<BigInt, _>(self.id)
                 .execute(conn.write().await.deref_mut()).await?;
 
-            sql_query("INSERT INTO search_operational_point(id, infra_id, obj_id, uic, trigram, ci, ch, name)
-                        SELECT op.id, $1, op.obj_id, uic, trigram, ci, ch, name FROM search_operational_point
+            sql_query("INSERT INTO search_operational_point(id, infra_id, obj_id, uic, trigram, ci, ch, name, geographic)
+                        SELECT op.id, $1, op.obj_id, uic, trigram, ci, ch, name, geographic FROM search_operational_point
                         JOIN infra_object_operational_point AS op ON search_operational_point.obj_id = op.obj_id and op.infra_id = $1
                         WHERE search_operational_point.infra_id = $2")
                 .bind::<BigInt, _>(cloned_infra.id)
@@ -265,12 +269,22 @@ impl Infra {
 
         conn.transaction(|conn| {
             Box::pin(async move {
-                // Disable the trigger to speed up the deletion
+                // Disable the triggers to speed up the deletion
                 sql_query(
                     "ALTER TABLE infra_object_track_section DISABLE TRIGGER search_track__del_trig",
                 )
                 .execute(conn.write().await.deref_mut())
                 .await?;
+                sql_query(
+                    "ALTER TABLE infra_object_track_section DISABLE TRIGGER search_track__geo_del_trig",
+                )
+                .execute(conn.write().await.deref_mut())
+                .await?;
+                sql_query(
+                    "ALTER TABLE infra_object_track_section DISABLE TRIGGER search_located_object__geo_del_trig",
+                )
+                .execute(conn.write().await.deref_mut())
+                .await?;
                 // Delete the track sections
                 delete(
                     track_section_dsl::infra_object_track_section
@@ -288,13 +302,25 @@ impl Infra {
                 .await
                 .expect("Failed to delete from search_track");
 
-                // Re-Enable the trigger to speed up the deletion
+                // Re-Enable the triggers to speed up the deletion
                 sql_query(
                     "ALTER TABLE infra_object_track_section ENABLE TRIGGER search_track__del_trig",
                 )
                 .execute(conn.write().await.deref_mut())
                 .await
                 .expect("Failed to enable trigger");
+                sql_query(
+                    "ALTER TABLE infra_object_track_section ENABLE TRIGGER search_track__geo_del_trig",
+                )
+                .execute(conn.write().await.deref_mut())
+                .await
+                .expect("Failed to enable trigger");
+                sql_query(
+                    "ALTER TABLE infra_object_track_section ENABLE TRIGGER search_located_object__geo_del_trig",
+                )
+                .execute(conn.write().await.deref_mut())
+                .await
+                .expect("Failed to enable trigger");
 
                 // Delete the rest of the infra
                 Self::delete_static(&mut conn.clone(), infra_id).await
@@ -1,10 +1,13 @@
+use std::ops::DerefMut;
+
 use diesel::pg::Pg;
 use editoast_schemas::primitives::Identifier;
+use editoast_schemas::primitives::ObjectType;
 use serde::Deserialize;
 
 use super::Infra;
 use crate::error::Result;
-use crate::generated_data::infra_error::{InfraError, InfraErrorTypeLabel};
+use crate::generated_data::infra_error::{InfraError, InfraErrorSeverity, InfraErrorTypeLabel};
 use crate::models::pagination::load_for_pagination;
 use editoast_models::DbConnection;
 
@@ -23,6 +26,7 @@ impl Infra {
         conn: &mut DbConnection,
         level: Level,
         error_type: Option<InfraErrorTypeLabel>,
+        severity: Option<InfraErrorSeverity>,
         object_id: Option<Identifier>,
         page: u64,
         page_size: u64,
@@ -50,6 +54,11 @@ impl Infra {
                 Box::new(sql::<Text>("information->>'error_type'").eq(ty.to_owned()))
             })
             .unwrap_or_else(sql_true);
+        let severity_filter: Filter = severity
+            .map(|severity| -> Filter {
+                Box::new(sql::<Text>("information->>'severity'").eq(severity.as_ref().to_owned()))
+            })
+            .unwrap_or_else(sql_true);
         let object_id_filter: Filter = object_id
             .map(|id| id.0)
             .map(|id| -> Filter { Box::new(sql::<Text>("information->>'obj_id'").eq(id)) })
@@ -60,6 +69,7 @@ impl Infra {
             .filter(dsl::infra_id.eq(self.id))
             .filter(level_filter)
             .filter(error_type_filter)
+            .filter(severity_filter)
             .filter(object_id_filter);
 
         #[derive(QueryableByName)]
@@ -72,4 +82,77 @@ impl Infra {
         let results = results.into_iter().map(|r| r.information.0).collect();
         Ok((results, count))
     }
+
+    /// Counts the errors of the infra, grouped by error type, object type and severity
+    pub async fn get_error_counts(&self, conn: &mut DbConnection) -> Result<Vec<InfraErrorCount>> {
+        use diesel::prelude::*;
+        use diesel::sql_query;
+        use diesel::sql_types::*;
+        use diesel_async::RunQueryDsl;
+
+        // Malformed values make the query fail instead of being skipped
+        #[derive(QueryableByName)]
+        struct Count {
+            #[diesel(sql_type = Jsonb)]
+            error_type: diesel_json::Json<InfraErrorTypeLabel>,
+            #[diesel(sql_type = Jsonb)]
+            obj_type: diesel_json::Json<ObjectType>,
+            #[diesel(sql_type = Jsonb)]
+            severity: diesel_json::Json<InfraErrorSeverity>,
+            #[diesel(sql_type = BigInt)]
+            count: i64,
+        }
+        let counts = sql_query(
+            "SELECT information->'error_type' AS error_type,
+                information->'obj_type' AS obj_type,
+                information->'severity' AS severity,
+                COUNT(*) AS count
+            FROM infra_layer_error
+            WHERE infra_id = $1
+            GROUP BY 1, 2, 3",
+        )
+        .bind::<BigInt, _>(self.id)
+        .load::<Count>(conn.write().await.deref_mut())
+        .await?;
+
+        Ok(counts
+            .into_iter()
+            .map(|count| InfraErrorCount {
+                error_type: count.error_type.0,
+                obj_type: count.obj_type.0,
+                severity: count.severity.0,
+                count: count.count as u64,
+            })
+            .collect())
+    }
+
+    /// Whether the infra has errors of [InfraErrorSeverity::Blocking] severity
+    pub async fn has_blocking_errors(&self, conn: &mut DbConnection) -> Result<bool> {
+        use diesel::dsl::exists;
+        use diesel::dsl::select;
+        use diesel::dsl::sql;
+        use diesel::prelude::*;
+        use diesel::sql_types::*;
+        use diesel_async::RunQueryDsl;
+        use editoast_models::tables::infra_layer_error::dsl;
+
+        let blocking_errors = dsl::infra_layer_error
+            .filter(dsl::infra_id.eq(self.id))
+            .filter(
+                sql::<Text>("information->>'severity'")
+                    .eq(InfraErrorSeverity::Blocking.as_ref().to_owned()),
+            );
+        Ok(select(exists(blocking_errors))
+            .get_result(conn.write().await.deref_mut())
+            .await?)
+    }
+}
+
+/// The number of errors of an infra sharing the same type, object type and severity
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfraErrorCount {
+    pub error_type: InfraErrorTypeLabel,
+    pub obj_type: ObjectType,
+    pub severity: InfraErrorSeverity,
+    pub count: u64,
 }
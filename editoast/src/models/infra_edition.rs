@@ -0,0 +1,119 @@
+use std::ops::DerefMut;
+
+use chrono::DateTime;
+use chrono::Utc;
+use diesel::ExpressionMethods;
+use diesel::QueryDsl;
+use diesel_async::RunQueryDsl;
+use editoast_derive::Model;
+use editoast_models::DbConnection;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::error::Result;
+use crate::infra_cache::operation::Operation;
+use crate::models::prelude::*;
+
+editoast_common::schemas! {
+    InfraEdition,
+}
+
+/// A batch of operations applied to an infra, journaled to be undone and redone
+#[derive(Clone, Debug, Serialize, Deserialize, Model, ToSchema)]
+#[model(table = editoast_models::tables::infra_edition)]
+#[model(gen(ops = cr))]
+pub struct InfraEdition {
+    pub id: i64,
+    pub infra_id: i64,
+    /// The operations of the batch, as they were applied
+    #[model(json)]
+    pub operations: Vec<Operation>,
+    /// The operations reverting the batch, in the order they must be applied
+    #[model(json)]
+    pub inverse_operations: Vec<Operation>,
+    /// Whether the batch has been undone and can be redone
+    pub undone: bool,
+    pub created: DateTime<Utc>,
+}
+
+impl InfraEdition {
+    /// Lists the edition history of an infra, latest batch first
+    pub async fn list_for_infra(conn: &mut DbConnection, infra_id: i64) -> Result<Vec<Self>> {
+        use editoast_models::tables::infra_edition::dsl;
+        let editions = dsl::infra_edition
+            .filter(dsl::infra_id.eq(infra_id))
+            .order_by(dsl::id.desc())
+            .load::<Row<InfraEdition>>(conn.write().await.deref_mut())
+            .await?;
+        Ok(editions.into_iter().map(Into::into).collect())
+    }
+
+    /// Locks the edition history of an infra until the end of the current transaction
+    ///
+    /// The infra row is locked, so that concurrent editions, undos and redos of the same infra
+    /// see the history left by each other instead of applying the same batches twice.
+    pub async fn lock_history(conn: &mut DbConnection, infra_id: i64) -> Result<()> {
+        use editoast_models::tables::infra::dsl;
+        dsl::infra
+            .filter(dsl::id.eq(infra_id))
+            .select(dsl::id)
+            .for_update()
+            .execute(conn.write().await.deref_mut())
+            .await?;
+        Ok(())
+    }
+
+    /// Retrieves the `count` latest batches of an infra that can be undone, latest first
+    ///
+    /// Must be called in a transaction, after [InfraEdition::lock_history].
+    pub async fn undoable(conn: &mut DbConnection, infra_id: i64, count: u64) -> Result<Vec<Self>> {
+        use editoast_models::tables::infra_edition::dsl;
+        let editions = dsl::infra_edition
+            .filter(dsl::infra_id.eq(infra_id))
+            .filter(dsl::undone.eq(false))
+            .order_by(dsl::id.desc())
+            .limit(count as i64)
+            .load::<Row<InfraEdition>>(conn.write().await.deref_mut())
+            .await?;
+        Ok(editions.into_iter().map(Into::into).collect())
+    }
+
+    /// Retrieves the `count` oldest undone batches of an infra, oldest first
+    ///
+    /// Must be called in a transaction, after [InfraEdition::lock_history].
+    pub async fn redoable(conn: &mut DbConnection, infra_id: i64, count: u64) -> Result<Vec<Self>> {
+        use editoast_models::tables::infra_edition::dsl;
+        let editions = dsl::infra_edition
+            .filter(dsl::infra_id.eq(infra_id))
+            .filter(dsl::undone.eq(true))
+            .order_by(dsl::id.asc())
+            .limit(count as i64)
+            .load::<Row<InfraEdition>>(conn.write().await.deref_mut())
+            .await?;
+        Ok(editions.into_iter().map(Into::into).collect())
+    }
+
+    /// Flags the given batches as undone or not
+    pub async fn set_undone(conn: &mut DbConnection, ids: &[i64], undone: bool) -> Result<()> {
+        use editoast_models::tables::infra_edition::dsl;
+        diesel::update(dsl::infra_edition.filter(dsl::id.eq_any(ids)))
+            .set(dsl::undone.eq(undone))
+            .execute(conn.write().await.deref_mut())
+            .await?;
+        Ok(())
+    }
+
+    /// Drops the undone batches of an infra, which can't be redone once a new batch is applied
+    pub async fn clear_undone(conn: &mut DbConnection, infra_id: i64) -> Result<()> {
+        use editoast_models::tables::infra_edition::dsl;
+        diesel::delete(
+            dsl::infra_edition
+                .filter(dsl::infra_id.eq(infra_id))
+                .filter(dsl::undone.eq(true)),
+        )
+        .execute(conn.write().await.deref_mut())
+        .await?;
+        Ok(())
+    }
+}
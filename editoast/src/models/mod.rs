@@ -1,9 +1,11 @@
+pub mod audit_log;
 pub mod documents;
 pub mod electrical_profiles;
 #[allow(unused)]
 #[cfg(test)]
 pub mod fixtures;
 pub mod infra;
+pub mod infra_edition;
 pub mod infra_objects;
 pub mod layers;
 pub mod stdcm_log;
@@ -17,12 +19,14 @@ pub mod railjson;
 pub mod rolling_stock_image;
 pub mod rolling_stock_livery;
 pub mod rolling_stock_model;
+pub mod saved_search;
 pub mod scenario;
 pub mod stdcm_search_environment;
 pub mod study;
 pub mod temporary_speed_limits;
 pub mod timetable;
 pub mod towed_rolling_stock;
+pub mod train_pattern;
 pub mod train_schedule;
 pub mod work_schedules;
 
@@ -39,7 +43,9 @@ pub use scenario::Scenario;
 pub use study::Study;
 
 editoast_common::schemas! {
+    audit_log::schemas(),
     infra::schemas(),
+    infra_edition::schemas(),
     projects::schemas(),
     rolling_stock_model::schemas(),
     stdcm_log::schemas(),
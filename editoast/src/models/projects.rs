@@ -1,10 +1,12 @@
 use chrono::NaiveDateTime;
 use chrono::Utc;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_derive::Model;
 use serde::Deserialize;
 use serde::Serialize;
 use utoipa::ToSchema;
 
+use crate::error::InternalError;
 use crate::error::Result;
 use crate::models::prelude::*;
 use crate::models::Document;
@@ -92,8 +94,7 @@ impl Project {
             .update_or_fail(conn, project_id, || ProjectError::NotFound { project_id })
             .await?;
         if let Some(image) = image_to_delete {
-            // We don't check the result. We don't want to throw an error if the image is used in another project.
-            let _ = Document::delete_static(conn, image).await;
+            prune_document(conn, image).await;
         }
         Ok(project)
     }
@@ -108,14 +109,25 @@ impl Project {
         let _ = Project::delete_static(conn, project_id).await?;
 
         if let Some(image) = project_obj.image {
-            // We don't check the result. We don't want to throw an error if the image is used in another project.
-            let _ = Document::delete_static(conn, image).await;
+            prune_document(conn, image).await;
         }
 
         Ok(true)
     }
 }
 
+/// Deletes the image of a project, unless another project uses it
+///
+/// We don't check the result, as we don't want to throw an error if the image is used in another
+/// project. The deletion runs in a nested transaction so that its failure doesn't abort the caller's.
+async fn prune_document(conn: &mut DbConnection, image: i64) {
+    let _ = conn
+        .transaction::<_, InternalError, _>(|conn| {
+            async move { Document::delete_static(&mut conn.clone(), image).await }.scope_boxed()
+        })
+        .await;
+}
+
 #[cfg(test)]
 pub mod tests {
     use pretty_assertions::assert_eq;
@@ -0,0 +1,25 @@
+use chrono::DateTime;
+use chrono::Utc;
+use editoast_derive::Model;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::models::prelude::*;
+
+/// A named search query, saved by a user to run it again later
+#[derive(Clone, Debug, Serialize, Deserialize, Model)]
+#[model(table = editoast_models::tables::saved_search)]
+#[model(gen(ops = crd, list))]
+pub struct SavedSearch {
+    pub id: i64,
+    /// Unique among the saved searches of its owner
+    pub name: String,
+    pub owner_identity: String,
+    /// The searched object kind, as in `/search` payloads
+    pub object: String,
+    #[model(json)]
+    pub query: serde_json::Value,
+    #[model(json)]
+    pub order_by: Vec<serde_json::Value>,
+    pub created: DateTime<Utc>,
+}
@@ -5,6 +5,7 @@ use editoast_models::DbConnection;
 use opentelemetry::trace::TraceContextExt;
 use serde::Deserialize;
 use serde::Serialize;
+use strum::AsRefStr;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 use utoipa::ToSchema;
 
@@ -14,11 +15,12 @@ use crate::models::prelude::*;
 
 editoast_common::schemas! {
     StdcmLog,
+    StdcmLogOutcome,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Model, ToSchema)]
 #[model(table = editoast_models::tables::stdcm_logs)]
-#[model(gen(ops = c))]
+#[model(gen(ops = cr, list))]
 pub struct StdcmLog {
     pub id: i64,
     pub trace_id: String,
@@ -52,3 +54,62 @@ impl StdcmLog {
         }
     }
 }
+
+/// How a logged STDCM request ended, as reported by core
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, AsRefStr, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StdcmLogOutcome {
+    Success,
+    PathNotFound,
+    PreprocessingSimulationError,
+}
+
+impl From<&Response> for StdcmLogOutcome {
+    fn from(response: &Response) -> Self {
+        match response {
+            Response::Success { .. } => Self::Success,
+            Response::PathNotFound => Self::PathNotFound,
+            Response::PreprocessingSimulationError { .. } => Self::PreprocessingSimulationError,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StdcmLogFilter {
+    pub user_id: Option<i64>,
+    /// Only keeps the logs recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only keeps the logs recorded before this time
+    pub until: Option<DateTime<Utc>>,
+    pub outcome: Option<StdcmLogOutcome>,
+}
+
+impl StdcmLogFilter {
+    pub fn apply(self, mut settings: SelectionSettings<StdcmLog>) -> SelectionSettings<StdcmLog> {
+        use diesel::ExpressionMethods;
+        use diesel::PgAnyJsonExpressionMethods;
+        use editoast_models::tables::stdcm_logs;
+
+        if let Some(user_id) = self.user_id {
+            settings = settings.filter(move || StdcmLog::USER_ID.eq(Some(user_id)));
+        }
+        if let Some(since) = self.since {
+            settings = settings.filter(move || FilterSetting::new(stdcm_logs::created.ge(since)));
+        }
+        if let Some(until) = self.until {
+            settings = settings.filter(move || FilterSetting::new(stdcm_logs::created.lt(until)));
+        }
+        if let Some(outcome) = self.outcome {
+            // The outcome is the tag of the serialized core response
+            settings = settings.filter(move || {
+                FilterSetting::new(
+                    stdcm_logs::response
+                        .retrieve_as_text("status")
+                        .eq(outcome.as_ref().to_owned()),
+                )
+            });
+        }
+        settings
+    }
+}
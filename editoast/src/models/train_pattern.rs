@@ -0,0 +1,79 @@
+use editoast_derive::Model;
+use editoast_models::DbConnection;
+use editoast_schemas::train_schedule::TrainPatternBase;
+use editoast_schemas::train_schedule::TrainPatternRecurrence;
+use editoast_schemas::train_schedule::TrainScheduleBase;
+
+use super::train_schedule::TrainSchedule;
+use super::train_schedule::TrainScheduleChangeset;
+use crate::error::Result;
+use crate::models::prelude::*;
+
+/// A train schedule repeated at regular intervals in a timetable
+///
+/// The trains of the pattern are materialized as train schedules of the timetable, which refer
+/// to the pattern they come from.
+#[derive(Debug, Clone, Model)]
+#[model(table = editoast_models::tables::train_pattern)]
+#[model(gen(ops = crud, list))]
+pub struct TrainPattern {
+    pub id: i64,
+    pub timetable_id: i64,
+    #[model(json)]
+    pub template: TrainScheduleBase,
+    #[model(json)]
+    pub recurrence: TrainPatternRecurrence,
+}
+
+impl From<TrainPatternBase> for TrainPatternChangeset {
+    fn from(
+        TrainPatternBase {
+            template,
+            recurrence,
+        }: TrainPatternBase,
+    ) -> Self {
+        TrainPattern::changeset()
+            .template(template)
+            .recurrence(recurrence)
+    }
+}
+
+impl From<TrainPattern> for TrainPatternBase {
+    fn from(pattern: TrainPattern) -> Self {
+        Self {
+            template: pattern.template,
+            recurrence: pattern.recurrence,
+        }
+    }
+}
+
+impl TrainPattern {
+    /// Retrieve the ids of the train schedules of the pattern, in departure order
+    pub async fn train_ids(&self, conn: &mut DbConnection) -> Result<Vec<i64>> {
+        let pattern_id = self.id;
+        let settings = SelectionSettings::new()
+            .filter(move || TrainSchedule::TRAIN_PATTERN_ID.eq(Some(pattern_id)))
+            .order_by(|| TrainSchedule::START_TIME.asc());
+        let train_schedules = TrainSchedule::list(conn, settings).await?;
+        Ok(train_schedules
+            .into_iter()
+            .map(|train_schedule| train_schedule.id)
+            .collect())
+    }
+
+    /// Create the train schedules of the pattern, replacing the ones it had
+    pub async fn materialize(&self, conn: &mut DbConnection) -> Result<Vec<TrainSchedule>> {
+        let previous_train_ids = self.train_ids(conn).await?;
+        TrainSchedule::delete_batch(conn, previous_train_ids).await?;
+
+        let changesets = TrainPatternBase::from(self.clone())
+            .train_schedules()
+            .map(|train_schedule| {
+                TrainScheduleChangeset::from(train_schedule)
+                    .timetable_id(self.timetable_id)
+                    .train_pattern_id(Some(self.id))
+            })
+            .collect::<Vec<_>>();
+        TrainSchedule::create_batch(conn, changesets).await
+    }
+}
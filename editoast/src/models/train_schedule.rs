@@ -38,6 +38,8 @@ pub struct TrainSchedule {
     pub power_restrictions: Vec<PowerRestrictionItem>,
     #[model(json)]
     pub options: TrainScheduleOptions,
+    /// The pattern the train schedule was materialized from, if any
+    pub train_pattern_id: Option<i64>,
 }
 
 impl From<TrainScheduleBase> for TrainScheduleChangeset {
@@ -0,0 +1,138 @@
+use axum::extract::Json;
+use axum::extract::Query;
+use axum::extract::State;
+use axum::Extension;
+use chrono::DateTime;
+use chrono::Utc;
+use editoast_authz::BuiltinRole;
+use editoast_models::DbConnectionPoolV2;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::IntoParams;
+use utoipa::ToSchema;
+
+use crate::error::Result;
+use crate::models::audit_log::AuditLog;
+use crate::models::audit_log::AuditLogFilter;
+use crate::models::prelude::*;
+use crate::views::pagination::PaginatedList as _;
+use crate::views::pagination::PaginationQueryParams;
+use crate::views::pagination::PaginationStats;
+use crate::views::AuthenticationExt;
+use crate::views::AuthorizationError;
+
+crate::routes! {
+    "/audit" => list,
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AuditQueryParams {
+    /// Only list the changes made by this user
+    user_id: Option<i64>,
+    /// Only list the changes of this type of resource, `subject` being used for users and groups
+    resource_type: Option<String>,
+    resource_id: Option<i64>,
+    /// Only list the changes made at or after this time
+    since: Option<DateTime<Utc>>,
+}
+
+impl From<AuditQueryParams> for AuditLogFilter {
+    fn from(params: AuditQueryParams) -> Self {
+        Self {
+            user_id: params.user_id,
+            resource_type: params.resource_type,
+            resource_id: params.resource_id,
+            since: params.since,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct AuditLogListResponse {
+    results: Vec<AuditLog>,
+    #[serde(flatten)]
+    stats: PaginationStats,
+}
+
+/// List the changes made through the API, latest first
+#[utoipa::path(
+    get, path = "",
+    tag = "audit",
+    params(PaginationQueryParams, AuditQueryParams),
+    responses(
+        (status = 200, body = inline(AuditLogListResponse), description = "The audit log entries"),
+    )
+)]
+async fn list(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Query(pagination_params): Query<PaginationQueryParams>,
+    Query(audit_params): Query<AuditQueryParams>,
+) -> Result<Json<AuditLogListResponse>> {
+    let authorized = auth
+        .check_roles([BuiltinRole::AuditRead].into())
+        .await
+        .map_err(AuthorizationError::AuthError)?;
+    if !authorized {
+        return Err(AuthorizationError::Unauthorized.into());
+    }
+
+    let settings = pagination_params
+        .validate(1000)?
+        .warn_page_size(100)
+        .into_selection_settings()
+        .order_by(|| AuditLog::ID.desc());
+    let settings = AuditLogFilter::from(audit_params).apply(settings);
+
+    let conn = &mut db_pool.get().await?;
+    let (results, stats) = AuditLog::list_paginated(conn, settings).await?;
+    Ok(Json(AuditLogListResponse { results, stats }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::*;
+    use crate::views::test_app::TestAppBuilder;
+
+    #[rstest]
+    async fn project_changes_are_audited() {
+        let app = TestAppBuilder::default_app();
+
+        let request = app
+            .post("/projects")
+            .json(&json!({ "name": "audited project" }));
+        let project: serde_json::Value =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+        let project_id = project["id"]
+            .as_i64()
+            .expect("the project should have an id");
+
+        let request = app.delete(&format!("/projects/{project_id}"));
+        app.fetch(request).assert_status(StatusCode::NO_CONTENT);
+
+        let request = app.get(&format!(
+            "/audit?resource_type=project&resource_id={project_id}"
+        ));
+        let response: AuditLogListResponse =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let actions = response
+            .results
+            .iter()
+            .map(|entry| entry.action.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(actions, vec!["delete", "create"]);
+        assert_eq!(
+            response.results[1].summary,
+            "Created project 'audited project'"
+        );
+        assert_eq!(response.stats.count, 2);
+    }
+}
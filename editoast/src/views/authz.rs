@@ -1,15 +1,33 @@
 use std::collections::HashSet;
 
+use crate::error::InternalError;
 use crate::error::Result;
+use crate::models::audit_log::AuditAction;
+use crate::models::audit_log::AuditTarget;
 use crate::models::auth::{AuthDriverError, PgAuthDriver};
+use crate::models::prelude::*;
+use crate::models::timetable::Timetable;
+use crate::models::Infra;
+use crate::models::Project;
+use crate::models::RollingStockModel;
+use crate::models::Scenario;
+use crate::models::Study;
 use axum::extract::Path;
+use axum::extract::State;
 use axum::response::Json;
 use axum::Extension;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::authorizer::Authorizer;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
+use editoast_models::DbConnection;
+use editoast_models::DbConnectionPoolV2;
+use itertools::Itertools;
 
-use super::{AuthenticationExt, AuthorizationError};
+use super::{Authentication, AuthenticationExt, AuthorizationError};
 
 crate::routes! {
     "/authz/roles" => {
@@ -20,10 +38,17 @@ crate::routes! {
             strip_roles,
         },
     },
+    "/authz/resources/{resource_type}/{resource_id}" => {
+        list_grants,
+        grant_privilege,
+        "/{subject_id}" => revoke_privilege,
+    },
 }
 
 editoast_common::schemas! {
     BuiltinRole,
+    Privilege,
+    ResourceType,
 }
 
 #[derive(serde::Deserialize, utoipa::IntoParams)]
@@ -40,6 +65,13 @@ enum AuthzError {
     Driver(#[from] AuthDriverError),
     #[error("Authorization error")]
     Authz(#[from] AuthorizationError),
+    #[error("Subject {subject_id} is the last owner of {resource_type} {resource_id}")]
+    #[editoast_error(status = 409)]
+    LastOwner {
+        resource_type: String,
+        resource_id: i64,
+        subject_id: i64,
+    },
 }
 
 #[derive(Debug, thiserror::Error, EditoastError)]
@@ -50,6 +82,20 @@ enum NoSuchUserError {
     NoSuchUser { user_id: i64 },
 }
 
+#[derive(Debug, thiserror::Error, EditoastError)]
+#[editoast_error(base_id = "authz")]
+enum NoSuchResourceError {
+    #[error("No {resource_type} with ID {resource_id} found")]
+    #[editoast_error(status = 404)]
+    NoSuchResource {
+        resource_type: String,
+        resource_id: i64,
+    },
+    #[error("No user or group with ID {subject_id} found")]
+    #[editoast_error(status = 404)]
+    NoSuchSubject { subject_id: i64 },
+}
+
 #[derive(serde::Serialize, utoipa::ToSchema)]
 struct Roles {
     builtin: HashSet<BuiltinRole>,
@@ -133,6 +179,7 @@ struct RoleListBody {
     ),
 )]
 async fn grant_roles(
+    State(db_pool): State<DbConnectionPoolV2>,
     Path(UserIdPathParam { user_id }): Path<UserIdPathParam>,
     Extension(auth): AuthenticationExt,
     Json(RoleListBody { roles }): Json<RoleListBody>,
@@ -145,13 +192,30 @@ async fn grant_roles(
         return Err(AuthorizationError::Unauthorized.into());
     }
 
-    let mut authorizer = auth.authorizer()?;
+    let authorizer = auth.clone().authorizer()?;
     check_user_exists(user_id, &authorizer).await?;
 
-    authorizer
-        .grant_roles(user_id, HashSet::from_iter(roles))
-        .await
-        .map_err(AuthzError::from)?;
+    let summary = format!("Granted roles {}", roles.iter().join(", "));
+    let roles = HashSet::from_iter(roles);
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                crate::models::auth::grant_roles(&mut conn.clone(), user_id, &roles)
+                    .await
+                    .map_err(AuthzError::from)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Grant,
+                    AuditTarget::Subject(user_id),
+                    summary,
+                )
+                .await
+            }
+            .scope_boxed()
+        })
+        .await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
@@ -165,6 +229,7 @@ async fn grant_roles(
     ),
 )]
 async fn strip_roles(
+    State(db_pool): State<DbConnectionPoolV2>,
     Path(UserIdPathParam { user_id }): Path<UserIdPathParam>,
     Extension(auth): AuthenticationExt,
     Json(RoleListBody { roles }): Json<RoleListBody>,
@@ -177,12 +242,308 @@ async fn strip_roles(
         return Err(AuthorizationError::Unauthorized.into());
     }
 
-    let mut authorizer = auth.authorizer()?;
+    let authorizer = auth.clone().authorizer()?;
     check_user_exists(user_id, &authorizer).await?;
 
-    authorizer
-        .strip_roles(user_id, HashSet::from_iter(roles))
+    let summary = format!("Stripped roles {}", roles.iter().join(", "));
+    let roles = HashSet::from_iter(roles);
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                crate::models::auth::strip_roles::<BuiltinRole>(&mut conn.clone(), user_id, &roles)
+                    .await
+                    .map_err(AuthzError::from)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Revoke,
+                    AuditTarget::Subject(user_id),
+                    summary,
+                )
+                .await
+            }
+            .scope_boxed()
+        })
+        .await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+struct ResourcePathParam {
+    resource_type: ResourceType,
+    resource_id: i64,
+}
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+struct SubjectIdPathParam {
+    /// The ID of a user or a group
+    subject_id: i64,
+}
+
+/// The builtin role required to read or manage the privileges granted over a kind of resource
+fn required_role(resource_type: ResourceType, write: bool) -> BuiltinRole {
+    match (resource_type, write) {
+        (ResourceType::Infra, false) => BuiltinRole::InfraRead,
+        (ResourceType::Infra, true) => BuiltinRole::InfraWrite,
+        (ResourceType::Project | ResourceType::Study | ResourceType::Scenario, false) => {
+            BuiltinRole::OpsRead
+        }
+        (ResourceType::Project | ResourceType::Study | ResourceType::Scenario, true) => {
+            BuiltinRole::OpsWrite
+        }
+        (ResourceType::Timetable, false) => BuiltinRole::TimetableRead,
+        (ResourceType::Timetable, true) => BuiltinRole::TimetableWrite,
+        (ResourceType::RollingStock, false) => BuiltinRole::RollingStockCollectionRead,
+        (ResourceType::RollingStock, true) => BuiltinRole::RollingStockCollectionWrite,
+    }
+}
+
+/// Returns the resource followed by its ancestors, whose privileges apply to the resource
+async fn resource_ancestry(conn: &mut DbConnection, resource: Resource) -> Result<Vec<Resource>> {
+    let not_found = || NoSuchResourceError::NoSuchResource {
+        resource_type: resource.resource_type.to_string(),
+        resource_id: resource.id,
+    };
+    let exists = match resource.resource_type {
+        ResourceType::Infra => Infra::exists(conn, resource.id).await?,
+        ResourceType::Project => Project::exists(conn, resource.id).await?,
+        ResourceType::Timetable => Timetable::exists(conn, resource.id).await?,
+        ResourceType::RollingStock => RollingStockModel::exists(conn, resource.id).await?,
+        ResourceType::Study => {
+            let study = Study::retrieve_or_fail(conn, resource.id, not_found).await?;
+            return Ok(vec![
+                resource,
+                Resource::new(ResourceType::Project, study.project_id),
+            ]);
+        }
+        ResourceType::Scenario => {
+            let scenario = Scenario::retrieve_or_fail(conn, resource.id, not_found).await?;
+            let study = Study::retrieve_or_fail(conn, scenario.study_id, not_found).await?;
+            return Ok(vec![
+                resource,
+                Resource::new(ResourceType::Study, study.id),
+                Resource::new(ResourceType::Project, study.project_id),
+            ]);
+        }
+    };
+    if !exists {
+        return Err(not_found().into());
+    }
+    Ok(vec![resource])
+}
+
+/// Checks that the issuer of the request can read or manage the privileges granted over a resource
+async fn check_resource_access(
+    db_pool: &DbConnectionPoolV2,
+    auth: &Authentication,
+    resource: Resource,
+    write: bool,
+) -> Result<()> {
+    let resources = resource_ancestry(&mut db_pool.get().await?, resource).await?;
+    let required_privilege = if write {
+        Privilege::Owner
+    } else {
+        Privilege::Reader
+    };
+    auth.authorize(
+        [required_role(resource.resource_type, write)].into(),
+        &[(&resources, required_privilege)],
+    )
+    .await?;
+    Ok(())
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct ResourceGrant {
+    /// The ID of the user or group
+    subject_id: i64,
+    privilege: Privilege,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct ResourceGrants {
+    /// The privileges granted directly over the resource, excluding the ones granted over its ancestors
+    grants: Vec<ResourceGrant>,
+}
+
+#[utoipa::path(
+    get, path = "",
+    tag = "authz",
+    params(ResourcePathParam),
+    responses(
+        (status = 200, description = "List the users and groups having a privilege over a resource", body = inline(ResourceGrants)),
+    ),
+)]
+async fn list_grants(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Path(ResourcePathParam {
+        resource_type,
+        resource_id,
+    }): Path<ResourcePathParam>,
+    Extension(auth): AuthenticationExt,
+) -> Result<Json<ResourceGrants>> {
+    let resource = Resource::new(resource_type, resource_id);
+    check_resource_access(&db_pool, &auth, resource, false).await?;
+
+    let authorizer = auth.authorizer()?;
+    let grants = authorizer
+        .resource_grants(resource)
         .await
-        .map_err(AuthzError::from)?;
+        .map_err(AuthzError::from)?
+        .into_iter()
+        .map(|grant| ResourceGrant {
+            subject_id: grant.subject_id,
+            privilege: grant.privilege,
+        })
+        .collect();
+    Ok(Json(ResourceGrants { grants }))
+}
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+struct GrantPrivilegeBody {
+    /// The ID of the user or group
+    subject_id: i64,
+    privilege: Privilege,
+}
+
+#[utoipa::path(
+    post, path = "",
+    tag = "authz",
+    params(ResourcePathParam),
+    request_body = inline(GrantPrivilegeBody),
+    responses(
+        (status = 204, description = "The privilege has been granted sucessfully, replacing the one previously granted to the subject"),
+    ),
+)]
+async fn grant_privilege(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Path(ResourcePathParam {
+        resource_type,
+        resource_id,
+    }): Path<ResourcePathParam>,
+    Extension(auth): AuthenticationExt,
+    Json(GrantPrivilegeBody {
+        subject_id,
+        privilege,
+    }): Json<GrantPrivilegeBody>,
+) -> Result<impl axum::response::IntoResponse> {
+    let resource = Resource::new(resource_type, resource_id);
+    check_resource_access(&db_pool, &auth, resource, true).await?;
+
+    let authorizer = auth.clone().authorizer()?;
+    check_subject_exists(subject_id, &authorizer).await?;
+    if privilege != Privilege::Owner {
+        check_not_last_owner(&authorizer, resource, subject_id).await?;
+    }
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                crate::models::auth::grant_privilege(
+                    &mut conn.clone(),
+                    subject_id,
+                    resource,
+                    privilege,
+                )
+                .await
+                .map_err(AuthzError::from)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Grant,
+                    resource,
+                    format!("Granted privilege {privilege} to subject {subject_id}"),
+                )
+                .await
+            }
+            .scope_boxed()
+        })
+        .await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
+
+#[utoipa::path(
+    delete, path = "",
+    tag = "authz",
+    params(ResourcePathParam, SubjectIdPathParam),
+    responses(
+        (status = 204, description = "The privilege has been revoked sucessfully"),
+    ),
+)]
+async fn revoke_privilege(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Path((resource_type, resource_id, subject_id)): Path<(ResourceType, i64, i64)>,
+    Extension(auth): AuthenticationExt,
+) -> Result<impl axum::response::IntoResponse> {
+    let resource = Resource::new(resource_type, resource_id);
+    check_resource_access(&db_pool, &auth, resource, true).await?;
+
+    let authorizer = auth.clone().authorizer()?;
+    check_subject_exists(subject_id, &authorizer).await?;
+    check_not_last_owner(&authorizer, resource, subject_id).await?;
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                let revoked =
+                    crate::models::auth::revoke_privilege(&mut conn.clone(), subject_id, resource)
+                        .await
+                        .map_err(AuthzError::from)?;
+                if revoked {
+                    auth.audit(
+                        &mut conn.clone(),
+                        AuditAction::Revoke,
+                        resource,
+                        format!("Revoked the privilege of subject {subject_id}"),
+                    )
+                    .await?;
+                }
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
+}
+
+async fn check_subject_exists(
+    subject_id: i64,
+    authorizer: &Authorizer<PgAuthDriver<BuiltinRole>>,
+) -> Result<()> {
+    if !authorizer
+        .subject_exists(subject_id)
+        .await
+        .map_err(AuthzError::from)?
+    {
+        return Err(NoSuchResourceError::NoSuchSubject { subject_id }.into());
+    }
+
+    Ok(())
+}
+
+/// Refuses to take the privilege of the last owner of a resource, which nobody could manage anymore
+async fn check_not_last_owner(
+    authorizer: &Authorizer<PgAuthDriver<BuiltinRole>>,
+    resource: Resource,
+    subject_id: i64,
+) -> Result<()> {
+    let owners = authorizer
+        .resource_grants(resource)
+        .await
+        .map_err(AuthzError::from)?
+        .into_iter()
+        .filter(|grant| grant.privilege == Privilege::Owner)
+        .map(|grant| grant.subject_id)
+        .collect_vec();
+    if owners == [subject_id] {
+        return Err(AuthzError::LastOwner {
+            resource_type: resource.resource_type.to_string(),
+            resource_id: resource.id,
+            subject_id,
+        }
+        .into());
+    }
+    Ok(())
+}
@@ -5,6 +5,9 @@ use axum::extract::Path;
 use axum::extract::State;
 use axum::Extension;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use serde::Deserialize;
 use thiserror::Error;
@@ -15,7 +18,6 @@ use crate::models::prelude::*;
 use crate::models::Infra;
 use crate::views::infra::InfraApiError;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 use editoast_schemas::primitives::ObjectType;
 
@@ -72,13 +74,14 @@ async fn attached(
     }): State<AppState>,
     Extension(auth): AuthenticationExt,
 ) -> Result<Json<HashMap<ObjectType, Vec<String>>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let mut conn = db_pool.get().await?;
     // TODO: lock for share
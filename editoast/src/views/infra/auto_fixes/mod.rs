@@ -5,17 +5,26 @@ use axum::extract::Json;
 use axum::extract::Path;
 use axum::extract::State;
 use axum::Extension;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
+use editoast_models::DbConnection;
 use itertools::Itertools as _;
+use serde::Deserialize;
+use serde::Serialize;
 use thiserror::Error;
 use tracing::debug;
 use tracing::error;
+use utoipa::ToSchema;
 
 use crate::error::InternalError;
 use crate::error::Result;
 use crate::generated_data::generate_infra_errors;
 use crate::generated_data::infra_error::InfraError;
+use crate::generated_data::infra_error::InfraErrorTypeLabel;
 use crate::infra_cache::operation::patch_infra_object;
 use crate::infra_cache::operation::CacheOperation;
 use crate::infra_cache::operation::DeleteOperation;
@@ -23,12 +32,16 @@ use crate::infra_cache::operation::Operation;
 use crate::infra_cache::operation::UpdateOperation;
 use crate::infra_cache::InfraCache;
 use crate::infra_cache::ObjectCache;
+use crate::map;
+use crate::models::audit_log::AuditAction;
 use crate::models::prelude::*;
 use crate::models::Infra;
+use crate::views::infra::edition::apply_edit;
+use crate::views::infra::edition::edition_summary;
+use crate::views::infra::edition::EditionJournal;
 use crate::views::infra::InfraApiError;
 use crate::views::infra::InfraIdParam;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 use editoast_schemas::infra::InfraObject;
 use editoast_schemas::primitives::OSRDIdentified as _;
@@ -71,7 +84,74 @@ pub enum OrderedOperation {
 
 // Return `/infra/<infra_id>/auto_fixes` routes
 crate::routes! {
-    "/auto_fixes" => list_auto_fixes,
+    "/auto_fixes" => {
+        list_auto_fixes,
+        apply_auto_fixes,
+    },
+}
+
+editoast_common::schemas! {
+    AutoFixesFilter,
+    AutoFixesIteration,
+    AutoFixesReport,
+    AutoFixesRequest,
+}
+
+/// Restricts the infra errors that auto-fixes are computed for
+#[derive(Debug, Default, Clone, Deserialize, ToSchema)]
+pub struct AutoFixesFilter {
+    /// Only fix errors of these types (all types if empty)
+    #[serde(default)]
+    pub error_types: Vec<InfraErrorTypeLabel>,
+    /// Only fix errors on objects of these types (all types if empty)
+    #[serde(default)]
+    pub object_types: Vec<ObjectType>,
+}
+
+impl AutoFixesFilter {
+    fn matches(&self, infra_error: &InfraError) -> bool {
+        (self.error_types.is_empty()
+            || self
+                .error_types
+                .contains(&InfraErrorTypeLabel::from(&infra_error.sub_type)))
+            && (self.object_types.is_empty() || self.object_types.contains(&infra_error.obj_type))
+    }
+
+    fn filter_errors(&self, infra_errors: Vec<InfraError>) -> Vec<InfraError> {
+        infra_errors
+            .into_iter()
+            .filter(|infra_error| self.matches(infra_error))
+            .collect()
+    }
+}
+
+/// An iteration of auto-fixes
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+pub struct AutoFixesIteration {
+    /// The infra errors present before the iteration, some of which it fixes
+    pub errors_before: Vec<InfraError>,
+    /// The operations fixing the errors
+    pub operations: Vec<Operation>,
+    /// The infra errors remaining once the operations are applied
+    pub errors_after: Vec<InfraError>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct AutoFixesRequest {
+    /// Only compute the fixes, without applying them
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(flatten)]
+    filter: AutoFixesFilter,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct AutoFixesReport {
+    /// Whether the fixes were only computed, and not applied
+    dry_run: bool,
+    iterations: Vec<AutoFixesIteration>,
 }
 
 /// Retrieve a list of operations to fix infra issues
@@ -92,13 +172,14 @@ async fn list_auto_fixes(
     }): State<AppState>,
     Extension(auth): AuthenticationExt,
 ) -> Result<Json<Vec<Operation>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
 
@@ -110,15 +191,128 @@ async fn list_auto_fixes(
         .await?
         .clone();
 
-    let mut fixes = vec![];
+    let iterations =
+        compute_auto_fixes(&mut infra_cache_clone, &AutoFixesFilter::default()).await?;
+    let fixes = iterations
+        .into_iter()
+        .flat_map(|iteration| iteration.operations)
+        .collect();
+    Ok(Json(fixes))
+}
+
+/// Fix infra issues server-side
+///
+/// The fixes are computed iteratively like the suggested operations, then applied in a
+/// single edition of the infra. With `dry_run`, the fixes are only computed.
+#[utoipa::path(
+    post, path = "",
+    tag = "infra",
+    params(InfraIdParam),
+    request_body = AutoFixesRequest,
+    responses(
+        (status = 200, description = "The iterations of fixes and the errors they fix", body = AutoFixesReport),
+        (status = 404, description = "The infra was not found"),
+    )
+)]
+async fn apply_auto_fixes(
+    Path(InfraIdParam { infra_id }): Path<InfraIdParam>,
+    State(AppState {
+        infra_caches,
+        db_pool,
+        valkey,
+        map_layers,
+        ..
+    }): State<AppState>,
+    Extension(auth): AuthenticationExt,
+    Json(AutoFixesRequest { dry_run, filter }): Json<AutoFixesRequest>,
+) -> Result<Json<AutoFixesReport>> {
+    let (role, privilege) = if dry_run {
+        (BuiltinRole::InfraRead, Privilege::Reader)
+    } else {
+        (BuiltinRole::InfraWrite, Privilege::Writer)
+    };
+    auth.authorize(
+        [role].into(),
+        &[(&[Resource::new(ResourceType::Infra, infra_id)], privilege)],
+    )
+    .await?;
+
+    let mut infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
+        InfraApiError::NotFound { infra_id }
+    })
+    .await?;
+    let mut infra_cache =
+        InfraCache::get_or_load_mut(&mut db_pool.get().await?, &infra_caches, &infra).await?;
+    let iterations = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                let iterations = compute_auto_fixes(&mut infra_cache.clone(), &filter).await?;
+                if !dry_run && !iterations.is_empty() {
+                    let operations = iterations
+                        .iter()
+                        .flat_map(|iteration| iteration.operations.clone())
+                        .collect_vec();
+                    auth.audit(
+                        &mut conn.clone(),
+                        AuditAction::Update,
+                        Resource::new(ResourceType::Infra, infra_id),
+                        format!("Applied auto fixes: {}", edition_summary(&operations)),
+                    )
+                    .await?;
+                    apply_edit(
+                        &mut conn.clone(),
+                        &mut infra,
+                        &operations,
+                        &mut infra_cache,
+                        EditionJournal::Record,
+                    )
+                    .await?;
+                }
+                Ok(iterations)
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+    if !dry_run && !iterations.is_empty() {
+        let mut conn = valkey.get_connection().await?;
+        map::invalidate_all(
+            &mut conn,
+            &map_layers.layers.keys().cloned().collect(),
+            infra_id,
+        )
+        .await?;
+    }
+
+    Ok(Json(AutoFixesReport {
+        dry_run,
+        iterations,
+    }))
+}
+
+/// Computes the fixes of the infra errors matching the filter, iteration after iteration
+///
+/// The fixes are applied to the given infra cache along the way.
+async fn compute_auto_fixes(
+    infra_cache: &mut InfraCache,
+    filter: &AutoFixesFilter,
+) -> Result<Vec<AutoFixesIteration>> {
+    let mut iterations = vec![];
+    let mut infra_errors = filter.filter_errors(generate_infra_errors(infra_cache).await);
     for _ in 0..MAX_AUTO_FIXES_ITERATIONS {
-        let infra_errors = generate_infra_errors(&infra_cache_clone).await;
-        let new_fixes = fix_infra(&mut infra_cache_clone, infra_errors)?;
-        if new_fixes.is_empty() {
+        let operations = fix_infra(infra_cache, infra_errors.clone())?;
+        if operations.is_empty() {
             // Every possible error is fixed
-            return Ok(Json(fixes));
+            return Ok(iterations);
         }
-        fixes.extend(new_fixes);
+        let errors_after = filter.filter_errors(generate_infra_errors(infra_cache).await);
+        iterations.push(AutoFixesIteration {
+            errors_before: std::mem::replace(&mut infra_errors, errors_after.clone()),
+            operations,
+            errors_after,
+        });
     }
 
     // Reapplying an auto-fix should do nothing.
@@ -127,6 +321,38 @@ async fn list_auto_fixes(
     Err(AutoFixesEditoastError::MaximumIterationReached.into())
 }
 
+/// Computes the fixes of the infra errors matching the filter and applies them, unless `dry_run` is set
+///
+/// The fixes are computed on a copy of the infra cache. They are then applied in a single
+/// edition, which is recorded in the edition history of the infra.
+pub async fn fix_and_apply(
+    conn: &mut DbConnection,
+    infra: &mut Infra,
+    infra_cache: &mut InfraCache,
+    filter: &AutoFixesFilter,
+    dry_run: bool,
+) -> Result<Vec<AutoFixesIteration>> {
+    let mut infra_cache_clone = infra_cache.clone();
+    let iterations = compute_auto_fixes(&mut infra_cache_clone, filter).await?;
+    if dry_run || iterations.is_empty() {
+        return Ok(iterations);
+    }
+
+    let operations = iterations
+        .iter()
+        .flat_map(|iteration| iteration.operations.clone())
+        .collect_vec();
+    apply_edit(
+        conn,
+        infra,
+        &operations,
+        infra_cache,
+        EditionJournal::Record,
+    )
+    .await?;
+    Ok(iterations)
+}
+
 fn fix_infra(
     infra_cache: &mut InfraCache,
     infra_errors: Vec<InfraError>,
@@ -348,6 +574,7 @@ mod tests {
     use crate::views::infra::errors::query_errors;
     use crate::views::test_app::TestApp;
     use crate::views::test_app::TestAppBuilder;
+    use editoast_models::DbConnectionPoolV2;
     use editoast_schemas::infra::ApplicableDirectionsTrackRange;
     use editoast_schemas::infra::Detector;
     use editoast_schemas::infra::Electrification;
@@ -366,11 +593,21 @@ mod tests {
     use editoast_schemas::primitives::Identifier;
     use editoast_schemas::primitives::ObjectRef;
     use editoast_schemas::primitives::ObjectType;
+    use serde_json::json;
 
     impl TestApp {
         fn auto_fixes_request(&self, infra_id: i64) -> axum_test::TestRequest {
             self.get(format!("/infra/{infra_id}/auto_fixes").as_str())
         }
+
+        fn apply_auto_fixes_request(
+            &self,
+            infra_id: i64,
+            body: serde_json::Value,
+        ) -> axum_test::TestRequest {
+            self.post(format!("/infra/{infra_id}/auto_fixes").as_str())
+                .json(&body)
+        }
     }
 
     #[rstest::rstest]
@@ -991,4 +1228,103 @@ mod tests {
         positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
         assert_eq!(positions, vec![0., 1_000.0]);
     }
+
+    async fn create_track_without_buffer_stops(db_pool: &DbConnectionPoolV2) -> Infra {
+        let empty_infra = create_empty_infra(&mut db_pool.get_ok()).await;
+        let track: InfraObject = TrackSection {
+            id: "track_with_no_buffer_stops".into(),
+            length: 1_000.0,
+            geo: geos::geojson::Geometry::new(geos::geojson::Value::LineString(vec![
+                vec![0., 0.],
+                vec![1., 1.],
+            ])),
+            ..Default::default()
+        }
+        .into();
+        apply_create_operation(&track, empty_infra.id, &mut db_pool.get_ok())
+            .await
+            .expect("Failed to create track section object");
+        empty_infra
+    }
+
+    async fn get_buffer_stops(db_pool: &DbConnectionPoolV2, infra: &Infra) -> Vec<String> {
+        let infra_cache = InfraCache::load(&mut db_pool.get_ok(), infra)
+            .await
+            .expect("Failed to load infra cache");
+        infra_cache.buffer_stops().keys().cloned().collect()
+    }
+
+    #[rstest::rstest]
+    async fn apply_auto_fixes_dry_run() {
+        // GIVEN
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+        let infra = create_track_without_buffer_stops(&db_pool).await;
+
+        // WHEN
+        let report: AutoFixesReport = app
+            .fetch(app.apply_auto_fixes_request(infra.id, json!({ "dry_run": true })))
+            .assert_status(StatusCode::OK)
+            .json_into();
+
+        // THEN
+        assert!(report.dry_run);
+        assert_eq!(report.iterations.len(), 1);
+        let iteration = &report.iterations[0];
+        assert_eq!(iteration.operations.len(), 2);
+        assert!(iteration
+            .errors_before
+            .iter()
+            .any(|e| matches!(e.sub_type, InfraErrorType::MissingBufferStop { .. })));
+        assert!(!iteration
+            .errors_after
+            .iter()
+            .any(|e| matches!(e.sub_type, InfraErrorType::MissingBufferStop { .. })));
+        assert!(get_buffer_stops(&db_pool, &infra).await.is_empty());
+    }
+
+    #[rstest::rstest]
+    async fn apply_auto_fixes() {
+        // GIVEN
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+        let infra = create_track_without_buffer_stops(&db_pool).await;
+
+        // WHEN
+        let report: AutoFixesReport = app
+            .fetch(app.apply_auto_fixes_request(infra.id, json!({})))
+            .assert_status(StatusCode::OK)
+            .json_into();
+
+        // THEN
+        assert!(!report.dry_run);
+        assert_eq!(report.iterations.len(), 1);
+        assert_eq!(get_buffer_stops(&db_pool, &infra).await.len(), 2);
+        let operations: Vec<Operation> = app
+            .fetch(app.auto_fixes_request(infra.id))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        assert!(operations.is_empty());
+    }
+
+    #[rstest::rstest]
+    async fn apply_auto_fixes_with_filter() {
+        // GIVEN
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+        let infra = create_track_without_buffer_stops(&db_pool).await;
+
+        // WHEN
+        let report: AutoFixesReport = app
+            .fetch(app.apply_auto_fixes_request(
+                infra.id,
+                json!({ "object_types": ["Signal"], "error_types": ["missing_buffer_stop"] }),
+            ))
+            .assert_status(StatusCode::OK)
+            .json_into();
+
+        // THEN
+        assert!(report.iterations.is_empty());
+        assert!(get_buffer_stops(&db_pool, &infra).await.is_empty());
+    }
 }
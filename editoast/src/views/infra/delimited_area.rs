@@ -2,12 +2,15 @@ use crate::error::Result;
 use crate::infra_cache::{Graph, InfraCache};
 use crate::models::Infra;
 use crate::views::infra::{InfraApiError, InfraIdParam};
-use crate::views::{AuthenticationExt, AuthorizationError};
+use crate::views::AuthenticationExt;
 use crate::AppState;
 use crate::Retrieve;
 use axum::extract::{Path, State};
 use axum::{Extension, Json};
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_schemas::{
     infra::{Direction, DirectionalTrackRange, Endpoint, Sign, TrackEndpoint},
@@ -137,13 +140,14 @@ async fn delimited_area(
 ) -> Result<Json<DelimitedAreaResponse>> {
     // TODO in case of a missing exit, return an empty list of track ranges instead of returning all
     // the track ranges explored until the stopping condition ?
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     // Retrieve the infra
 
@@ -0,0 +1,170 @@
+use axum::extract::Json;
+use axum::extract::Path;
+use axum::extract::State;
+use axum::Extension;
+use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::error::Result;
+use crate::infra_cache::diff::diff_operations;
+use crate::infra_cache::diff::CacheDiff;
+use crate::infra_cache::operation::Operation;
+use crate::infra_cache::InfraCache;
+use crate::models::prelude::*;
+use crate::models::Infra;
+use crate::views::infra::InfraApiError;
+use crate::views::infra::InfraIdParam;
+use crate::views::AuthenticationExt;
+use crate::AppState;
+
+crate::routes! {
+    "/diff/{other_infra_id}" => diff,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+struct OtherInfraIdParam {
+    /// The infra to compare with
+    other_infra_id: i64,
+}
+
+/// Compare the objects of two infras
+///
+/// Returns the operations that turn the infra into the other one: deletions, then creations,
+/// then JSON patches of modified objects. They can be posted as is to the edition endpoint
+/// of another infra to replay the changes.
+#[utoipa::path(
+    get, path = "",
+    tag = "infra",
+    params(InfraIdParam, OtherInfraIdParam),
+    responses(
+        (status = 200, body = Vec<Operation>, description = "The operations from the infra to the other one"),
+        (status = 404, description = "One of the infras was not found"),
+    )
+)]
+async fn diff(
+    Path(InfraIdParam { infra_id }): Path<InfraIdParam>,
+    Path(OtherInfraIdParam { other_infra_id }): Path<OtherInfraIdParam>,
+    State(AppState {
+        db_pool,
+        infra_caches,
+        ..
+    }): State<AppState>,
+    Extension(auth): AuthenticationExt,
+) -> Result<Json<Vec<Operation>>> {
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[
+            (
+                &[Resource::new(ResourceType::Infra, infra_id)],
+                Privilege::Reader,
+            ),
+            (
+                &[Resource::new(ResourceType::Infra, other_infra_id)],
+                Privilege::Reader,
+            ),
+        ],
+    )
+    .await?;
+
+    let conn = &mut db_pool.get().await?;
+    let infra =
+        Infra::retrieve_or_fail(conn, infra_id, || InfraApiError::NotFound { infra_id }).await?;
+    let other_infra = Infra::retrieve_or_fail(conn, other_infra_id, || InfraApiError::NotFound {
+        infra_id: other_infra_id,
+    })
+    .await?;
+
+    // Only the object ids are kept from each cache, so that loading the other one never waits
+    // on a lock held by the first
+    let object_ids = InfraCache::get_or_load(conn, &infra_caches, &infra)
+        .await?
+        .object_ids();
+    let other_object_ids = InfraCache::get_or_load(conn, &infra_caches, &other_infra)
+        .await?
+        .object_ids();
+    let cache_diff = CacheDiff::new(&object_ids, &other_object_ids);
+
+    let operations = diff_operations(conn, &infra, &other_infra, cache_diff).await?;
+    Ok(Json(operations))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::models::fixtures::create_empty_infra;
+    use crate::models::fixtures::create_infra_object;
+    use crate::views::test_app::TestAppBuilder;
+    use editoast_schemas::infra::BufferStop;
+    use editoast_schemas::infra::TrackSection;
+    use editoast_schemas::primitives::ObjectType;
+
+    #[rstest]
+    async fn diff_between_infras() {
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+        let infra = create_empty_infra(&mut db_pool.get_ok()).await;
+        let other_infra = create_empty_infra(&mut db_pool.get_ok()).await;
+
+        let track = TrackSection {
+            id: "track".into(),
+            length: 100.,
+            ..Default::default()
+        };
+        create_infra_object(&mut db_pool.get_ok(), infra.id, track.clone()).await;
+        create_infra_object(
+            &mut db_pool.get_ok(),
+            other_infra.id,
+            TrackSection {
+                length: 200.,
+                ..track
+            },
+        )
+        .await;
+        create_infra_object(
+            &mut db_pool.get_ok(),
+            infra.id,
+            BufferStop {
+                id: "buffer_stop".into(),
+                track: "track".into(),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let request = app.get(&format!("/infra/{}/diff/{}", infra.id, other_infra.id));
+        let operations: Vec<Operation> =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        assert_eq!(operations.len(), 2);
+        let Operation::Delete(deletion) = &operations[0] else {
+            panic!("expected a deletion, got {:?}", operations[0]);
+        };
+        assert_eq!(deletion.obj_type, ObjectType::BufferStop);
+        assert_eq!(deletion.obj_id, "buffer_stop");
+        let Operation::Update(update) = &operations[1] else {
+            panic!("expected an update, got {:?}", operations[1]);
+        };
+        assert_eq!(update.obj_id, "track");
+        assert_eq!(update.railjson_patch.0.len(), 1);
+    }
+
+    #[rstest]
+    async fn diff_with_missing_infra() {
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+        let infra = create_empty_infra(&mut db_pool.get_ok()).await;
+
+        let request = app.get(&format!("/infra/{}/diff/{}", infra.id, -1));
+
+        app.fetch(request).assert_status(StatusCode::NOT_FOUND);
+    }
+}
@@ -1,8 +1,13 @@
 use axum::extract::Json;
 use axum::extract::Path;
+use axum::extract::Query;
 use axum::extract::State;
 use axum::Extension;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_schemas::infra::ApplicableDirectionsTrackRange;
 use editoast_schemas::infra::DirectionalTrackRange;
@@ -17,13 +22,16 @@ use editoast_schemas::primitives::OSRDIdentified;
 use editoast_schemas::primitives::ObjectType;
 use itertools::Itertools;
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
+use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
 use thiserror::Error;
 use tracing::error;
 use tracing::info;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
+use crate::error::InternalError;
 use crate::error::Result;
 use crate::generated_data;
 use crate::infra_cache::object_cache::OperationalPointPartCache;
@@ -34,12 +42,13 @@ use crate::infra_cache::operation::UpdateOperation;
 use crate::infra_cache::InfraCache;
 use crate::infra_cache::ObjectCache;
 use crate::map;
+use crate::models::audit_log::AuditAction;
+use crate::models::infra_edition::InfraEdition;
 use crate::models::prelude::*;
 use crate::models::Infra;
 use crate::views::infra::InfraApiError;
 use crate::views::infra::InfraIdParam;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 use editoast_models::DbConnection;
 use editoast_schemas::infra::InfraObject;
@@ -47,6 +56,9 @@ use editoast_schemas::infra::InfraObject;
 crate::routes! {
     edit,
     "/split_track_section" => split_track_section,
+    "/edition_history" => edition_history,
+    "/undo" => undo,
+    "/redo" => redo,
 }
 
 /// Edit the content of an infrastructure
@@ -80,29 +92,187 @@ async fn edit<'a>(
     Extension(auth): AuthenticationExt,
     Json(operations): Json<Vec<Operation>>,
 ) -> Result<Json<Vec<InfraObject>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
-    // TODO: lock for update
     let mut infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
         InfraApiError::NotFound { infra_id }
     })
     .await?;
     let mut infra_cache =
         InfraCache::get_or_load_mut(&mut db_pool.get().await?, &infra_caches, &infra).await?;
-    let operation_results = apply_edit(
-        &mut db_pool.get().await?,
-        &mut infra,
-        &operations,
-        &mut infra_cache,
+    let operation_results = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::Infra, infra_id),
+                    format!("Edited infra: {}", edition_summary(&operations)),
+                )
+                .await?;
+                let operation_results = apply_edit(
+                    &mut conn.clone(),
+                    &mut infra,
+                    &operations,
+                    &mut infra_cache,
+                    EditionJournal::Record,
+                )
+                .await?;
+                Ok(operation_results)
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+    let mut conn = valkey.get_connection().await?;
+    map::invalidate_all(
+        &mut conn,
+        &map_layers.layers.keys().cloned().collect(),
+        infra_id,
     )
     .await?;
 
+    Ok(Json(operation_results))
+}
+
+/// List the edition history of an infrastructure
+///
+/// Each entry is a batch of operations applied through the edition endpoint,
+/// along with the operations reverting it. The latest batch comes first.
+#[utoipa::path(
+    get, path = "",
+    tag = "infra",
+    params(InfraIdParam),
+    responses(
+        (status = 200, body = Vec<InfraEdition>, description = "The edition history of the infra"),
+        (status = 404, description = "The infra was not found"),
+    )
+)]
+async fn edition_history(
+    Path(InfraIdParam { infra_id }): Path<InfraIdParam>,
+    State(AppState { db_pool, .. }): State<AppState>,
+    Extension(auth): AuthenticationExt,
+) -> Result<Json<Vec<InfraEdition>>> {
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
+
+    let conn = &mut db_pool.get().await?;
+    Infra::retrieve_or_fail(conn, infra_id, || InfraApiError::NotFound { infra_id }).await?;
+    let history = InfraEdition::list_for_infra(conn, infra_id).await?;
+    Ok(Json(history))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct EditionCountQueryParam {
+    /// The number of batches of operations to revert or reapply
+    #[serde(default = "default_edition_count")]
+    #[param(minimum = 1, default = 1)]
+    count: u64,
+}
+
+fn default_edition_count() -> u64 {
+    1
+}
+
+/// Undo the latest batches of operations applied to an infrastructure
+///
+/// The inverse operations of the batches are applied, latest batch first. Undone
+/// batches can be redone until a new batch of operations is applied to the infra.
+#[utoipa::path(
+    post, path = "",
+    tag = "infra",
+    params(InfraIdParam, EditionCountQueryParam),
+    responses(
+        (status = 200, body = Vec<InfraEdition>, description = "The undone batches, latest first"),
+        (status = 400, description = "There is no batch to undo"),
+        (status = 404, description = "The infra was not found"),
+    )
+)]
+async fn undo(
+    Path(InfraIdParam { infra_id }): Path<InfraIdParam>,
+    Query(EditionCountQueryParam { count }): Query<EditionCountQueryParam>,
+    State(AppState {
+        db_pool,
+        infra_caches,
+        valkey,
+        map_layers,
+        ..
+    }): State<AppState>,
+    Extension(auth): AuthenticationExt,
+) -> Result<Json<Vec<InfraEdition>>> {
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
+
+    let mut infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
+        InfraApiError::NotFound { infra_id }
+    })
+    .await?;
+    let mut infra_cache =
+        InfraCache::get_or_load_mut(&mut db_pool.get().await?, &infra_caches, &infra).await?;
+    let mut editions = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                InfraEdition::lock_history(&mut conn.clone(), infra_id).await?;
+                let editions = InfraEdition::undoable(&mut conn.clone(), infra_id, count).await?;
+                if editions.is_empty() {
+                    return Err(EditionError::NothingToUndo { infra_id }.into());
+                }
+                let operations = editions
+                    .iter()
+                    .flat_map(|edition| edition.inverse_operations.clone())
+                    .collect_vec();
+                let edition_ids = editions.iter().map(|edition| edition.id).collect_vec();
+                let summary = format!(
+                    "Undid {} editions: {}",
+                    editions.len(),
+                    edition_summary(&operations)
+                );
+
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::Infra, infra_id),
+                    summary,
+                )
+                .await?;
+                apply_edit(
+                    &mut conn.clone(),
+                    &mut infra,
+                    &operations,
+                    &mut infra_cache,
+                    EditionJournal::Undo(edition_ids),
+                )
+                .await?;
+                Ok(editions)
+            }
+            .scope_boxed()
+        })
+        .await?;
+
     let mut conn = valkey.get_connection().await?;
     map::invalidate_all(
         &mut conn,
@@ -111,7 +281,106 @@ async fn edit<'a>(
     )
     .await?;
 
-    Ok(Json(operation_results))
+    for edition in editions.iter_mut() {
+        edition.undone = true;
+    }
+    Ok(Json(editions))
+}
+
+/// Redo the oldest undone batches of operations of an infrastructure
+///
+/// The operations of the batches are applied again, oldest batch first.
+#[utoipa::path(
+    post, path = "",
+    tag = "infra",
+    params(InfraIdParam, EditionCountQueryParam),
+    responses(
+        (status = 200, body = Vec<InfraEdition>, description = "The redone batches, oldest first"),
+        (status = 400, description = "There is no batch to redo"),
+        (status = 404, description = "The infra was not found"),
+    )
+)]
+async fn redo(
+    Path(InfraIdParam { infra_id }): Path<InfraIdParam>,
+    Query(EditionCountQueryParam { count }): Query<EditionCountQueryParam>,
+    State(AppState {
+        db_pool,
+        infra_caches,
+        valkey,
+        map_layers,
+        ..
+    }): State<AppState>,
+    Extension(auth): AuthenticationExt,
+) -> Result<Json<Vec<InfraEdition>>> {
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
+
+    let mut infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
+        InfraApiError::NotFound { infra_id }
+    })
+    .await?;
+    let mut infra_cache =
+        InfraCache::get_or_load_mut(&mut db_pool.get().await?, &infra_caches, &infra).await?;
+    let mut editions = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                InfraEdition::lock_history(&mut conn.clone(), infra_id).await?;
+                let editions = InfraEdition::redoable(&mut conn.clone(), infra_id, count).await?;
+                if editions.is_empty() {
+                    return Err(EditionError::NothingToRedo { infra_id }.into());
+                }
+                let operations = editions
+                    .iter()
+                    .flat_map(|edition| edition.operations.clone())
+                    .collect_vec();
+                let edition_ids = editions.iter().map(|edition| edition.id).collect_vec();
+                let summary = format!(
+                    "Redid {} editions: {}",
+                    editions.len(),
+                    edition_summary(&operations)
+                );
+
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::Infra, infra_id),
+                    summary,
+                )
+                .await?;
+                apply_edit(
+                    &mut conn.clone(),
+                    &mut infra,
+                    &operations,
+                    &mut infra_cache,
+                    EditionJournal::Redo(edition_ids),
+                )
+                .await?;
+                Ok(editions)
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+    let mut conn = valkey.get_connection().await?;
+    map::invalidate_all(
+        &mut conn,
+        &map_layers.layers.keys().cloned().collect(),
+        infra_id,
+    )
+    .await?;
+
+    for edition in editions.iter_mut() {
+        edition.undone = false;
+    }
+    Ok(Json(editions))
 }
 
 #[utoipa::path(
@@ -135,13 +404,14 @@ pub async fn split_track_section<'a>(
     Extension(auth): AuthenticationExt,
     Json(payload): Json<TrackOffset>,
 ) -> Result<Json<Vec<String>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
     info!(
         track_id = payload.track.as_str(),
@@ -327,13 +597,37 @@ pub async fn split_track_section<'a>(
     }));
 
     // Apply operations
-    apply_edit(
-        &mut db_pool.get().await?,
-        &mut infra,
-        &operations,
-        &mut infra_cache,
-    )
-    .await?;
+    let summary = format!(
+        "Split track section '{}' at {}mm: {}",
+        payload.track,
+        payload.offset,
+        edition_summary(&operations)
+    );
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::Infra, infra_id),
+                    summary,
+                )
+                .await?;
+                apply_edit(
+                    &mut conn.clone(),
+                    &mut infra,
+                    &operations,
+                    &mut infra_cache,
+                    EditionJournal::Record,
+                )
+                .await?;
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await?;
     let mut conn = valkey.get_connection().await?;
     map::invalidate_all(
         &mut conn,
@@ -836,11 +1130,39 @@ fn get_split_patch_operations_for_ranges(
     patch_operations
 }
 
-async fn apply_edit(
+/// Describes a batch of operations in the audit log
+pub(in crate::views::infra) fn edition_summary(operations: &[Operation]) -> String {
+    let (mut created, mut updated, mut deleted) = (0, 0, 0);
+    for operation in operations {
+        match operation {
+            Operation::Create(_) => created += 1,
+            Operation::Update(_) => updated += 1,
+            Operation::Delete(_) => deleted += 1,
+        }
+    }
+    format!("{created} objects created, {updated} updated and {deleted} deleted")
+}
+
+/// How an applied batch of operations is recorded in the edition history of the infra
+pub(in crate::views::infra) enum EditionJournal {
+    /// A new batch, recorded with its inverse operations
+    Record,
+    /// The inverse operations of the given history entries
+    Undo(Vec<i64>),
+    /// The operations of the given undone history entries
+    Redo(Vec<i64>),
+}
+
+/// Applies a batch of operations to an infra and journals it
+///
+/// The infra cache is updated in place and is not restored if the caller's transaction is
+/// rolled back afterwards, so the batch must be the last fallible step of that transaction.
+pub(in crate::views::infra) async fn apply_edit(
     connection: &mut DbConnection,
     infra: &mut Infra,
     operations: &[Operation],
     infra_cache: &mut InfraCache,
+    journal: EditionJournal,
 ) -> Result<Vec<InfraObject>> {
     let infra_id = infra.id;
     // Check if the infra is locked
@@ -853,10 +1175,16 @@ async fn apply_edit(
         .clone()
         .transaction(|conn| {
             Box::pin(async move {
+                // Editions of the same infra are applied one after the other
+                InfraEdition::lock_history(&mut conn.clone(), infra_id).await?;
                 let mut railjsons = vec![];
                 let mut cache_operations = vec![];
+                let mut inverse_operations = vec![];
                 for operation in operations {
-                    let railjson = operation.apply(infra_id, &mut conn.clone()).await?;
+                    let (railjson, inverse) = operation
+                        .apply_with_inverse(infra_id, &mut conn.clone())
+                        .await?;
+                    inverse_operations.push(inverse);
                     match (operation, railjson) {
                         (Operation::Create(_), Some(railjson)) => {
                             railjsons.push(railjson.clone());
@@ -876,6 +1204,28 @@ async fn apply_edit(
                     }
                 }
 
+                // Journal the batch
+                match journal {
+                    EditionJournal::Record => {
+                        // Undone batches can't be redone on top of a new one
+                        InfraEdition::clear_undone(&mut conn.clone(), infra_id).await?;
+                        inverse_operations.reverse();
+                        InfraEdition::changeset()
+                            .infra_id(infra_id)
+                            .operations(operations.to_vec())
+                            .inverse_operations(inverse_operations)
+                            .undone(false)
+                            .create(&mut conn.clone())
+                            .await?;
+                    }
+                    EditionJournal::Undo(edition_ids) => {
+                        InfraEdition::set_undone(&mut conn.clone(), &edition_ids, true).await?;
+                    }
+                    EditionJournal::Redo(edition_ids) => {
+                        InfraEdition::set_undone(&mut conn.clone(), &edition_ids, false).await?;
+                    }
+                }
+
                 // Bump version
                 infra.bump_version(&mut conn.clone()).await?;
                 // Apply operations to infra cache
@@ -906,6 +1256,12 @@ enum EditionError {
     #[error("Infra {infra_id} is locked")]
     InfraIsLocked { infra_id: i64 },
 
+    #[error("Infra {infra_id} has no edition to undo")]
+    NothingToUndo { infra_id: i64 },
+
+    #[error("Infra {infra_id} has no undone edition to redo")]
+    NothingToRedo { infra_id: i64 },
+
     #[error("Invalid split offset for track section '{tracksection_id}' in infra '{infra_id}'. Expected a value between 0 and {tracksection_length} meters")]
     #[editoast_error(status = 400)]
     SplitTrackSectionBadOffset {
@@ -924,6 +1280,7 @@ pub mod tests {
     use super::*;
     use crate::generated_data::infra_error::InfraError;
     use crate::generated_data::infra_error::InfraErrorType;
+    use crate::models::fixtures::create_empty_infra;
     use crate::models::fixtures::create_small_infra;
     use crate::models::infra::ObjectQueryable;
     use crate::views::infra::errors::query_errors;
@@ -1055,10 +1412,15 @@ pub mod tests {
             }),
         ]
         .to_vec();
-        let result: Vec<InfraObject> =
-            apply_edit(conn, &mut small_infra, &operations, &mut infra_cache)
-                .await
-                .unwrap();
+        let result: Vec<InfraObject> = apply_edit(
+            conn,
+            &mut small_infra,
+            &operations,
+            &mut infra_cache,
+            EditionJournal::Record,
+        )
+        .await
+        .unwrap();
 
         // Check that the updated track has the new length
         assert_eq!(1234.0, result[0].get_data()["length"]);
@@ -1101,7 +1463,14 @@ pub mod tests {
             }),
         ]
         .to_vec();
-        let result = apply_edit(conn, &mut small_infra, &operations, &mut infra_cache).await;
+        let result = apply_edit(
+            conn,
+            &mut small_infra,
+            &operations,
+            &mut infra_cache,
+            EditionJournal::Record,
+        )
+        .await;
 
         // Check that we have an error
         assert!(result.is_err());
@@ -1113,4 +1482,134 @@ pub mod tests {
             .unwrap();
         assert_eq!(2000.0, res[0].railjson.as_object().unwrap()["length"]);
     }
+
+    fn get_track_length(railjson: &ObjectQueryable) -> f64 {
+        railjson.railjson["length"].as_f64().unwrap()
+    }
+
+    fn track_section(id: &str) -> TrackSection {
+        TrackSection {
+            id: id.into(),
+            length: 100.,
+            geo: geos::geojson::Geometry::new(geos::geojson::Value::LineString(vec![
+                vec![0., 0.],
+                vec![1., 1.],
+            ])),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    async fn undo_and_redo_edition() {
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+        let infra = create_empty_infra(&mut db_pool.get_ok()).await;
+        let track = track_section("track");
+
+        // Create a track, then update its length
+        let request = app
+            .post(&format!("/infra/{}", infra.id))
+            .json(&json!([Operation::Create(Box::new(track.clone().into()))]));
+        app.fetch(request).assert_status(StatusCode::OK);
+        let request = app
+            .post(&format!("/infra/{}", infra.id))
+            .json(&json!([Operation::Update(UpdateOperation {
+                obj_type: ObjectType::TrackSection,
+                obj_id: "track".to_string(),
+                railjson_patch: Patch(vec![PatchOperation::Replace(ReplaceOperation {
+                    path: "/length".parse().unwrap(),
+                    value: json!(200.),
+                })]),
+            })]));
+        app.fetch(request).assert_status(StatusCode::OK);
+
+        let request = app.get(&format!("/infra/{}/edition_history", infra.id));
+        let history: Vec<InfraEdition> =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+        assert_eq!(history.len(), 2);
+        assert!(matches!(
+            history[1].inverse_operations.as_slice(),
+            [Operation::Delete(_)]
+        ));
+
+        // Undo the update
+        let request = app.post(&format!("/infra/{}/undo", infra.id));
+        let undone: Vec<InfraEdition> =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+        assert_eq!(undone.len(), 1);
+        assert!(undone[0].undone);
+        let tracks = infra
+            .get_objects(
+                &mut db_pool.get_ok(),
+                ObjectType::TrackSection,
+                &vec!["track".to_string()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_track_length(&tracks[0]), 100.);
+
+        // Undo the creation
+        let request = app.post(&format!("/infra/{}/undo", infra.id));
+        app.fetch(request).assert_status(StatusCode::OK);
+        let tracks = infra
+            .get_objects(
+                &mut db_pool.get_ok(),
+                ObjectType::TrackSection,
+                &vec!["track".to_string()],
+            )
+            .await
+            .unwrap();
+        assert!(tracks.is_empty());
+        let request = app.post(&format!("/infra/{}/undo", infra.id));
+        app.fetch(request).assert_status(StatusCode::BAD_REQUEST);
+
+        // Redo both batches
+        let request = app.post(&format!("/infra/{}/redo?count=2", infra.id));
+        let redone: Vec<InfraEdition> =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+        assert_eq!(redone.len(), 2);
+        let tracks = infra
+            .get_objects(
+                &mut db_pool.get_ok(),
+                ObjectType::TrackSection,
+                &vec!["track".to_string()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_track_length(&tracks[0]), 200.);
+        let request = app.post(&format!("/infra/{}/redo", infra.id));
+        app.fetch(request).assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[rstest]
+    async fn new_edition_drops_undone_editions() {
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+        let infra = create_empty_infra(&mut db_pool.get_ok()).await;
+        for id in ["A", "B"] {
+            let track = track_section(id);
+            let request = app
+                .post(&format!("/infra/{}", infra.id))
+                .json(&json!([Operation::Create(Box::new(track.into()))]));
+            app.fetch(request).assert_status(StatusCode::OK);
+        }
+
+        let request = app.post(&format!("/infra/{}/undo", infra.id));
+        app.fetch(request).assert_status(StatusCode::OK);
+        let request = app
+            .post(&format!("/infra/{}", infra.id))
+            .json(&json!([Operation::Delete(DeleteOperation {
+                obj_type: ObjectType::TrackSection,
+                obj_id: "A".to_string(),
+            })]));
+        app.fetch(request).assert_status(StatusCode::OK);
+
+        let history = InfraEdition::list_for_infra(&mut db_pool.get_ok(), infra.id)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|edition| !edition.undone));
+        let request = app.post(&format!("/infra/{}/redo", infra.id));
+        app.fetch(request).assert_status(StatusCode::BAD_REQUEST);
+    }
 }
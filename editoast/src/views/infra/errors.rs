@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use axum::extract::Json;
@@ -6,14 +7,19 @@ use axum::extract::Query;
 use axum::extract::State;
 use axum::Extension;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_schemas::primitives::Identifier;
+use editoast_schemas::primitives::ObjectType;
 use serde::Deserialize;
 use serde::Serialize;
 use thiserror::Error;
 
 use crate::error::Result;
 use crate::generated_data::infra_error::InfraError;
+use crate::generated_data::infra_error::InfraErrorSeverity;
 use crate::generated_data::infra_error::InfraErrorTypeLabel;
 use crate::models::infra::errors::Level;
 use crate::models::prelude::*;
@@ -22,13 +28,19 @@ use crate::views::infra::InfraIdParam;
 use crate::views::pagination::PaginationQueryParams;
 use crate::views::pagination::PaginationStats;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use editoast_models::DbConnectionPoolV2;
 
 use super::InfraApiError;
 
 crate::routes! {
-    "/errors" => list_errors,
+    "/errors" => {
+        list_errors,
+        "/stats" => error_stats,
+    },
+}
+
+editoast_common::schemas! {
+    InfraErrorStats,
 }
 
 #[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
@@ -41,6 +53,8 @@ struct ErrorListQueryParams {
     /// The type of error to filter on
     #[param(value_type = Option<InfraErrorTypeLabel>)]
     error_type: Option<String>,
+    /// The severity of errors to filter on
+    severity: Option<InfraErrorSeverity>,
     /// Filter errors and warnings related to a given object
     #[param(value_type = Option<String>)]
     object_id: Option<Identifier>,
@@ -78,16 +92,18 @@ async fn list_errors(
     Query(ErrorListQueryParams {
         level,
         error_type,
+        severity,
         object_id,
     }): Query<ErrorListQueryParams>,
 ) -> Result<Json<ErrorListResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let (page, page_size) = pagination_params
         .validate(100)?
@@ -106,7 +122,9 @@ async fn list_errors(
         Infra::retrieve_or_fail(conn, infra_id, || InfraApiError::NotFound { infra_id }).await?;
 
     let (results, total_count) = infra
-        .get_paginated_errors(conn, level, error_type, object_id, page, page_size)
+        .get_paginated_errors(
+            conn, level, error_type, severity, object_id, page, page_size,
+        )
         .await?;
     let results = results
         .into_iter()
@@ -116,6 +134,64 @@ async fn list_errors(
     Ok(Json(ErrorListResponse { stats, results }))
 }
 
+/// Error counts of an infra
+#[derive(Debug, Default, Serialize, utoipa::ToSchema)]
+#[cfg_attr(test, derive(Deserialize, PartialEq))]
+pub(in crate::views) struct InfraErrorStats {
+    /// The total number of errors and warnings
+    total: u64,
+    /// The number of errors of each type
+    by_error_type: HashMap<InfraErrorTypeLabel, u64>,
+    /// The number of errors related to each type of object
+    by_object_type: HashMap<ObjectType, u64>,
+    /// The number of errors of each severity
+    by_severity: HashMap<InfraErrorSeverity, u64>,
+}
+
+/// Count the errors of an infra by type, object type and severity
+#[utoipa::path(
+    get, path = "",
+    tag = "infra",
+    params(InfraIdParam),
+    responses(
+        (status = 200, body = InfraErrorStats, description = "The error counts of the infra"),
+        (status = 404, description = "The infra was not found"),
+    ),
+)]
+async fn error_stats(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(InfraIdParam { infra_id }): Path<InfraIdParam>,
+) -> Result<Json<InfraErrorStats>> {
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
+
+    let conn = &mut db_pool.get().await?;
+    let infra =
+        Infra::retrieve_or_fail(conn, infra_id, || InfraApiError::NotFound { infra_id }).await?;
+
+    let mut stats = InfraErrorStats::default();
+    for error_count in infra.get_error_counts(conn).await? {
+        stats.total += error_count.count;
+        *stats
+            .by_error_type
+            .entry(error_count.error_type)
+            .or_default() += error_count.count;
+        *stats
+            .by_object_type
+            .entry(error_count.obj_type)
+            .or_default() += error_count.count;
+        *stats.by_severity.entry(error_count.severity).or_default() += error_count.count;
+    }
+    Ok(Json(stats))
+}
+
 #[derive(Debug, Error, EditoastError)]
 #[editoast_error(base_id = "infra:errors")]
 enum ListErrorsErrors {
@@ -129,7 +205,7 @@ pub(in crate::views) async fn query_errors(
     infra: &Infra,
 ) -> (Vec<InfraError>, u64) {
     infra
-        .get_paginated_errors(conn, Level::All, None, None, 1, 10000)
+        .get_paginated_errors(conn, Level::All, None, None, None, 1, 10000)
         .await
         .expect("errors should be fetched successfully")
 }
@@ -137,9 +213,13 @@ pub(in crate::views) async fn query_errors(
 #[cfg(test)]
 mod tests {
     use axum::http::StatusCode;
+    use editoast_schemas::infra::TrackSection;
+    use pretty_assertions::assert_eq;
     use rstest::rstest;
 
+    use super::*;
     use crate::models::fixtures::create_empty_infra;
+    use crate::models::fixtures::create_infra_object;
     use crate::views::test_app::TestAppBuilder;
 
     #[rstest]
@@ -160,4 +240,60 @@ mod tests {
         );
         app.fetch(req).assert_status(StatusCode::OK);
     }
+
+    #[rstest]
+    async fn error_stats_get() {
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+        let infra = create_empty_infra(&mut db_pool.get_ok()).await;
+        create_infra_object(
+            &mut db_pool.get_ok(),
+            infra.id,
+            TrackSection {
+                id: "track_with_no_buffer_stops".into(),
+                length: 1_000.0,
+                geo: geos::geojson::Geometry::new(geos::geojson::Value::LineString(vec![
+                    vec![0., 0.],
+                    vec![1., 1.],
+                ])),
+                ..Default::default()
+            },
+        )
+        .await;
+        let request = app.post(&format!("/infra/refresh?infras={}&force=true", infra.id));
+        app.fetch(request).assert_status(StatusCode::OK);
+
+        let request = app.get(&format!("/infra/{}/errors/stats", infra.id));
+        let stats: InfraErrorStats = app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let (errors, error_count) = query_errors(&mut db_pool.get_ok(), &infra).await;
+        assert_eq!(stats.total, error_count);
+        assert_eq!(
+            stats
+                .by_error_type
+                .get(&InfraErrorTypeLabel::MissingBufferStop),
+            Some(&2)
+        );
+        assert_eq!(
+            stats.by_object_type.get(&ObjectType::TrackSection),
+            Some(&stats.total)
+        );
+        let warning_count = errors
+            .iter()
+            .filter(|error| error.severity == InfraErrorSeverity::Warning)
+            .count() as u64;
+        assert_eq!(
+            stats.by_severity.get(&InfraErrorSeverity::Warning),
+            Some(&warning_count)
+        );
+    }
+
+    #[rstest]
+    async fn error_stats_with_missing_infra() {
+        let app = TestAppBuilder::default_app();
+
+        let request = app.get(&format!("/infra/{}/errors/stats", -1));
+
+        app.fetch(request).assert_status(StatusCode::NOT_FOUND);
+    }
 }
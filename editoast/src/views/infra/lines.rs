@@ -3,6 +3,9 @@ use axum::extract::Path;
 use axum::extract::State;
 use axum::Extension;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_schemas::primitives::BoundingBox;
 use thiserror::Error;
@@ -15,7 +18,6 @@ use crate::models::Infra;
 use crate::views::infra::InfraApiError;
 use crate::views::infra::InfraIdParam;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 
 crate::routes! {
@@ -50,13 +52,14 @@ async fn get_line_bbox(
     }): State<AppState>,
     Extension(auth): AuthenticationExt,
 ) -> Result<Json<BoundingBox>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let line_code: i32 = line_code.try_into().unwrap();
 
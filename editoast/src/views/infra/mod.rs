@@ -1,6 +1,7 @@
 mod attached;
-mod auto_fixes;
+pub mod auto_fixes;
 mod delimited_area;
+mod diff;
 mod edition;
 mod errors;
 mod lines;
@@ -16,7 +17,11 @@ use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Extension;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_osrdyne_client::OsrdyneClient;
 use itertools::Itertools;
@@ -29,13 +34,16 @@ use utoipa::ToSchema;
 
 use super::pagination::PaginationStats;
 use super::params::List;
+use super::Authentication;
 use super::AuthenticationExt;
 use crate::core::infra_loading::InfraLoadRequest;
 use crate::core::AsCoreRequest;
+use crate::error::InternalError;
 use crate::error::Result;
 use crate::infra_cache::InfraCache;
 use crate::infra_cache::ObjectCache;
 use crate::map;
+use crate::models::audit_log::AuditAction;
 use crate::models::prelude::*;
 use crate::models::Infra;
 use crate::views::pagination::PaginatedList as _;
@@ -62,6 +70,7 @@ crate::routes! {
             &edition,
             &errors,
             &delimited_area,
+            &diff,
 
             get,
             "/load" => load,
@@ -78,6 +87,8 @@ crate::routes! {
 }
 
 editoast_common::schemas! {
+    auto_fixes::schemas(),
+    errors::schemas(),
     pathfinding::schemas(),
     delimited_area::schemas(),
     InfraState,
@@ -221,6 +232,7 @@ async fn list(
         .validate(1000)?
         .warn_page_size(100)
         .into_selection_settings();
+    let settings = auth.filter_readable(settings, ResourceType::Infra);
 
     let (infras, stats) = {
         let conn = &mut db_pool.get().await?;
@@ -305,13 +317,14 @@ async fn get(
     Extension(auth): AuthenticationExt,
     Path(infra): Path<InfraIdParam>,
 ) -> Result<Json<InfraWithState>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let infra_id = infra.infra_id;
     let infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
@@ -359,8 +372,31 @@ async fn create(
         return Err(AuthorizationError::Unauthorized.into());
     }
 
+    let conn = &mut db_pool.get().await?;
     let infra: Changeset<Infra> = infra_form.into();
-    let infra = infra.create(&mut db_pool.get().await?).await?;
+    let infra = conn
+        .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
+            async move {
+                let infra = infra.create(&mut conn.clone()).await?;
+                auth.grant_ownership(
+                    &mut conn.clone(),
+                    Resource::new(ResourceType::Infra, infra.id),
+                )
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Create,
+                    Resource::new(ResourceType::Infra, infra.id),
+                    format!("Created infra '{}'", infra.name),
+                )
+                .await?;
+                Ok(infra)
+            }
+            .scope_boxed()
+        })
+        .await?;
     Ok((StatusCode::CREATED, Json(infra)))
 }
 
@@ -387,13 +423,14 @@ async fn clone(
     State(db_pool): State<DbConnectionPoolV2>,
     Query(CloneQuery { name }): Query<CloneQuery>,
 ) -> Result<Json<i64>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, params.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
 
@@ -401,7 +438,29 @@ async fn clone(
         infra_id: params.infra_id,
     })
     .await?;
-    let cloned_infra = infra.clone(conn, name).await?;
+    let cloned_infra = conn
+        .transaction::<_, InternalError, _>(|conn| {
+            let (auth, infra) = (&auth, &infra);
+            async move {
+                let cloned_infra = infra.clone(&mut conn.clone(), name).await?;
+                auth.grant_ownership(
+                    &mut conn.clone(),
+                    Resource::new(ResourceType::Infra, cloned_infra.id),
+                )
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Create,
+                    Resource::new(ResourceType::Infra, cloned_infra.id),
+                    format!("Cloned infra {} as '{}'", infra.id, cloned_infra.name),
+                )
+                .await?;
+                Ok(cloned_infra)
+            }
+            .scope_boxed()
+        })
+        .await?;
     Ok(Json(cloned_infra.id))
 }
 
@@ -432,15 +491,36 @@ async fn delete(
     Extension(auth): AuthenticationExt,
     Path(InfraIdParam { infra_id }): Path<InfraIdParam>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Owner,
+        )],
+    )
+    .await?;
 
-    if Infra::fast_delete_static(db_pool.get().await?, infra_id).await? {
+    let deleted = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                if !Infra::fast_delete_static(conn.clone(), infra_id).await? {
+                    return Ok(false);
+                }
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Delete,
+                    Resource::new(ResourceType::Infra, infra_id),
+                    format!("Deleted infra {infra_id}"),
+                )
+                .await?;
+                Ok(true)
+            }
+            .scope_boxed()
+        })
+        .await?;
+    if deleted {
         infra_caches.remove(&infra_id);
         Ok(StatusCode::NO_CONTENT)
     } else {
@@ -477,18 +557,36 @@ async fn put(
     Path(infra): Path<i64>,
     Json(patch): Json<InfraPatchForm>,
 ) -> Result<Json<Infra>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
     let infra_cs: Changeset<Infra> = patch.into();
-    let infra = infra_cs
-        .update_or_fail(&mut db_pool.get().await?, infra, || {
-            InfraApiError::NotFound { infra_id: infra }
+    let infra = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                let infra = infra_cs
+                    .update_or_fail(&mut conn.clone(), infra, || InfraApiError::NotFound {
+                        infra_id: infra,
+                    })
+                    .await?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::Infra, infra.id),
+                    format!("Renamed infra to '{}'", infra.name),
+                )
+                .await?;
+                Ok(infra)
+            }
+            .scope_boxed()
         })
         .await?;
     Ok(Json(infra))
@@ -513,13 +611,14 @@ async fn get_switch_types(
     Extension(auth): AuthenticationExt,
     Path(infra): Path<InfraIdParam>,
 ) -> Result<Json<Vec<SwitchType>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
 
@@ -554,13 +653,14 @@ async fn get_speed_limit_tags(
     Path(infra): Path<InfraIdParam>,
     State(db_pool): State<DbConnectionPoolV2>,
 ) -> Result<Json<Vec<String>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
 
@@ -598,13 +698,14 @@ async fn get_voltages(
     Query(param): Query<GetVoltagesQueryParams>,
     State(db_pool): State<DbConnectionPoolV2>,
 ) -> Result<Json<Vec<String>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let include_rolling_stock_modes = param.include_rolling_stock_modes;
     let infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra.infra_id, || {
@@ -644,13 +745,40 @@ async fn get_all_voltages(
     Ok(Json(voltages.into_iter().map(|el| (el.voltage)).collect()))
 }
 
-async fn set_locked(infra_id: i64, locked: bool, db_pool: DbConnectionPoolV2) -> Result<()> {
-    let mut infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
-        InfraApiError::NotFound { infra_id }
-    })
-    .await?;
-    infra.locked = locked;
-    infra.save(&mut db_pool.get().await?).await
+async fn set_locked(
+    auth: &Authentication,
+    infra_id: i64,
+    locked: bool,
+    db_pool: DbConnectionPoolV2,
+) -> Result<()> {
+    let (action, summary) = if locked {
+        (AuditAction::Lock, "Locked infra")
+    } else {
+        (AuditAction::Unlock, "Unlocked infra")
+    };
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                let mut infra = Infra::retrieve_or_fail(&mut conn.clone(), infra_id, || {
+                    InfraApiError::NotFound { infra_id }
+                })
+                .await?;
+                infra.locked = locked;
+                infra.save(&mut conn.clone()).await?;
+                auth.audit(
+                    &mut conn.clone(),
+                    action,
+                    Resource::new(ResourceType::Infra, infra_id),
+                    summary,
+                )
+                .await?;
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
 }
 
 /// Lock an infra
@@ -668,15 +796,16 @@ async fn lock(
     Path(infra): Path<InfraIdParam>,
     State(db_pool): State<DbConnectionPoolV2>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra.infra_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
-    set_locked(infra.infra_id, true, db_pool).await?;
+    set_locked(&auth, infra.infra_id, true, db_pool).await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -695,15 +824,16 @@ async fn unlock(
     Path(infra): Path<InfraIdParam>,
     State(db_pool): State<DbConnectionPoolV2>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra.infra_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
-    set_locked(infra.infra_id, false, db_pool).await?;
+    set_locked(&auth, infra.infra_id, false, db_pool).await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -726,13 +856,14 @@ async fn load(
     Extension(auth): AuthenticationExt,
     Path(path): Path<InfraIdParam>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, path.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let infra_id = path.infra_id;
     let infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
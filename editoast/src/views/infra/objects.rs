@@ -6,6 +6,9 @@ use axum::extract::Path;
 use axum::extract::State;
 use axum::Extension;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_models::DbConnectionPoolV2;
 use editoast_schemas::primitives::ObjectType;
@@ -17,7 +20,6 @@ use crate::error::Result;
 use crate::models::infra::ObjectQueryable;
 use crate::models::Infra;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::Retrieve;
 
 crate::routes! {
@@ -62,13 +64,14 @@ async fn get_objects(
     Extension(auth): AuthenticationExt,
     Json(obj_ids): Json<Vec<String>>,
 ) -> Result<Json<Vec<ObjectQueryable>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id_param.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let infra_id = infra_id_param.infra_id;
     if !has_unique_ids(&obj_ids) {
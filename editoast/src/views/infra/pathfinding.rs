@@ -7,6 +7,9 @@ use axum::extract::State;
 use axum::Extension;
 use derivative::Derivative;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use pathfinding::prelude::yen;
 use serde::Deserialize;
@@ -23,7 +26,6 @@ use crate::models::Infra;
 use crate::views::infra::InfraApiError;
 use crate::views::infra::InfraIdParam;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 use editoast_schemas::infra::Direction;
 use editoast_schemas::infra::DirectionalTrackRange;
@@ -106,13 +108,14 @@ async fn pathfinding_view(
     Query(params): Query<QueryParam>,
     Json(input): Json<InfraPathfindingInput>,
 ) -> Result<Json<Vec<PathfindingOutput>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     // Parse and check input
     let infra_id = infra.infra_id;
@@ -6,10 +6,16 @@ use axum::http::header;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Extension;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
+use editoast_schemas::infra::migrate_railjson;
 use editoast_schemas::infra::RailJson;
-use editoast_schemas::infra::RAILJSON_VERSION;
+use editoast_schemas::infra::RailJsonMigrationError;
+use editoast_schemas::infra::RailJsonMigrationReport;
 use enum_map::EnumMap;
 use futures::future::try_join_all;
 use serde::Deserialize;
@@ -19,8 +25,10 @@ use thiserror::Error;
 use utoipa::IntoParams;
 use utoipa::ToSchema;
 
+use crate::error::InternalError;
 use crate::error::Result;
 use crate::infra_cache::InfraCache;
+use crate::models::audit_log::AuditAction;
 use crate::models::prelude::*;
 use crate::models::Infra;
 use crate::views::infra::InfraApiError;
@@ -41,6 +49,24 @@ crate::routes! {
 enum ListErrorsRailjson {
     #[error("Wrong Railjson version provided")]
     WrongRailjsonVersionProvided,
+    #[error("Invalid Railjson provided: {cause}")]
+    InvalidRailjson { cause: String },
+}
+
+impl From<RailJsonMigrationError> for ListErrorsRailjson {
+    fn from(error: RailJsonMigrationError) -> Self {
+        match error {
+            RailJsonMigrationError::MissingVersion
+            | RailJsonMigrationError::UnsupportedVersion { .. } => {
+                Self::WrongRailjsonVersionProvided
+            }
+            RailJsonMigrationError::NotAnObject | RailJsonMigrationError::InvalidDocument(_) => {
+                Self::InvalidRailjson {
+                    cause: error.to_string(),
+                }
+            }
+        }
+    }
 }
 
 /// Serialize an infra
@@ -58,13 +84,14 @@ async fn get_railjson(
     State(db_pool): State<DbConnectionPoolV2>,
     Extension(auth): AuthenticationExt,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra.infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let infra_id = infra.infra_id;
     let infra_meta = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
@@ -151,12 +178,16 @@ struct PostRailjsonQueryParams {
     generate_data: bool,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 struct PostRailjsonResponse {
     pub infra: i64,
+    /// The upgrades applied to the provided railjson if it was written with an older version
+    pub migration: RailJsonMigrationReport,
 }
 
 /// Import an infra from railjson
+///
+/// Railjson files written with an older version of the format are migrated to the current one.
 #[utoipa::path(
     post, path = "",
     tag = "infra",
@@ -175,7 +206,7 @@ async fn post_railjson(
     }): State<AppState>,
     Extension(auth): AuthenticationExt,
     Query(params): Query<PostRailjsonQueryParams>,
-    Json(railjson): Json<RailJson>,
+    Json(railjson): Json<serde_json::Value>,
 ) -> Result<Json<PostRailjsonResponse>> {
     let authorized = auth
         .check_roles([BuiltinRole::InfraWrite].into())
@@ -185,13 +216,35 @@ async fn post_railjson(
         return Err(AuthorizationError::Unauthorized.into());
     }
 
-    if railjson.version != RAILJSON_VERSION {
-        return Err(ListErrorsRailjson::WrongRailjsonVersionProvided.into());
-    }
-    let mut infra = Infra::changeset()
-        .name(params.name.clone())
-        .last_railjson_version()
-        .persist(railjson, &mut db_pool.get().await?)
+    let (railjson, migration) = migrate_railjson(railjson).map_err(ListErrorsRailjson::from)?;
+    let mut infra = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            let (auth, name) = (&auth, params.name.clone());
+            async move {
+                let infra = Infra::changeset()
+                    .name(name)
+                    .last_railjson_version()
+                    .persist(railjson, &mut conn.clone())
+                    .await?;
+                auth.grant_ownership(
+                    &mut conn.clone(),
+                    Resource::new(ResourceType::Infra, infra.id),
+                )
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Create,
+                    Resource::new(ResourceType::Infra, infra.id),
+                    format!("Imported infra '{}' from railjson", infra.name),
+                )
+                .await?;
+                Ok(infra)
+            }
+            .scope_boxed()
+        })
         .await?;
     let infra_id = infra.id;
 
@@ -205,7 +258,10 @@ async fn post_railjson(
         infra.refresh(db_pool, true, &infra_cache).await?;
     }
 
-    Ok(Json(PostRailjsonResponse { infra: infra.id }))
+    Ok(Json(PostRailjsonResponse {
+        infra: infra.id,
+        migration,
+    }))
 }
 
 #[cfg(test)]
@@ -219,6 +275,7 @@ mod tests {
     use crate::models::fixtures::create_empty_infra;
     use crate::views::test_app::TestAppBuilder;
     use editoast_schemas::infra::SwitchType;
+    use editoast_schemas::infra::RAILJSON_VERSION;
 
     #[rstest]
     // PostgreSQL deadlock can happen in this test, see section `Deadlock` of [DbConnectionPoolV2::get] for more information
@@ -271,8 +328,68 @@ mod tests {
 
         let res: PostRailjsonResponse = app.fetch(req).assert_status(StatusCode::OK).json_into();
 
+        assert!(!res.migration.is_migrated());
+        assert!(Infra::delete_static(&mut db_pool.get_ok(), res.infra)
+            .await
+            .unwrap());
+    }
+
+    #[rstest]
+    // PostgreSQL deadlock can happen in this test, see section `Deadlock` of [DbConnectionPoolV2::get] for more information
+    #[serial_test::serial]
+    async fn test_post_railjson_older_version() {
+        let app = TestAppBuilder::default_app();
+        let db_pool = app.db_pool();
+
+        let railjson: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/railjson_3.4.6.json")).unwrap();
+
+        let req = app
+            .post("/infra/railjson?name=post_railjson_older_version_test")
+            .json(&railjson);
+
+        let res: PostRailjsonResponse = app.fetch(req).assert_status(StatusCode::OK).json_into();
+
+        assert_eq!(res.migration.from_version, "3.4.6");
+        assert_eq!(res.migration.to_version, RAILJSON_VERSION);
+        let modified_objects: Vec<_> = res
+            .migration
+            .steps
+            .iter()
+            .map(|step| step.modified_objects)
+            .collect();
+        assert_eq!(modified_objects, vec![2, 0, 2, 0, 1, 1, 0]);
+
+        let request = app.get(&format!("/infra/{}/railjson", res.infra));
+        let railjson: RailJson = app.fetch(request).assert_status(StatusCode::OK).json_into();
+        assert_eq!(railjson.electrifications[0].voltage.0, "25000V");
+        assert_eq!(railjson.speed_sections[0].speed_limit, None);
+        assert_eq!(railjson.speed_sections[0].speed_limit_by_tag.len(), 1);
+        assert_eq!(
+            railjson.signals[0].logical_signals[0]
+                .default_parameters
+                .len(),
+            1
+        );
+
         assert!(Infra::delete_static(&mut db_pool.get_ok(), res.infra)
             .await
             .unwrap());
     }
+
+    #[rstest]
+    async fn test_post_railjson_unsupported_version() {
+        let app = TestAppBuilder::default_app();
+
+        let railjson = RailJson {
+            version: "1.0.0".to_owned(),
+            ..Default::default()
+        };
+
+        let req = app
+            .post("/infra/railjson?name=post_railjson_unsupported_version_test")
+            .json(&railjson);
+
+        app.fetch(req).assert_status(StatusCode::BAD_REQUEST);
+    }
 }
@@ -1,13 +1,25 @@
 use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 
 use axum::extract::Json;
 use axum::extract::Path;
 use axum::extract::Query;
 use axum::extract::State;
+use axum::http::header::CACHE_CONTROL;
 use axum::http::header::CONTENT_TYPE;
+use axum::http::header::ETAG;
+use axum::http::header::IF_NONE_MATCH;
+use axum::http::HeaderMap;
+use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use axum::response::Response;
 use axum::Extension;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use redis::AsyncCommands;
 use serde::Deserialize;
@@ -26,7 +38,6 @@ use crate::map::Tile;
 use crate::models::layers::geo_json_and_data::create_and_fill_mvt_tile;
 use crate::models::layers::geo_json_and_data::GeoJsonAndData;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 
 crate::routes! {
@@ -119,13 +130,14 @@ async fn layer_view(
     Path((layer_slug, view_slug)): Path<(String, String)>,
     Query(InfraQueryParam { infra: infra_id }): Query<InfraQueryParam>,
 ) -> Result<Json<ViewMetadata>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::MapRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::MapRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let layer = match map_layers.layers.get(&layer_slug) {
         Some(layer) => layer,
@@ -186,14 +198,16 @@ async fn cache_and_get_mvt_tile(
     Extension(auth): AuthenticationExt,
     Path((layer_slug, view_slug, z, x, y)): Path<(String, String, u64, u64, u64)>,
     Query(InfraQueryParam { infra: infra_id }): Query<InfraQueryParam>,
-) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::MapRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    headers: HeaderMap,
+) -> Result<Response> {
+    auth.authorize(
+        [BuiltinRole::MapRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let layer = match map_layers.layers.get(&layer_slug) {
         Some(layer) => layer,
@@ -212,7 +226,7 @@ async fn cache_and_get_mvt_tile(
     let cached_value: Option<Vec<u8>> = valkey.get(&cache_key).await?;
 
     if let Some(value) = cached_value {
-        return Ok(([(CONTENT_TYPE, "application/x-protobuf")], value));
+        return Ok(tile_response(&headers, value));
     }
 
     let conn = &mut db_pool.get().await?;
@@ -226,18 +240,48 @@ async fn cache_and_get_mvt_tile(
         .await
         .unwrap_or_else(|_| panic!("Failed to set value in valkey with key {cache_key}"));
 
-    Ok(([(CONTENT_TYPE, "application/x-protobuf")], mvt_bytes))
+    Ok(tile_response(&headers, mvt_bytes))
+}
+
+/// Tiles change when their infra is edited, so clients and proxies must revalidate them using their ETag
+fn tile_response(request_headers: &HeaderMap, mvt_bytes: Vec<u8>) -> Response {
+    let mut hasher = DefaultHasher::new();
+    mvt_bytes.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+
+    let not_modified = request_headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|tags| {
+            tags.split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag == etag)
+        });
+    let headers = [
+        (CACHE_CONTROL, "no-cache".to_owned()),
+        (ETAG, etag),
+        (CONTENT_TYPE, "application/x-protobuf".to_owned()),
+    ];
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+    (headers, mvt_bytes).into_response()
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
+    use axum::http::header::CACHE_CONTROL;
+    use axum::http::header::ETAG;
+    use axum::http::header::IF_NONE_MATCH;
+    use axum::http::HeaderMap;
     use axum::http::StatusCode;
     use rstest::rstest;
     use serde::de::DeserializeOwned;
     use serde_json::to_value;
 
+    use super::tile_response;
     use super::LayersError;
     use crate::error::InternalError;
     use crate::map::MapLayers;
@@ -305,4 +349,23 @@ mod tests {
             test_get_query_with_preset_values(expected_root_url).await;
         }
     }
+
+    #[rstest]
+    async fn tile_revalidation() {
+        let response = tile_response(&HeaderMap::new(), b"tile".to_vec());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CACHE_CONTROL], "no-cache");
+        let etag = response.headers()[ETAG].clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, etag.clone());
+        let response = tile_response(&headers, b"tile".to_vec());
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag);
+
+        // Edited tiles get a new ETag
+        let response = tile_response(&headers, b"edited tile".to_vec());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[ETAG], etag);
+    }
 }
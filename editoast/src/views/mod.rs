@@ -1,3 +1,4 @@
+mod audit;
 mod authz;
 mod documents;
 pub mod electrical_profiles;
@@ -14,6 +15,7 @@ pub mod scenario;
 pub mod search;
 pub mod speed_limit_tags;
 pub mod sprites;
+mod stdcm_logs;
 pub mod stdcm_search_environment;
 pub mod study;
 pub mod temporary_speed_limits;
@@ -42,6 +44,9 @@ use dashmap::DashMap;
 use editoast_authz::authorizer::Authorizer;
 use editoast_authz::authorizer::UserInfo;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 
 use editoast_osrdyne_client::OsrdyneClient;
 use futures::TryFutureExt;
@@ -51,6 +56,7 @@ use axum::extract::Json;
 use axum::extract::State;
 use editoast_derive::EditoastError;
 use editoast_models::db_connection_pool::ping_database;
+use editoast_models::DbConnection;
 use editoast_models::DbConnectionPoolV2;
 use serde::Deserialize;
 use serde::Serialize;
@@ -83,7 +89,16 @@ use crate::infra_cache::operation;
 use crate::infra_cache::InfraCache;
 use crate::map::MapLayers;
 use crate::models;
+use crate::models::audit_log::AuditAction;
+use crate::models::audit_log::AuditLog;
+use crate::models::audit_log::AuditTarget;
+use crate::models::auth::grant_privilege;
+use crate::models::auth::readable_condition;
+use crate::models::auth::readable_filter;
+use crate::models::auth::referenced_readable_condition;
 use crate::models::auth::PgAuthDriver;
+use crate::models::prelude::Model;
+use crate::models::prelude::SelectionSettings;
 use crate::valkey_utils::ValkeyConfig;
 use crate::ValkeyClient;
 
@@ -95,6 +110,7 @@ crate::routes! {
     "/version" => version,
     "/version/core" => core_version,
 
+    &audit,
     &authz,
     &documents,
     &electrical_profiles,
@@ -105,6 +121,7 @@ crate::routes! {
     &search,
     &speed_limit_tags,
     &sprites,
+    &stdcm_logs,
     &stdcm_search_environment,
     &work_schedules,
     &temporary_speed_limits,
@@ -134,6 +151,7 @@ editoast_common::schemas! {
     projects::schemas(),
     rolling_stock::schemas(),
     search::schemas(),
+    stdcm_logs::schemas(),
     stdcm_search_environment::schemas(),
     work_schedules::schemas(),
     train_schedule::schemas(),
@@ -171,6 +189,163 @@ impl Authentication {
         }
     }
 
+    /// Checks if the issuer of the request has at least some privilege over a resource, given
+    /// with its ancestors. Always returns `false` if the request is unauthenticated.
+    pub async fn check_privilege(
+        &self,
+        resources: &[Resource],
+        required_privilege: Privilege,
+    ) -> Result<bool, <PgAuthDriver<BuiltinRole> as editoast_authz::authorizer::StorageDriver>::Error>
+    {
+        match self {
+            Authentication::SkipAuthorization => Ok(true),
+            Authentication::Unauthenticated => Ok(false),
+            Authentication::Authenticated(authorizer) => {
+                authorizer
+                    .check_privilege(resources, required_privilege)
+                    .await
+            }
+        }
+    }
+
+    /// Checks that the issuer of the request has the required roles and at least some privilege
+    /// over resources, each given with its ancestors
+    ///
+    /// Fails with [AuthorizationError::Unauthorized] if any of them is missing.
+    pub async fn authorize(
+        &self,
+        required_roles: HashSet<BuiltinRole>,
+        required_privileges: &[(&[Resource], Privilege)],
+    ) -> Result<(), AuthorizationError> {
+        if !self.check_roles(required_roles).await? {
+            return Err(AuthorizationError::Unauthorized);
+        }
+        for (resources, required_privilege) in required_privileges {
+            if !self.check_privilege(resources, *required_privilege).await? {
+                return Err(AuthorizationError::Unauthorized);
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the issuer of the request the owner of a resource it created
+    ///
+    /// This must run in the transaction creating the resource, so that it never exists without
+    /// an owner. Nothing is granted to the superuser nor to trusted services, which aren't users.
+    pub async fn grant_ownership(
+        &self,
+        conn: &mut DbConnection,
+        resource: Resource,
+    ) -> Result<(), <PgAuthDriver<BuiltinRole> as editoast_authz::authorizer::StorageDriver>::Error>
+    {
+        match self {
+            Authentication::Authenticated(authorizer) if !authorizer.is_superuser() => {
+                grant_privilege(conn, authorizer.user_id(), resource, Privilege::Owner).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the SQL condition selecting the resources the issuer of the request can read,
+    /// see [readable_condition], or `None` if it can read everything
+    ///
+    /// Trusted services and the superuser can read everything, unauthenticated requests nothing.
+    pub fn readable_condition(&self, resource_type: ResourceType) -> Option<String> {
+        self.readable_condition_with(resource_type, |subject_id, unrestricted_readable| {
+            readable_condition(subject_id, unrestricted_readable, resource_type)
+        })
+    }
+
+    /// Like [Self::readable_condition], for the resources referred to by a column of another
+    /// table, see [referenced_readable_condition]
+    pub fn referenced_readable_condition(
+        &self,
+        resource_type: ResourceType,
+        column: &str,
+    ) -> Option<String> {
+        self.readable_condition_with(resource_type, |subject_id, unrestricted_readable| {
+            referenced_readable_condition(subject_id, unrestricted_readable, resource_type, column)
+        })
+    }
+
+    fn readable_condition_with(
+        &self,
+        resource_type: ResourceType,
+        condition: impl FnOnce(i64, bool) -> String,
+    ) -> Option<String> {
+        match self {
+            Authentication::SkipAuthorization => None,
+            Authentication::Authenticated(authorizer) if authorizer.is_superuser() => None,
+            Authentication::Authenticated(authorizer) => Some(condition(
+                authorizer.user_id(),
+                authorizer.unrestricted_privilege(resource_type).is_some(),
+            )),
+            Authentication::Unauthenticated => Some("FALSE".to_owned()),
+        }
+    }
+
+    /// Restricts a listing to the resources the issuer of the request can read
+    pub fn filter_readable<M: Model + 'static>(
+        &self,
+        settings: SelectionSettings<M>,
+        resource_type: ResourceType,
+    ) -> SelectionSettings<M> {
+        match self.readable_condition(resource_type) {
+            Some(condition) => settings.filter(move || readable_filter(&condition)),
+            None => settings,
+        }
+    }
+
+    /// Like [Self::filter_readable], for a listing of rows referring to resources by a column
+    pub fn filter_referenced_readable<M: Model + 'static>(
+        &self,
+        settings: SelectionSettings<M>,
+        resource_type: ResourceType,
+        column: &str,
+    ) -> SelectionSettings<M> {
+        match self.referenced_readable_condition(resource_type, column) {
+            Some(condition) => settings.filter(move || readable_filter(&condition)),
+            None => settings,
+        }
+    }
+
+    /// Records a change made by the issuer of the request in the audit log
+    ///
+    /// This must run in the transaction making the change, whose failure it makes fail.
+    /// Unauthenticated requests are never recorded, as they can't change anything.
+    pub async fn audit(
+        &self,
+        conn: &mut DbConnection,
+        action: AuditAction,
+        target: impl Into<AuditTarget>,
+        summary: impl Into<String>,
+    ) -> Result<()> {
+        let (author, user_id) = match self {
+            Authentication::Unauthenticated => return Ok(()),
+            Authentication::SkipAuthorization => (
+                UserInfo {
+                    identity: "service".to_owned(),
+                    name: "Trusted service".to_owned(),
+                },
+                None,
+            ),
+            // The superuser used when authorization is disabled isn't a registered user
+            Authentication::Authenticated(authorizer) => (
+                authorizer.user().clone(),
+                Some(authorizer.user_id()).filter(|user_id| *user_id >= 0),
+            ),
+        };
+        AuditLog::log(
+            conn,
+            &author,
+            user_id,
+            action,
+            target.into(),
+            summary.into(),
+        )
+        .await
+    }
+
     /// Returns the underlying authorizer if the request is authenticated, otherwise returns an
     /// error. If the request comes from Core, this returns false as well as it makes no sense to
     /// have an Authorizer without an authenticated user.
@@ -344,6 +519,12 @@ pub struct CoreConfig {
     pub timeout: Duration,
     pub single_worker: bool,
     pub num_channels: usize,
+    pub max_retries: u32,
+    pub retry_backoff: Duration,
+    pub circuit_breaker_threshold: u32,
+    pub circuit_breaker_cooldown: Duration,
+    /// Calls a single core worker over HTTP instead of going through RabbitMQ
+    pub url: Option<Url>,
 }
 
 pub struct OsrdyneConfig {
@@ -434,15 +615,33 @@ impl AppState {
                 timeout,
                 single_worker,
                 num_channels,
+                max_retries,
+                retry_backoff,
+                circuit_breaker_threshold,
+                circuit_breaker_cooldown,
+                url,
             } = config.osrdyne_config.core.clone();
-            let options = mq_client::Options {
-                uri: config.osrdyne_config.mq_url.clone(),
-                worker_pool_identifier: "core".to_owned(),
-                timeout: timeout.num_seconds() as u64,
-                single_worker,
-                num_channels,
-            };
-            CoreClient::new_mq(options).await?.into()
+            let timeout = timeout.num_seconds() as u64;
+            if let Some(url) = url {
+                CoreClient::new_http(url, timeout)?.into()
+            } else {
+                let options = mq_client::Options {
+                    uri: config.osrdyne_config.mq_url.clone(),
+                    worker_pool_identifier: "core".to_owned(),
+                    timeout,
+                    single_worker,
+                    num_channels,
+                    max_retries,
+                    retry_backoff: std::time::Duration::from_millis(
+                        retry_backoff.num_milliseconds() as u64,
+                    ),
+                    circuit_breaker_threshold,
+                    circuit_breaker_cooldown: std::time::Duration::from_secs(
+                        circuit_breaker_cooldown.num_seconds() as u64,
+                    ),
+                };
+                CoreClient::new_mq(options).await?.into()
+            }
         };
 
         let osrdyne_client = Arc::new(OsrdyneClient::new(
@@ -9,6 +9,9 @@ use axum::extract::Path;
 use axum::extract::State;
 use axum::Extension;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_schemas::rolling_stock::LoadingGaugeType;
 use editoast_schemas::train_schedule::PathItemLocation;
 use ordered_float::OrderedFloat;
@@ -35,8 +38,8 @@ use crate::valkey_utils::ValkeyConnection;
 use crate::views::get_app_version;
 use crate::views::path::path_item_cache::PathItemCache;
 use crate::views::path::PathfindingError;
+use crate::views::train_schedule::check_blocking_errors;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 use editoast_models::DbConnection;
 
@@ -167,13 +170,14 @@ async fn post(
     Path(infra_id): Path<i64>,
     Json(path_input): Json<PathfindingInput>,
 ) -> Result<Json<PathfindingResult>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
     let mut valkey_conn = valkey.get_connection().await?;
@@ -207,6 +211,8 @@ async fn pathfinding_blocks_batch(
     infra: &Infra,
     pathfinding_inputs: &[PathfindingInput],
 ) -> Result<Vec<PathfindingResult>> {
+    check_blocking_errors(conn, infra).await?;
+
     // Compute hashes of all path_inputs
     let hashes: Vec<_> = pathfinding_inputs
         .iter()
@@ -6,7 +6,11 @@ use axum::response::IntoResponse;
 use axum::Extension;
 use chrono::Utc;
 use derivative::Derivative;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_models::DbConnection;
 use editoast_models::DbConnectionPoolV2;
@@ -21,7 +25,9 @@ use super::pagination::PaginatedList;
 use super::pagination::PaginationStats;
 use super::study;
 use super::AuthenticationExt;
+use crate::error::InternalError;
 use crate::error::Result;
+use crate::models::audit_log::AuditAction;
 use crate::models::projects::Tags;
 use crate::models::Changeset;
 use crate::models::Create;
@@ -162,7 +168,29 @@ async fn create(
         check_image_content(conn, image).await?;
     }
     let project: Changeset<Project> = project_create_form.into();
-    let project = project.create(conn).await?;
+    let project = conn
+        .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
+            async move {
+                let project = project.create(&mut conn.clone()).await?;
+                auth.grant_ownership(
+                    &mut conn.clone(),
+                    Resource::new(ResourceType::Project, project.id),
+                )
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Create,
+                    Resource::new(ResourceType::Project, project.id),
+                    format!("Created project '{}'", project.name),
+                )
+                .await?;
+                Ok(project)
+            }
+            .scope_boxed()
+        })
+        .await?;
     let project_with_studies = ProjectWithStudyCount::try_fetch(conn, project).await?;
 
     Ok(Json(project_with_studies))
@@ -206,6 +234,7 @@ async fn list(
         .warn_page_size(100)
         .into_selection_settings()
         .order_by(move || ordering.as_project_ordering());
+    let settings = auth.filter_readable(settings, ResourceType::Project);
 
     let conn = &mut db_pool.get().await?;
 
@@ -244,13 +273,14 @@ async fn get(
     Extension(auth): AuthenticationExt,
     Path(project_id): Path<i64>,
 ) -> Result<Json<ProjectWithStudyCount>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Project, project_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
     let conn = &mut db_pool.get().await?;
     let project =
         Project::retrieve_or_fail(conn, project_id, || ProjectError::NotFound { project_id })
@@ -273,19 +303,34 @@ async fn delete(
     Extension(auth): AuthenticationExt,
     State(db_pool): State<DbConnectionPoolV2>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
-    let conn = &mut db_pool.get().await?;
-    if Project::delete_and_prune_document(conn, project_id).await? {
-        Ok(axum::http::StatusCode::NO_CONTENT)
-    } else {
-        Err(ProjectError::NotFound { project_id }.into())
-    }
+    auth.authorize(
+        [BuiltinRole::OpsWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Project, project_id)],
+            Privilege::Owner,
+        )],
+    )
+    .await?;
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                if !Project::delete_and_prune_document(&mut conn.clone(), project_id).await? {
+                    return Err(ProjectError::NotFound { project_id }.into());
+                }
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Delete,
+                    Resource::new(ResourceType::Project, project_id),
+                    format!("Deleted project {project_id}"),
+                )
+                .await
+            }
+            .scope_boxed()
+        })
+        .await?;
+    Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
 /// Patch form for a project
@@ -340,19 +385,40 @@ async fn patch(
     Path(project_id): Path<i64>,
     Json(form): Json<ProjectPatchForm>,
 ) -> Result<Json<ProjectWithStudyCount>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Project, project_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
     let conn = &mut db_pool.get().await?;
     if let Some(image) = form.image {
         check_image_content(conn, image).await?;
     }
     let project_changeset: Changeset<Project> = form.into();
-    let project = Project::update_and_prune_document(conn, project_changeset, project_id).await?;
+    let project = conn
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                let project = Project::update_and_prune_document(
+                    &mut conn.clone(),
+                    project_changeset,
+                    project_id,
+                )
+                .await?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::Project, project_id),
+                    format!("Updated project '{}'", project.name),
+                )
+                .await?;
+                Ok(project)
+            }
+            .scope_boxed()
+        })
+        .await?;
     Ok(Json(ProjectWithStudyCount::try_fetch(conn, project).await?))
 }
 
@@ -368,6 +434,7 @@ pub mod tests {
     use crate::models::fixtures::create_project;
     use crate::models::prelude::*;
     use crate::views::test_app::TestAppBuilder;
+    use crate::views::test_app::TestRequestExt as _;
 
     #[rstest]
     async fn project_post() {
@@ -479,4 +546,57 @@ pub mod tests {
         assert_eq!(project.budget, Some(updated_budget));
         assert!(project.last_modification > created_project.last_modification);
     }
+
+    #[rstest]
+    async fn project_access_follows_privileges() {
+        let app = TestAppBuilder::authorized_app();
+        let db_pool = app.db_pool();
+        let roles = [BuiltinRole::OpsRead, BuiltinRole::OpsWrite];
+        app.create_user("owner", roles).await;
+        app.create_user("other", roles).await;
+
+        let request = app
+            .post("/projects")
+            .json(&json!({
+                "name": "owned_project",
+                "description": "",
+                "objectives": "",
+                "funders": "",
+            }))
+            .as_user("owner");
+        let owned: ProjectWithStudyCount =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+        let owned_id = owned.project.id;
+        // Nothing was granted over projects created without the API
+        let unrestricted = create_project(&mut db_pool.get_ok(), "unrestricted_project").await;
+
+        let request = app.get(&format!("/projects/{owned_id}")).as_user("other");
+        app.fetch(request).assert_status(StatusCode::FORBIDDEN);
+        let request = app
+            .delete(&format!("/projects/{owned_id}"))
+            .as_user("other");
+        app.fetch(request).assert_status(StatusCode::FORBIDDEN);
+
+        let request = app.get("/projects/?page_size=1000").as_user("other");
+        let response: ProjectWithStudyCountList =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+        let listed: Vec<_> = response.results.iter().map(|p| p.project.id).collect();
+        assert!(!listed.contains(&owned_id));
+        assert!(listed.contains(&unrestricted.id));
+
+        // Roles give readers and writers of unrestricted projects, never their owners
+        let request = app
+            .get(&format!("/projects/{}", unrestricted.id))
+            .as_user("other");
+        app.fetch(request).assert_status(StatusCode::OK);
+        let request = app
+            .delete(&format!("/projects/{}", unrestricted.id))
+            .as_user("other");
+        app.fetch(request).assert_status(StatusCode::FORBIDDEN);
+
+        let request = app
+            .delete(&format!("/projects/{owned_id}"))
+            .as_user("owner");
+        app.fetch(request).assert_status(StatusCode::NO_CONTENT);
+    }
 }
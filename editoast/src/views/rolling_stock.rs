@@ -16,6 +16,9 @@ use axum::response::IntoResponse;
 use axum::Extension;
 use diesel_async::scoped_futures::ScopedFutureExt as _;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_models::DbConnection;
 use editoast_models::DbConnectionPoolV2;
@@ -35,6 +38,7 @@ use validator::Validate;
 
 use crate::error::InternalError;
 use crate::error::Result;
+use crate::models::audit_log::AuditAction;
 use crate::models::prelude::*;
 use crate::models::rolling_stock_livery::RollingStockLiveryModel;
 use crate::models::rolling_stock_model::ScenarioReference;
@@ -215,13 +219,14 @@ async fn get(
     Extension(auth): AuthenticationExt,
     Path(rolling_stock_id): Path<i64>,
 ) -> Result<Json<RollingStockWithLiveries>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::RollingStockCollectionRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::RollingStockCollectionRead].into(),
+        &[(
+            &[Resource::new(ResourceType::RollingStock, rolling_stock_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
     let rolling_stock = retrieve_existing_rolling_stock(
         &mut db_pool.get().await?,
         RollingStockKey::Id(rolling_stock_id),
@@ -259,6 +264,17 @@ async fn get_by_name(
         RollingStockKey::Name(rolling_stock_name),
     )
     .await?;
+    // The id of the rolling stock is only known once it is retrieved
+    let authorized = auth
+        .check_privilege(
+            &[Resource::new(ResourceType::RollingStock, rolling_stock.id)],
+            Privilege::Reader,
+        )
+        .await
+        .map_err(AuthorizationError::AuthError)?;
+    if !authorized {
+        return Err(AuthorizationError::Unauthorized.into());
+    }
     let rolling_stock_with_liveries =
         RollingStockWithLiveries::try_fetch(&mut db_pool.get().await?, rolling_stock).await?;
     Ok(Json(rolling_stock_with_liveries))
@@ -328,12 +344,34 @@ async fn create(
     let rolling_stock_name = rolling_stock_form.name.clone();
     let rolling_stock_changeset: Changeset<RollingStockModel> = rolling_stock_form.into();
 
-    let rolling_stock = rolling_stock_changeset
-        .locked(query_params.locked)
-        .version(0)
-        .create(conn)
-        .await
-        .map_err(|e| map_diesel_error(e, rolling_stock_name))?;
+    let rolling_stock = conn
+        .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
+            async move {
+                let rolling_stock = rolling_stock_changeset
+                    .locked(query_params.locked)
+                    .version(0)
+                    .create(&mut conn.clone())
+                    .await
+                    .map_err(|e| map_diesel_error(e, rolling_stock_name))?;
+                auth.grant_ownership(
+                    &mut conn.clone(),
+                    Resource::new(ResourceType::RollingStock, rolling_stock.id),
+                )
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Create,
+                    Resource::new(ResourceType::RollingStock, rolling_stock.id),
+                    format!("Created rolling stock '{}'", rolling_stock.name),
+                )
+                .await?;
+                Ok(rolling_stock)
+            }
+            .scope_boxed()
+        })
+        .await?;
 
     Ok(Json(rolling_stock))
 }
@@ -354,13 +392,14 @@ async fn update(
     Path(rolling_stock_id): Path<i64>,
     Json(rolling_stock_form): Json<RollingStockForm>,
 ) -> Result<Json<RollingStockWithLiveries>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::RollingStockCollectionWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::RollingStockCollectionWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::RollingStock, rolling_stock_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
     rolling_stock_form.validate()?;
     let name = rolling_stock_form.name.clone();
 
@@ -368,6 +407,7 @@ async fn update(
         .get()
         .await?
         .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
             async move {
                 let previous_rolling_stock = RollingStockModel::retrieve_or_fail(
                     &mut conn.clone(),
@@ -395,14 +435,25 @@ async fn update(
                         .await
                         .map_err(|err| map_diesel_error(err, name))?;
                 }
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::RollingStock, rolling_stock_id),
+                    format!(
+                        "Updated rolling stock '{}' to version {}",
+                        new_rolling_stock.name, new_rolling_stock.version
+                    ),
+                )
+                .await?;
                 Ok(new_rolling_stock)
             }
             .scope_boxed()
         })
         .await?;
 
+    let conn = &mut db_pool.get().await?;
     let new_rolling_stock_with_liveries =
-        RollingStockWithLiveries::try_fetch(&mut db_pool.get().await?, new_rolling_stock).await?;
+        RollingStockWithLiveries::try_fetch(conn, new_rolling_stock).await?;
 
     Ok(Json(new_rolling_stock_with_liveries))
 }
@@ -433,13 +484,14 @@ async fn delete(
     Path(rolling_stock_id): Path<i64>,
     Query(DeleteRollingStockQueryParams { force }): Query<DeleteRollingStockQueryParams>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::RollingStockCollectionWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::RollingStockCollectionWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::RollingStock, rolling_stock_id)],
+            Privilege::Owner,
+        )],
+    )
+    .await?;
     let conn = &mut db_pool.get().await?;
 
     let rolling_stock = RollingStockModel::retrieve_or_fail(conn, rolling_stock_id, || {
@@ -450,21 +502,33 @@ async fn delete(
     .await?;
     assert_rolling_stock_unlocked(&rolling_stock)?;
 
-    if force {
-        delete_rolling_stock(conn, rolling_stock_id).await?;
-        return Ok(StatusCode::NO_CONTENT);
+    if !force {
+        let scenarios_using_rs = rolling_stock.get_usage(conn).await?;
+        if !scenarios_using_rs.is_empty() {
+            return Err(RollingStockError::IsUsed {
+                rolling_stock_id,
+                usage: scenarios_using_rs,
+            }
+            .into());
+        }
     }
 
-    let scenarios_using_rs = rolling_stock.get_usage(conn).await?;
-    if scenarios_using_rs.is_empty() {
-        delete_rolling_stock(conn, rolling_stock_id).await?;
-        return Ok(StatusCode::NO_CONTENT);
-    }
-    Err(RollingStockError::IsUsed {
-        rolling_stock_id,
-        usage: scenarios_using_rs,
-    }
-    .into())
+    conn.transaction::<_, InternalError, _>(|conn| {
+        async move {
+            delete_rolling_stock(&mut conn.clone(), rolling_stock_id).await?;
+            auth.audit(
+                &mut conn.clone(),
+                AuditAction::Delete,
+                Resource::new(ResourceType::RollingStock, rolling_stock_id),
+                format!("Deleted rolling stock '{}'", rolling_stock.name),
+            )
+            .await?;
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 async fn delete_rolling_stock(conn: &mut DbConnection, rolling_stock_id: i64) -> Result<()> {
@@ -500,20 +564,43 @@ async fn update_locked(
     Path(rolling_stock_id): Path<i64>,
     Json(RollingStockLockedUpdateForm { locked }): Json<RollingStockLockedUpdateForm>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::RollingStockCollectionWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
-
-    let conn = &mut db_pool.get().await?;
+    auth.authorize(
+        [BuiltinRole::RollingStockCollectionWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::RollingStock, rolling_stock_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
-    RollingStockModel::changeset()
-        .locked(locked)
-        .update_or_fail(conn, rolling_stock_id, || RollingStockError::KeyNotFound {
-            rolling_stock_key: RollingStockKey::Id(rolling_stock_id),
+    let (action, summary) = if locked {
+        (AuditAction::Lock, "Locked rolling stock")
+    } else {
+        (AuditAction::Unlock, "Unlocked rolling stock")
+    };
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                RollingStockModel::changeset()
+                    .locked(locked)
+                    .update_or_fail(&mut conn.clone(), rolling_stock_id, || {
+                        RollingStockError::KeyNotFound {
+                            rolling_stock_key: RollingStockKey::Id(rolling_stock_id),
+                        }
+                    })
+                    .await?;
+                auth.audit(
+                    &mut conn.clone(),
+                    action,
+                    Resource::new(ResourceType::RollingStock, rolling_stock_id),
+                    summary,
+                )
+                .await?;
+                Ok(())
+            }
+            .scope_boxed()
         })
         .await?;
 
@@ -582,53 +669,71 @@ async fn create_livery(
     Path(rolling_stock_id): Path<i64>,
     form: Multipart,
 ) -> Result<Json<RollingStockLivery>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::RollingStockCollectionWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
-    let conn = &mut db_pool.get().await?;
-
+    auth.authorize(
+        [BuiltinRole::RollingStockCollectionWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::RollingStock, rolling_stock_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
     let (name, images) = parse_multipart_content(form)
         .await
         .map_err(RollingStockError::from)?;
 
     let formatted_images = format_images(images)?;
 
-    // create compound image
-    let compound_image = create_compound_image(conn, formatted_images.clone()).await?;
-
-    // create livery
-    let rolling_stock_livery: RollingStockLivery = RollingStockLiveryModel::changeset()
-        .name(name)
-        .rolling_stock_id(rolling_stock_id)
-        .compound_image_id(Some(compound_image.id))
-        .create(conn)
+    let rolling_stock_livery = db_pool
+        .get()
         .await?
-        .into();
-
-    // create separated images
-    let FormattedImages { images, .. } = formatted_images;
-    for (index, image) in images.into_iter().enumerate() {
-        let mut w = Cursor::new(Vec::new());
-        image.write_to(&mut w, ImageFormat::Png).unwrap();
-
-        use crate::models::Create;
-        let image = Document::changeset()
-            .content_type(String::from("image/png"))
-            .data(w.into_inner())
-            .create(conn)
-            .await?;
-
-        let _ = RollingStockSeparatedImageModel::changeset()
-            .image_id(image.id)
-            .livery_id(rolling_stock_livery.id)
-            .order(index.try_into().unwrap())
-            .create(conn)
-            .await?;
-    }
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                let conn = &mut conn.clone();
+
+                // create compound image
+                let compound_image = create_compound_image(conn, formatted_images.clone()).await?;
+
+                // create livery
+                let rolling_stock_livery: RollingStockLivery = RollingStockLiveryModel::changeset()
+                    .name(name)
+                    .rolling_stock_id(rolling_stock_id)
+                    .compound_image_id(Some(compound_image.id))
+                    .create(conn)
+                    .await?
+                    .into();
+
+                // create separated images
+                let FormattedImages { images, .. } = formatted_images;
+                for (index, image) in images.into_iter().enumerate() {
+                    let mut w = Cursor::new(Vec::new());
+                    image.write_to(&mut w, ImageFormat::Png).unwrap();
+
+                    use crate::models::Create;
+                    let image = Document::changeset()
+                        .content_type(String::from("image/png"))
+                        .data(w.into_inner())
+                        .create(conn)
+                        .await?;
+
+                    let _ = RollingStockSeparatedImageModel::changeset()
+                        .image_id(image.id)
+                        .livery_id(rolling_stock_livery.id)
+                        .order(index.try_into().unwrap())
+                        .create(conn)
+                        .await?;
+                }
+                auth.audit(
+                    conn,
+                    AuditAction::Update,
+                    Resource::new(ResourceType::RollingStock, rolling_stock_id),
+                    format!("Created livery '{}'", rolling_stock_livery.name),
+                )
+                .await?;
+                Ok(rolling_stock_livery)
+            }
+            .scope_boxed()
+        })
+        .await?;
 
     Ok(Json(rolling_stock_livery))
 }
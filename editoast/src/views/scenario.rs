@@ -8,6 +8,9 @@ use chrono::Utc;
 use derivative::Derivative;
 use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_models::DbConnection;
 use editoast_models::DbConnectionPoolV2;
@@ -19,6 +22,7 @@ use utoipa::ToSchema;
 
 use crate::error::InternalError;
 use crate::error::Result;
+use crate::models::audit_log::AuditAction;
 use crate::models::prelude::*;
 use crate::models::scenario::Scenario;
 use crate::models::timetable::Timetable;
@@ -192,13 +196,17 @@ async fn create(
     Path((project_id, study_id)): Path<(i64, i64)>,
     Json(data): Json<ScenarioCreateForm>,
 ) -> Result<Json<ScenarioResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsWrite].into(),
+        &[(
+            &[
+                Resource::new(ResourceType::Study, study_id),
+                Resource::new(ResourceType::Project, project_id),
+            ],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
     let timetable_id = data.timetable_id;
     let infra_id = data.infra_id;
@@ -208,6 +216,7 @@ async fn create(
         .get()
         .await?
         .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
             async move {
                 // Check if the project and the study exist
                 let (mut project, study) =
@@ -229,6 +238,19 @@ async fn create(
                     .study_id(study_id)
                     .create(&mut conn.clone())
                     .await?;
+                auth.grant_ownership(
+                    &mut conn.clone(),
+                    Resource::new(ResourceType::Scenario, scenario.id),
+                )
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Create,
+                    Resource::new(ResourceType::Scenario, scenario.id),
+                    format!("Created scenario '{}' in study {study_id}", scenario.name),
+                )
+                .await?;
 
                 // Update study last_modification field
                 study
@@ -273,18 +295,24 @@ async fn delete(
         scenario_id,
     }): Path<ScenarioPathParam>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsWrite].into(),
+        &[(
+            &[
+                Resource::new(ResourceType::Scenario, scenario_id),
+                Resource::new(ResourceType::Study, study_id),
+                Resource::new(ResourceType::Project, project_id),
+            ],
+            Privilege::Owner,
+        )],
+    )
+    .await?;
 
     db_pool
         .get()
         .await?
         .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
             async move {
                 // Check if the project and the study exist
                 let (mut project, study) =
@@ -297,6 +325,13 @@ async fn delete(
                     ScenarioError::NotFound { scenario_id }
                 })
                 .await?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Delete,
+                    Resource::new(ResourceType::Scenario, scenario_id),
+                    format!("Deleted scenario {scenario_id} of study {study_id}"),
+                )
+                .await?;
 
                 // Update project last_modification field
                 project.update_last_modified(&mut conn.clone()).await?;
@@ -360,18 +395,24 @@ async fn patch(
     }): Path<ScenarioPathParam>,
     Json(form): Json<ScenarioPatchForm>,
 ) -> Result<Json<ScenarioResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsWrite].into(),
+        &[(
+            &[
+                Resource::new(ResourceType::Scenario, scenario_id),
+                Resource::new(ResourceType::Study, study_id),
+                Resource::new(ResourceType::Project, project_id),
+            ],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
     let scenarios_response = db_pool
         .get()
         .await?
         .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
             async move {
                 // Check if project and study exist
                 let (mut project, study) =
@@ -391,6 +432,13 @@ async fn patch(
                         scenario_id,
                     })
                     .await?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::Scenario, scenario_id),
+                    format!("Updated scenario '{}'", scenario.name),
+                )
+                .await?;
 
                 // Update study last_modification field
                 study
@@ -435,13 +483,18 @@ async fn get(
         scenario_id,
     }): Path<ScenarioPathParam>,
 ) -> Result<Json<ScenarioResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsRead].into(),
+        &[(
+            &[
+                Resource::new(ResourceType::Scenario, scenario_id),
+                Resource::new(ResourceType::Study, study_id),
+                Resource::new(ResourceType::Project, project_id),
+            ],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
 
@@ -487,13 +540,17 @@ async fn list(
     Query(pagination_params): Query<PaginationQueryParams>,
     Query(OperationalStudiesOrderingParam { ordering }): Query<OperationalStudiesOrderingParam>,
 ) -> Result<Json<ListScenariosResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsRead].into(),
+        &[(
+            &[
+                Resource::new(ResourceType::Study, study_id),
+                Resource::new(ResourceType::Project, project_id),
+            ],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
 
@@ -505,6 +562,7 @@ async fn list(
         .into_selection_settings()
         .order_by(move || ordering.as_scenario_ordering())
         .filter(move || Scenario::STUDY_ID.eq(study_id));
+    let settings = auth.filter_readable(settings, ResourceType::Scenario);
     let (scenarios, stats) = Scenario::list_paginated(conn, settings).await?;
 
     let futs = scenarios
@@ -200,19 +200,24 @@
 use std::ops::DerefMut;
 
 use axum::extract::Json;
+use axum::extract::Path;
 use axum::extract::Query;
 use axum::extract::State;
+use axum::http::StatusCode;
 use axum::Extension;
 use chrono::DateTime;
 use chrono::NaiveDateTime;
 use chrono::Utc;
 use diesel::pg::Pg;
+use diesel::query_builder::BoxedSqlQuery;
+use diesel::query_builder::SqlQuery;
 use diesel::sql_query;
 use diesel::sql_types::Jsonb;
 use diesel::sql_types::Text;
 use diesel::QueryableByName;
 use diesel_async::RunQueryDsl;
 use editoast_authz::BuiltinRole;
+use editoast_authz::ResourceType;
 use editoast_common::geometry::GeoJsonPoint;
 use editoast_derive::EditoastError;
 use editoast_derive::Search;
@@ -223,25 +228,54 @@ use editoast_schemas::train_schedule::PowerRestrictionItem;
 use editoast_schemas::train_schedule::ScheduleItem;
 use editoast_schemas::train_schedule::TrainScheduleOptions;
 use editoast_search::query_into_sql;
+use editoast_search::SearchConfig;
 use editoast_search::SearchConfigStore as _;
 use editoast_search::SearchError;
+use editoast_search::SearchOptions;
+use itertools::Itertools as _;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::json;
 use serde_json::value::Value as JsonValue;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use utoipa::IntoParams;
 use utoipa::ToSchema;
+use validator::Validate;
 
+use crate::error::InternalError;
 use crate::error::Result;
+use crate::models::prelude::*;
+use crate::models::saved_search::SavedSearch;
+use crate::views::pagination::PaginatedList as _;
 use crate::views::pagination::PaginationQueryParams;
+use crate::views::pagination::PaginationStats;
+use crate::views::Authentication;
 use crate::views::AuthenticationExt;
 use crate::views::AuthorizationError;
 use editoast_models::DbConnectionPoolV2;
 
 crate::routes! {
-    "/search" => search,
+    "/search" => {
+        search,
+        "/federated" => federated_search,
+        "/saved" => {
+            list_saved,
+            create_saved,
+            "/{saved_search_id}" => {
+                get_saved,
+                delete_saved,
+                "/run" => run_saved,
+            },
+        },
+    },
 }
 
 editoast_common::schemas! {
+    FederatedSearchHit,
+    FederatedSearchPayload,
+    SavedSearchForm,
+    SavedSearchResponse,
     SearchPayload,
     SearchQuery,
     SearchResultItem::schemas(),
@@ -254,6 +288,25 @@ enum SearchApiError {
     ObjectType { object_type: String },
     #[error(transparent)]
     SearchEngineError(#[from] SearchError),
+    #[error("saved search '{saved_search_id}' could not be found")]
+    #[editoast_error(status = 404)]
+    SavedSearchNotFound { saved_search_id: i64 },
+    #[error("a saved search named '{name}' already exists")]
+    #[editoast_error(status = 400)]
+    SavedSearchNameAlreadyUsed { name: String },
+}
+
+fn map_diesel_error(e: InternalError, name: impl AsRef<str>) -> InternalError {
+    if e.message
+        .contains(r#"duplicate key value violates unique constraint "saved_search_owner_name_key""#)
+    {
+        SearchApiError::SavedSearchNameAlreadyUsed {
+            name: name.as_ref().to_string(),
+        }
+        .into()
+    } else {
+        e
+    }
 }
 
 /// A search query
@@ -281,6 +334,16 @@ pub struct SearchPayload {
     /// The query to run
     #[schema(value_type = SearchQuery)]
     query: JsonValue,
+    /// The keys to sort the results by, most significant first
+    ///
+    /// Each key is an expression such as `["rank", ["name"], "mich st"]`,
+    /// optionally wrapped in `["asc", key]` or `["desc", key]`.
+    #[serde(default)]
+    #[schema(value_type = Vec<SearchQuery>)]
+    order_by: Vec<JsonValue>,
+    /// Whether to add the matched fragments of the columns searched with `search` to the results
+    #[serde(default)]
+    highlight: bool,
     /// Whether to return the SQL query instead of executing it
     ///
     /// Only available in debug builds.
@@ -301,6 +364,8 @@ struct SearchDBResult {
 ///     {
 ///         "object": string,
 ///         "query": query,
+///         "order_by": [query], # default: []
+///         "highlight": boolean, # default: false
 ///         "dry": boolean, # default: false
 ///     }
 ///
@@ -308,10 +373,13 @@ struct SearchDBResult {
 /// - `object` can be any search object declared in `search.yml`
 /// - `query` is a JSON document which can be deserialized into a [editoast_search::SearchAst].
 ///   Check out examples below.
+/// - `order_by` lists the sort keys of the results, see [editoast_search::SearchOptions]
 ///
 /// # Response
 ///
-/// The response structure depends on the `object`.
+/// The response structure depends on the `object`. With `highlight`, each result
+/// also has a `highlights` object which maps the columns searched with `search`
+/// to their value, the matched words being enclosed in `<b></b>`.
 ///
 /// # Query language
 ///
@@ -333,6 +401,10 @@ struct SearchDBResult {
 ///   `["or", ["search", ["name"], "Paris"], ["search", ["name"], "Lyon"]]`
 /// * All railway stations with "Paris" in their name but not PNO :
 ///   `["and", ["search", ["name"], "Paris"], ["not", ["=", ["trigram"], "pno"]]]`
+/// * The railway stations matching "mich st", best matches first:
+///   `"query": ["search", ["name"], "mich st"], "order_by": [["desc", ["rank", ["name"], "mich st"]]]`
+/// * The signals less than 500 meters away from a given point (longitude, latitude):
+///   `["within_distance", ["geographic"], 2.3522, 48.8566, 500]`
 ///
 /// See [editoast_search::SearchAst] for a more detailed view of the query language.
 #[utoipa::path(
@@ -348,9 +420,27 @@ async fn search(
     State(db_pool): State<DbConnectionPoolV2>,
     Extension(auth): AuthenticationExt,
     Query(query_params): Query<PaginationQueryParams>,
-    Json(SearchPayload { object, query, dry }): Json<SearchPayload>,
+    Json(SearchPayload {
+        object,
+        query,
+        order_by,
+        highlight,
+        dry,
+    }): Json<SearchPayload>,
 ) -> Result<Json<serde_json::Value>> {
-    let roles: HashSet<BuiltinRole> = match object.as_str() {
+    check_object_roles(&auth, &object).await?;
+    let options = SearchOptions {
+        order_by,
+        highlight,
+        sql_constraint: readable_constraint(&auth, &object),
+        ..Default::default()
+    };
+    run_search(&db_pool, &object, query, &options, query_params, dry).await
+}
+
+/// Returns whether the issuer of the request may search some kind of object
+async fn may_search(auth: &Authentication, object: &str) -> Result<bool> {
+    let roles: HashSet<BuiltinRole> = match object {
         "track" | "operationalpoint" | "signal" => HashSet::from([BuiltinRole::InfraRead]),
         "trainschedule" => HashSet::from([BuiltinRole::TimetableRead]),
         "project" | "study" | "scenario" => HashSet::from([BuiltinRole::OpsRead]),
@@ -366,23 +456,54 @@ async fn search(
         .check_roles(roles)
         .await
         .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
+    Ok(authorized)
+}
+
+/// Checks that the issuer of the request may search some kind of object
+async fn check_object_roles(auth: &Authentication, object: &str) -> Result<()> {
+    if !may_search(auth, object).await? {
         return Err(AuthorizationError::Unauthorized.into());
     }
+    Ok(())
+}
 
-    let (page, per_page) = query_params.validate(1000)?.warn_page_size(100).unpack();
-    let search_config =
-        SearchConfigFinder::find(&object).ok_or_else(|| SearchApiError::ObjectType {
+/// Restricts the results of a search to the resources the issuer of the request can read
+///
+/// Infra objects and train schedules are restricted to the readable infras and timetables.
+fn readable_constraint(auth: &Authentication, object: &str) -> Option<String> {
+    let (resource_type, column) = match object {
+        "project" => return auth.readable_condition(ResourceType::Project),
+        "study" => return auth.readable_condition(ResourceType::Study),
+        "scenario" => return auth.readable_condition(ResourceType::Scenario),
+        "track" => (ResourceType::Infra, "search_track.infra_id"),
+        "operationalpoint" => (ResourceType::Infra, "OP.infra_id"),
+        "signal" => (ResourceType::Infra, "sig.infra_id"),
+        "trainschedule" => (ResourceType::Timetable, "train_schedule.timetable_id"),
+        _ => return None,
+    };
+    auth.referenced_readable_condition(resource_type, column)
+}
+
+fn find_search_config(object: &str) -> Result<SearchConfig> {
+    SearchConfigFinder::find(object).ok_or_else(|| {
+        SearchApiError::ObjectType {
             object_type: object.to_owned(),
-        })?;
-    let offset = (page - 1) * per_page;
-    let (sql, bindings) = query_into_sql(query, &search_config, per_page, offset, "result")
-        .map_err(SearchApiError::from)?;
+        }
+        .into()
+    })
+}
 
-    let mut query = sql_query(sql).into_boxed();
-    for string in bindings {
-        query = query.bind::<Text, _>(string.to_owned());
-    }
+async fn run_search(
+    db_pool: &DbConnectionPoolV2,
+    object: &str,
+    query: JsonValue,
+    options: &SearchOptions,
+    query_params: PaginationQueryParams,
+    dry: bool,
+) -> Result<Json<serde_json::Value>> {
+    let (page, per_page) = query_params.validate(1000)?.warn_page_size(100).unpack();
+    let offset = (page - 1) * per_page;
+    let query = build_search_query(object, query, options, per_page, offset)?;
 
     if cfg!(debug_assertions) && dry {
         tracing::debug!("not running query");
@@ -390,11 +511,418 @@ async fn search(
         return Ok(Json(serde_json::to_value(query).unwrap()));
     }
 
+    let results = load_search_results(db_pool, query).await?;
+    Ok(Json(serde_json::to_value(results).unwrap()))
+}
+
+fn build_search_query(
+    object: &str,
+    query: JsonValue,
+    options: &SearchOptions,
+    limit: i64,
+    offset: i64,
+) -> Result<BoxedSqlQuery<'static, Pg, SqlQuery>> {
+    let search_config = find_search_config(object)?;
+    let (sql, bindings) = query_into_sql(query, &search_config, limit, offset, "result", options)
+        .map_err(SearchApiError::from)?;
+
+    let mut query = sql_query(sql).into_boxed();
+    for string in bindings {
+        query = query.bind::<Text, _>(string.to_owned());
+    }
+    Ok(query)
+}
+
+async fn load_search_results(
+    db_pool: &DbConnectionPoolV2,
+    query: BoxedSqlQuery<'static, Pg, SqlQuery>,
+) -> Result<Vec<JsonValue>> {
     let objects = query
         .load::<SearchDBResult>(&mut db_pool.get().await?.write().await.deref_mut())
         .await?;
-    let results: Vec<_> = objects.into_iter().map(|r| r.result).collect();
-    Ok(Json(serde_json::to_value(results).unwrap()))
+    Ok(objects.into_iter().map(|r| r.result.0).collect())
+}
+
+/// The objects a federated search looks into, with the column their text is searched in
+const FEDERATED_OBJECTS: [(&str, &str); 6] = [
+    ("project", "name"),
+    ("study", "name"),
+    ("scenario", "name"),
+    ("operationalpoint", "name"),
+    ("track", "line_name"),
+    ("signal", "label"),
+];
+
+/// The payload of a federated search request
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "text": "mich st",
+    "objects": ["operationalpoint", "track"],
+    "filters": {
+        "operationalpoint": ["=", ["infra_id"], 2],
+        "track": ["=", ["infra_id"], 2]
+    }
+}))]
+struct FederatedSearchPayload {
+    /// The text to look for
+    text: String,
+    /// The object kinds to search, by default all those the user may search
+    objects: Option<Vec<String>>,
+    /// Additional constraints on the results of some object kinds
+    #[serde(default)]
+    #[schema(value_type = HashMap<String, SearchQuery>)]
+    filters: HashMap<String, JsonValue>,
+    /// Factors applied to the scores of some object kinds, 1 by default
+    #[serde(default)]
+    weights: HashMap<String, f64>,
+    /// Whether to add the matched fragments of the searched column to the results
+    #[serde(default)]
+    highlight: bool,
+    /// The maximum number of hits to return
+    #[serde(default = "default_federated_limit")]
+    #[validate(range(min = 1, max = 100))]
+    #[schema(default = 20, minimum = 1, maximum = 100)]
+    limit: i64,
+}
+
+fn default_federated_limit() -> i64 {
+    20
+}
+
+/// A result of a federated search
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct FederatedSearchHit {
+    /// The object kind of the result
+    object: String,
+    /// How well the result matches the searched text, between 0 and 1, weighted by object kind
+    score: f64,
+    /// The result, as returned by the search route for this object kind
+    #[schema(value_type = SearchResultItem)]
+    result: JsonValue,
+}
+
+/// Search a text in several kinds of objects at once
+///
+/// The text is searched in a column of each object kind: the name of projects,
+/// studies, scenarios and operational points, the line name of tracks and the
+/// label of signals. Hits are sorted by decreasing score: the word similarity between
+/// the text and the searched column, from 0 to 1, multiplied by the weight of the
+/// object kind.
+#[utoipa::path(
+    post, path = "",
+    tag = "search",
+    request_body = FederatedSearchPayload,
+    responses(
+        (status = 200, body = Vec<FederatedSearchHit>, description = "The search results, best hits first"),
+    )
+)]
+async fn federated_search(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Json(payload): Json<FederatedSearchPayload>,
+) -> Result<Json<Vec<FederatedSearchHit>>> {
+    payload.validate()?;
+    let FederatedSearchPayload {
+        text,
+        objects,
+        filters,
+        weights,
+        highlight,
+        limit,
+    } = payload;
+
+    // Explicitly requested objects must be searchable, defaults the user may not search are skipped
+    let objects = match objects {
+        Some(objects) => {
+            for object in &objects {
+                check_object_roles(&auth, object).await?;
+            }
+            objects
+        }
+        None => {
+            let mut objects = Vec::new();
+            for (object, _) in FEDERATED_OBJECTS {
+                if may_search(&auth, object).await? {
+                    objects.push(object.to_owned());
+                }
+            }
+            objects
+        }
+    };
+
+    let mut hits = Vec::new();
+    for object in objects {
+        let Some((_, column)) = FEDERATED_OBJECTS.iter().find(|(name, _)| *name == object) else {
+            return Err(SearchApiError::ObjectType {
+                object_type: object,
+            }
+            .into());
+        };
+        let mut query = json!(["search", [column], text]);
+        if let Some(filter) = filters.get(&object) {
+            query = json!(["and", query, filter]);
+        }
+        let rank = json!(["rank", [column], text]);
+        let options = SearchOptions {
+            order_by: vec![json!(["desc", rank])],
+            highlight,
+            score: Some(rank),
+            sql_constraint: readable_constraint(&auth, &object),
+            ..Default::default()
+        };
+        let query = build_search_query(&object, query, &options, limit, 0)?;
+        let weight = weights.get(&object).copied().unwrap_or(1.0);
+        for mut result in load_search_results(&db_pool, query).await? {
+            let score = result
+                .as_object_mut()
+                .and_then(|result| result.remove("score"))
+                .and_then(|score| score.as_f64())
+                .unwrap_or_default();
+            hits.push(FederatedSearchHit {
+                object: object.clone(),
+                score: score * weight,
+                result,
+            });
+        }
+    }
+    // The sort is stable: equal scores keep the order of the object kinds
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit as usize);
+    Ok(Json(hits))
+}
+
+/// A search query to save under a name
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "name": "Stations of Mont-Saint-Michel",
+    "object": "operationalpoint",
+    "query": ["search", ["name"], "mich st"],
+    "order_by": [["desc", ["rank", ["name"], "mich st"]]]
+}))]
+struct SavedSearchForm {
+    /// Unique among the saved searches of the user
+    #[schema(max_length = 255)]
+    name: String,
+    /// The object kind to query, as in search payloads
+    object: String,
+    #[schema(value_type = SearchQuery)]
+    query: JsonValue,
+    #[serde(default)]
+    #[schema(value_type = Vec<SearchQuery>)]
+    order_by: Vec<JsonValue>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct SavedSearchResponse {
+    id: i64,
+    name: String,
+    object: String,
+    #[schema(value_type = SearchQuery)]
+    query: JsonValue,
+    #[schema(value_type = Vec<SearchQuery>)]
+    order_by: Vec<JsonValue>,
+    created: DateTime<Utc>,
+}
+
+impl From<SavedSearch> for SavedSearchResponse {
+    fn from(saved_search: SavedSearch) -> Self {
+        Self {
+            id: saved_search.id,
+            name: saved_search.name,
+            object: saved_search.object,
+            query: saved_search.query,
+            order_by: saved_search.order_by,
+            created: saved_search.created,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct SavedSearchListResponse {
+    results: Vec<SavedSearchResponse>,
+    #[serde(flatten)]
+    stats: PaginationStats,
+}
+
+/// Retrieves a saved search of the issuer of the request
+async fn retrieve_owned_search(
+    db_pool: &DbConnectionPoolV2,
+    auth: &Authentication,
+    saved_search_id: i64,
+) -> Result<SavedSearch> {
+    let identity = auth.clone().authorizer()?.user().identity.clone();
+    let conn = &mut db_pool.get().await?;
+    let not_found = || SearchApiError::SavedSearchNotFound { saved_search_id };
+    let saved_search = SavedSearch::retrieve_or_fail(conn, saved_search_id, not_found).await?;
+    // The searches of other users are hidden rather than forbidden
+    if saved_search.owner_identity != identity {
+        return Err(not_found().into());
+    }
+    Ok(saved_search)
+}
+
+/// List the saved searches of the current user, by name
+#[utoipa::path(
+    get, path = "",
+    tag = "search",
+    params(PaginationQueryParams),
+    responses(
+        (status = 200, body = inline(SavedSearchListResponse), description = "The saved searches"),
+    )
+)]
+async fn list_saved(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Query(pagination_params): Query<PaginationQueryParams>,
+) -> Result<Json<SavedSearchListResponse>> {
+    let identity = auth.authorizer()?.user().identity.clone();
+    let settings = pagination_params
+        .validate(1000)?
+        .warn_page_size(100)
+        .into_selection_settings()
+        .filter(move || SavedSearch::OWNER_IDENTITY.eq(identity.clone()))
+        .order_by(|| SavedSearch::NAME.asc());
+
+    let conn = &mut db_pool.get().await?;
+    let (saved_searches, stats) = SavedSearch::list_paginated(conn, settings).await?;
+    let results = saved_searches.into_iter().map_into().collect();
+    Ok(Json(SavedSearchListResponse { results, stats }))
+}
+
+/// Save a search query under a name, for the current user
+///
+/// The query is checked as when running it.
+#[utoipa::path(
+    post, path = "",
+    tag = "search",
+    request_body = SavedSearchForm,
+    responses(
+        (status = 200, body = SavedSearchResponse, description = "The saved search"),
+    )
+)]
+async fn create_saved(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Json(form): Json<SavedSearchForm>,
+) -> Result<Json<SavedSearchResponse>> {
+    check_object_roles(&auth, &form.object).await?;
+    let identity = auth.authorizer()?.user().identity.clone();
+
+    let search_config = find_search_config(&form.object)?;
+    let options = SearchOptions {
+        order_by: form.order_by.clone(),
+        ..Default::default()
+    };
+    query_into_sql(form.query.clone(), &search_config, 1, 0, "result", &options)
+        .map_err(SearchApiError::from)?;
+
+    let conn = &mut db_pool.get().await?;
+    let saved_search = SavedSearch::changeset()
+        .name(form.name.clone())
+        .owner_identity(identity)
+        .object(form.object)
+        .query(form.query)
+        .order_by(form.order_by)
+        .create(conn)
+        .await
+        .map_err(|e| map_diesel_error(e, &form.name))?;
+    Ok(Json(saved_search.into()))
+}
+
+#[derive(IntoParams)]
+#[allow(unused)]
+struct SavedSearchIdParam {
+    /// The id of a saved search
+    saved_search_id: i64,
+}
+
+/// Retrieve a saved search of the current user
+#[utoipa::path(
+    get, path = "",
+    tag = "search",
+    params(SavedSearchIdParam),
+    responses(
+        (status = 200, body = SavedSearchResponse, description = "The saved search"),
+        (status = 404, body = InternalError, description = "The saved search was not found"),
+    )
+)]
+async fn get_saved(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(saved_search_id): Path<i64>,
+) -> Result<Json<SavedSearchResponse>> {
+    let saved_search = retrieve_owned_search(&db_pool, &auth, saved_search_id).await?;
+    Ok(Json(saved_search.into()))
+}
+
+/// Delete a saved search of the current user
+#[utoipa::path(
+    delete, path = "",
+    tag = "search",
+    params(SavedSearchIdParam),
+    responses(
+        (status = 204, description = "The saved search was deleted"),
+        (status = 404, body = InternalError, description = "The saved search was not found"),
+    )
+)]
+async fn delete_saved(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(saved_search_id): Path<i64>,
+) -> Result<StatusCode> {
+    let saved_search = retrieve_owned_search(&db_pool, &auth, saved_search_id).await?;
+    let conn = &mut db_pool.get().await?;
+    saved_search.delete(conn).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct RunSavedSearchParams {
+    /// Whether to add the matched fragments of the columns searched with `search` to the results
+    #[serde(default)]
+    highlight: bool,
+}
+
+/// Run a saved search of the current user
+///
+/// The results are the same as those of the search route.
+#[utoipa::path(
+    post, path = "",
+    tag = "search",
+    params(SavedSearchIdParam, PaginationQueryParams, RunSavedSearchParams),
+    responses(
+        (status = 200, body = Vec<SearchResultItem>, description = "The search results"),
+        (status = 404, body = InternalError, description = "The saved search was not found"),
+    )
+)]
+async fn run_saved(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(saved_search_id): Path<i64>,
+    Query(query_params): Query<PaginationQueryParams>,
+    Query(RunSavedSearchParams { highlight }): Query<RunSavedSearchParams>,
+) -> Result<Json<serde_json::Value>> {
+    let saved_search = retrieve_owned_search(&db_pool, &auth, saved_search_id).await?;
+    check_object_roles(&auth, &saved_search.object).await?;
+    let options = SearchOptions {
+        order_by: saved_search.order_by,
+        highlight,
+        sql_constraint: readable_constraint(&auth, &saved_search.object),
+        ..Default::default()
+    };
+    run_search(
+        &db_pool,
+        &saved_search.object,
+        saved_search.query,
+        &options,
+        query_params,
+        false,
+    )
+    .await
 }
 
 // NOTE: every structure deriving `Search` here might have to `#[allow(unused)]`
@@ -412,7 +940,8 @@ async fn search(
     table = "search_track",
     column(name = "infra_id", data_type = "INT"),
     column(name = "line_code", data_type = "INT"),
-    column(name = "line_name", data_type = "TEXT")
+    column(name = "line_name", data_type = "TEXT"),
+    column(name = "geographic", data_type = "geometry(Geometry, 4326)")
 )]
 #[allow(unused)]
 /// A search result item for a query with `object = "track"`
@@ -470,6 +999,18 @@ pub(super) struct SearchResultItemTrack {
         data_type = "text",
         sql = "infra_object_operational_point.data->'extensions'->'identifier'->>'name'",
         textual_search,
+    ),
+    column(
+        name = "geographic",
+        data_type = "geometry(Geometry, 4326)",
+        sql = "SELECT ST_Collect(ST_LineInterpolatePoint(
+                ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+                LEAST(GREATEST((part->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+            ))
+            FROM jsonb_array_elements(infra_object_operational_point.data->'parts') AS part
+            INNER JOIN infra_object_track_section AS track_section
+                ON track_section.infra_id = infra_object_operational_point.infra_id
+                AND track_section.obj_id = part->>'track'",
     )
 )]
 #[allow(unused)]
@@ -551,6 +1092,14 @@ pub(super) struct SearchResultItemOperationalPointTrackSections {
         data_type = "integer",
         sql = "(track_section.data->'extensions'->'sncf'->>'line_code')::integer"
     ),
+    column(
+        name = "geographic",
+        data_type = "geometry(Point, 4326)",
+        sql = "ST_LineInterpolatePoint(
+            ST_SetSRID(ST_GeomFromGeoJSON(track_section.data->>'geo'), 4326),
+            LEAST(GREATEST((infra_object_signal.data->>'position')::float / (track_section.data->>'length')::float, 0.), 1.)
+        )"
+    ),
     joins = "
         INNER JOIN infra_object_signal AS sig ON sig.id = search_signal.id
         INNER JOIN infra_object_track_section AS track_section ON track_section.obj_id = sig.data->>'track' AND track_section.infra_id = sig.infra_id
@@ -769,8 +1318,16 @@ pub mod tests {
     use serde_json::json;
 
     use super::*;
-    use crate::models::fixtures::{create_simple_train_schedule, create_timetable};
+    use crate::models::fixtures::{
+        create_project, create_simple_train_schedule, create_small_infra, create_study,
+        create_timetable,
+    };
     use crate::views::test_app::TestAppBuilder;
+    use crate::views::test_app::TestRequestExt as _;
+    use editoast_schemas::infra::RailJson;
+    use editoast_schemas::infra::TrackSection;
+    use editoast_schemas::infra::TrackSectionExtensions;
+    use editoast_schemas::infra::TrackSectionSncfExtension;
 
     #[rstest]
     async fn search_trainschedule_post_found() {
@@ -824,4 +1381,254 @@ pub mod tests {
 
         assert_eq!(response.len(), 0);
     }
+
+    #[rstest]
+    async fn search_projects_by_rank_with_highlights() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        create_project(&mut pool.get_ok(), "xyzzy Saint-Malo").await;
+        create_project(&mut pool.get_ok(), "xyzzy Mont Saint-Michel").await;
+
+        let request = app.post("/search").json(&json!({
+            "object": "project",
+            "query": ["search", ["name"], "xyzzy"],
+            "order_by": [["desc", ["rank", ["name"], "xyzzy michel"]]],
+            "highlight": true,
+        }));
+        let response: Vec<JsonValue> = app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let names = response
+            .iter()
+            .map(|project| project["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["xyzzy Mont Saint-Michel", "xyzzy Saint-Malo"]);
+        let highlight = response[0]["highlights"]["name"].as_str().unwrap();
+        assert!(highlight.contains("<b>xyzzy</b>"), "{highlight}");
+    }
+
+    #[rstest]
+    async fn search_signals_within_bbox() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        let infra = create_small_infra(&mut pool.get_ok()).await;
+
+        let search_in = |bbox: [f64; 4]| {
+            let [xmin, ymin, xmax, ymax] = bbox;
+            app.post("/search").json(&json!({
+                "object": "signal",
+                "query": ["and", ["=", ["infra_id"], infra.id],
+                                 ["within_bbox", ["geographic"], xmin, ymin, xmax, ymax]],
+            }))
+        };
+
+        let everywhere: Vec<JsonValue> = app
+            .fetch(search_in([-180.0, -90.0, 180.0, 90.0]))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        assert!(!everywhere.is_empty());
+
+        let elsewhere: Vec<JsonValue> = app
+            .fetch(search_in([10.0, 10.0, 11.0, 11.0]))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        assert!(elsewhere.is_empty());
+    }
+
+    #[rstest]
+    async fn federated_search_merges_hits_by_score() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        let project = create_project(&mut pool.get_ok(), "plugh Mont Saint-Michel").await;
+        create_study(&mut pool.get_ok(), "plugh Saint-Malo", project.id).await;
+
+        let search_weighted = |weights: JsonValue| {
+            app.post("/search/federated").json(&json!({
+                "text": "plugh",
+                "objects": ["study", "project"],
+                "weights": weights,
+            }))
+        };
+
+        let hits: Vec<FederatedSearchHit> = app
+            .fetch(search_weighted(json!({"project": 2.0})))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        let objects = hits
+            .iter()
+            .map(|hit| hit.object.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(objects, vec!["project", "study"]);
+        assert_eq!(hits[0].result["name"], "plugh Mont Saint-Michel");
+        assert!(hits[0].result.get("score").is_none());
+        // Both names contain the searched word, whose similarity is 1
+        assert_eq!(hits[0].score, 2.0);
+        assert_eq!(hits[1].score, 1.0);
+
+        let hits: Vec<FederatedSearchHit> = app
+            .fetch(search_weighted(json!({"study": 2.0})))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        let objects = hits
+            .iter()
+            .map(|hit| hit.object.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(objects, vec!["study", "project"]);
+        assert!(hits[0].score > hits[1].score);
+    }
+
+    #[rstest]
+    async fn federated_search_hides_unreadable_projects() {
+        let app = TestAppBuilder::authorized_app();
+        let pool = app.db_pool();
+        let roles = [BuiltinRole::OpsRead, BuiltinRole::OpsWrite];
+        app.create_user("owner", roles).await;
+        app.create_user("other", roles).await;
+
+        let request = app
+            .post("/projects")
+            .json(&json!({
+                "name": "xyzzy owned",
+                "description": "",
+                "objectives": "",
+                "funders": "",
+            }))
+            .as_user("owner");
+        app.fetch(request).assert_status(StatusCode::OK);
+        // Nothing was granted over projects created without the API
+        create_project(&mut pool.get_ok(), "xyzzy unrestricted").await;
+
+        let search_as = |user: &str| {
+            app.post("/search/federated")
+                .json(&json!({ "text": "xyzzy", "objects": ["project"] }))
+                .as_user(user)
+        };
+        let hits: Vec<FederatedSearchHit> = app
+            .fetch(search_as("other"))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        let names = hits
+            .iter()
+            .map(|hit| hit.result["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["xyzzy unrestricted"]);
+
+        let hits: Vec<FederatedSearchHit> = app
+            .fetch(search_as("owner"))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        assert_eq!(hits.len(), 2);
+    }
+
+    #[rstest]
+    async fn federated_search_hides_tracks_of_unreadable_infras() {
+        let app = TestAppBuilder::authorized_app();
+        let roles = [BuiltinRole::InfraRead, BuiltinRole::InfraWrite];
+        app.create_user("owner", roles).await;
+        app.create_user("other", roles).await;
+
+        let railjson = RailJson {
+            track_sections: vec![TrackSection {
+                id: "track".into(),
+                geo: geos::geojson::Geometry::new(geos::geojson::Value::LineString(vec![
+                    vec![0., 0.],
+                    vec![1., 1.],
+                ])),
+                extensions: TrackSectionExtensions {
+                    sncf: Some(TrackSectionSncfExtension {
+                        line_name: "xyzzy line".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let request = app
+            .post("/infra/railjson?name=xyzzy_infra")
+            .json(&railjson)
+            .as_user("owner");
+        app.fetch(request).assert_status(StatusCode::OK);
+
+        let search_as = |user: &str| {
+            app.post("/search/federated")
+                .json(&json!({ "text": "xyzzy", "objects": ["track"] }))
+                .as_user(user)
+        };
+        let hits: Vec<FederatedSearchHit> = app
+            .fetch(search_as("other"))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        assert!(hits.is_empty());
+
+        let hits: Vec<FederatedSearchHit> = app
+            .fetch(search_as("owner"))
+            .assert_status(StatusCode::OK)
+            .json_into();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].result["line_name"], "xyzzy line");
+    }
+
+    #[rstest]
+    async fn federated_search_rejects_unsupported_objects() {
+        let app = TestAppBuilder::default_app();
+
+        let request = app.post("/search/federated").json(&json!({
+            "text": "plugh",
+            "objects": ["trainschedule"],
+        }));
+        app.fetch(request).assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[rstest]
+    async fn saved_search_can_be_run() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        let timetable = create_timetable(&mut pool.get_ok()).await;
+        let train = create_simple_train_schedule(&mut pool.get_ok(), timetable.id).await;
+
+        let form = json!({
+            "name": "Trains of my timetable",
+            "object": "trainschedule",
+            "query": ["=", ["timetable_id"], timetable.id],
+            "order_by": [["train_name"]],
+        });
+        let request = app.post("/search/saved").json(&form);
+        let saved_search: SavedSearchResponse =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let request = app.post("/search/saved").json(&form);
+        app.fetch(request).assert_status(StatusCode::BAD_REQUEST);
+
+        let request = app.get("/search/saved");
+        let saved_searches: SavedSearchListResponse =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+        assert!(saved_searches
+            .results
+            .iter()
+            .any(|result| result.id == saved_search.id));
+
+        let request = app.post(&format!("/search/saved/{}/run", saved_search.id));
+        let response: Vec<SearchResultItemTrainSchedule> =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+        assert_eq!(response.len(), 1);
+        assert_eq!(response[0].train_name, train.train_name);
+
+        let request = app.delete(&format!("/search/saved/{}", saved_search.id));
+        app.fetch(request).assert_status(StatusCode::NO_CONTENT);
+        let request = app.get(&format!("/search/saved/{}", saved_search.id));
+        app.fetch(request).assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[rstest]
+    async fn invalid_queries_are_not_saved() {
+        let app = TestAppBuilder::default_app();
+        let request = app.post("/search/saved").json(&json!({
+            "name": "Invalid sort key",
+            "object": "trainschedule",
+            "query": true,
+            "order_by": [["list", "a", "b"]],
+        }));
+        app.fetch(request).assert_status(StatusCode::BAD_REQUEST);
+    }
 }
@@ -1,7 +1,10 @@
 use axum::extract::Json;
 use axum::extract::Path;
 use axum::extract::Request;
+use axum::http::header::CACHE_CONTROL;
+use axum::http::HeaderValue;
 use axum::response::IntoResponse;
+use axum::response::Response;
 use axum::Extension;
 use editoast_authz::BuiltinRole;
 use editoast_derive::EditoastError;
@@ -72,7 +75,7 @@ async fn sprites(
     Extension(auth): AuthenticationExt,
     Path((signaling_system, file_name)): Path<(String, String)>,
     request: Request,
-) -> Result<impl IntoResponse> {
+) -> Result<Response> {
     let authorized = auth
         .check_roles([BuiltinRole::MapRead].into())
         .await
@@ -92,7 +95,13 @@ async fn sprites(
         return Err(SpriteErrors::FileNotFound { file: file_name }.into());
     }
 
-    Ok(ServeFile::new(&path).oneshot(request).await)
+    // Sprites only change on deployments, and are revalidated using their Last-Modified date
+    let mut response = ServeFile::new(&path).oneshot(request).await.into_response();
+    response.headers_mut().insert(
+        CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=3600"),
+    );
+    Ok(response)
 }
 
 #[cfg(test)]
@@ -121,6 +130,10 @@ mod tests {
         let request = app.get("/sprites/TVM300/REP%20TGV.svg");
         let response = app.fetch(request).assert_status(StatusCode::OK);
         assert_eq!("image/svg+xml", response.content_type());
+        assert_eq!(
+            "public, max-age=3600",
+            response.header(CACHE_CONTROL.as_str())
+        );
         let response = response.bytes();
         let expected =
             std::fs::read(get_dynamic_assets_path().join("signal_sprites/TVM300/REP TGV.svg"))
@@ -0,0 +1,248 @@
+use axum::extract::Json;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::State;
+use axum::Extension;
+use chrono::DateTime;
+use chrono::Utc;
+use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
+use editoast_derive::EditoastError;
+use editoast_models::DbConnectionPoolV2;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::IntoParams;
+use utoipa::ToSchema;
+
+use crate::error::Result;
+use crate::models::prelude::*;
+use crate::models::stdcm_log::StdcmLog;
+use crate::models::stdcm_log::StdcmLogFilter;
+use crate::models::stdcm_log::StdcmLogOutcome;
+use crate::views::pagination::PaginatedList as _;
+use crate::views::pagination::PaginationQueryParams;
+use crate::views::pagination::PaginationStats;
+use crate::views::Authentication;
+use crate::views::AuthenticationExt;
+use crate::views::AuthorizationError;
+
+crate::routes! {
+    "/stdcm/logs" => {
+        list,
+        "/{log_id}" => get,
+    },
+}
+
+editoast_common::schemas! {
+    StdcmLogSummary,
+}
+
+#[derive(Debug, thiserror::Error, EditoastError)]
+#[editoast_error(base_id = "stdcm_log")]
+enum StdcmLogError {
+    #[error("STDCM log '{log_id}' could not be found")]
+    #[editoast_error(status = 404)]
+    NotFound { log_id: i64 },
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct StdcmLogQueryParams {
+    /// Only list the requests made by this user
+    user_id: Option<i64>,
+    /// Only list the requests made at or after this time
+    since: Option<DateTime<Utc>>,
+    /// Only list the requests made before this time
+    until: Option<DateTime<Utc>>,
+    /// Only list the requests which ended this way
+    outcome: Option<StdcmLogOutcome>,
+}
+
+impl From<StdcmLogQueryParams> for StdcmLogFilter {
+    fn from(params: StdcmLogQueryParams) -> Self {
+        Self {
+            user_id: params.user_id,
+            since: params.since,
+            until: params.until,
+            outcome: params.outcome,
+        }
+    }
+}
+
+/// A logged STDCM request, without the request and response sent to and received from core
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct StdcmLogSummary {
+    id: i64,
+    /// The trace of the request, to look it up in the telemetry
+    trace_id: String,
+    created: DateTime<Utc>,
+    user_id: Option<i64>,
+    infra_id: i64,
+    outcome: StdcmLogOutcome,
+}
+
+impl From<StdcmLog> for StdcmLogSummary {
+    fn from(log: StdcmLog) -> Self {
+        Self {
+            outcome: StdcmLogOutcome::from(&log.response),
+            id: log.id,
+            trace_id: log.trace_id,
+            created: log.created,
+            user_id: log.user_id,
+            infra_id: log.request.infra,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct StdcmLogListResponse {
+    results: Vec<StdcmLogSummary>,
+    #[serde(flatten)]
+    stats: PaginationStats,
+}
+
+async fn check_stdcm_admin(auth: &Authentication) -> Result<()> {
+    let authorized = auth
+        .check_roles([BuiltinRole::StdcmAdmin].into())
+        .await
+        .map_err(AuthorizationError::AuthError)?;
+    if !authorized {
+        return Err(AuthorizationError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+/// List the logged STDCM requests, latest first
+#[utoipa::path(
+    get, path = "",
+    tag = "stdcm_log",
+    params(PaginationQueryParams, StdcmLogQueryParams),
+    responses(
+        (status = 200, body = inline(StdcmLogListResponse), description = "The logged STDCM requests"),
+    )
+)]
+async fn list(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Query(pagination_params): Query<PaginationQueryParams>,
+    Query(log_params): Query<StdcmLogQueryParams>,
+) -> Result<Json<StdcmLogListResponse>> {
+    check_stdcm_admin(&auth).await?;
+
+    let settings = pagination_params
+        .validate(1000)?
+        .warn_page_size(100)
+        .into_selection_settings()
+        .order_by(|| StdcmLog::ID.desc());
+    let settings = StdcmLogFilter::from(log_params).apply(settings);
+    let settings = auth.filter_referenced_readable(
+        settings,
+        ResourceType::Infra,
+        "(stdcm_logs.request->>'infra')::bigint",
+    );
+
+    let conn = &mut db_pool.get().await?;
+    let (logs, stats) = StdcmLog::list_paginated(conn, settings).await?;
+    let results = logs.into_iter().map(StdcmLogSummary::from).collect();
+    Ok(Json(StdcmLogListResponse { results, stats }))
+}
+
+#[derive(IntoParams, Deserialize)]
+struct StdcmLogIdParam {
+    log_id: i64,
+}
+
+/// Retrieve a logged STDCM request, with the request sent to core and its response
+#[utoipa::path(
+    get, path = "",
+    tag = "stdcm_log",
+    params(StdcmLogIdParam),
+    responses(
+        (status = 200, body = StdcmLog, description = "The logged STDCM request"),
+        (status = 404, description = "The log was not found"),
+    )
+)]
+async fn get(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(StdcmLogIdParam { log_id }): Path<StdcmLogIdParam>,
+) -> Result<Json<StdcmLog>> {
+    check_stdcm_admin(&auth).await?;
+
+    let conn = &mut db_pool.get().await?;
+    let log =
+        StdcmLog::retrieve_or_fail(conn, log_id, || StdcmLogError::NotFound { log_id }).await?;
+    let authorized = auth
+        .check_privilege(
+            &[Resource::new(ResourceType::Infra, log.request.infra)],
+            Privilege::Reader,
+        )
+        .await
+        .map_err(AuthorizationError::AuthError)?;
+    if !authorized {
+        return Err(AuthorizationError::Unauthorized.into());
+    }
+    Ok(Json(log))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::core::simulation::SimulationResponse;
+    use crate::core::stdcm::Response;
+    use crate::models::fixtures::create_stdcm_core_request;
+    use crate::views::test_app::TestAppBuilder;
+
+    #[rstest]
+    async fn logs_are_filtered_by_outcome() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        let infra_id = 424242;
+        let not_found = StdcmLog::changeset()
+            .trace_id("trace_not_found".to_owned())
+            .request(create_stdcm_core_request(infra_id))
+            .response(Response::PathNotFound)
+            .create(&mut pool.get_ok())
+            .await
+            .expect("Failed to create STDCM log");
+        StdcmLog::changeset()
+            .trace_id("trace_error".to_owned())
+            .request(create_stdcm_core_request(infra_id))
+            .response(Response::PreprocessingSimulationError {
+                error: SimulationResponse::default(),
+            })
+            .create(&mut pool.get_ok())
+            .await
+            .expect("Failed to create STDCM log");
+
+        let since = not_found.created.to_rfc3339().replace('+', "%2B");
+        let request = app.get(&format!("/stdcm/logs?outcome=path_not_found&since={since}"));
+        let response: StdcmLogListResponse =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let trace_ids = response
+            .results
+            .iter()
+            .map(|log| log.trace_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(trace_ids, vec!["trace_not_found"]);
+        assert_eq!(response.results[0].infra_id, infra_id);
+        assert_eq!(response.results[0].outcome, StdcmLogOutcome::PathNotFound);
+    }
+
+    #[rstest]
+    async fn missing_log_is_not_found() {
+        let app = TestAppBuilder::default_app();
+
+        let request = app.get("/stdcm/logs/-1");
+        app.fetch(request).assert_status(StatusCode::NOT_FOUND);
+    }
+}
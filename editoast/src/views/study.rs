@@ -9,6 +9,9 @@ use chrono::Utc;
 use derivative::Derivative;
 use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_models::DbConnection;
 use editoast_models::DbConnectionPoolV2;
@@ -24,6 +27,7 @@ use super::AuthenticationExt;
 use super::AuthorizationError;
 use crate::error::InternalError;
 use crate::error::Result;
+use crate::models::audit_log::AuditAction;
 use crate::models::prelude::*;
 use crate::models::Project;
 use crate::models::Study;
@@ -139,18 +143,20 @@ async fn create(
     Path(project_id): Path<i64>,
     Json(data): Json<StudyCreateForm>,
 ) -> Result<Json<StudyResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Project, project_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
     let connection = db_pool.get().await?;
 
     let (study, project) = connection
         .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
             async move {
                 // Check if project exists
                 let mut project = Project::retrieve_or_fail(&mut conn.clone(), project_id, || {
@@ -163,6 +169,19 @@ async fn create(
                     .into_study_changeset(project_id)?
                     .create(&mut conn.clone())
                     .await?;
+                auth.grant_ownership(
+                    &mut conn.clone(),
+                    Resource::new(ResourceType::Study, study.id),
+                )
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Create,
+                    Resource::new(ResourceType::Study, study.id),
+                    format!("Created study '{}' in project {project_id}", study.name),
+                )
+                .await?;
 
                 // Update project last_modification field
                 project.update_last_modified(&mut conn.clone()).await?;
@@ -204,25 +223,48 @@ async fn delete(
     Extension(auth): AuthenticationExt,
     Path((project_id, study_id)): Path<(i64, i64)>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
-    // Check if project exists
-    let conn = &mut db_pool.get().await?;
-    let mut project =
-        Project::retrieve_or_fail(conn, project_id, || ProjectError::NotFound { project_id })
-            .await?;
+    auth.authorize(
+        [BuiltinRole::OpsWrite].into(),
+        &[(
+            &[
+                Resource::new(ResourceType::Study, study_id),
+                Resource::new(ResourceType::Project, project_id),
+            ],
+            Privilege::Owner,
+        )],
+    )
+    .await?;
+    db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                // Check if project exists
+                let mut project = Project::retrieve_or_fail(&mut conn.clone(), project_id, || {
+                    ProjectError::NotFound { project_id }
+                })
+                .await?;
 
-    // Delete study
-    Study::delete_static_or_fail(conn, study_id, || StudyError::NotFound { study_id }).await?;
+                // Delete study
+                Study::delete_static_or_fail(&mut conn.clone(), study_id, || {
+                    StudyError::NotFound { study_id }
+                })
+                .await?;
 
-    // Update project last_modification field
-    project.update_last_modified(conn).await?;
+                // Update project last_modification field
+                project.update_last_modified(&mut conn.clone()).await?;
 
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Delete,
+                    Resource::new(ResourceType::Study, study_id),
+                    format!("Deleted study {study_id} of project {project_id}"),
+                )
+                .await
+            }
+            .scope_boxed()
+        })
+        .await?;
     Ok(axum::http::StatusCode::NO_CONTENT)
 }
 
@@ -241,13 +283,17 @@ async fn get(
     Extension(auth): AuthenticationExt,
     Path((project_id, study_id)): Path<(i64, i64)>,
 ) -> Result<Json<StudyResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsRead].into(),
+        &[(
+            &[
+                Resource::new(ResourceType::Study, study_id),
+                Resource::new(ResourceType::Project, project_id),
+            ],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
     // Check if project exists
     let conn = &mut db_pool.get().await?;
     use crate::models::Retrieve;
@@ -325,16 +371,21 @@ async fn patch(
     Path((project_id, study_id)): Path<(i64, i64)>,
     Json(data): Json<StudyPatchForm>,
 ) -> Result<Json<StudyResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsWrite].into(),
+        &[(
+            &[
+                Resource::new(ResourceType::Study, study_id),
+                Resource::new(ResourceType::Project, project_id),
+            ],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
     let connection = db_pool.get().await?;
     let (study_scenarios, project) = connection
         .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
             async move {
                 // Check if project exists
                 let mut project = Project::retrieve_or_fail(&mut conn.clone(), project_id, || {
@@ -350,6 +401,13 @@ async fn patch(
                         study_id,
                     })
                     .await?;
+                auth.audit(
+                    &mut conn.clone(),
+                    AuditAction::Update,
+                    Resource::new(ResourceType::Study, study_id),
+                    format!("Updated study '{}'", study.name),
+                )
+                .await?;
                 let study_scenarios =
                     StudyWithScenarioCount::try_fetch(&mut conn.clone(), study).await?;
 
@@ -408,13 +466,14 @@ async fn list(
     Query(pagination_params): Query<PaginationQueryParams>,
     Query(ordering_params): Query<OperationalStudiesOrderingParam>,
 ) -> Result<Json<StudyListResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::OpsRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::OpsRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Project, project_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let ordering = ordering_params.ordering;
     if !Project::exists(&mut db_pool.get().await?, project_id).await? {
@@ -427,6 +486,7 @@ async fn list(
         .into_selection_settings()
         .filter(move || Study::PROJECT_ID.eq(project_id))
         .order_by(move || ordering.as_study_ordering());
+    let settings = auth.filter_readable(settings, ResourceType::Study);
 
     let (studies, stats) = Study::list_paginated(&mut db_pool.get().await?, settings).await?;
     let results = studies
@@ -1,4 +1,5 @@
 pub mod stdcm;
+mod train_pattern;
 
 use std::collections::HashMap;
 
@@ -10,7 +11,11 @@ use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Extension;
 use derivative::Derivative;
+use diesel_async::scoped_futures::ScopedFutureExt;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
 use editoast_schemas::train_schedule::TrainScheduleBase;
 use itertools::Itertools;
@@ -25,6 +30,7 @@ use crate::core::conflict_detection::ConflictDetectionRequest;
 use crate::core::conflict_detection::TrainRequirements;
 use crate::core::simulation::SimulationResponse;
 use crate::core::AsCoreRequest;
+use crate::error::InternalError;
 use crate::error::Result;
 use crate::models::prelude::*;
 use crate::models::timetable::Timetable;
@@ -50,6 +56,7 @@ crate::routes! {
             "/conflicts" => conflicts,
             "/train_schedule" => train_schedule,
             &stdcm,
+            &train_pattern,
         },
     },
 }
@@ -58,6 +65,7 @@ editoast_common::schemas! {
     TimetableResult,
     TimetableDetailedResult,
     stdcm::schemas(),
+    train_pattern::schemas(),
 }
 
 #[derive(Debug, Error, EditoastError)]
@@ -124,13 +132,14 @@ async fn get(
     Extension(auth): AuthenticationExt,
     Path(TimetableIdParam { id: timetable_id }): Path<TimetableIdParam>,
 ) -> Result<Json<TimetableDetailedResult>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::TimetableRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::TimetableRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Timetable, timetable_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
     let timetable = TimetableWithTrains::retrieve_or_fail(conn, timetable_id, || {
@@ -163,7 +172,22 @@ async fn post(
 
     let conn = &mut db_pool.get().await?;
 
-    let timetable = Timetable::create(conn).await?;
+    let timetable = conn
+        .transaction::<_, InternalError, _>(|conn| {
+            let auth = &auth;
+            async move {
+                let timetable = Timetable::create(&mut conn.clone()).await?;
+                auth.grant_ownership(
+                    &mut conn.clone(),
+                    Resource::new(ResourceType::Timetable, timetable.id),
+                )
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+                Ok(timetable)
+            }
+            .scope_boxed()
+        })
+        .await?;
 
     Ok(Json(timetable.into()))
 }
@@ -183,13 +207,14 @@ async fn delete(
     Extension(auth): AuthenticationExt,
     Path(TimetableIdParam { id: timetable_id }): Path<TimetableIdParam>,
 ) -> Result<impl IntoResponse> {
-    let authorized = auth
-        .check_roles([BuiltinRole::TimetableWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::TimetableWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Timetable, timetable_id)],
+            Privilege::Owner,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
     Timetable::delete_static_or_fail(conn, timetable_id, || TimetableError::NotFound {
@@ -215,13 +240,14 @@ async fn train_schedule(
     Path(TimetableIdParam { id: timetable_id }): Path<TimetableIdParam>,
     Json(train_schedules): Json<Vec<TrainScheduleBase>>,
 ) -> Result<Json<Vec<TrainScheduleResult>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::TimetableWrite].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::TimetableWrite].into(),
+        &[(
+            &[Resource::new(ResourceType::Timetable, timetable_id)],
+            Privilege::Writer,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
 
@@ -278,13 +304,14 @@ async fn conflicts(
         electrical_profile_set_id,
     }): Query<ElectricalProfileSetIdQueryParam>,
 ) -> Result<Json<Vec<Conflict>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead, BuiltinRole::TimetableRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead, BuiltinRole::TimetableRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Timetable, timetable_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     // 1. Retrieve Timetable / Infra / Trains / Simultion
     let timetable_trains =
@@ -1,3 +1,4 @@
+mod batch;
 mod failure_handler;
 mod request;
 
@@ -9,7 +10,11 @@ use axum::Extension;
 use chrono::Utc;
 use chrono::{DateTime, Duration};
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_derive::EditoastError;
+use editoast_models::DbConnection;
 use editoast_models::DbConnectionPoolV2;
 use editoast_schemas::primitives::PositiveDuration;
 use editoast_schemas::train_schedule::MarginValue;
@@ -42,22 +47,30 @@ use crate::models::prelude::*;
 use crate::models::stdcm_log::StdcmLog;
 use crate::models::timetable::TimetableWithTrains;
 use crate::models::train_schedule::TrainSchedule;
+use crate::models::work_schedules::WorkSchedule;
 use crate::models::Infra;
 use crate::models::RollingStockModel;
 use crate::views::path::pathfinding::PathfindingResult;
+use crate::views::train_schedule::check_blocking_errors;
 use crate::views::train_schedule::train_simulation;
 use crate::views::train_schedule::train_simulation_batch;
+use crate::views::Authentication;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 use crate::ValkeyClient;
 
 editoast_common::schemas! {
     request::schemas(),
+    batch::schemas(),
+    StdcmResponse,
+    StdcmAlternative,
 }
 
 crate::routes! {
-    "/stdcm" => stdcm,
+    "/stdcm" => {
+        stdcm,
+        &batch,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
@@ -70,6 +83,9 @@ enum StdcmResponse {
         simulation: SimulationResponse,
         path: PathfindingResultSuccess,
         departure_time: DateTime<Utc>,
+        /// The other paths found within the maximum departure delay, best first.
+        /// The other fields describe the best path, which is not repeated here.
+        alternatives: Vec<StdcmAlternative>,
     },
     Conflicts {
         pathfinding_result: PathfindingResult,
@@ -80,6 +96,49 @@ enum StdcmResponse {
     },
 }
 
+/// Minimum duration between the departures of two alternatives in milliseconds
+///
+/// Without it, the next search would find the same path departing a few seconds later.
+const ALTERNATIVE_DEPARTURE_GAP: u64 = 10 * 60 * 1000;
+
+/// A path found for the requested train, with a summary of its simulation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
+struct StdcmAlternative {
+    departure_time: DateTime<Utc>,
+    /// Difference in milliseconds between the requested time and the time the train passes the
+    /// step it was requested on
+    departure_time_deviation: u64,
+    /// Duration between the departure and the arrival in milliseconds
+    run_time: u64,
+    simulation: SimulationResponse,
+    path: PathfindingResultSuccess,
+}
+
+impl StdcmAlternative {
+    fn new(
+        simulation: SimulationResponse,
+        path: PathfindingResultSuccess,
+        departure_time: DateTime<Utc>,
+        requested_step_time: Option<(usize, DateTime<Utc>)>,
+    ) -> Self {
+        let departure_time_deviation =
+            requested_step_time.map_or(0, |(step_index, requested_time)| {
+                let time_to_step = simulation.path_item_time(step_index).unwrap_or_default();
+                let step_time = departure_time + Duration::milliseconds(time_to_step as i64);
+                (step_time - requested_time)
+                    .num_milliseconds()
+                    .unsigned_abs()
+            });
+        Self {
+            departure_time,
+            departure_time_deviation,
+            run_time: simulation.simulation_run_time().unwrap_or_default(),
+            simulation,
+            path,
+        }
+    }
+}
+
 #[derive(Debug, Error, EditoastError, Serialize)]
 #[editoast_error(base_id = "stdcm_v2")]
 enum StdcmError {
@@ -133,13 +192,20 @@ async fn stdcm(
     Query(query): Query<InfraIdQueryParam>,
     Json(stdcm_request): Json<Request>,
 ) -> Result<Json<StdcmResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::Stdcm].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::Stdcm].into(),
+        &[
+            (
+                &[Resource::new(ResourceType::Timetable, id)],
+                Privilege::Reader,
+            ),
+            (
+                &[Resource::new(ResourceType::Infra, query.infra)],
+                Privilege::Reader,
+            ),
+        ],
+    )
+    .await?;
 
     stdcm_request.validate()?;
     let mut conn = db_pool.get().await?;
@@ -147,167 +213,329 @@ async fn stdcm(
     let timetable_id = id;
     let infra_id = query.infra;
 
-    // 1. Retrieve Timetable / Infra / Trains / Simulation / Rolling Stock
-    let timetable_trains = TimetableWithTrains::retrieve_or_fail(&mut conn, timetable_id, || {
+    // 1. Retrieve Timetable / Infra / Trains
+    let (infra, train_schedules) =
+        retrieve_timetable_trains(&mut conn, timetable_id, infra_id).await?;
+
+    let search = StdcmSearch {
+        db_pool,
+        valkey_client,
+        core_client,
+        infra,
+        timetable_id,
+        user_id: stdcm_log_user_id(auth),
+    };
+    let (stdcm_response, _) = search
+        .compute(&mut conn, train_schedules, &[], stdcm_request)
+        .await?;
+    Ok(Json(stdcm_response))
+}
+
+async fn retrieve_timetable_trains(
+    conn: &mut DbConnection,
+    timetable_id: i64,
+    infra_id: i64,
+) -> Result<(Infra, Vec<TrainSchedule>)> {
+    let timetable_trains = TimetableWithTrains::retrieve_or_fail(conn, timetable_id, || {
         StdcmError::TimetableNotFound { timetable_id }
     })
     .await?;
 
-    let infra = Infra::retrieve_or_fail(&mut conn, infra_id, || StdcmError::InfraNotFound {
-        infra_id,
-    })
-    .await?;
+    let infra =
+        Infra::retrieve_or_fail(conn, infra_id, || StdcmError::InfraNotFound { infra_id }).await?;
 
     let (train_schedules, _): (Vec<_>, _) =
-        TrainSchedule::retrieve_batch(&mut conn, timetable_trains.train_ids.clone()).await?;
-
-    let rolling_stock =
-        RollingStockModel::retrieve_or_fail(&mut conn, stdcm_request.rolling_stock_id, || {
-            StdcmError::RollingStockNotFound {
-                rolling_stock_id: stdcm_request.rolling_stock_id,
-            }
-        })
-        .await?;
+        TrainSchedule::retrieve_batch(conn, timetable_trains.train_ids.clone()).await?;
+    Ok((infra, train_schedules))
+}
 
-    // 2. Compute the earliest start time and maximum departure delay
-    let virtual_train_run = VirtualTrainRun::simulate(
-        db_pool.clone(),
-        valkey_client.clone(),
-        core_client.clone(),
-        &stdcm_request,
-        &infra,
-        &rolling_stock,
-        timetable_id,
+/// The user the STDCM requests are logged for, if the current tracing level is debug or greater
+fn stdcm_log_user_id(auth: Authentication) -> Option<i64> {
+    if !is_stdcm_logging_enabled() {
+        return None;
+    }
+    auth.authorizer().map_or_else(
+        |e| {
+            tracing::error!("Authorization failed: {e}. Unable to retrieve user ID.");
+            None
+        },
+        |auth| Some(auth.user_id()),
     )
-    .await?;
+}
 
-    // Only the success variant of the simulation response contains the simulation run time.
-    let Some(simulation_run_time) = virtual_train_run.simulation.simulation_run_time() else {
-        return Ok(Json(StdcmResponse::PreprocessingSimulationError {
-            error: virtual_train_run.simulation,
-        }));
-    };
+/// Everything STDCM searches on a timetable need besides the requests
+struct StdcmSearch {
+    db_pool: Arc<DbConnectionPoolV2>,
+    valkey_client: Arc<ValkeyClient>,
+    core_client: Arc<CoreClient>,
+    infra: Infra,
+    timetable_id: i64,
+    /// The user the STDCM requests are logged for
+    user_id: Option<i64>,
+}
 
-    let earliest_departure_time = stdcm_request.get_earliest_departure_time(simulation_run_time);
-    let latest_simulation_end = stdcm_request.get_latest_simulation_end(simulation_run_time);
-
-    // 3. Get scheduled train requirements
-    let simulations: Vec<_> = train_simulation_batch(
-        &mut conn,
-        valkey_client.clone(),
-        core_client.clone(),
-        &train_schedules,
-        &infra,
-        stdcm_request.electrical_profile_set_id,
-    )
-    .await?
-    .into_iter()
-    .map(|(sim, _)| sim)
-    .collect();
-
-    let trains_requirements = build_train_requirements(
-        train_schedules.clone(),
-        simulations.clone(),
-        earliest_departure_time,
-        latest_simulation_end,
-    );
-
-    // 4. Retrieve work schedules
-    let work_schedules = stdcm_request.get_work_schedules(&mut conn).await?;
-
-    // 5. Build STDCM request
-    let stdcm_request = crate::core::stdcm::Request {
-        infra: infra.id,
-        expected_version: infra.version.clone(),
-        rolling_stock_loading_gauge: rolling_stock.loading_gauge,
-        rolling_stock_supported_signaling_systems: rolling_stock
-            .supported_signaling_systems
-            .clone(),
-        rolling_stock: PhysicsConsistParameters {
-            max_speed: stdcm_request.max_speed,
-            total_length: stdcm_request.total_length,
-            total_mass: stdcm_request.total_mass,
-            towed_rolling_stock: stdcm_request
-                .get_towed_rolling_stock(&mut conn)
-                .await?
-                .map(From::from),
-            traction_engine: rolling_stock.into(),
-        }
-        .into(),
-        temporary_speed_limits: stdcm_request
-            .get_temporary_speed_limits(&mut conn, simulation_run_time)
-            .await?,
-        comfort: stdcm_request.comfort,
-        path_items: stdcm_request
-            .get_stdcm_path_items(&mut conn, infra_id)
-            .await?,
-        start_time: earliest_departure_time,
-        trains_requirements,
-        maximum_departure_delay: stdcm_request.get_maximum_departure_delay(simulation_run_time),
-        maximum_run_time: stdcm_request.get_maximum_run_time(simulation_run_time),
-        speed_limit_tag: stdcm_request.speed_limit_tags,
-        time_gap_before: stdcm_request.time_gap_before,
-        time_gap_after: stdcm_request.time_gap_after,
-        margin: stdcm_request.margin,
-        time_step: Some(2000),
-        work_schedules: work_schedules
-            .iter()
-            .filter_map(|ws| {
-                ws.as_core_work_schedule(earliest_departure_time, latest_simulation_end)
+impl StdcmSearch {
+    /// Computes a STDCM between the trains of the timetable and some trains placed beforehand
+    ///
+    /// Also returns the train schedule of the virtual train following the requested steps.
+    async fn compute(
+        &self,
+        conn: &mut DbConnection,
+        mut train_schedules: Vec<TrainSchedule>,
+        placed_trains: &[(TrainSchedule, SimulationResponse)],
+        stdcm_request: Request,
+    ) -> Result<(StdcmResponse, TrainSchedule)> {
+        let Self {
+            db_pool,
+            valkey_client,
+            core_client,
+            infra,
+            timetable_id,
+            user_id,
+        } = self;
+        let infra_id = infra.id;
+        check_blocking_errors(conn, infra).await?;
+
+        let rolling_stock =
+            RollingStockModel::retrieve_or_fail(conn, stdcm_request.rolling_stock_id, || {
+                StdcmError::RollingStockNotFound {
+                    rolling_stock_id: stdcm_request.rolling_stock_id,
+                }
             })
-            .collect(),
-    };
+            .await?;
+
+        // 2. Compute the earliest start time and maximum departure delay
+        let virtual_train_run = VirtualTrainRun::simulate(
+            db_pool.clone(),
+            valkey_client.clone(),
+            core_client.clone(),
+            &stdcm_request,
+            infra,
+            &rolling_stock,
+            *timetable_id,
+        )
+        .await?;
+        let virtual_train_schedule = virtual_train_run.train_schedule.clone();
 
-    let stdcm_response = stdcm_request.fetch(core_client.as_ref()).await?;
+        // Only the success variant of the simulation response contains the simulation run time.
+        let Some(simulation_run_time) = virtual_train_run.simulation.simulation_run_time() else {
+            return Ok((
+                StdcmResponse::PreprocessingSimulationError {
+                    error: virtual_train_run.simulation,
+                },
+                virtual_train_schedule,
+            ));
+        };
 
-    // 6. Check if the current tracing level is debug or greater, and if so, log STDCM request and response
-    if tracing::level_filters::LevelFilter::current() >= tracing::Level::DEBUG {
-        let user_id = auth.authorizer().map_or_else(
-            |e| {
-                tracing::error!("Authorization failed: {e}. Unable to retrieve user ID.");
-                None
-            },
-            |auth| Some(auth.user_id()),
-        );
-        let _ = tokio::spawn(
-            // We just don't await the creation of the log entry since we want
-            // the endpoint to return as soon as possible, and because failing
-            // to persist a log entry is not a very important error here.
-            StdcmLog::log(conn, stdcm_request, stdcm_response.clone(), user_id).in_current_span(),
+        let earliest_departure_time =
+            stdcm_request.get_earliest_departure_time(simulation_run_time);
+        let latest_simulation_end = stdcm_request.get_latest_simulation_end(simulation_run_time);
+
+        // 3. Get scheduled train requirements
+        let mut simulations: Vec<_> = train_simulation_batch(
+            conn,
+            valkey_client.clone(),
+            core_client.clone(),
+            &train_schedules,
+            infra,
+            stdcm_request.electrical_profile_set_id,
         )
-        .await;
+        .await?
+        .into_iter()
+        .map(|(sim, _)| sim)
+        .collect();
+        for (train_schedule, simulation) in placed_trains {
+            train_schedules.push(train_schedule.clone());
+            simulations.push(simulation.clone());
+        }
+
+        let trains_requirements = build_train_requirements(
+            train_schedules.clone(),
+            simulations.clone(),
+            earliest_departure_time,
+            latest_simulation_end,
+        );
+
+        // 4. Retrieve work schedules
+        let work_schedules = stdcm_request.get_work_schedules(conn).await?;
+
+        // 5. Build STDCM request
+        let alternative_count = stdcm_request.alternative_count;
+        let requested_step_time = stdcm_request.get_requested_step_time();
+        let stdcm_request = crate::core::stdcm::Request {
+            infra: infra.id,
+            expected_version: infra.version.clone(),
+            rolling_stock_loading_gauge: rolling_stock.loading_gauge,
+            rolling_stock_supported_signaling_systems: rolling_stock
+                .supported_signaling_systems
+                .clone(),
+            rolling_stock: PhysicsConsistParameters {
+                max_speed: stdcm_request.max_speed,
+                total_length: stdcm_request.total_length,
+                total_mass: stdcm_request.total_mass,
+                towed_rolling_stock: stdcm_request
+                    .get_towed_rolling_stock(conn)
+                    .await?
+                    .map(From::from),
+                traction_engine: rolling_stock.into(),
+            }
+            .into(),
+            temporary_speed_limits: stdcm_request
+                .get_temporary_speed_limits(conn, simulation_run_time)
+                .await?,
+            comfort: stdcm_request.comfort,
+            path_items: stdcm_request.get_stdcm_path_items(conn, infra_id).await?,
+            start_time: earliest_departure_time,
+            trains_requirements,
+            maximum_departure_delay: stdcm_request.get_maximum_departure_delay(simulation_run_time),
+            maximum_run_time: stdcm_request.get_maximum_run_time(simulation_run_time),
+            speed_limit_tag: stdcm_request.speed_limit_tags,
+            time_gap_before: stdcm_request.time_gap_before,
+            time_gap_after: stdcm_request.time_gap_after,
+            margin: stdcm_request.margin,
+            time_step: Some(2000),
+            work_schedules: work_schedules
+                .iter()
+                .filter_map(|ws| {
+                    ws.as_core_work_schedule(earliest_departure_time, latest_simulation_end)
+                })
+                .collect(),
+        };
+
+        // 6. Send the request, logging it with its response if the tracing level allows it
+        let stdcm_response = stdcm_request.fetch(core_client.as_ref()).await?;
+        log_stdcm_request(db_pool, *user_id, &stdcm_request, &stdcm_response).await?;
+
+        // 7. Handle STDCM Core Response
+        let stdcm_response = match stdcm_response {
+            crate::core::stdcm::Response::Success {
+                simulation,
+                path,
+                departure_time,
+            } => {
+                let mut alternatives = vec![StdcmAlternative::new(
+                    simulation,
+                    path,
+                    departure_time,
+                    requested_step_time,
+                )];
+
+                // 8. Look for other paths, each one departing some time after the previous one
+                let mut last_departure_time = departure_time;
+                while alternatives.len() < alternative_count {
+                    let Some(alternative_request) = delay_stdcm_request(
+                        &stdcm_request,
+                        last_departure_time
+                            + Duration::milliseconds(ALTERNATIVE_DEPARTURE_GAP as i64),
+                        &work_schedules,
+                        latest_simulation_end,
+                    ) else {
+                        break;
+                    };
+                    let alternative_response =
+                        alternative_request.fetch(core_client.as_ref()).await?;
+                    log_stdcm_request(
+                        db_pool,
+                        *user_id,
+                        &alternative_request,
+                        &alternative_response,
+                    )
+                    .await?;
+                    let crate::core::stdcm::Response::Success {
+                        simulation,
+                        path,
+                        departure_time,
+                    } = alternative_response
+                    else {
+                        break;
+                    };
+                    last_departure_time = departure_time.max(alternative_request.start_time);
+                    alternatives.push(StdcmAlternative::new(
+                        simulation,
+                        path,
+                        departure_time,
+                        requested_step_time,
+                    ));
+                }
+
+                alternatives.sort_by_key(|alternative| {
+                    (alternative.departure_time_deviation, alternative.run_time)
+                });
+                let best = alternatives.remove(0);
+                StdcmResponse::Success {
+                    simulation: best.simulation,
+                    path: best.path,
+                    departure_time: best.departure_time,
+                    alternatives,
+                }
+            }
+            crate::core::stdcm::Response::PreprocessingSimulationError { error } => {
+                StdcmResponse::PreprocessingSimulationError { error }
+            }
+            crate::core::stdcm::Response::PathNotFound => {
+                let simulation_failure_handler = SimulationFailureHandler {
+                    core_client: core_client.clone(),
+                    infra_id,
+                    infra_version: infra.version.clone(),
+                    train_schedules,
+                    simulations,
+                    work_schedules,
+                    virtual_train_run,
+                    earliest_departure_time,
+                    latest_simulation_end,
+                };
+                simulation_failure_handler.compute_conflicts().await?
+            }
+        };
+        Ok((stdcm_response, virtual_train_schedule))
     }
+}
 
-    // 7. Handle STDCM Core Response
-    match stdcm_response {
-        crate::core::stdcm::Response::Success {
-            simulation,
-            path,
-            departure_time,
-        } => Ok(Json(StdcmResponse::Success {
-            simulation,
-            path,
-            departure_time,
-        })),
-        crate::core::stdcm::Response::PreprocessingSimulationError { error } => {
-            Ok(Json(StdcmResponse::PreprocessingSimulationError { error }))
-        }
-        crate::core::stdcm::Response::PathNotFound => {
-            let simulation_failure_handler = SimulationFailureHandler {
-                core_client,
-                infra_id,
-                infra_version: infra.version,
-                train_schedules,
-                simulations,
-                work_schedules,
-                virtual_train_run,
-                earliest_departure_time,
-                latest_simulation_end,
-            };
-            let stdcm_response = simulation_failure_handler.compute_conflicts().await?;
-            Ok(Json(stdcm_response))
-        }
+fn is_stdcm_logging_enabled() -> bool {
+    tracing::level_filters::LevelFilter::current() >= tracing::Level::DEBUG
+}
+
+/// Logs a STDCM request sent to core and its response, if the tracing level allows it
+async fn log_stdcm_request(
+    db_pool: &DbConnectionPoolV2,
+    user_id: Option<i64>,
+    request: &crate::core::stdcm::Request,
+    response: &crate::core::stdcm::Response,
+) -> Result<()> {
+    if !is_stdcm_logging_enabled() {
+        return Ok(());
     }
+    let conn = db_pool.get().await?;
+    let _ = tokio::spawn(
+        // We just don't await the creation of the log entry since we want
+        // the endpoint to return as soon as possible, and because failing
+        // to persist a log entry is not a very important error here.
+        StdcmLog::log(conn, request.clone(), response.clone(), user_id).in_current_span(),
+    )
+    .await;
+    Ok(())
+}
+
+/// Returns the request looking for a path departing at or after `start_time`, within the
+/// departure window of the given request, or `None` if `start_time` is outside of it
+fn delay_stdcm_request(
+    request: &crate::core::stdcm::Request,
+    start_time: DateTime<Utc>,
+    work_schedules: &[WorkSchedule],
+    latest_simulation_end: DateTime<Utc>,
+) -> Option<crate::core::stdcm::Request> {
+    let delay = u64::try_from((start_time - request.start_time).num_milliseconds()).ok()?;
+    let maximum_departure_delay = request.maximum_departure_delay.checked_sub(delay)?;
+    Some(crate::core::stdcm::Request {
+        start_time,
+        maximum_departure_delay,
+        // Work schedule times are relative to the start time
+        work_schedules: work_schedules
+            .iter()
+            .filter_map(|ws| ws.as_core_work_schedule(start_time, latest_simulation_end))
+            .collect(),
+        ..request.clone()
+    })
 }
 
 /// Build the list of scheduled train requirements, only including requirements
@@ -439,6 +667,7 @@ impl VirtualTrainRun {
             speed_limit_tag: stdcm_request.speed_limit_tags.clone(),
             power_restrictions: vec![],
             options: Default::default(),
+            train_pattern_id: None,
         };
 
         let (simulation, pathfinding) = train_simulation(
@@ -650,7 +879,7 @@ mod tests {
         assert_eq!(physics_consist.max_speed, 20_f64);
     }
 
-    fn pathfinding_result_success() -> PathfindingResult {
+    pub(super) fn pathfinding_result_success() -> PathfindingResult {
         PathfindingResult::Success(PathfindingResultSuccess {
             blocks: vec![],
             routes: vec![],
@@ -660,7 +889,7 @@ mod tests {
         })
     }
 
-    fn simulation_response() -> SimulationResponse {
+    pub(super) fn simulation_response() -> SimulationResponse {
         SimulationResponse::Success {
             base: ReportTrain {
                 positions: vec![],
@@ -700,7 +929,7 @@ mod tests {
         }
     }
 
-    fn stdcm_payload(rolling_stock_id: i64) -> serde_json::Value {
+    pub(super) fn stdcm_payload(rolling_stock_id: i64) -> serde_json::Value {
         json!({
           "comfort": "STANDARD",
           "margin": "4.5min/100km",
@@ -723,7 +952,7 @@ mod tests {
         })
     }
 
-    fn core_mocking_client() -> MockingClient {
+    pub(super) fn core_mocking_client() -> MockingClient {
         let mut core = MockingClient::new();
         core.stub("/v2/pathfinding/blocks")
             .method(reqwest::Method::POST)
@@ -788,12 +1017,58 @@ mod tests {
                     simulation: simulation_response(),
                     path,
                     departure_time: DateTime::from_str("2024-01-02T00:00:00Z")
-                        .expect("Failed to parse datetime")
+                        .expect("Failed to parse datetime"),
+                    alternatives: vec![],
                 }
             );
         }
     }
 
+    #[rstest]
+    async fn stdcm_return_alternatives_within_the_departure_window() {
+        let db_pool = DbConnectionPoolV2::for_tests();
+        let mut core = core_mocking_client();
+        core.stub("/v2/stdcm")
+            .method(reqwest::Method::POST)
+            .response(StatusCode::OK)
+            .json(json!({
+                "status": "success",
+                "simulation": serde_json::to_value(simulation_response()).unwrap(),
+                "path": serde_json::to_value(pathfinding_result_success()).unwrap(),
+                "departure_time": "2024-09-17T18:05:00Z"
+            }))
+            .finish();
+
+        let app = TestAppBuilder::new()
+            .db_pool(db_pool.clone())
+            .core_client(core.into())
+            .build();
+        let small_infra = create_small_infra(&mut db_pool.get_ok()).await;
+        let timetable = create_timetable(&mut db_pool.get_ok()).await;
+        let rolling_stock =
+            create_fast_rolling_stock(&mut db_pool.get_ok(), &Uuid::new_v4().to_string()).await;
+        let mut payload = stdcm_payload(rolling_stock.id);
+        payload["alternative_count"] = json!(10);
+        // Leaves room for searches starting 10 and 20 minutes after the requested time
+        payload["maximum_departure_delay"] = json!(25 * 60 * 1000);
+
+        let request = app
+            .post(format!("/timetable/{}/stdcm?infra={}", timetable.id, small_infra.id).as_str())
+            .json(&payload);
+
+        let stdcm_response: StdcmResponse =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let StdcmResponse::Success { alternatives, .. } = stdcm_response else {
+            panic!("STDCM request should succeed, got {stdcm_response:?}");
+        };
+        // The best path is described by the other fields
+        assert_eq!(alternatives.len(), 2);
+        assert!(alternatives
+            .iter()
+            .all(|alternative| alternative.departure_time_deviation == 0));
+    }
+
     #[rstest]
     async fn stdcm_return_conflicts() {
         let db_pool = DbConnectionPoolV2::for_tests();
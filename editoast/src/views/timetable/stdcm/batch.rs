@@ -0,0 +1,385 @@
+use axum::extract::Json;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::State;
+use axum::Extension;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
+use editoast_schemas::primitives::PositiveDuration;
+use editoast_schemas::train_schedule::ReceptionSignal;
+use editoast_schemas::train_schedule::ScheduleItem;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::request::Request;
+use super::retrieve_timetable_trains;
+use super::stdcm_log_user_id;
+use super::InfraIdQueryParam;
+use super::StdcmResponse;
+use super::StdcmSearch;
+use crate::core::simulation::SimulationResponse;
+use crate::error::InternalError;
+use crate::error::Result;
+use crate::models::prelude::*;
+use crate::models::train_schedule::TrainSchedule;
+use crate::models::train_schedule::TrainScheduleChangeset;
+use crate::views::train_schedule::TrainScheduleForm;
+use crate::views::train_schedule::TrainScheduleResult;
+use crate::views::AuthenticationExt;
+use crate::views::AuthorizationError;
+use crate::AppState;
+
+crate::routes! {
+    "/batch" => stdcm_batch,
+}
+
+editoast_common::schemas! {
+    StdcmBatchItem,
+    StdcmBatchResult,
+}
+
+/// A STDCM request of a batch
+#[derive(Debug, Deserialize, ToSchema)]
+struct StdcmBatchItem {
+    /// The name of the train following the path found
+    train_name: String,
+    request: Request,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+struct StdcmBatchForm {
+    /// The requests, in the order they are solved
+    #[validate(length(min = 1, max = 100))]
+    #[schema(min_items = 1, max_items = 100)]
+    requests: Vec<StdcmBatchItem>,
+    /// Whether to add the trains following the paths found to the timetable.
+    /// Otherwise, the timetable is left unchanged.
+    #[serde(default)]
+    commit: bool,
+}
+
+/// The result of a STDCM request of a batch
+///
+/// The trains placed by the previous requests of the batch are referred to by negative ids in
+/// conflicts: -1 for the first train placed, -2 for the second, and so on.
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+#[serde(tag = "status", rename_all = "snake_case")]
+// Like the STDCM response it holds, failures are the smaller variant
+#[allow(clippy::large_enum_variant)]
+enum StdcmBatchResult {
+    /// The request was solved, and the train following the path found, if any, was placed
+    /// before solving the next requests
+    Solved {
+        response: StdcmResponse,
+        /// The train schedule added to the timetable, if a path was found and the batch committed
+        train_schedule_id: Option<i64>,
+    },
+    /// The request could not be solved
+    Failed { error: InternalError },
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct StdcmBatchResponse {
+    /// The result of each request, in the order of the requests
+    results: Vec<StdcmBatchResult>,
+}
+
+/// Computes STDCM requests one after the other on the same timetable
+///
+/// The train following each path found is placed in the timetable before solving the next
+/// request, so that the next paths avoid it. These trains are only saved if the batch is committed.
+#[utoipa::path(
+    post, path = "",
+    tag = "stdcm",
+    request_body = inline(StdcmBatchForm),
+    params(("infra" = i64, Query, description = "The infra id"),
+        ("id" = i64, Path, description = "timetable_id"),
+    ),
+    responses(
+        (status = 200, body = inline(StdcmBatchResponse), description = "The result of each request"),
+    )
+)]
+async fn stdcm_batch(
+    State(AppState {
+        db_pool,
+        valkey: valkey_client,
+        core_client,
+        ..
+    }): State<AppState>,
+    Extension(auth): AuthenticationExt,
+    Path(timetable_id): Path<i64>,
+    Query(InfraIdQueryParam { infra: infra_id }): Query<InfraIdQueryParam>,
+    Json(batch): Json<StdcmBatchForm>,
+) -> Result<Json<StdcmBatchResponse>> {
+    let mut authorized = auth
+        .check_roles([BuiltinRole::Stdcm].into())
+        .await
+        .map_err(AuthorizationError::AuthError)?
+        && auth
+            .check_privilege(
+                &[Resource::new(ResourceType::Infra, infra_id)],
+                Privilege::Reader,
+            )
+            .await
+            .map_err(AuthorizationError::AuthError)?;
+    let timetable_privilege = if batch.commit {
+        authorized = authorized
+            && auth
+                .check_roles([BuiltinRole::TimetableWrite].into())
+                .await
+                .map_err(AuthorizationError::AuthError)?;
+        Privilege::Writer
+    } else {
+        Privilege::Reader
+    };
+    authorized = authorized
+        && auth
+            .check_privilege(
+                &[Resource::new(ResourceType::Timetable, timetable_id)],
+                timetable_privilege,
+            )
+            .await
+            .map_err(AuthorizationError::AuthError)?;
+    if !authorized {
+        return Err(AuthorizationError::Unauthorized.into());
+    }
+
+    batch.validate()?;
+    for item in &batch.requests {
+        item.request.validate()?;
+    }
+    let mut conn = db_pool.get().await?;
+
+    let (infra, train_schedules) =
+        retrieve_timetable_trains(&mut conn, timetable_id, infra_id).await?;
+    let search = StdcmSearch {
+        db_pool: db_pool.clone(),
+        valkey_client,
+        core_client,
+        infra,
+        timetable_id,
+        user_id: stdcm_log_user_id(auth),
+    };
+
+    let mut placed_trains: Vec<(TrainSchedule, SimulationResponse)> = Vec::new();
+    let mut results = Vec::new();
+    for StdcmBatchItem {
+        train_name,
+        request,
+    } in batch.requests
+    {
+        let stop_durations = request.get_stop_durations();
+        let (response, virtual_train_schedule) = match search
+            .compute(&mut conn, train_schedules.clone(), &placed_trains, request)
+            .await
+        {
+            Ok(solved) => solved,
+            Err(error) => {
+                results.push(StdcmBatchResult::Failed { error });
+                continue;
+            }
+        };
+        if let StdcmResponse::Success {
+            simulation,
+            departure_time,
+            ..
+        } = &response
+        {
+            let train_schedule = TrainSchedule {
+                id: -(placed_trains.len() as i64) - 1,
+                train_name,
+                ..placed_train_schedule(
+                    virtual_train_schedule,
+                    &stop_durations,
+                    *departure_time,
+                    simulation,
+                )
+            };
+            placed_trains.push((train_schedule, simulation.clone()));
+        }
+        results.push(StdcmBatchResult::Solved {
+            response,
+            train_schedule_id: None,
+        });
+    }
+
+    if batch.commit && !placed_trains.is_empty() {
+        let changesets = placed_trains
+            .into_iter()
+            .map(|(train_schedule, _)| TrainScheduleForm {
+                timetable_id: Some(timetable_id),
+                train_schedule: TrainScheduleResult::from(train_schedule).train_schedule,
+            })
+            .map(TrainScheduleChangeset::from);
+        let created: Vec<TrainSchedule> =
+            TrainSchedule::create_batch(&mut conn, changesets).await?;
+        let mut created_ids = created.into_iter().map(|train_schedule| train_schedule.id);
+        for result in &mut results {
+            if let StdcmBatchResult::Solved {
+                response: StdcmResponse::Success { .. },
+                train_schedule_id,
+            } = result
+            {
+                *train_schedule_id = created_ids.next();
+            }
+        }
+    }
+
+    Ok(Json(StdcmBatchResponse { results }))
+}
+
+/// Builds the train schedule following a path found by STDCM from the virtual train's one
+///
+/// The times at which STDCM makes the train pass the steps are set as arrival times, so that
+/// simulating the train schedule gives a run close to the one STDCM found.
+fn placed_train_schedule(
+    virtual_train_schedule: TrainSchedule,
+    stop_durations: &[Option<u64>],
+    departure_time: DateTime<Utc>,
+    simulation: &SimulationResponse,
+) -> TrainSchedule {
+    let duration = |milliseconds: u64| {
+        PositiveDuration::try_from(Duration::milliseconds(milliseconds as i64)).unwrap()
+    };
+    let last_index = virtual_train_schedule.path.len() - 1;
+    let schedule = virtual_train_schedule
+        .path
+        .iter()
+        .zip(stop_durations)
+        .enumerate()
+        .skip(1)
+        .map(|(index, (path_item, stop_duration))| ScheduleItem {
+            at: path_item.id.clone(),
+            arrival: simulation.path_item_time(index).map(duration),
+            // Make the train stop at the end
+            stop_for: stop_duration
+                .or((index == last_index).then_some(0))
+                .map(duration),
+            reception_signal: ReceptionSignal::Open,
+            locked: false,
+        })
+        .collect();
+    TrainSchedule {
+        start_time: departure_time,
+        schedule,
+        ..virtual_train_schedule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use editoast_models::DbConnectionPoolV2;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::models::fixtures::create_fast_rolling_stock;
+    use crate::models::fixtures::create_small_infra;
+    use crate::models::fixtures::create_timetable;
+    use crate::models::timetable::TimetableWithTrains;
+    use crate::views::test_app::TestAppBuilder;
+    use crate::views::timetable::stdcm::tests::core_mocking_client;
+    use crate::views::timetable::stdcm::tests::pathfinding_result_success;
+    use crate::views::timetable::stdcm::tests::simulation_response;
+    use crate::views::timetable::stdcm::tests::stdcm_payload;
+
+    #[rstest]
+    #[case::committed(true)]
+    #[case::rolled_back(false)]
+    async fn stdcm_batch_places_each_train_found(#[case] commit: bool) {
+        let db_pool = DbConnectionPoolV2::for_tests();
+        let mut core = core_mocking_client();
+        core.stub("/v2/stdcm")
+            .method(reqwest::Method::POST)
+            .response(StatusCode::OK)
+            .json(json!({
+                "status": "success",
+                "simulation": serde_json::to_value(simulation_response()).unwrap(),
+                "path": serde_json::to_value(pathfinding_result_success()).unwrap(),
+                "departure_time": "2024-09-17T18:05:00Z"
+            }))
+            .finish();
+        let core_requests = core.received_requests();
+
+        let app = TestAppBuilder::new()
+            .db_pool(db_pool.clone())
+            .core_client(core.into())
+            .build();
+        let small_infra = create_small_infra(&mut db_pool.get_ok()).await;
+        let timetable = create_timetable(&mut db_pool.get_ok()).await;
+        let rolling_stock =
+            create_fast_rolling_stock(&mut db_pool.get_ok(), &Uuid::new_v4().to_string()).await;
+
+        let request = app
+            .post(
+                format!(
+                    "/timetable/{}/stdcm/batch?infra={}",
+                    timetable.id, small_infra.id
+                )
+                .as_str(),
+            )
+            .json(&json!({
+                "requests": [
+                    { "train_name": "first", "request": stdcm_payload(rolling_stock.id) },
+                    { "train_name": "second", "request": stdcm_payload(rolling_stock.id) },
+                ],
+                "commit": commit,
+            }));
+
+        let batch_response: StdcmBatchResponse =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let train_schedule_ids = batch_response
+            .results
+            .iter()
+            .map(|result| match result {
+                StdcmBatchResult::Solved {
+                    response: StdcmResponse::Success { .. },
+                    train_schedule_id,
+                } => *train_schedule_id,
+                _ => panic!("STDCM request should succeed, got {result:?}"),
+            })
+            .collect::<Vec<_>>();
+        // The second search avoids the train placed by the first one
+        let stdcm_requests = core_requests.bodies("/v2/stdcm");
+        assert_eq!(stdcm_requests.len(), 2);
+        assert!(stdcm_requests[0]["trains_requirements"].get("-1").is_none());
+        assert!(stdcm_requests[1]["trains_requirements"].get("-1").is_some());
+        let timetable = TimetableWithTrains::retrieve(&mut db_pool.get_ok(), timetable.id)
+            .await
+            .expect("Failed to retrieve timetable")
+            .expect("Timetable not found");
+        if commit {
+            let mut train_ids = timetable.train_ids.clone();
+            train_ids.sort();
+            assert_eq!(
+                train_schedule_ids.into_iter().flatten().collect::<Vec<_>>(),
+                train_ids
+            );
+            let train_schedule = TrainSchedule::retrieve(&mut db_pool.get_ok(), train_ids[1])
+                .await
+                .expect("Failed to retrieve train schedule")
+                .expect("Train schedule not found");
+            assert_eq!(train_schedule.train_name, "second");
+            assert_eq!(
+                train_schedule.start_time,
+                "2024-09-17T18:05:00Z".parse::<DateTime<Utc>>().unwrap()
+            );
+        } else {
+            assert_eq!(train_schedule_ids, vec![None, None]);
+            assert!(timetable.train_ids.is_empty());
+        }
+    }
+}
@@ -112,6 +112,16 @@ pub(super) struct Request {
     #[validate(range(exclusive_min = 0.0))]
     pub(super) max_speed: Option<f64>,
     pub(super) loading_gauge_type: Option<LoadingGaugeType>,
+    /// How many paths to look for within the maximum departure delay, departing at least
+    /// 10 minutes apart
+    #[serde(default = "default_alternative_count")]
+    #[validate(range(min = 1, max = 10))]
+    #[schema(default = 1, minimum = 1, maximum = 10)]
+    pub(super) alternative_count: usize,
+}
+
+fn default_alternative_count() -> usize {
+    1
 }
 
 impl Request {
@@ -132,6 +142,24 @@ impl Request {
             .expect("No time specified for stdcm request")
     }
 
+    /// Returns the index of the step whose time was requested, with that time
+    ///
+    /// It is the departure when a start time is given, otherwise the first step with timing data.
+    pub(super) fn get_requested_step_time(&self) -> Option<(usize, DateTime<Utc>)> {
+        if let Some(start_time) = self.start_time {
+            return Some((0, start_time));
+        }
+        self.steps
+            .iter()
+            .enumerate()
+            .find_map(|(index, step)| Some((index, step.timing_data.as_ref()?.arrival_time)))
+    }
+
+    /// Returns the stop duration of each step in milliseconds, `None` where the train does not stop
+    pub(super) fn get_stop_durations(&self) -> Vec<Option<u64>> {
+        self.steps.iter().map(|step| step.duration).collect()
+    }
+
     /// Returns the earliest tolerance window that has been set on any step
     fn get_earliest_step_tolerance_window(&self) -> u64 {
         // Get the earliest time window that has been specified for any step, if maximum_run_time is not none
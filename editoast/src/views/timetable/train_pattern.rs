@@ -0,0 +1,441 @@
+use axum::extract::Json;
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Extension;
+use chrono::Duration;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
+use editoast_derive::EditoastError;
+use editoast_models::DbConnection;
+use editoast_models::DbConnectionPoolV2;
+use editoast_schemas::train_schedule::TrainPatternBase;
+use editoast_schemas::train_schedule::TrainPatternRecurrence;
+use editoast_schemas::train_schedule::TrainScheduleBase;
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+use utoipa::IntoParams;
+use utoipa::ToSchema;
+
+use super::TimetableError;
+use super::TimetableIdParam;
+use crate::error::InternalError;
+use crate::error::Result;
+use crate::models::prelude::*;
+use crate::models::timetable::Timetable;
+use crate::models::train_pattern::TrainPattern;
+use crate::models::train_pattern::TrainPatternChangeset;
+use crate::views::Authentication;
+use crate::views::AuthenticationExt;
+
+crate::routes! {
+    "/train_pattern" => {
+        create,
+        list,
+        "/{pattern_id}" => {
+            get,
+            put,
+            delete,
+        },
+    },
+}
+
+editoast_common::schemas! {
+    TrainPatternResult,
+}
+
+/// The maximum number of trains a pattern can materialize
+const MAX_TRAINS: usize = 1000;
+
+#[derive(Debug, Error, EditoastError)]
+#[editoast_error(base_id = "train_pattern")]
+enum TrainPatternError {
+    #[error("Train pattern '{pattern_id}' could not be found")]
+    #[editoast_error(status = 404)]
+    NotFound { pattern_id: i64 },
+    #[error("The interval between two trains of a pattern must be at least a minute")]
+    #[editoast_error(status = 400)]
+    IntervalTooShort,
+    #[error("A train pattern can't have more than {max_trains} trains")]
+    #[editoast_error(status = 400)]
+    TooManyTrains { max_trains: usize },
+}
+
+#[derive(IntoParams, Deserialize)]
+struct TrainPatternIdParam {
+    /// A timetable ID
+    id: i64,
+    /// A train pattern ID
+    pattern_id: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[cfg_attr(test, derive(Deserialize))]
+struct TrainPatternResult {
+    id: i64,
+    timetable_id: i64,
+    template: TrainScheduleBase,
+    recurrence: TrainPatternRecurrence,
+    /// The train schedules materialized from the pattern, in departure order
+    train_ids: Vec<i64>,
+}
+
+impl TrainPatternResult {
+    async fn new(conn: &mut DbConnection, pattern: TrainPattern) -> Result<Self> {
+        let train_ids = pattern.train_ids(conn).await?;
+        Ok(Self {
+            id: pattern.id,
+            timetable_id: pattern.timetable_id,
+            template: pattern.template,
+            recurrence: pattern.recurrence,
+            train_ids,
+        })
+    }
+}
+
+async fn check_timetable_access(
+    auth: &Authentication,
+    timetable_id: i64,
+    privilege: Privilege,
+) -> Result<()> {
+    let role = match privilege {
+        Privilege::Reader => BuiltinRole::TimetableRead,
+        _ => BuiltinRole::TimetableWrite,
+    };
+    auth.authorize(
+        [role].into(),
+        &[(
+            &[Resource::new(ResourceType::Timetable, timetable_id)],
+            privilege,
+        )],
+    )
+    .await?;
+    Ok(())
+}
+
+/// Checks that a pattern gives a reasonable number of trains before materializing it
+fn check_pattern(pattern: &TrainPatternBase) -> Result<()> {
+    if *pattern.recurrence.interval < Duration::minutes(1) {
+        return Err(TrainPatternError::IntervalTooShort.into());
+    }
+    let mut departures = pattern.recurrence.departures(pattern.template.start_time);
+    if departures.nth(MAX_TRAINS).is_some() {
+        return Err(TrainPatternError::TooManyTrains {
+            max_trains: MAX_TRAINS,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Retrieve a train pattern, making sure it belongs to the timetable
+async fn retrieve_pattern(
+    conn: &mut DbConnection,
+    timetable_id: i64,
+    pattern_id: i64,
+) -> Result<TrainPattern> {
+    let pattern = TrainPattern::retrieve_or_fail(conn, pattern_id, || {
+        TrainPatternError::NotFound { pattern_id }
+    })
+    .await?;
+    if pattern.timetable_id != timetable_id {
+        return Err(TrainPatternError::NotFound { pattern_id }.into());
+    }
+    Ok(pattern)
+}
+
+/// Create a train pattern and the train schedules it describes
+#[utoipa::path(
+    post, path = "",
+    tag = "timetable,train_schedule",
+    params(TimetableIdParam),
+    request_body = TrainPatternBase,
+    responses(
+        (status = 200, description = "The created train pattern", body = TrainPatternResult),
+        (status = 404, description = "Timetable not found"),
+    )
+)]
+async fn create(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(TimetableIdParam { id: timetable_id }): Path<TimetableIdParam>,
+    Json(pattern): Json<TrainPatternBase>,
+) -> Result<Json<TrainPatternResult>> {
+    check_timetable_access(&auth, timetable_id, Privilege::Writer).await?;
+    check_pattern(&pattern)?;
+
+    let result = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                Timetable::retrieve_or_fail(&mut conn.clone(), timetable_id, || {
+                    TimetableError::NotFound { timetable_id }
+                })
+                .await?;
+                let pattern = TrainPatternChangeset::from(pattern)
+                    .timetable_id(timetable_id)
+                    .create(&mut conn.clone())
+                    .await?;
+                pattern.materialize(&mut conn.clone()).await?;
+                TrainPatternResult::new(&mut conn.clone(), pattern).await
+            }
+            .scope_boxed()
+        })
+        .await?;
+    Ok(Json(result))
+}
+
+/// List the train patterns of a timetable
+#[utoipa::path(
+    get, path = "",
+    tag = "timetable,train_schedule",
+    params(TimetableIdParam),
+    responses(
+        (status = 200, description = "The train patterns of the timetable", body = Vec<TrainPatternResult>),
+    )
+)]
+async fn list(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(TimetableIdParam { id: timetable_id }): Path<TimetableIdParam>,
+) -> Result<Json<Vec<TrainPatternResult>>> {
+    check_timetable_access(&auth, timetable_id, Privilege::Reader).await?;
+
+    let conn = &mut db_pool.get().await?;
+    let settings = SelectionSettings::new()
+        .filter(move || TrainPattern::TIMETABLE_ID.eq(timetable_id))
+        .order_by(|| TrainPattern::ID.asc());
+    let mut results = Vec::new();
+    for pattern in TrainPattern::list(conn, settings).await? {
+        results.push(TrainPatternResult::new(conn, pattern).await?);
+    }
+    Ok(Json(results))
+}
+
+/// Return a specific train pattern
+#[utoipa::path(
+    get, path = "",
+    tag = "timetable,train_schedule",
+    params(TrainPatternIdParam),
+    responses(
+        (status = 200, description = "The train pattern", body = TrainPatternResult),
+        (status = 404, description = "Train pattern not found"),
+    )
+)]
+async fn get(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(TrainPatternIdParam {
+        id: timetable_id,
+        pattern_id,
+    }): Path<TrainPatternIdParam>,
+) -> Result<Json<TrainPatternResult>> {
+    check_timetable_access(&auth, timetable_id, Privilege::Reader).await?;
+
+    let conn = &mut db_pool.get().await?;
+    let pattern = retrieve_pattern(conn, timetable_id, pattern_id).await?;
+    Ok(Json(TrainPatternResult::new(conn, pattern).await?))
+}
+
+/// Update a train pattern
+///
+/// The train schedules of the pattern are deleted and created again from the new pattern,
+/// discarding any change made to them.
+#[utoipa::path(
+    put, path = "",
+    tag = "timetable,train_schedule",
+    params(TrainPatternIdParam),
+    request_body = TrainPatternBase,
+    responses(
+        (status = 200, description = "The updated train pattern", body = TrainPatternResult),
+        (status = 404, description = "Train pattern not found"),
+    )
+)]
+async fn put(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(TrainPatternIdParam {
+        id: timetable_id,
+        pattern_id,
+    }): Path<TrainPatternIdParam>,
+    Json(pattern): Json<TrainPatternBase>,
+) -> Result<Json<TrainPatternResult>> {
+    check_timetable_access(&auth, timetable_id, Privilege::Writer).await?;
+    check_pattern(&pattern)?;
+
+    let result = db_pool
+        .get()
+        .await?
+        .transaction::<_, InternalError, _>(|conn| {
+            async move {
+                retrieve_pattern(&mut conn.clone(), timetable_id, pattern_id).await?;
+                let pattern = TrainPatternChangeset::from(pattern)
+                    .update_or_fail(&mut conn.clone(), pattern_id, || {
+                        TrainPatternError::NotFound { pattern_id }
+                    })
+                    .await?;
+                pattern.materialize(&mut conn.clone()).await?;
+                TrainPatternResult::new(&mut conn.clone(), pattern).await
+            }
+            .scope_boxed()
+        })
+        .await?;
+    Ok(Json(result))
+}
+
+/// Delete a train pattern and its train schedules
+#[utoipa::path(
+    delete, path = "",
+    tag = "timetable,train_schedule",
+    params(TrainPatternIdParam),
+    responses(
+        (status = 204, description = "No content"),
+        (status = 404, description = "Train pattern not found"),
+    )
+)]
+async fn delete(
+    State(db_pool): State<DbConnectionPoolV2>,
+    Extension(auth): AuthenticationExt,
+    Path(TrainPatternIdParam {
+        id: timetable_id,
+        pattern_id,
+    }): Path<TrainPatternIdParam>,
+) -> Result<impl IntoResponse> {
+    check_timetable_access(&auth, timetable_id, Privilege::Writer).await?;
+
+    let conn = &mut db_pool.get().await?;
+    retrieve_pattern(conn, timetable_id, pattern_id).await?;
+    // The train schedules of the pattern are deleted in cascade
+    TrainPattern::delete_static(conn, pattern_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::fixtures::create_timetable;
+    use crate::models::fixtures::simple_train_schedule_base;
+    use crate::models::train_schedule::TrainSchedule;
+    use crate::views::test_app::TestAppBuilder;
+
+    fn pattern_payload(interval: &str, end_time: &str) -> serde_json::Value {
+        json!({
+            "template": TrainScheduleBase {
+                train_name: "pattern {time}".to_string(),
+                start_time: "2024-12-30T08:00:00Z".parse().unwrap(),
+                ..simple_train_schedule_base()
+            },
+            "recurrence": {
+                "interval": interval,
+                "end_time": end_time,
+            },
+        })
+    }
+
+    #[rstest]
+    async fn train_pattern_materializes_its_trains() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        let timetable = create_timetable(&mut pool.get_ok()).await;
+
+        let request = app
+            .post(&format!("/timetable/{}/train_pattern", timetable.id))
+            .json(&pattern_payload("PT30M", "2024-12-30T09:00:00Z"));
+        let pattern: TrainPatternResult =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let (train_schedules, _): (Vec<TrainSchedule>, _) =
+            TrainSchedule::retrieve_batch(&mut pool.get_ok(), pattern.train_ids.clone())
+                .await
+                .expect("Failed to retrieve train schedules");
+        let mut train_names = train_schedules
+            .iter()
+            .map(|train_schedule| train_schedule.train_name.as_str())
+            .collect::<Vec<_>>();
+        train_names.sort();
+        assert_eq!(
+            train_names,
+            vec!["pattern 08:00", "pattern 08:30", "pattern 09:00"]
+        );
+        assert!(train_schedules
+            .iter()
+            .all(|train_schedule| train_schedule.train_pattern_id == Some(pattern.id)));
+    }
+
+    #[rstest]
+    async fn updated_train_pattern_regenerates_its_trains() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        let timetable = create_timetable(&mut pool.get_ok()).await;
+        let request = app
+            .post(&format!("/timetable/{}/train_pattern", timetable.id))
+            .json(&pattern_payload("PT30M", "2024-12-30T09:00:00Z"));
+        let pattern: TrainPatternResult =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let request = app
+            .put(&format!(
+                "/timetable/{}/train_pattern/{}",
+                timetable.id, pattern.id
+            ))
+            .json(&pattern_payload("PT1H", "2024-12-30T10:00:00Z"));
+        let updated_pattern: TrainPatternResult =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        assert_eq!(updated_pattern.train_ids.len(), 3);
+        assert!(pattern
+            .train_ids
+            .iter()
+            .all(|train_id| !updated_pattern.train_ids.contains(train_id)));
+        let timetable_trains = Timetable::trains_count(timetable.id, &mut pool.get_ok())
+            .await
+            .expect("Failed to count trains");
+        assert_eq!(timetable_trains, 3);
+    }
+
+    #[rstest]
+    async fn deleted_train_pattern_deletes_its_trains() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        let timetable = create_timetable(&mut pool.get_ok()).await;
+        let request = app
+            .post(&format!("/timetable/{}/train_pattern", timetable.id))
+            .json(&pattern_payload("PT30M", "2024-12-30T09:00:00Z"));
+        let pattern: TrainPatternResult =
+            app.fetch(request).assert_status(StatusCode::OK).json_into();
+
+        let request = app.delete(&format!(
+            "/timetable/{}/train_pattern/{}",
+            timetable.id, pattern.id
+        ));
+        app.fetch(request).assert_status(StatusCode::NO_CONTENT);
+
+        let timetable_trains = Timetable::trains_count(timetable.id, &mut pool.get_ok())
+            .await
+            .expect("Failed to count trains");
+        assert_eq!(timetable_trains, 0);
+    }
+
+    #[rstest]
+    async fn train_pattern_with_too_many_trains_is_rejected() {
+        let app = TestAppBuilder::default_app();
+        let pool = app.db_pool();
+        let timetable = create_timetable(&mut pool.get_ok()).await;
+
+        let request = app
+            .post(&format!("/timetable/{}/train_pattern", timetable.id))
+            .json(&pattern_payload("PT1M", "2025-12-30T08:00:00Z"));
+
+        app.fetch(request).assert_status(StatusCode::BAD_REQUEST);
+    }
+}
@@ -14,6 +14,9 @@ use axum::extract::State;
 use axum::response::IntoResponse;
 use axum::Extension;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use itertools::Itertools;
 use serde::Deserialize;
 use serde::Serialize;
@@ -41,6 +44,7 @@ use crate::core::AsCoreRequest;
 use crate::core::CoreClient;
 use crate::error::InternalError;
 use crate::error::Result;
+use crate::generated_data::custom_rules::CustomRules;
 use crate::models::infra::Infra;
 use crate::models::prelude::*;
 use crate::models::train_schedule::TrainSchedule;
@@ -50,6 +54,7 @@ use crate::views::path::pathfinding::PathfindingFailure;
 use crate::views::path::pathfinding::PathfindingResult;
 use crate::views::path::pathfinding_from_train_batch;
 use crate::views::path::PathfindingError;
+use crate::views::Authentication;
 use crate::views::AuthenticationExt;
 use crate::views::AuthorizationError;
 use crate::AppState;
@@ -98,6 +103,9 @@ pub enum TrainScheduleError {
     #[error("Infra '{infra_id}', could not be found")]
     #[editoast_error(status = 404)]
     InfraNotFound { infra_id: i64 },
+    #[error("Infra '{infra_id}' has blocking errors, trains can't be simulated on it")]
+    #[editoast_error(status = 409)]
+    InfraHasBlockingErrors { infra_id: i64 },
 }
 
 #[derive(IntoParams, Deserialize)]
@@ -186,6 +194,7 @@ async fn get(
         TrainScheduleError::NotFound { train_schedule_id }
     })
     .await?;
+    check_timetables_privilege(&auth, [train_schedule.timetable_id], Privilege::Reader).await?;
     Ok(Json(train_schedule.into()))
 }
 
@@ -224,6 +233,12 @@ async fn get_batch(
             }
         })
         .await?;
+    check_timetables_privilege(
+        &auth,
+        train_schedules.iter().map(|ts| ts.timetable_id),
+        Privilege::Reader,
+    )
+    .await?;
     Ok(Json(train_schedules.into_iter().map_into().collect()))
 }
 
@@ -251,6 +266,19 @@ async fn delete(
 
     use crate::models::DeleteBatch;
     let conn = &mut db_pool.get().await?;
+    let train_schedules: Vec<TrainSchedule> =
+        TrainSchedule::retrieve_batch_or_fail(conn, train_ids.clone(), |missing| {
+            TrainScheduleError::BatchTrainScheduleNotFound {
+                number: missing.len(),
+            }
+        })
+        .await?;
+    check_timetables_privilege(
+        &auth,
+        train_schedules.iter().map(|ts| ts.timetable_id),
+        Privilege::Writer,
+    )
+    .await?;
     TrainSchedule::delete_batch_or_fail(conn, train_ids, |number| {
         TrainScheduleError::BatchTrainScheduleNotFound { number }
     })
@@ -286,6 +314,16 @@ async fn put(
     }
 
     let conn = &mut db_pool.get().await?;
+    let train_schedule = TrainSchedule::retrieve_or_fail(conn, train_schedule_id, || {
+        TrainScheduleError::NotFound { train_schedule_id }
+    })
+    .await?;
+    check_timetables_privilege(
+        &auth,
+        std::iter::once(train_schedule.timetable_id).chain(train_schedule_form.timetable_id),
+        Privilege::Writer,
+    )
+    .await?;
     let ts_changeset: TrainScheduleChangeset = train_schedule_form.into();
     let ts_result = ts_changeset
         .update_or_fail(conn, train_schedule_id, || TrainScheduleError::NotFound {
@@ -296,6 +334,27 @@ async fn put(
     Ok(Json(ts_result.into()))
 }
 
+/// Checks that the issuer of the request has a privilege over the timetables of some train schedules
+async fn check_timetables_privilege(
+    auth: &Authentication,
+    timetable_ids: impl IntoIterator<Item = i64>,
+    privilege: Privilege,
+) -> Result<()> {
+    for timetable_id in timetable_ids.into_iter().unique() {
+        let authorized = auth
+            .check_privilege(
+                &[Resource::new(ResourceType::Timetable, timetable_id)],
+                privilege,
+            )
+            .await
+            .map_err(AuthorizationError::AuthError)?;
+        if !authorized {
+            return Err(AuthorizationError::Unauthorized.into());
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize, IntoParams, ToSchema)]
 #[into_params(parameter_in = Query)]
 pub struct InfraIdQueryParam {
@@ -333,13 +392,14 @@ async fn simulation(
         electrical_profile_set_id,
     }): Query<ElectricalProfileSetIdQueryParam>,
 ) -> Result<Json<SimulationResponse>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead, BuiltinRole::TimetableRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead, BuiltinRole::TimetableRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     // Retrieve infra or fail
     let infra = Infra::retrieve_or_fail(&mut db_pool.get().await?, infra_id, || {
@@ -353,6 +413,7 @@ async fn simulation(
             TrainScheduleError::NotFound { train_schedule_id }
         })
         .await?;
+    check_timetables_privilege(&auth, [train_schedule.timetable_id], Privilege::Reader).await?;
 
     Ok(Json(
         train_simulation(
@@ -368,6 +429,18 @@ async fn simulation(
     ))
 }
 
+/// Refuses infras with blocking errors, if the custom validation rules require it
+///
+/// Checked by the pathfinding, on which simulations rely, and by STDCM before calling core.
+pub async fn check_blocking_errors(conn: &mut DbConnection, infra: &Infra) -> Result<()> {
+    let refuse_blocking_errors =
+        CustomRules::installed().is_some_and(|rules| rules.refuse_simulation_on_blocking_errors);
+    if refuse_blocking_errors && infra.has_blocking_errors(conn).await? {
+        return Err(TrainScheduleError::InfraHasBlockingErrors { infra_id: infra.id }.into());
+    }
+    Ok(())
+}
+
 /// Compute simulation of a train schedule
 pub async fn train_simulation(
     conn: &mut DbConnection,
@@ -666,13 +739,14 @@ async fn simulation_summary(
         ids: train_schedule_ids,
     }): Json<SimulationBatchForm>,
 ) -> Result<Json<HashMap<i64, SimulationSummaryResult>>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead, BuiltinRole::TimetableRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead, BuiltinRole::TimetableRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
 
@@ -687,6 +761,12 @@ async fn simulation_summary(
             }
         })
         .await?;
+    check_timetables_privilege(
+        &auth,
+        train_schedules.iter().map(|ts| ts.timetable_id),
+        Privilege::Reader,
+    )
+    .await?;
 
     let simulations = train_simulation_batch(
         conn,
@@ -769,13 +849,14 @@ async fn get_path(
     }): Path<TrainScheduleIdParam>,
     Query(InfraIdQueryParam { infra_id }): Query<InfraIdQueryParam>,
 ) -> Result<Json<PathfindingResult>> {
-    let authorized = auth
-        .check_roles([BuiltinRole::InfraRead, BuiltinRole::TimetableRead].into())
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [BuiltinRole::InfraRead, BuiltinRole::TimetableRead].into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let conn = &mut db_pool.get().await?;
     let mut valkey_conn = valkey_client.get_connection().await?;
@@ -788,6 +869,7 @@ async fn get_path(
         TrainScheduleError::NotFound { train_schedule_id }
     })
     .await?;
+    check_timetables_privilege(&auth, [train_schedule.timetable_id], Privilege::Reader).await?;
     Ok(Json(
         pathfinding_from_train(conn, &mut valkey_conn, core, &infra, train_schedule).await?,
     ))
@@ -811,6 +893,7 @@ mod tests {
     use crate::models::fixtures::simple_train_schedule_base;
     use crate::views::test_app::TestApp;
     use crate::views::test_app::TestAppBuilder;
+    use crate::views::test_app::TestRequestExt as _;
 
     #[rstest]
     async fn train_schedule_get() {
@@ -922,6 +1005,48 @@ mod tests {
         )
     }
 
+    #[rstest]
+    async fn train_schedule_changes_require_timetable_privilege() {
+        let app = TestAppBuilder::authorized_app();
+        let pool = app.db_pool();
+        let roles = [
+            BuiltinRole::InfraRead,
+            BuiltinRole::TimetableRead,
+            BuiltinRole::TimetableWrite,
+        ];
+        app.create_user("owner", roles).await;
+        app.create_user("other", roles).await;
+
+        let request = app.post("/timetable").as_user("owner");
+        let timetable_id = app
+            .fetch(request)
+            .assert_status(StatusCode::OK)
+            .json_into::<serde_json::Value>()["timetable_id"]
+            .as_i64()
+            .unwrap();
+        let train_schedule = create_simple_train_schedule(&mut pool.get_ok(), timetable_id).await;
+
+        let request = app
+            .put(format!("/train_schedule/{}", train_schedule.id).as_str())
+            .json(&json!(TrainScheduleForm {
+                timetable_id: Some(timetable_id),
+                train_schedule: simple_train_schedule_base(),
+            }))
+            .as_user("other");
+        app.fetch(request).assert_status(StatusCode::FORBIDDEN);
+        let request = app
+            .delete("/train_schedule/")
+            .json(&json!({"ids": vec![train_schedule.id]}))
+            .as_user("other");
+        app.fetch(request).assert_status(StatusCode::FORBIDDEN);
+
+        let request = app
+            .delete("/train_schedule/")
+            .json(&json!({"ids": vec![train_schedule.id]}))
+            .as_user("owner");
+        app.fetch(request).assert_status(StatusCode::NO_CONTENT);
+    }
+
     fn mocked_core_pathfinding_sim_and_proj(train_id: i64) -> MockingClient {
         let mut core = MockingClient::new();
         core.stub("/v2/pathfinding/blocks")
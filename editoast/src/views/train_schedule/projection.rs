@@ -4,6 +4,9 @@ use axum::Extension;
 use chrono::DateTime;
 use chrono::Utc;
 use editoast_authz::BuiltinRole;
+use editoast_authz::Privilege;
+use editoast_authz::Resource;
+use editoast_authz::ResourceType;
 use editoast_schemas::primitives::Identifier;
 use futures::join;
 use itertools::izip;
@@ -36,13 +39,13 @@ use crate::models::RetrieveBatch;
 use crate::views::path::pathfinding::PathfindingResult;
 use crate::views::path::projection::PathProjection;
 use crate::views::path::projection::TrackLocationFromPath;
+use crate::views::train_schedule::check_timetables_privilege;
 use crate::views::train_schedule::train_simulation_batch;
 use crate::views::train_schedule::CompleteReportTrain;
 use crate::views::train_schedule::ReportTrain;
 use crate::views::train_schedule::SignalCriticalPosition;
 use crate::views::train_schedule::ZoneUpdate;
 use crate::views::AuthenticationExt;
-use crate::views::AuthorizationError;
 use crate::AppState;
 use crate::RollingStockModel;
 
@@ -140,20 +143,19 @@ async fn project_path(
         electrical_profile_set_id,
     }): Json<ProjectPathForm>,
 ) -> Result<Json<HashMap<i64, ProjectPathTrainResult>>> {
-    let authorized = auth
-        .check_roles(
-            [
-                BuiltinRole::InfraRead,
-                BuiltinRole::TimetableRead,
-                BuiltinRole::RollingStockCollectionRead,
-            ]
-            .into(),
-        )
-        .await
-        .map_err(AuthorizationError::AuthError)?;
-    if !authorized {
-        return Err(AuthorizationError::Unauthorized.into());
-    }
+    auth.authorize(
+        [
+            BuiltinRole::InfraRead,
+            BuiltinRole::TimetableRead,
+            BuiltinRole::RollingStockCollectionRead,
+        ]
+        .into(),
+        &[(
+            &[Resource::new(ResourceType::Infra, infra_id)],
+            Privilege::Reader,
+        )],
+    )
+    .await?;
 
     let ProjectPathInput {
         track_section_ranges: path_track_ranges,
@@ -175,6 +177,12 @@ async fn project_path(
             }
         })
         .await?;
+    check_timetables_privilege(
+        &auth,
+        trains.iter().map(|train| train.timetable_id),
+        Privilege::Reader,
+    )
+    .await?;
 
     let (rolling_stocks, _): (Vec<_>, _) = RollingStockModel::retrieve_batch(
         &mut db_pool.get().await?,
@@ -1,5 +1,8 @@
 import type { InfraErrorTypeLabel } from 'common/api/osrdEditoastApi';
 
+// Errors of custom validation rules are errors or warnings depending on the rule severity
+const CUSTOM_RULES_ERRORS: InfraErrorTypeLabel[] = ['slope_too_steep', 'switch_too_close'];
+
 export const INFRA_ERRORS_BY_LEVEL: Record<'errors' | 'warnings', Set<InfraErrorTypeLabel>> = {
   errors: new Set([
     'invalid_group',
@@ -10,6 +13,7 @@ export const INFRA_ERRORS_BY_LEVEL: Record<'errors' | 'warnings', Set<InfraError
     'out_of_range',
     'unknown_port_name',
     'node_endpoints_not_unique',
+    ...CUSTOM_RULES_ERRORS,
   ]),
   warnings: new Set([
     'duplicated_group',
@@ -21,7 +25,10 @@ export const INFRA_ERRORS_BY_LEVEL: Record<'errors' | 'warnings', Set<InfraError
     'overlapping_switches',
     'overlapping_electrifications',
     'unused_port',
+    ...CUSTOM_RULES_ERRORS,
   ]),
 };
 
-export const INFRA_ERRORS = [...INFRA_ERRORS_BY_LEVEL.warnings, ...INFRA_ERRORS_BY_LEVEL.errors];
+export const INFRA_ERRORS = [
+  ...new Set([...INFRA_ERRORS_BY_LEVEL.warnings, ...INFRA_ERRORS_BY_LEVEL.errors]),
+];
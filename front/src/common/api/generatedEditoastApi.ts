@@ -1,6 +1,7 @@
 import { baseEditoastApi as api } from './baseGeneratedApis';
 
 export const addTagTypes = [
+  'audit',
   'authz',
   'documents',
   'electrical_profiles',
@@ -17,6 +18,7 @@ export const addTagTypes = [
   'search',
   'speed_limit_tags',
   'sprites',
+  'stdcm_log',
   'stdcm_search_environment',
   'temporary_speed_limits',
   'timetable',
@@ -30,6 +32,50 @@ const injectedRtkApi = api
   })
   .injectEndpoints({
     endpoints: (build) => ({
+      getAudit: build.query<GetAuditApiResponse, GetAuditApiArg>({
+        query: (queryArg) => ({
+          url: `/audit`,
+          params: {
+            page: queryArg.page,
+            page_size: queryArg.pageSize,
+            user_id: queryArg.userId,
+            resource_type: queryArg.resourceType,
+            resource_id: queryArg.resourceId,
+            since: queryArg.since,
+          },
+        }),
+        providesTags: ['audit'],
+      }),
+      getAuthzResourcesByResourceTypeAndResourceId: build.query<
+        GetAuthzResourcesByResourceTypeAndResourceIdApiResponse,
+        GetAuthzResourcesByResourceTypeAndResourceIdApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/authz/resources/${queryArg.resourceType}/${queryArg.resourceId}`,
+        }),
+        providesTags: ['authz'],
+      }),
+      postAuthzResourcesByResourceTypeAndResourceId: build.mutation<
+        PostAuthzResourcesByResourceTypeAndResourceIdApiResponse,
+        PostAuthzResourcesByResourceTypeAndResourceIdApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/authz/resources/${queryArg.resourceType}/${queryArg.resourceId}`,
+          method: 'POST',
+          body: queryArg.body,
+        }),
+        invalidatesTags: ['authz'],
+      }),
+      deleteAuthzResourcesByResourceTypeAndResourceIdSubjectId: build.mutation<
+        DeleteAuthzResourcesByResourceTypeAndResourceIdSubjectIdApiResponse,
+        DeleteAuthzResourcesByResourceTypeAndResourceIdSubjectIdApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/authz/resources/${queryArg.resourceType}/${queryArg.resourceId}/${queryArg.subjectId}`,
+          method: 'DELETE',
+        }),
+        invalidatesTags: ['authz'],
+      }),
       getAuthzRolesMe: build.query<GetAuthzRolesMeApiResponse, GetAuthzRolesMeApiArg>({
         query: () => ({ url: `/authz/roles/me` }),
         providesTags: ['authz'],
@@ -209,6 +255,17 @@ const injectedRtkApi = api
         query: (queryArg) => ({ url: `/infra/${queryArg.infraId}/auto_fixes` }),
         providesTags: ['infra'],
       }),
+      postInfraByInfraIdAutoFixes: build.mutation<
+        PostInfraByInfraIdAutoFixesApiResponse,
+        PostInfraByInfraIdAutoFixesApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/infra/${queryArg.infraId}/auto_fixes`,
+          method: 'POST',
+          body: queryArg.autoFixesRequest,
+        }),
+        invalidatesTags: ['infra'],
+      }),
       postInfraByInfraIdClone: build.mutation<
         PostInfraByInfraIdCloneApiResponse,
         PostInfraByInfraIdCloneApiArg
@@ -230,6 +287,22 @@ const injectedRtkApi = api
         }),
         providesTags: ['delimited_area'],
       }),
+      getInfraByInfraIdDiffAndOtherInfraId: build.query<
+        GetInfraByInfraIdDiffAndOtherInfraIdApiResponse,
+        GetInfraByInfraIdDiffAndOtherInfraIdApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/infra/${queryArg.infraId}/diff/${queryArg.otherInfraId}`,
+        }),
+        providesTags: ['infra'],
+      }),
+      getInfraByInfraIdEditionHistory: build.query<
+        GetInfraByInfraIdEditionHistoryApiResponse,
+        GetInfraByInfraIdEditionHistoryApiArg
+      >({
+        query: (queryArg) => ({ url: `/infra/${queryArg.infraId}/edition_history` }),
+        providesTags: ['infra'],
+      }),
       getInfraByInfraIdErrors: build.query<
         GetInfraByInfraIdErrorsApiResponse,
         GetInfraByInfraIdErrorsApiArg
@@ -241,11 +314,19 @@ const injectedRtkApi = api
             page_size: queryArg.pageSize,
             level: queryArg.level,
             error_type: queryArg.errorType,
+            severity: queryArg.severity,
             object_id: queryArg.objectId,
           },
         }),
         providesTags: ['infra'],
       }),
+      getInfraByInfraIdErrorsStats: build.query<
+        GetInfraByInfraIdErrorsStatsApiResponse,
+        GetInfraByInfraIdErrorsStatsApiArg
+      >({
+        query: (queryArg) => ({ url: `/infra/${queryArg.infraId}/errors/stats` }),
+        providesTags: ['infra'],
+      }),
       getInfraByInfraIdLinesAndLineCodeBbox: build.query<
         GetInfraByInfraIdLinesAndLineCodeBboxApiResponse,
         GetInfraByInfraIdLinesAndLineCodeBboxApiArg
@@ -322,6 +403,17 @@ const injectedRtkApi = api
         query: (queryArg) => ({ url: `/infra/${queryArg.infraId}/railjson` }),
         providesTags: ['infra'],
       }),
+      postInfraByInfraIdRedo: build.mutation<
+        PostInfraByInfraIdRedoApiResponse,
+        PostInfraByInfraIdRedoApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/infra/${queryArg.infraId}/redo`,
+          method: 'POST',
+          params: { count: queryArg.count },
+        }),
+        invalidatesTags: ['infra'],
+      }),
       postInfraByInfraIdRoutesNodes: build.mutation<
         PostInfraByInfraIdRoutesNodesApiResponse,
         PostInfraByInfraIdRoutesNodesApiArg
@@ -377,6 +469,17 @@ const injectedRtkApi = api
         query: (queryArg) => ({ url: `/infra/${queryArg.infraId}/switch_types` }),
         providesTags: ['infra'],
       }),
+      postInfraByInfraIdUndo: build.mutation<
+        PostInfraByInfraIdUndoApiResponse,
+        PostInfraByInfraIdUndoApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/infra/${queryArg.infraId}/undo`,
+          method: 'POST',
+          params: { count: queryArg.count },
+        }),
+        invalidatesTags: ['infra'],
+      }),
       postInfraByInfraIdUnlock: build.mutation<
         PostInfraByInfraIdUnlockApiResponse,
         PostInfraByInfraIdUnlockApiArg
@@ -682,6 +785,61 @@ const injectedRtkApi = api
         }),
         invalidatesTags: ['search'],
       }),
+      postSearchFederated: build.mutation<
+        PostSearchFederatedApiResponse,
+        PostSearchFederatedApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/search/federated`,
+          method: 'POST',
+          body: queryArg.federatedSearchPayload,
+        }),
+        invalidatesTags: ['search'],
+      }),
+      getSearchSaved: build.query<GetSearchSavedApiResponse, GetSearchSavedApiArg>({
+        query: (queryArg) => ({
+          url: `/search/saved`,
+          params: { page: queryArg.page, page_size: queryArg.pageSize },
+        }),
+        providesTags: ['search'],
+      }),
+      postSearchSaved: build.mutation<PostSearchSavedApiResponse, PostSearchSavedApiArg>({
+        query: (queryArg) => ({
+          url: `/search/saved`,
+          method: 'POST',
+          body: queryArg.savedSearchForm,
+        }),
+        invalidatesTags: ['search'],
+      }),
+      getSearchSavedBySavedSearchId: build.query<
+        GetSearchSavedBySavedSearchIdApiResponse,
+        GetSearchSavedBySavedSearchIdApiArg
+      >({
+        query: (queryArg) => ({ url: `/search/saved/${queryArg.savedSearchId}` }),
+        providesTags: ['search'],
+      }),
+      deleteSearchSavedBySavedSearchId: build.mutation<
+        DeleteSearchSavedBySavedSearchIdApiResponse,
+        DeleteSearchSavedBySavedSearchIdApiArg
+      >({
+        query: (queryArg) => ({ url: `/search/saved/${queryArg.savedSearchId}`, method: 'DELETE' }),
+        invalidatesTags: ['search'],
+      }),
+      postSearchSavedBySavedSearchIdRun: build.mutation<
+        PostSearchSavedBySavedSearchIdRunApiResponse,
+        PostSearchSavedBySavedSearchIdRunApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/search/saved/${queryArg.savedSearchId}/run`,
+          method: 'POST',
+          params: {
+            page: queryArg.page,
+            page_size: queryArg.pageSize,
+            highlight: queryArg.highlight,
+          },
+        }),
+        invalidatesTags: ['search'],
+      }),
       getSpeedLimitTags: build.query<GetSpeedLimitTagsApiResponse, GetSpeedLimitTagsApiArg>({
         query: () => ({ url: `/speed_limit_tags` }),
         providesTags: ['speed_limit_tags'],
@@ -700,6 +858,27 @@ const injectedRtkApi = api
         query: (queryArg) => ({ url: `/sprites/${queryArg.signalingSystem}/${queryArg.fileName}` }),
         providesTags: ['sprites'],
       }),
+      getStdcmLogs: build.query<GetStdcmLogsApiResponse, GetStdcmLogsApiArg>({
+        query: (queryArg) => ({
+          url: `/stdcm/logs`,
+          params: {
+            page: queryArg.page,
+            page_size: queryArg.pageSize,
+            user_id: queryArg.userId,
+            since: queryArg.since,
+            until: queryArg.until,
+            outcome: queryArg.outcome,
+          },
+        }),
+        providesTags: ['stdcm_log'],
+      }),
+      getStdcmLogsByLogId: build.query<
+        GetStdcmLogsByLogIdApiResponse,
+        GetStdcmLogsByLogIdApiArg
+      >({
+        query: (queryArg) => ({ url: `/stdcm/logs/${queryArg.logId}` }),
+        providesTags: ['stdcm_log'],
+      }),
       getStdcmSearchEnvironment: build.query<
         GetStdcmSearchEnvironmentApiResponse,
         GetStdcmSearchEnvironmentApiArg
@@ -769,6 +948,66 @@ const injectedRtkApi = api
         }),
         invalidatesTags: ['stdcm'],
       }),
+      postTimetableByIdStdcmBatch: build.mutation<
+        PostTimetableByIdStdcmBatchApiResponse,
+        PostTimetableByIdStdcmBatchApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/timetable/${queryArg.id}/stdcm/batch`,
+          method: 'POST',
+          body: queryArg.body,
+          params: { infra: queryArg.infra },
+        }),
+        invalidatesTags: ['stdcm'],
+      }),
+      getTimetableByIdTrainPattern: build.query<
+        GetTimetableByIdTrainPatternApiResponse,
+        GetTimetableByIdTrainPatternApiArg
+      >({
+        query: (queryArg) => ({ url: `/timetable/${queryArg.id}/train_pattern` }),
+        providesTags: ['timetable', 'train_schedule'],
+      }),
+      postTimetableByIdTrainPattern: build.mutation<
+        PostTimetableByIdTrainPatternApiResponse,
+        PostTimetableByIdTrainPatternApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/timetable/${queryArg.id}/train_pattern`,
+          method: 'POST',
+          body: queryArg.trainPatternBase,
+        }),
+        invalidatesTags: ['timetable', 'train_schedule'],
+      }),
+      getTimetableByIdTrainPatternAndPatternId: build.query<
+        GetTimetableByIdTrainPatternAndPatternIdApiResponse,
+        GetTimetableByIdTrainPatternAndPatternIdApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/timetable/${queryArg.id}/train_pattern/${queryArg.patternId}`,
+        }),
+        providesTags: ['timetable', 'train_schedule'],
+      }),
+      putTimetableByIdTrainPatternAndPatternId: build.mutation<
+        PutTimetableByIdTrainPatternAndPatternIdApiResponse,
+        PutTimetableByIdTrainPatternAndPatternIdApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/timetable/${queryArg.id}/train_pattern/${queryArg.patternId}`,
+          method: 'PUT',
+          body: queryArg.trainPatternBase,
+        }),
+        invalidatesTags: ['timetable', 'train_schedule'],
+      }),
+      deleteTimetableByIdTrainPatternAndPatternId: build.mutation<
+        DeleteTimetableByIdTrainPatternAndPatternIdApiResponse,
+        DeleteTimetableByIdTrainPatternAndPatternIdApiArg
+      >({
+        query: (queryArg) => ({
+          url: `/timetable/${queryArg.id}/train_pattern/${queryArg.patternId}`,
+          method: 'DELETE',
+        }),
+        invalidatesTags: ['timetable', 'train_schedule'],
+      }),
       postTimetableByIdTrainSchedule: build.mutation<
         PostTimetableByIdTrainScheduleApiResponse,
         PostTimetableByIdTrainScheduleApiArg
@@ -979,6 +1218,50 @@ const injectedRtkApi = api
     overrideExisting: false,
   });
 export { injectedRtkApi as generatedEditoastApi };
+export type GetAuditApiResponse = /** status 200 The audit log entries */ PaginationStats & {
+  results: AuditLog[];
+};
+export type GetAuditApiArg = {
+  page?: number;
+  pageSize?: number | null;
+  /** Only list the changes made by this user */
+  userId?: number | null;
+  /** Only list the changes of this type of resource, `subject` being used for users and groups */
+  resourceType?: string | null;
+  resourceId?: number | null;
+  /** Only list the changes made at or after this time */
+  since?: string | null;
+};
+export type GetAuthzResourcesByResourceTypeAndResourceIdApiResponse =
+  /** status 200 List the users and groups having a privilege over a resource */ {
+    /** The privileges granted directly over the resource, excluding the ones granted over its ancestors */
+    grants: {
+      privilege: Privilege;
+      /** The ID of the user or group */
+      subject_id: number;
+    }[];
+  };
+export type GetAuthzResourcesByResourceTypeAndResourceIdApiArg = {
+  resourceType: ResourceType;
+  resourceId: number;
+};
+export type PostAuthzResourcesByResourceTypeAndResourceIdApiResponse = unknown;
+export type PostAuthzResourcesByResourceTypeAndResourceIdApiArg = {
+  resourceType: ResourceType;
+  resourceId: number;
+  body: {
+    privilege: Privilege;
+    /** The ID of the user or group */
+    subject_id: number;
+  };
+};
+export type DeleteAuthzResourcesByResourceTypeAndResourceIdSubjectIdApiResponse = unknown;
+export type DeleteAuthzResourcesByResourceTypeAndResourceIdSubjectIdApiArg = {
+  resourceType: ResourceType;
+  resourceId: number;
+  /** The ID of a user or a group */
+  subjectId: number;
+};
 export type GetAuthzRolesMeApiResponse =
   /** status 200 List the roles of the issuer of the request */ {
     builtin: BuiltinRole[];
@@ -1070,6 +1353,7 @@ export type PostInfraApiArg = {
 };
 export type PostInfraRailjsonApiResponse = /** status 201 The imported infra id */ {
   infra: number;
+  migration: RailJsonMigrationReport;
 };
 export type PostInfraRailjsonApiArg = {
   /** The name of the infrastructure. */
@@ -1133,6 +1417,13 @@ export type GetInfraByInfraIdAutoFixesApiArg = {
   /** An existing infra ID */
   infraId: number;
 };
+export type PostInfraByInfraIdAutoFixesApiResponse =
+  /** status 200 The iterations of fixes and the errors they fix */ AutoFixesReport;
+export type PostInfraByInfraIdAutoFixesApiArg = {
+  /** An existing infra ID */
+  infraId: number;
+  autoFixesRequest: AutoFixesRequest;
+};
 export type PostInfraByInfraIdCloneApiResponse = unknown;
 export type PostInfraByInfraIdCloneApiArg = {
   /** An existing infra ID */
@@ -1151,6 +1442,20 @@ export type GetInfraByInfraIdDelimitedAreaApiArg = {
     track_ranges: DirectionalTrackRange[];
   };
 };
+export type GetInfraByInfraIdDiffAndOtherInfraIdApiResponse =
+  /** status 200 The operations from the infra to the other one */ Operation[];
+export type GetInfraByInfraIdDiffAndOtherInfraIdApiArg = {
+  /** An existing infra ID */
+  infraId: number;
+  /** The infra to compare with */
+  otherInfraId: number;
+};
+export type GetInfraByInfraIdEditionHistoryApiResponse =
+  /** status 200 The edition history of the infra */ InfraEdition[];
+export type GetInfraByInfraIdEditionHistoryApiArg = {
+  /** An existing infra ID */
+  infraId: number;
+};
 export type GetInfraByInfraIdErrorsApiResponse =
   /** status 200 A paginated list of errors */ PaginationStats & {
     results: {
@@ -1166,9 +1471,17 @@ export type GetInfraByInfraIdErrorsApiArg = {
   level?: 'warnings' | 'errors' | 'all';
   /** The type of error to filter on */
   errorType?: InfraErrorTypeLabel | null;
+  /** The severity of errors to filter on */
+  severity?: InfraErrorSeverity | null;
   /** Filter errors and warnings related to a given object */
   objectId?: string | null;
 };
+export type GetInfraByInfraIdErrorsStatsApiResponse =
+  /** status 200 The error counts of the infra */ InfraErrorStats;
+export type GetInfraByInfraIdErrorsStatsApiArg = {
+  /** An existing infra ID */
+  infraId: number;
+};
 export type GetInfraByInfraIdLinesAndLineCodeBboxApiResponse =
   /** status 200 The BBox of the line */ BoundingBox;
 export type GetInfraByInfraIdLinesAndLineCodeBboxApiArg = {
@@ -1225,6 +1538,14 @@ export type GetInfraByInfraIdRailjsonApiArg = {
   /** An existing infra ID */
   infraId: number;
 };
+export type PostInfraByInfraIdRedoApiResponse =
+  /** status 200 The redone batches, oldest first */ InfraEdition[];
+export type PostInfraByInfraIdRedoApiArg = {
+  /** An existing infra ID */
+  infraId: number;
+  /** The number of batches of operations to revert or reapply */
+  count?: number;
+};
 export type PostInfraByInfraIdRoutesNodesApiResponse =
   /** status 200 A list of route IDs along with available positions for each specified node */ {
     /** List of available positions for each node on the corresponding routes */
@@ -1292,6 +1613,14 @@ export type GetInfraByInfraIdSwitchTypesApiArg = {
   /** An existing infra ID */
   infraId: number;
 };
+export type PostInfraByInfraIdUndoApiResponse =
+  /** status 200 The undone batches, latest first */ InfraEdition[];
+export type PostInfraByInfraIdUndoApiArg = {
+  /** An existing infra ID */
+  infraId: number;
+  /** The number of batches of operations to revert or reapply */
+  count?: number;
+};
 export type PostInfraByInfraIdUnlockApiResponse = unknown;
 export type PostInfraByInfraIdUnlockApiArg = {
   /** An existing infra ID */
@@ -1518,6 +1847,44 @@ export type PostSearchApiArg = {
   pageSize?: number | null;
   searchPayload: SearchPayload;
 };
+export type PostSearchFederatedApiResponse =
+  /** status 200 The search results, best hits first */ FederatedSearchHit[];
+export type PostSearchFederatedApiArg = {
+  federatedSearchPayload: FederatedSearchPayload;
+};
+export type GetSearchSavedApiResponse = /** status 200 The saved searches */ PaginationStats & {
+  results: SavedSearchResponse[];
+};
+export type GetSearchSavedApiArg = {
+  page?: number;
+  pageSize?: number | null;
+};
+export type PostSearchSavedApiResponse = /** status 200 The saved search */ SavedSearchResponse;
+export type PostSearchSavedApiArg = {
+  savedSearchForm: SavedSearchForm;
+};
+export type GetSearchSavedBySavedSearchIdApiResponse =
+  /** status 200 The saved search */ SavedSearchResponse;
+export type GetSearchSavedBySavedSearchIdApiArg = {
+  /** The id of a saved search */
+  savedSearchId: number;
+};
+export type DeleteSearchSavedBySavedSearchIdApiResponse =
+  /** status 204 The saved search was deleted */ void;
+export type DeleteSearchSavedBySavedSearchIdApiArg = {
+  /** The id of a saved search */
+  savedSearchId: number;
+};
+export type PostSearchSavedBySavedSearchIdRunApiResponse =
+  /** status 200 The search results */ SearchResultItem[];
+export type PostSearchSavedBySavedSearchIdRunApiArg = {
+  /** The id of a saved search */
+  savedSearchId: number;
+  page?: number;
+  pageSize?: number | null;
+  /** Whether to add the matched fragments of the columns searched with `search` to the results */
+  highlight?: boolean;
+};
 export type GetSpeedLimitTagsApiResponse =
   /** status 200 List of configured speed-limit tags */ string[];
 export type GetSpeedLimitTagsApiArg = void;
@@ -1531,6 +1898,26 @@ export type GetSpritesBySignalingSystemAndFileNameApiArg = {
   /** File name (json, png or svg) */
   fileName: string;
 };
+export type GetStdcmLogsApiResponse =
+  /** status 200 The logged STDCM requests */ PaginationStats & {
+    results: StdcmLogSummary[];
+  };
+export type GetStdcmLogsApiArg = {
+  page?: number;
+  pageSize?: number | null;
+  /** Only list the requests made by this user */
+  userId?: number | null;
+  /** Only list the requests made at or after this time */
+  since?: string | null;
+  /** Only list the requests made before this time */
+  until?: string | null;
+  /** Only list the requests which ended this way */
+  outcome?: StdcmLogOutcome | null;
+};
+export type GetStdcmLogsByLogIdApiResponse = /** status 200 The logged STDCM request */ StdcmLog;
+export type GetStdcmLogsByLogIdApiArg = {
+  logId: number;
+};
 export type GetStdcmSearchEnvironmentApiResponse =
   /** status 200  */ StdcmSearchEnvironment | /** status 204 No search environment was created */ void;
 export type GetStdcmSearchEnvironmentApiArg = void;
@@ -1577,6 +1964,9 @@ export type GetTimetableByIdConflictsApiArg = {
 };
 export type PostTimetableByIdStdcmApiResponse = /** status 201 The simulation result */
   | {
+      /** The other paths found within the maximum departure delay, best first.
+        The other fields describe the best path, which is not repeated here. */
+      alternatives: StdcmAlternative[];
       departure_time: string;
       path: PathfindingResultSuccess;
       simulation: SimulationResponse;
@@ -1597,6 +1987,9 @@ export type PostTimetableByIdStdcmApiArg = {
   /** timetable_id */
   id: number;
   body: {
+    /** How many paths to look for within the maximum departure delay, departing at least
+        10 minutes apart */
+    alternative_count?: number;
     comfort: Comfort;
     electrical_profile_set_id?: number | null;
     loading_gauge_type?: LoadingGaugeType | null;
@@ -1635,6 +2028,61 @@ export type PostTimetableByIdStdcmApiArg = {
     work_schedule_group_id?: number | null;
   };
 };
+export type PostTimetableByIdStdcmBatchApiResponse =
+  /** status 200 The result of each request */ {
+    /** The result of each request, in the order of the requests */
+    results: StdcmBatchResult[];
+  };
+export type PostTimetableByIdStdcmBatchApiArg = {
+  /** The infra id */
+  infra: number;
+  /** timetable_id */
+  id: number;
+  body: {
+    /** Whether to add the trains following the paths found to the timetable.
+        Otherwise, the timetable is left unchanged. */
+    commit?: boolean;
+    /** The requests, in the order they are solved */
+    requests: StdcmBatchItem[];
+  };
+};
+export type GetTimetableByIdTrainPatternApiResponse =
+  /** status 200 The train patterns of the timetable */ TrainPatternResult[];
+export type GetTimetableByIdTrainPatternApiArg = {
+  /** A timetable ID */
+  id: number;
+};
+export type PostTimetableByIdTrainPatternApiResponse =
+  /** status 200 The created train pattern */ TrainPatternResult;
+export type PostTimetableByIdTrainPatternApiArg = {
+  /** A timetable ID */
+  id: number;
+  trainPatternBase: TrainPatternBase;
+};
+export type GetTimetableByIdTrainPatternAndPatternIdApiResponse =
+  /** status 200 The train pattern */ TrainPatternResult;
+export type GetTimetableByIdTrainPatternAndPatternIdApiArg = {
+  /** A timetable ID */
+  id: number;
+  /** A train pattern ID */
+  patternId: number;
+};
+export type PutTimetableByIdTrainPatternAndPatternIdApiResponse =
+  /** status 200 The updated train pattern */ TrainPatternResult;
+export type PutTimetableByIdTrainPatternAndPatternIdApiArg = {
+  /** A timetable ID */
+  id: number;
+  /** A train pattern ID */
+  patternId: number;
+  trainPatternBase: TrainPatternBase;
+};
+export type DeleteTimetableByIdTrainPatternAndPatternIdApiResponse = unknown;
+export type DeleteTimetableByIdTrainPatternAndPatternIdApiArg = {
+  /** A timetable ID */
+  id: number;
+  /** A train pattern ID */
+  patternId: number;
+};
 export type PostTimetableByIdTrainScheduleApiResponse =
   /** status 200 The created train schedules */ TrainScheduleResult[];
 export type PostTimetableByIdTrainScheduleApiArg = {
@@ -1794,6 +2242,48 @@ export type PostWorkSchedulesProjectPathApiArg = {
     work_schedule_group_id: number;
   };
 };
+export type PaginationStats = {
+  /** The total number of items */
+  count: number;
+  /** The current page number */
+  current: number;
+  /** The next page number, if any */
+  next: number | null;
+  /** The total number of pages */
+  page_count: number;
+  /** The number of items per page */
+  page_size: number;
+  /** The previous page number, if any */
+  previous: number | null;
+};
+/** A change made through the API, and who made it */
+export type AuditLog = {
+  /** One of `create`, `update`, `delete`, `lock`, `unlock`, `grant` or `revoke` */
+  action: string;
+  created: string;
+  id: number;
+  resource_id: number;
+  /** The type of the changed resource, or `subject` for changes of users and groups */
+  resource_type: string;
+  /** A human readable description of the change */
+  summary: string;
+  /** Unset if the author has been deleted, or isn't a registered user */
+  user_id?: number | null;
+  user_identity: string;
+  user_name: string;
+};
+/** The kinds of resources on which privileges can be granted */
+export type ResourceType =
+  | 'infra'
+  | 'project'
+  | 'study'
+  | 'scenario'
+  | 'timetable'
+  | 'rolling_stock';
+/** The privilege of a subject over a resource
+
+Privileges are ordered: each one includes the ones below it. */
+export type Privilege = 'reader' | 'writer' | 'owner';
 export type BuiltinRole =
   | 'Superuser'
   | 'OpsWrite'
@@ -1814,7 +2304,8 @@ export type BuiltinRole =
   | 'SubjectRead'
   | 'SubjectWrite'
   | 'RoleRead'
-  | 'RoleWrite';
+  | 'RoleWrite'
+  | 'AuditRead';
 export type NewDocumentResponse = {
   document_key: number;
 };
@@ -1856,20 +2347,6 @@ export type ElectricalProfileSet = {
   id: number;
   name: string;
 };
-export type PaginationStats = {
-  /** The total number of items */
-  count: number;
-  /** The current page number */
-  current: number;
-  /** The next page number, if any */
-  next: number | null;
-  /** The total number of pages */
-  page_count: number;
-  /** The number of items per page */
-  page_size: number;
-  /** The previous page number, if any */
-  previous: number | null;
-};
 export type Infra = {
   created: string;
   generated_version: string | null;
@@ -2154,6 +2631,21 @@ export type RailJson = {
   /** The version of the RailJSON format. Defaults to the current version. */
   version: string;
 };
+export type RailJsonMigrationStepReport = {
+  description: string;
+  from: string;
+  /** The number of infrastructure objects modified by this step */
+  modified_objects: number;
+  to: string;
+};
+export type RailJsonMigrationReport = {
+  /** The version of the provided document */
+  from_version: string;
+  /** The applied steps, in order. Empty if the document was already up to date. */
+  steps: RailJsonMigrationStepReport[];
+  /** The version of the resulting document, always the current one */
+  to_version: string;
+};
 export type InfraObject =
   | {
       obj_type: 'TrackSection';
@@ -2290,6 +2782,17 @@ export type Operation =
     } & {
       operation_type: 'DELETE';
     });
+export type InfraEdition = {
+  created: string;
+  id: number;
+  infra_id: number;
+  /** The operations reverting the batch, in the order they must be applied */
+  inverse_operations: Operation[];
+  /** The operations of the batch, as they were applied */
+  operations: Operation[];
+  /** Whether the batch has been undone and can be redone */
+  undone: boolean;
+};
 export type ObjectRef = {
   obj_id: string;
   type: ObjectType;
@@ -2351,6 +2854,19 @@ export type InfraErrorType =
       error_type: 'overlapping_switches';
       reference: ObjectRef;
     }
+  | {
+      error_type: 'slope_too_steep';
+      gradient: number;
+      max_gradient: number;
+      rule: string;
+    }
+  | {
+      distance: number;
+      error_type: 'switch_too_close';
+      min_distance: number;
+      reference: ObjectRef;
+      rule: string;
+    }
   | {
       error_type: 'unknown_port_name';
       port_name: string;
@@ -2359,11 +2875,14 @@ export type InfraErrorType =
       error_type: 'unused_port';
       port_name: string;
     };
+export type InfraErrorSeverity = 'info' | 'warning' | 'error' | 'blocking';
 export type InfraError = InfraErrorType & {
   field: string | null;
+  /** Whether the severity of the error is at most [InfraErrorSeverity::Warning] */
   is_warning: boolean;
   obj_id: string;
   obj_type: ObjectType;
+  severity: InfraErrorSeverity;
 };
 export type InfraErrorTypeLabel =
   | 'duplicated_group'
@@ -2381,8 +2900,49 @@ export type InfraErrorTypeLabel =
   | 'overlapping_electrifications'
   | 'overlapping_speed_sections'
   | 'overlapping_switches'
+  | 'slope_too_steep'
+  | 'switch_too_close'
   | 'unknown_port_name'
   | 'unused_port';
+export type InfraErrorStats = {
+  /** The number of errors of each type */
+  by_error_type: {
+    [key: string]: number;
+  };
+  /** The number of errors related to each type of object */
+  by_object_type: {
+    [key: string]: number;
+  };
+  /** The number of errors of each severity */
+  by_severity: {
+    [key: string]: number;
+  };
+  /** The total number of errors and warnings */
+  total: number;
+};
+export type AutoFixesIteration = {
+  /** The infra errors remaining once the operations are applied */
+  errors_after: InfraError[];
+  /** The infra errors present before the iteration, some of which it fixes */
+  errors_before: InfraError[];
+  /** The operations fixing the errors */
+  operations: Operation[];
+};
+export type AutoFixesReport = {
+  /** Whether the fixes were only computed, and not applied */
+  dry_run: boolean;
+  iterations: AutoFixesIteration[];
+};
+export type AutoFixesFilter = {
+  /** Only fix errors of these types (all types if empty) */
+  error_types?: InfraErrorTypeLabel[];
+  /** Only fix errors on objects of these types (all types if empty) */
+  object_types?: ObjectType[];
+};
+export type AutoFixesRequest = AutoFixesFilter & {
+  /** Only compute the fixes, without applying them */
+  dry_run?: boolean;
+};
 export type BoundingBox = (number & number)[][];
 export type GeoJsonPoint = {
   coordinates: GeoJsonPointValue;
@@ -3133,53 +3693,124 @@ export type SearchPayload = {
     
     Only available in debug builds. */
   dry?: boolean;
+  /** Whether to add the matched fragments of the columns searched with `search` to the results */
+  highlight?: boolean;
   /** The object kind to query - run `editoast search list` to get all possible values */
   object: string;
+  /** The keys to sort the results by, most significant first
+    
+    Each key is an expression such as `["rank", ["name"], "mich st"]`,
+    optionally wrapped in `["asc", key]` or `["desc", key]`. */
+  order_by?: SearchQuery[];
   query: SearchQuery;
 };
-export type StdcmSearchEnvironment = {
-  electrical_profile_set_id?: number;
+export type FederatedSearchHit = {
+  /** The object kind of the result */
+  object: string;
+  result: SearchResultItem;
+  /** How well the result matches the searched text, between 0 and 1, weighted by object kind */
+  score: number;
+};
+export type FederatedSearchPayload = {
+  /** Additional constraints on the results of some object kinds */
+  filters?: {
+    [key: string]: SearchQuery;
+  };
+  /** Whether to add the matched fragments of the searched column to the results */
+  highlight?: boolean;
+  /** The maximum number of hits to return */
+  limit?: number;
+  /** The object kinds to search, by default all those the user may search */
+  objects?: string[] | null;
+  /** The text to look for */
+  text: string;
+  /** Factors applied to the scores of some object kinds, 1 by default */
+  weights?: {
+    [key: string]: number;
+  };
+};
+export type SavedSearchResponse = {
+  created: string;
   id: number;
-  infra_id: number;
-  search_window_begin: string;
-  search_window_end: string;
-  temporary_speed_limit_group_id?: number;
-  timetable_id: number;
-  work_schedule_group_id?: number;
+  name: string;
+  object: string;
+  order_by: SearchQuery[];
+  query: SearchQuery;
 };
-export type StdcmSearchEnvironmentCreateForm = {
-  electrical_profile_set_id?: number | null;
-  infra_id: number;
-  search_window_begin: string;
-  search_window_end: string;
-  temporary_speed_limit_group_id?: number | null;
-  timetable_id: number;
-  work_schedule_group_id?: number | null;
+export type SavedSearchForm = {
+  /** Unique among the saved searches of the user */
+  name: string;
+  /** The object kind to query, as in search payloads */
+  object: string;
+  order_by?: SearchQuery[];
+  query: SearchQuery;
 };
-export type TimetableResult = {
-  timetable_id: number;
+export type StdcmLogOutcome = 'success' | 'path_not_found' | 'preprocessing_simulation_error';
+/** A logged STDCM request, without the request and response sent to and received from core */
+export type StdcmLogSummary = {
+  created: string;
+  id: number;
+  infra_id: number;
+  outcome: StdcmLogOutcome;
+  /** The trace of the request, to look it up in the telemetry */
+  trace_id: string;
+  user_id?: number | null;
 };
-export type TimetableDetailedResult = {
-  timetable_id: number;
-  train_ids: number[];
+export type StepTimingData = {
+  /** Time at which the train should arrive at the location */
+  arrival_time: string;
+  /** The train may arrive up to this duration after the expected arrival time */
+  arrival_time_tolerance_after: number;
+  /** The train may arrive up to this duration before the expected arrival time */
+  arrival_time_tolerance_before: number;
 };
-export type ConflictRequirement = {
-  end_time: string;
-  start_time: string;
-  zone: string;
+export type PathfindingItem = {
+  /** The stop duration in milliseconds, None if the train does not stop. */
+  duration?: number | null;
+  location: PathItemLocation;
+  timing_data?: StepTimingData | null;
 };
-export type Conflict = {
-  conflict_type: 'Spacing' | 'Routing';
-  /** Datetime of the end of the conflict */
-  end_time: string;
-  /** List of requirements causing the conflict */
-  requirements: ConflictRequirement[];
-  /** Datetime of the start of the conflict */
-  start_time: string;
-  /** List of train ids involved in the conflict */
-  train_ids: number[];
-  /** List of work schedule ids involved in the conflict */
-  work_schedule_ids: number[];
+/** An STDCM request */
+export type Request = {
+  /** How many paths to look for within the maximum departure delay, departing at least
+    10 minutes apart */
+  alternative_count?: number;
+  comfort: Comfort;
+  electrical_profile_set_id?: number | null;
+  loading_gauge_type?: LoadingGaugeType | null;
+  /** Can be a percentage `X%`, a time in minutes per 100 kilometer `Xmin/100km` */
+  margin?: string | null;
+  /** Maximum speed of the consist in km/h */
+  max_speed?: number | null;
+  /** By how long we can shift the departure time in milliseconds
+    Deprecated, first step data should be used instead */
+  maximum_departure_delay?: number | null;
+  /** Specifies how long the total run time can be in milliseconds
+    Deprecated, first step data should be used instead */
+  maximum_run_time?: number | null;
+  rolling_stock_id: number;
+  /** Train categories for speed limits */
+  speed_limit_tags?: string | null;
+  /** Deprecated, first step arrival time should be used instead */
+  start_time?: string | null;
+  steps: PathfindingItem[];
+  temporary_speed_limit_group_id?: number | null;
+  /** Margin after the train passage in milliseconds
+    
+    Enforces that the path used by the train should be free and
+    available at least that many milliseconds after its passage. */
+  time_gap_after?: number;
+  /** Margin before the train passage in seconds
+    
+    Enforces that the path used by the train should be free and
+    available at least that many milliseconds before its passage. */
+  time_gap_before?: number;
+  /** Total length of the consist in meters */
+  total_length?: number | null;
+  /** Total mass of the consist in kg */
+  total_mass?: number | null;
+  towed_rolling_stock_id?: number | null;
+  work_schedule_group_id?: number | null;
 };
 export type ReportTrain = {
   /** Total energy consumption */
@@ -3291,19 +3922,247 @@ export type SimulationResponse =
       core_error: InternalError;
       status: 'simulation_failed';
     };
-export type StepTimingData = {
-  /** Time at which the train should arrive at the location */
+export type CoreStdcmStepTimingData = {
+  /** Time the train should arrive at this point */
   arrival_time: string;
-  /** The train may arrive up to this duration after the expected arrival time */
+  /** Tolerance for the arrival time, when it arrives after the expected time, in ms */
   arrival_time_tolerance_after: number;
-  /** The train may arrive up to this duration before the expected arrival time */
+  /** Tolerance for the arrival time, when it arrives before the expected time, in ms */
   arrival_time_tolerance_before: number;
 };
-export type PathfindingItem = {
-  /** The stop duration in milliseconds, None if the train does not stop. */
-  duration?: number | null;
-  location: PathItemLocation;
-  timing_data?: StepTimingData | null;
+export type CoreStdcmPathItem = {
+  /** The track offsets of the path item */
+  locations: TrackOffset[];
+  step_timing_data?: CoreStdcmStepTimingData | null;
+  /** Stop duration in milliseconds. None if the train does not stop at this path item. */
+  stop_duration?: number | null;
+};
+export type PhysicsConsist = {
+  base_power_class?: string | null;
+  /** In m/s² */
+  comfort_acceleration: number;
+  /** The constant gamma braking coefficient used when NOT circulating
+    under ETCS/ERTMS signaling system in m/s^2 */
+  const_gamma: number;
+  effort_curves: EffortCurves;
+  /** The time the train takes before actually using electrical power (in milliseconds).
+    Is null if the train is not electric or the value not specified. */
+  electrical_power_startup_time?: number | null;
+  inertia_coefficient: number;
+  /** Length of the rolling stock in mm */
+  length: number;
+  /** Mass of the rolling stock in kg */
+  mass: number;
+  /** Maximum speed of the rolling stock in m/s */
+  max_speed: number;
+  /** Mapping of power restriction code to power class */
+  power_restrictions?: {
+    [key: string]: string;
+  };
+  /** The time it takes to raise this train's pantograph in milliseconds.
+    Is null if the train is not electric or the value not specified. */
+  raise_pantograph_time?: number | null;
+  rolling_resistance: RollingResistance;
+  /** In m/s² */
+  startup_acceleration: number;
+  startup_time: number;
+};
+export type CoreStdcmTemporarySpeedLimit = {
+  /** Speed limitation in m/s */
+  speed_limit: number;
+  /** Track ranges on which the speed limitation applies */
+  track_ranges: TrackRange[];
+};
+export type TrainRequirements = {
+  routing_requirements: RoutingRequirement[];
+  spacing_requirements: SpacingRequirement[];
+  start_time: string;
+};
+export type UndirectedTrackRange = {
+  /** The beginning of the range in mm. */
+  begin: number;
+  /** The end of the range in mm. */
+  end: number;
+  /** The track section identifier. */
+  track_section: string;
+};
+export type CoreStdcmWorkSchedule = {
+  /** End time as a time delta from the stdcm start time in ms */
+  end_time: number;
+  /** Start time as a time delta from the stdcm start time in ms */
+  start_time: number;
+  /** List of unavailable track ranges */
+  track_ranges: UndirectedTrackRange[];
+};
+export type CoreStdcmRequest = {
+  comfort: Comfort;
+  /** Infrastructure expected version */
+  expected_version: string;
+  /** Infrastructure id */
+  infra: number;
+  /** Margin to apply to the whole train */
+  margin?: string | null;
+  /** Maximum departure delay in milliseconds. */
+  maximum_departure_delay: number;
+  /** Maximum run time of the simulation in milliseconds */
+  maximum_run_time: number;
+  /** List of waypoints. Each waypoint is a list of track offset. */
+  path_items: CoreStdcmPathItem[];
+  rolling_stock: PhysicsConsist;
+  rolling_stock_loading_gauge: LoadingGaugeType;
+  rolling_stock_supported_signaling_systems: RollingStockSupportedSignalingSystems;
+  speed_limit_tag?: string | null;
+  start_time: string;
+  /** List of applicable temporary speed limits between the train departure and arrival */
+  temporary_speed_limits: CoreStdcmTemporarySpeedLimit[];
+  /** Gap between the created train and following trains in milliseconds */
+  time_gap_after: number;
+  /** Gap between the created train and previous trains in milliseconds */
+  time_gap_before: number;
+  /** Numerical integration time step in milliseconds. Use default value if not specified. */
+  time_step?: number | null;
+  trains_requirements: {
+    [key: string]: TrainRequirements;
+  };
+  /** List of planned work schedules */
+  work_schedules: CoreStdcmWorkSchedule[];
+};
+export type Response =
+  | {
+      departure_time: string;
+      path: PathfindingResultSuccess;
+      simulation: SimulationResponse;
+      status: 'success';
+    }
+  | {
+      status: 'path_not_found';
+    }
+  | {
+      error: SimulationResponse;
+      status: 'preprocessing_simulation_error';
+    };
+export type StdcmLog = {
+  created: string;
+  id: number;
+  request: CoreStdcmRequest;
+  response: Response;
+  trace_id: string;
+  user_id?: number | null;
+};
+export type StdcmSearchEnvironment = {
+  electrical_profile_set_id?: number;
+  id: number;
+  infra_id: number;
+  search_window_begin: string;
+  search_window_end: string;
+  temporary_speed_limit_group_id?: number;
+  timetable_id: number;
+  work_schedule_group_id?: number;
+};
+export type StdcmSearchEnvironmentCreateForm = {
+  electrical_profile_set_id?: number | null;
+  infra_id: number;
+  search_window_begin: string;
+  search_window_end: string;
+  temporary_speed_limit_group_id?: number | null;
+  timetable_id: number;
+  work_schedule_group_id?: number | null;
+};
+export type TimetableResult = {
+  timetable_id: number;
+};
+export type TimetableDetailedResult = {
+  timetable_id: number;
+  train_ids: number[];
+};
+export type ConflictRequirement = {
+  end_time: string;
+  start_time: string;
+  zone: string;
+};
+export type Conflict = {
+  conflict_type: 'Spacing' | 'Routing';
+  /** Datetime of the end of the conflict */
+  end_time: string;
+  /** List of requirements causing the conflict */
+  requirements: ConflictRequirement[];
+  /** Datetime of the start of the conflict */
+  start_time: string;
+  /** List of train ids involved in the conflict */
+  train_ids: number[];
+  /** List of work schedule ids involved in the conflict */
+  work_schedule_ids: number[];
+};
+/** A path found for the requested train, with a summary of its simulation */
+export type StdcmAlternative = {
+  departure_time: string;
+  /** Difference in milliseconds between the requested time and the time the train passes the
+    step it was requested on */
+  departure_time_deviation: number;
+  path: PathfindingResultSuccess;
+  /** Duration between the departure and the arrival in milliseconds */
+  run_time: number;
+  simulation: SimulationResponse;
+};
+export type StdcmResponse =
+  | {
+      /** The other paths found within the maximum departure delay, best first.
+    The other fields describe the best path, which is not repeated here. */
+      alternatives: StdcmAlternative[];
+      departure_time: string;
+      path: PathfindingResultSuccess;
+      simulation: SimulationResponse;
+      status: 'success';
+    }
+  | {
+      conflicts: Conflict[];
+      pathfinding_result: PathfindingResult;
+      status: 'conflicts';
+    }
+  | {
+      error: SimulationResponse;
+      status: 'preprocessing_simulation_error';
+    };
+/** The result of a STDCM request of a batch
+
+The trains placed by the previous requests of the batch are referred to by negative ids in
+conflicts: -1 for the first train placed, -2 for the second, and so on. */
+export type StdcmBatchResult =
+  | {
+      response: StdcmResponse;
+      status: 'solved';
+      /** The train schedule added to the timetable, if a path was found and the batch committed */
+      train_schedule_id?: number | null;
+    }
+  | {
+      error: InternalError;
+      status: 'failed';
+    };
+/** A STDCM request of a batch */
+export type StdcmBatchItem = {
+  request: Request;
+  /** The name of the train following the path found */
+  train_name: string;
+};
+export type DayOfWeek =
+  | 'monday'
+  | 'tuesday'
+  | 'wednesday'
+  | 'thursday'
+  | 'friday'
+  | 'saturday'
+  | 'sunday';
+/** When the trains of a pattern depart
+
+The first train departs at the start time of the template. Then a train departs every
+`interval`, until `end_time`, on the days listed in `days`. */
+export type TrainPatternRecurrence = {
+  /** The days of the week (in UTC) on which trains depart, every day if empty */
+  days?: DayOfWeek[];
+  /** No train of the pattern departs after this time */
+  end_time: string;
+  /** Duration between two departures */
+  interval: string;
 };
 export type Distribution = 'STANDARD' | 'MARECO';
 export type TrainScheduleBase = {
@@ -3351,6 +4210,19 @@ export type TrainScheduleBase = {
   start_time: string;
   train_name: string;
 };
+export type TrainPatternResult = {
+  id: number;
+  recurrence: TrainPatternRecurrence;
+  template: TrainScheduleBase;
+  timetable_id: number;
+  /** The train schedules materialized from the pattern, in departure order */
+  train_ids: number[];
+};
+/** A train schedule repeated at regular intervals */
+export type TrainPatternBase = {
+  recurrence: TrainPatternRecurrence;
+  template: TrainScheduleBase;
+};
 export type TrainScheduleResult = TrainScheduleBase & {
   id: number;
   timetable_id: number;
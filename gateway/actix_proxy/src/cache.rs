@@ -0,0 +1,744 @@
+//! An HTTP cache for upstream responses, honoring `Cache-Control` and validators.
+//!
+//! Only successful `GET` responses are stored. Fresh responses are served without contacting
+//! the upstream, and stale ones are revalidated using `If-None-Match` / `If-Modified-Since`.
+//! Entries are kept in a bounded in-memory store, and optionally in a bounded on-disk store
+//! which survives restarts.
+//!
+//! As the cache is shared by all the clients of a target, responses marked as `private`
+//! are never stored, and responses to authenticated requests are only served to the same user.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use actix_web::{
+    http::{
+        header::{self, HeaderMap, HeaderName, HeaderValue},
+        Method, StatusCode, Uri,
+    },
+    web::{self, Bytes},
+    HttpRequest, HttpResponse,
+};
+use log::{debug, warn};
+use opentelemetry::{global, metrics::Counter, KeyValue};
+use serde::{Deserialize, Serialize};
+
+use crate::header_classifier::HeaderClassifier;
+
+/// Options of the cache of a proxy target
+#[derive(Clone, Debug)]
+pub struct CacheOptions {
+    /// The maximum total size of the response bodies kept in memory, in bytes
+    pub memory_capacity: u64,
+    /// Responses with a larger body are not cached, in bytes
+    pub max_entry_size: u64,
+    /// Where to keep responses on disk, if anywhere
+    pub disk: Option<DiskCacheOptions>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DiskCacheOptions {
+    /// The folder cache entries are written to
+    pub path: PathBuf,
+    /// The maximum total size of the entries kept on disk, in bytes
+    pub capacity: u64,
+}
+
+/// How a request was handled by the cache, reported in metrics
+#[derive(Clone, Copy, Debug)]
+enum CacheResult {
+    /// A fresh response was served from the cache
+    Hit,
+    /// A stale response was served after being revalidated upstream
+    Revalidated,
+    /// The response was fetched upstream
+    Miss,
+    /// The request wasn't allowed to use the cache
+    Bypass,
+}
+
+impl CacheResult {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CacheResult::Hit => "hit",
+            CacheResult::Revalidated => "revalidated",
+            CacheResult::Miss => "miss",
+            CacheResult::Bypass => "bypass",
+        }
+    }
+}
+
+/// The `Cache-Control` directives relevant to the cache
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let mut cache_control = CacheControl::default();
+        let directives = headers
+            .get_all(header::CACHE_CONTROL)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        for directive in directives {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name, Some(value.trim().trim_matches('"'))),
+                None => (directive, None),
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "no-store" => cache_control.no_store = true,
+                "no-cache" => cache_control.no_cache = true,
+                "private" => cache_control.private = true,
+                "max-age" => cache_control.max_age = value.and_then(|v| v.parse().ok()),
+                "s-maxage" => cache_control.s_maxage = value.and_then(|v| v.parse().ok()),
+                _ => (),
+            }
+        }
+        cache_control
+    }
+
+    /// How long a response stays fresh, as seen by a shared cache
+    fn freshness_lifetime(&self) -> Duration {
+        if self.no_cache {
+            return Duration::ZERO;
+        }
+        Duration::from_secs(self.s_maxage.or(self.max_age).unwrap_or(0))
+    }
+}
+
+/// A stored response, along with what is needed to check its freshness
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct CachedResponse {
+    key: String,
+    status: u16,
+    headers: Vec<(String, Vec<u8>)>,
+    stored_at: SystemTime,
+    freshness_lifetime: Duration,
+    #[serde(skip)]
+    body: Bytes,
+}
+
+impl CachedResponse {
+    fn header(&self, name: &HeaderName) -> Option<&[u8]> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.as_str() == name.as_str())
+            .map(|(_, value)| value.as_slice())
+    }
+
+    fn age(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.stored_at).unwrap_or_default()
+    }
+
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        self.age(now) < self.freshness_lifetime
+    }
+
+    fn has_validator(&self) -> bool {
+        self.header(&header::ETAG).is_some() || self.header(&header::LAST_MODIFIED).is_some()
+    }
+
+    /// Refreshes the entry with the headers of a `304 Not Modified` upstream response
+    fn revalidated(&self, headers: &HeaderMap, now: SystemTime) -> Self {
+        let mut entry = self.clone();
+        entry.stored_at = now;
+        if headers.contains_key(header::CACHE_CONTROL) {
+            entry.freshness_lifetime = CacheControl::from_headers(headers).freshness_lifetime();
+        }
+        for name in [header::CACHE_CONTROL, header::ETAG, header::EXPIRES] {
+            let Some(value) = headers.get(&name) else {
+                continue;
+            };
+            entry
+                .headers
+                .retain(|(header_name, _)| header_name != name.as_str());
+            entry
+                .headers
+                .push((name.as_str().to_owned(), value.as_bytes().to_vec()));
+        }
+        entry
+    }
+
+    /// Whether the client already has this version of the response
+    fn matches_client_validator(&self, req: &HttpRequest) -> bool {
+        let (Some(etag), Some(if_none_match)) = (
+            self.header(&header::ETAG),
+            req.headers().get(header::IF_NONE_MATCH),
+        ) else {
+            return false;
+        };
+        let etag = String::from_utf8_lossy(etag);
+        let etag = etag.trim_start_matches("W/");
+        if_none_match
+            .to_str()
+            .map(|tags| {
+                tags.split(',')
+                    .map(|tag| tag.trim())
+                    .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+            })
+            .unwrap_or(false)
+    }
+
+    fn to_response(&self, req: &HttpRequest, now: SystemTime) -> HttpResponse {
+        let status = if self.matches_client_validator(req) {
+            StatusCode::NOT_MODIFIED
+        } else {
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK)
+        };
+        let mut response = HttpResponse::build(status);
+        for (name, value) in &self.headers {
+            let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name.as_str()),
+                HeaderValue::from_bytes(value),
+            ) else {
+                continue;
+            };
+            response.append_header((name, value));
+        }
+        response.insert_header((header::AGE, self.age(now).as_secs().to_string()));
+        if status == StatusCode::NOT_MODIFIED {
+            return response.finish();
+        }
+        response.body(self.body.clone())
+    }
+
+    /// Serializes the entry for the disk store: a line of JSON metadata, followed by the body
+    fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = serde_json::to_vec(self)?;
+        bytes.push(b'\n');
+        bytes.extend_from_slice(&self.body);
+        Ok(bytes)
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> io::Result<Self> {
+        let Some(separator) = bytes.iter().position(|b| *b == b'\n') else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "missing cache entry metadata",
+            ));
+        };
+        let mut entry: CachedResponse = serde_json::from_slice(&bytes[..separator])?;
+        entry.body = Bytes::copy_from_slice(&bytes[separator + 1..]);
+        Ok(entry)
+    }
+}
+
+/// Keeps track of the least recently used entries of a store
+struct Lru<V> {
+    entries: HashMap<String, (V, u64, u64)>,
+    /// Keys, by last use
+    order: BTreeMap<u64, String>,
+    size: u64,
+    capacity: u64,
+    next_use: u64,
+}
+
+impl<V: Clone> Lru<V> {
+    fn new(capacity: u64) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            size: 0,
+            capacity,
+            next_use: 0,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<V> {
+        let (value, _, last_use) = self.entries.get_mut(key)?;
+        self.order.remove(last_use);
+        *last_use = self.next_use;
+        self.order.insert(self.next_use, key.to_owned());
+        self.next_use += 1;
+        Some(value.clone())
+    }
+
+    /// Inserts an entry, returning the keys evicted to make room for it
+    fn insert(&mut self, key: String, value: V, size: u64) -> Vec<String> {
+        self.remove(&key);
+        if size > self.capacity {
+            return vec![];
+        }
+
+        let mut evicted = vec![];
+        while self.size + size > self.capacity {
+            let Some((_, lru_key)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some((_, lru_size, _)) = self.entries.remove(&lru_key) {
+                self.size -= lru_size;
+            }
+            evicted.push(lru_key);
+        }
+
+        self.order.insert(self.next_use, key.clone());
+        self.entries.insert(key, (value, size, self.next_use));
+        self.next_use += 1;
+        self.size += size;
+        evicted
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some((_, size, last_use)) = self.entries.remove(key) {
+            self.order.remove(&last_use);
+            self.size -= size;
+        }
+    }
+}
+
+struct DiskStore {
+    path: PathBuf,
+    /// Entry file names, with their size
+    index: Mutex<Lru<()>>,
+}
+
+impl DiskStore {
+    /// Opens the store, picking up the entries written by previous runs
+    fn open(options: &DiskCacheOptions) -> io::Result<Self> {
+        std::fs::create_dir_all(&options.path)?;
+        let mut files = vec![];
+        for dir_entry in std::fs::read_dir(&options.path)? {
+            let dir_entry = dir_entry?;
+            let file_name = dir_entry.file_name().to_string_lossy().into_owned();
+            if !file_name.ends_with(".entry") {
+                continue;
+            }
+            let metadata = dir_entry.metadata()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((modified, file_name, metadata.len()));
+        }
+        files.sort();
+
+        let mut index = Lru::new(options.capacity);
+        for (_, file_name, size) in files {
+            for evicted in index.insert(file_name, (), size) {
+                let _ = std::fs::remove_file(options.path.join(evicted));
+            }
+        }
+        Ok(Self {
+            path: options.path.clone(),
+            index: Mutex::new(index),
+        })
+    }
+
+    fn file_name(key: &str) -> String {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        format!("{:016x}.entry", hasher.finish())
+    }
+
+    async fn get(self: Arc<Self>, key: String) -> Option<CachedResponse> {
+        let file_name = Self::file_name(&key);
+        self.index.lock().unwrap().get(&file_name)?;
+        let path = self.path.join(&file_name);
+        let entry = web::block(move || CachedResponse::from_bytes(std::fs::read(path)?))
+            .await
+            .ok()?;
+        match entry {
+            Ok(entry) if entry.key == key => Some(entry),
+            Ok(_) => None,
+            Err(err) => {
+                warn!("proxy cache: cannot read entry {file_name}: {err}");
+                self.index.lock().unwrap().remove(&file_name);
+                None
+            }
+        }
+    }
+
+    async fn put(self: Arc<Self>, entry: CachedResponse) {
+        let file_name = Self::file_name(&entry.key);
+        let store = self.clone();
+        let written = web::block(move || -> io::Result<(String, u64)> {
+            let bytes = entry.to_bytes()?;
+            let path = store.path.join(&file_name);
+            let tmp_path = store.path.join(format!("{file_name}.tmp"));
+            std::fs::write(&tmp_path, &bytes)?;
+            std::fs::rename(tmp_path, path)?;
+            Ok((file_name, bytes.len() as u64))
+        })
+        .await;
+
+        let (file_name, size) = match written {
+            Ok(Ok(written)) => written,
+            Ok(Err(err)) => {
+                warn!("proxy cache: cannot write entry: {err}");
+                return;
+            }
+            Err(_) => return,
+        };
+        let evicted = self.index.lock().unwrap().insert(file_name, (), size);
+        for evicted in evicted {
+            let _ = std::fs::remove_file(self.path.join(evicted));
+        }
+    }
+}
+
+/// The outcome of a cache lookup, before contacting the upstream
+pub(crate) enum Lookup {
+    /// The cached response can be served as is
+    Fresh(HttpResponse),
+    /// The upstream must be contacted, with the given validators if any
+    Fetch {
+        stale: Option<Arc<CachedResponse>>,
+        validators: Vec<(HeaderName, HeaderValue)>,
+    },
+}
+
+/// A response cache, shared by all the workers serving a proxy target
+#[derive(Clone)]
+pub struct ResponseCache {
+    inner: Arc<CacheInner>,
+}
+
+struct CacheInner {
+    options: CacheOptions,
+    memory: Mutex<Lru<Arc<CachedResponse>>>,
+    disk: Option<Arc<DiskStore>>,
+    requests_counter: Counter<u64>,
+}
+
+impl ResponseCache {
+    pub fn new(options: CacheOptions) -> io::Result<Self> {
+        let disk = options
+            .disk
+            .as_ref()
+            .map(DiskStore::open)
+            .transpose()?
+            .map(Arc::new);
+        let requests_counter = global::meter("actix_proxy")
+            .u64_counter("proxy.cache.requests")
+            .with_description("Requests handled by the proxy cache, by result")
+            .init();
+        Ok(Self {
+            inner: Arc::new(CacheInner {
+                memory: Mutex::new(Lru::new(options.memory_capacity)),
+                options,
+                disk,
+                requests_counter,
+            }),
+        })
+    }
+
+    /// Whether a request can be served by the cache
+    pub(crate) fn applies_to(req: &HttpRequest) -> bool {
+        req.method() == Method::GET && !CacheControl::from_headers(req.headers()).no_store
+    }
+
+    /// Requests with different encodings or identities may get different upstream responses.
+    ///
+    /// The identity is made of the headers which authenticate the request upstream, such as
+    /// `Authorization`, `Cookie` or the user headers set by the request modifier. It is hashed,
+    /// so that credentials aren't written to the disk store.
+    pub(crate) fn key<'a>(
+        upstream_uri: &Uri,
+        req: &HttpRequest,
+        identity: impl Iterator<Item = (&'a HeaderName, &'a HeaderValue)>,
+    ) -> String {
+        let accept_encoding = req
+            .headers()
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let mut identity = identity
+            .map(|(name, value)| (name.as_str(), value.as_bytes()))
+            .peekable();
+        if identity.peek().is_none() {
+            return format!("{upstream_uri} {accept_encoding}");
+        }
+        let mut hasher = DefaultHasher::new();
+        for (name, value) in identity {
+            name.hash(&mut hasher);
+            value.hash(&mut hasher);
+        }
+        format!("{upstream_uri} {accept_encoding} {:016x}", hasher.finish())
+    }
+
+    fn record(&self, result: CacheResult, proxy_name: &str) {
+        debug!("proxy cache: {} for {proxy_name}", result.as_str());
+        self.inner.requests_counter.add(
+            1,
+            &[
+                KeyValue::new("proxy.name", proxy_name.to_owned()),
+                KeyValue::new("result", result.as_str()),
+            ],
+        );
+    }
+
+    async fn get(&self, key: &str) -> Option<Arc<CachedResponse>> {
+        if let Some(entry) = self.inner.memory.lock().unwrap().get(key) {
+            return Some(entry);
+        }
+        let disk = self.inner.disk.clone()?;
+        let entry = Arc::new(disk.get(key.to_owned()).await?);
+        // promote the entry to the memory store
+        let size = entry.body.len() as u64;
+        self.inner
+            .memory
+            .lock()
+            .unwrap()
+            .insert(key.to_owned(), entry.clone(), size);
+        Some(entry)
+    }
+
+    async fn put(&self, entry: CachedResponse) {
+        let size = entry.body.len() as u64;
+        let entry = Arc::new(entry);
+        self.inner
+            .memory
+            .lock()
+            .unwrap()
+            .insert(entry.key.clone(), entry.clone(), size);
+        if let Some(disk) = self.inner.disk.clone() {
+            disk.put(CachedResponse::clone(&entry)).await;
+        }
+    }
+
+    pub(crate) async fn lookup(&self, key: &str, req: &HttpRequest, proxy_name: &str) -> Lookup {
+        let now = SystemTime::now();
+        let request_cache_control = CacheControl::from_headers(req.headers());
+        let Some(entry) = self.get(key).await else {
+            return Lookup::Fetch {
+                stale: None,
+                validators: vec![],
+            };
+        };
+
+        if !request_cache_control.no_cache && entry.is_fresh(now) {
+            self.record(CacheResult::Hit, proxy_name);
+            return Lookup::Fresh(entry.to_response(req, now));
+        }
+
+        let mut validators = vec![];
+        if let Some(etag) = entry
+            .header(&header::ETAG)
+            .and_then(|etag| HeaderValue::from_bytes(etag).ok())
+        {
+            validators.push((header::IF_NONE_MATCH, etag));
+        }
+        if let Some(last_modified) = entry
+            .header(&header::LAST_MODIFIED)
+            .and_then(|date| HeaderValue::from_bytes(date).ok())
+        {
+            validators.push((header::IF_MODIFIED_SINCE, last_modified));
+        }
+        Lookup::Fetch {
+            stale: Some(entry),
+            validators,
+        }
+    }
+
+    /// Serves a stale entry confirmed by the upstream
+    pub(crate) async fn revalidated(
+        &self,
+        stale: &CachedResponse,
+        upstream_headers: &HeaderMap,
+        req: &HttpRequest,
+        proxy_name: &str,
+    ) -> HttpResponse {
+        let now = SystemTime::now();
+        let entry = stale.revalidated(upstream_headers, now);
+        let response = entry.to_response(req, now);
+        self.put(entry).await;
+        self.record(CacheResult::Revalidated, proxy_name);
+        response
+    }
+
+    /// Bodies of larger responses are not buffered, and not cached
+    pub(crate) fn max_entry_size(&self) -> u64 {
+        self.inner.options.max_entry_size
+    }
+
+    /// Whether an upstream response can be stored
+    pub(crate) fn storable(&self, status: StatusCode, upstream_headers: &HeaderMap) -> bool {
+        let cache_control = CacheControl::from_headers(upstream_headers);
+        let varies = upstream_headers
+            .get_all(header::VARY)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|name| !name.trim().eq_ignore_ascii_case("accept-encoding"));
+        // chunked bodies are buffered up to the maximum entry size, and not stored if larger
+        let content_length = upstream_headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        let small_enough = !matches!(
+            content_length,
+            Some(length) if length > self.inner.options.max_entry_size
+        );
+        let has_validator = upstream_headers.contains_key(header::ETAG)
+            || upstream_headers.contains_key(header::LAST_MODIFIED);
+
+        status == StatusCode::OK
+            && !cache_control.no_store
+            && !cache_control.private
+            && !varies
+            && small_enough
+            && (has_validator || !cache_control.freshness_lifetime().is_zero())
+    }
+
+    /// Stores an upstream response, and serves it
+    pub(crate) async fn store(
+        &self,
+        key: String,
+        status: StatusCode,
+        upstream_headers: &HeaderMap,
+        body: Bytes,
+        req: &HttpRequest,
+        proxy_name: &str,
+    ) -> HttpResponse {
+        let now = SystemTime::now();
+        let header_classifier = HeaderClassifier::from_headermap(upstream_headers);
+        let headers = upstream_headers
+            .iter()
+            // the length of the body is set when it is served
+            .filter(|(name, _)| {
+                header_classifier.forwardable(name) && **name != header::CONTENT_LENGTH
+            })
+            .map(|(name, value)| (name.as_str().to_owned(), value.as_bytes().to_vec()))
+            .collect();
+        let entry = CachedResponse {
+            key,
+            status: status.as_u16(),
+            headers,
+            stored_at: now,
+            freshness_lifetime: CacheControl::from_headers(upstream_headers).freshness_lifetime(),
+            body,
+        };
+        let response = entry.to_response(req, now);
+        self.put(entry).await;
+        self.record(CacheResult::Miss, proxy_name);
+        response
+    }
+
+    pub(crate) fn record_uncached(&self, bypassed: bool, proxy_name: &str) {
+        let result = if bypassed {
+            CacheResult::Bypass
+        } else {
+            CacheResult::Miss
+        };
+        self.record(result, proxy_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(cache_control: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_str(cache_control).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn parse_cache_control() {
+        let cache_control =
+            CacheControl::from_headers(&headers("public, max-age=60, s-maxage=\"120\""));
+        assert_eq!(
+            cache_control,
+            CacheControl {
+                max_age: Some(60),
+                s_maxage: Some(120),
+                ..Default::default()
+            }
+        );
+        assert_eq!(cache_control.freshness_lifetime(), Duration::from_secs(120));
+
+        let cache_control = CacheControl::from_headers(&headers("No-Cache, max-age=60"));
+        assert!(cache_control.no_cache);
+        assert_eq!(cache_control.freshness_lifetime(), Duration::ZERO);
+
+        assert!(CacheControl::from_headers(&headers("private")).private);
+        assert!(CacheControl::from_headers(&headers("no-store")).no_store);
+    }
+
+    #[test]
+    fn key_depends_on_identity() {
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        let uri = Uri::from_static("http://upstream/tile");
+        let user = |name: &'static str| {
+            (
+                HeaderName::from_static("x-remote-user-identity"),
+                HeaderValue::from_static(name),
+            )
+        };
+        let (alice, bob) = (user("alice"), user("bob"));
+
+        let anonymous = ResponseCache::key(&uri, &req, std::iter::empty());
+        let alice_key = ResponseCache::key(&uri, &req, std::iter::once((&alice.0, &alice.1)));
+        let bob_key = ResponseCache::key(&uri, &req, std::iter::once((&bob.0, &bob.1)));
+        assert_eq!(anonymous, "http://upstream/tile ");
+        assert_ne!(alice_key, anonymous);
+        assert_ne!(alice_key, bob_key);
+        assert!(!alice_key.contains("alice"));
+    }
+
+    #[test]
+    fn lru_eviction() {
+        let mut lru = Lru::new(10);
+        assert!(lru.insert("a".to_owned(), 1, 4).is_empty());
+        assert!(lru.insert("b".to_owned(), 2, 4).is_empty());
+        // using "a" makes "b" the least recently used entry
+        assert_eq!(lru.get("a"), Some(1));
+        assert_eq!(lru.insert("c".to_owned(), 3, 4), vec!["b".to_owned()]);
+        assert_eq!(lru.get("b"), None);
+        assert_eq!(lru.size, 8);
+        // entries larger than the store are not kept
+        assert!(lru.insert("d".to_owned(), 4, 11).is_empty());
+        assert_eq!(lru.get("d"), None);
+    }
+
+    #[test]
+    fn disk_entry_round_trip() {
+        let entry = CachedResponse {
+            key: "http://upstream/tile gzip".to_owned(),
+            status: 200,
+            headers: vec![("etag".to_owned(), b"\"abc\"".to_vec())],
+            stored_at: SystemTime::now(),
+            freshness_lifetime: Duration::from_secs(60),
+            body: Bytes::from_static(b"tile\ndata"),
+        };
+        let parsed = CachedResponse::from_bytes(entry.to_bytes().unwrap()).unwrap();
+        assert_eq!(parsed.key, entry.key);
+        assert_eq!(parsed.headers, entry.headers);
+        assert_eq!(parsed.body, entry.body);
+        assert!(parsed.is_fresh(SystemTime::now()));
+    }
+
+    #[test]
+    fn revalidation_refreshes_entry() {
+        let stored_at = SystemTime::now() - Duration::from_secs(120);
+        let entry = CachedResponse {
+            key: "key".to_owned(),
+            status: 200,
+            headers: vec![("cache-control".to_owned(), b"max-age=60".to_vec())],
+            stored_at,
+            freshness_lifetime: Duration::from_secs(60),
+            body: Bytes::new(),
+        };
+        let now = SystemTime::now();
+        assert!(!entry.is_fresh(now));
+
+        let entry = entry.revalidated(&headers("max-age=300"), now);
+        assert!(entry.is_fresh(now));
+        assert_eq!(entry.freshness_lifetime, Duration::from_secs(300));
+        assert_eq!(
+            entry.header(&header::CACHE_CONTROL),
+            Some(&b"max-age=300"[..])
+        );
+    }
+}
@@ -10,7 +10,8 @@ use actix_web::{
         uri::Authority,
         StatusCode,
     },
-    web, FromRequest, HttpRequest, HttpResponse, ResponseError,
+    web::{self, BytesMut},
+    FromRequest, HttpRequest, HttpResponse, HttpResponseBuilder, ResponseError,
 };
 use actix_web_actors::ws;
 use actix_web_opentelemetry::ClientExt;
@@ -31,19 +32,23 @@ use percent_encoding::{utf8_percent_encode, AsciiSet};
 
 use awc::error::{ConnectError, SendRequestError as AwcSendRequestError};
 use futures_util::StreamExt;
-use std::{collections::HashSet, net::IpAddr};
+use std::{any::Any, collections::HashSet, net::IpAddr};
 use std::{fmt, rc::Rc};
 use std::{
     future::{ready, Ready},
     time::Duration,
 };
 
+use cache::Lookup;
 use dyn_clone::DynClone;
 use header_classifier::HeaderClassifier;
 
+mod cache;
 mod header_classifier;
 mod websocket;
 
+pub use cache::{CacheOptions, DiskCacheOptions, ResponseCache};
+
 // re-exports
 pub use actix_web::http::{
     header::{HeaderName, HeaderValue}, // for the request modifier / forwarded headers
@@ -67,6 +72,19 @@ pub trait RequestModifier: DynClone {
 }
 dyn_clone::clone_trait_object!(RequestModifier);
 
+/// A value held while a request is relayed upstream, and dropped once the upstream response is received
+pub type RequestPermit = Box<dyn Any>;
+
+pub trait RequestLimiter: DynClone {
+    /// Decides whether a request can be relayed upstream.
+    /// Returns a permit, or an error response if the request must be rejected.
+    fn acquire(
+        &self,
+        client_request: &HttpRequest,
+    ) -> LocalBoxFuture<'static, Result<RequestPermit, actix_web::Error>>;
+}
+dyn_clone::clone_trait_object!(RequestLimiter);
+
 #[derive(Clone)]
 pub struct Proxy {
     mount_path: String,
@@ -74,6 +92,8 @@ pub struct Proxy {
     forwarded_headers: Option<Vec<HeaderName>>,
     blocked_headers: HashSet<HeaderName>,
     request_modifier: Option<Box<dyn RequestModifier + Send>>,
+    request_limiter: Option<Box<dyn RequestLimiter + Send>>,
+    cache: Option<ResponseCache>,
     upstream_scheme: String,
     upstream_authority: Authority,
     upstream_path_prefix: String,
@@ -122,6 +142,8 @@ impl Proxy {
         forwarded_headers: Option<Vec<HeaderName>>,
         blocked_headers: HashSet<HeaderName>,
         request_modifier: Option<Box<dyn RequestModifier + Send>>,
+        request_limiter: Option<Box<dyn RequestLimiter + Send>>,
+        cache: Option<ResponseCache>,
         timeout: Option<Duration>,
         tracing_name: Option<String>,
     ) -> Self {
@@ -148,10 +170,16 @@ impl Proxy {
             forwarded_headers,
             blocked_headers,
             request_modifier,
+            request_limiter,
+            cache,
             timeout,
         }
     }
 
+    fn name(&self) -> &str {
+        self.tracing_name.as_deref().unwrap_or("unnamed")
+    }
+
     fn rebase_uri(&self, new_protocol: Option<&str>, path: &str, query: &str) -> Uri {
         let scheme = new_protocol.unwrap_or(&self.upstream_scheme);
 
@@ -261,6 +289,12 @@ impl InnerProxyService {
         query: &str,
         context: Context,
     ) -> Result<HttpResponse, actix_web::Error> {
+        // the permit is held until the upstream response is received
+        let _permit = match &self.proxy.request_limiter {
+            Some(limiter) => Some(limiter.acquire(req).await?),
+            None => None,
+        };
+
         match req.headers().get(&header::UPGRADE) {
             Some(header) if header.as_bytes() == b"websocket" => {
                 self.handle_websocket(req, stream, unprocessed_path, query, context)
@@ -407,17 +441,54 @@ impl InnerProxyService {
             modifier.modify_http_request(req, &mut back_request)?;
         }
 
+        // look for the response in the cache, once the request is allowed upstream
+        let cache = self.proxy.cache.as_ref();
+        let cache_key = match cache {
+            Some(_) if ResponseCache::applies_to(req) => {
+                // users are identified by the headers set by the request modifier,
+                // and by the credentials of the client
+                let client_credentials =
+                    self.iter_forwarded_req_headers(req.headers())
+                        .filter(|(header_name, _)| {
+                            (**header_name == header::AUTHORIZATION
+                                || **header_name == header::COOKIE)
+                                && !self.proxy.blocked_headers.contains(*header_name)
+                        });
+                let identity = back_request.headers().iter().chain(client_credentials);
+                Some(ResponseCache::key(back_request.get_uri(), req, identity))
+            }
+            Some(cache) => {
+                cache.record_uncached(true, self.proxy.name());
+                None
+            }
+            None => None,
+        };
+        let (stale_entry, cache_validators) = match (cache, &cache_key) {
+            (Some(cache), Some(key)) => match cache.lookup(key, req, self.proxy.name()).await {
+                Lookup::Fresh(response) => return Ok(response),
+                Lookup::Fetch { stale, validators } => (stale, validators),
+            },
+            _ => (None, vec![]),
+        };
+
         {
             // forward request headers
             let header_classifier = HeaderClassifier::from_headermap(req.headers());
             for (header_name, header_value) in self.iter_forwarded_req_headers(req.headers()) {
+                // the cache fetches full responses, and answers client validators itself
+                let conditional = header_name == header::IF_NONE_MATCH
+                    || header_name == header::IF_MODIFIED_SINCE;
                 if header_classifier.forwardable(header_name)
                     && !self.proxy.blocked_headers.contains(header_name)
+                    && !(cache_key.is_some() && conditional)
                 {
                     back_request = back_request.append_header((header_name, header_value));
                 }
             }
         }
+        for validator in cache_validators {
+            back_request = back_request.insert_header(validator);
+        }
 
         // Set or update X-Forwarded-For
         if let Some(peer_addr) = req.peer_addr() {
@@ -438,28 +509,69 @@ impl InnerProxyService {
             back_request = back_request.append_header(("X-Forwarded-Proto", proto));
         }
 
-        let back_response = back_request
+        let mut back_response = back_request
             .trace_request()
             .send_stream(stream)
             .await
             .map_err(SendRequestError)?;
 
-        let mut response = HttpResponse::build(back_response.status());
-
-        {
-            // forward response headers
-            let header_classifier = HeaderClassifier::from_headermap(back_response.headers());
-            for response_header in back_response.headers() {
-                if header_classifier.forwardable(response_header.0) {
-                    response.append_header(response_header);
+        if let (Some(cache), Some(key)) = (cache, cache_key) {
+            let status = back_response.status();
+            if let (StatusCode::NOT_MODIFIED, Some(stale_entry)) = (status, &stale_entry) {
+                return Ok(cache
+                    .revalidated(stale_entry, back_response.headers(), req, self.proxy.name())
+                    .await);
+            }
+            if cache.storable(status, back_response.headers()) {
+                // buffer the body, unless it turns out to be too large to be cached
+                let mut body = BytesMut::new();
+                let mut overflow = None;
+                while let Some(chunk) = back_response.next().await {
+                    let chunk = chunk.map_err(actix_web::error::ErrorBadGateway)?;
+                    if (body.len() + chunk.len()) as u64 > cache.max_entry_size() {
+                        overflow = Some(chunk);
+                        break;
+                    }
+                    body.extend_from_slice(&chunk);
                 }
+
+                let Some(overflow) = overflow else {
+                    return Ok(cache
+                        .store(
+                            key,
+                            status,
+                            back_response.headers(),
+                            body.freeze(),
+                            req,
+                            self.proxy.name(),
+                        )
+                        .await);
+                };
+                cache.record_uncached(false, self.proxy.name());
+                let mut response = forwarded_response(status, back_response.headers());
+                let buffered = futures_util::stream::iter([Ok(body.freeze()), Ok(overflow)]);
+                return Ok(response.streaming(buffered.chain(back_response)));
             }
+            cache.record_uncached(false, self.proxy.name());
         }
 
+        let mut response = forwarded_response(back_response.status(), back_response.headers());
         Ok(response.streaming(back_response))
     }
 }
 
+/// Builds a response with the forwardable headers of an upstream response
+fn forwarded_response(status: StatusCode, upstream_headers: &HeaderMap) -> HttpResponseBuilder {
+    let mut response = HttpResponse::build(status);
+    let header_classifier = HeaderClassifier::from_headermap(upstream_headers);
+    for response_header in upstream_headers {
+        if header_classifier.forwardable(response_header.0) {
+            response.append_header(response_header);
+        }
+    }
+    response
+}
+
 impl ProxyService {
     pub fn new(
         client: Client,
@@ -492,11 +604,7 @@ impl Service<ServiceRequest> for ProxyService {
                     // set tracing attributes
                     cx.span().set_attribute(KeyValue::new(
                         "proxy.name",
-                        proxy_service
-                            .proxy
-                            .tracing_name
-                            .clone()
-                            .unwrap_or("unnamed".to_owned()),
+                        proxy_service.proxy.name().to_owned(),
                     ));
                     cx.span().set_attribute(KeyValue::new(
                         "proxy.mount_path",
@@ -115,6 +115,52 @@ pub struct ProxyTarget {
     pub timeout: Option<Duration>,
     /// The tracing name for this target
     pub tracing_name: Option<String>,
+    /// Limits of the requests relayed to this target, per user.
+    /// Unauthenticated requests are limited per client IP address.
+    pub rate_limit: Option<RateLimit>,
+    /// A cache of the responses of this target. Responses to authenticated requests are only
+    /// served to the same user. Only responses allowed to be stored by their `Cache-Control`
+    /// header are cached.
+    pub cache: Option<CacheConfig>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct RateLimit {
+    /// The maximum number of requests relayed in each period
+    pub max_requests: u32,
+    /// The duration of a rate limiting period
+    #[serde(with = "humantime_serde")]
+    pub period: Duration,
+    /// The maximum number of requests being relayed at the same time
+    pub max_concurrent_requests: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct CacheConfig {
+    /// The maximum total size of the responses kept in memory, in bytes
+    pub memory_capacity: u64,
+    /// Larger responses are not cached, in bytes. Defaults to 1MiB.
+    pub max_entry_size: Option<u64>,
+    /// Responses can also be kept on disk, where they survive restarts
+    pub disk: Option<DiskCacheConfig>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct DiskCacheConfig {
+    /// The folder responses are written to
+    pub path: String,
+    /// The maximum total size of the responses kept on disk, in bytes
+    pub capacity: u64,
+}
+
+/// Where rate limiting counters are stored
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum RateLimitBackend {
+    /// Counters are kept in memory, and are specific to each gateway instance
+    Memory,
+    /// Counters are shared by all the gateway instances using the same Valkey server
+    Valkey { url: String },
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -185,6 +231,8 @@ pub struct ProxyConfig {
     pub telemetry: Telemetry,
     /// Allowed origins for CORS (when empty, CORS is disabled entirely)
     pub allowed_origins: Option<Vec<String>>,
+    /// Storage of the rate limiting counters of targets
+    pub rate_limit_backend: RateLimitBackend,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -212,6 +260,7 @@ impl Default for ProxyConfig {
                 tracing: TracingTelemetry::None,
             },
             allowed_origins: None,
+            rate_limit_backend: RateLimitBackend::Memory,
         }
     }
 }
@@ -2,12 +2,15 @@ use std::{
     collections::HashSet,
     path::{Path, PathBuf},
     process::exit,
+    sync::{Arc, Mutex},
 };
 
 use actix_auth::{
     AuthContext, AuthProviderHandler, BearerProvider, MockProvider, OidcConfig, OidcProvider,
 };
-use actix_proxy::{HeaderName, IpNet, Proxy, Uri};
+use actix_proxy::{
+    CacheOptions, DiskCacheOptions, HeaderName, IpNet, Proxy, RequestLimiter, ResponseCache, Uri,
+};
 use actix_web::{
     cookie::Key,
     dev::{ServiceRequest, ServiceResponse},
@@ -17,10 +20,14 @@ use base64ct::Encoding;
 use log::error;
 
 use crate::{
-    config::{self, AuthConfig, FilesConfig},
+    config::{self, AuthConfig, CacheConfig, FilesConfig, RateLimitBackend},
+    rate_limiter::{LimiterBackend, TargetRateLimiter},
     request_modifier::ProxyAuthAdapter,
 };
 
+/// Responses larger than this are not cached, unless configured otherwise
+const DEFAULT_CACHE_MAX_ENTRY_SIZE: u64 = 1024 * 1024;
+
 #[derive(Debug)]
 pub enum UriParseError {
     SchemeOrAuthorityMissing,
@@ -40,6 +47,7 @@ fn parse_and_check_uri(s: &str) -> Result<Uri, UriParseError> {
 pub fn parse_targets(
     config: super::config::ProxyConfig,
     trusted_proxies: Vec<IpNet>,
+    limiter_backend: LimiterBackend,
 ) -> (Vec<Proxy>, Option<Proxy>) {
     let mut default_target: Option<Proxy> = None;
 
@@ -75,6 +83,16 @@ pub fn parse_targets(
             })
             .unwrap_or_default();
 
+        let request_limiter = target.rate_limit.clone().map(|rate_limit| {
+            let target_name = target.prefix.clone().unwrap_or("default".to_owned());
+            Box::new(TargetRateLimiter::new(
+                target_name,
+                rate_limit,
+                limiter_backend.clone(),
+                trusted_proxies.clone(),
+            )) as Box<dyn RequestLimiter + Send>
+        });
+
         let parsed_target = Proxy::new(
             target.prefix.clone(),
             parse_and_check_uri(target.upstream.as_str()).unwrap(),
@@ -86,6 +104,8 @@ pub fn parse_targets(
             } else {
                 None
             },
+            request_limiter,
+            target.cache.as_ref().map(parse_cache_config),
             target.timeout,
             target.tracing_name.clone(),
         );
@@ -105,6 +125,45 @@ pub fn parse_targets(
     (targets, default_target)
 }
 
+fn parse_cache_config(config: &CacheConfig) -> ResponseCache {
+    let options = CacheOptions {
+        memory_capacity: config.memory_capacity,
+        max_entry_size: config
+            .max_entry_size
+            .unwrap_or(DEFAULT_CACHE_MAX_ENTRY_SIZE),
+        disk: config.disk.as_ref().map(|disk| DiskCacheOptions {
+            path: disk.path.clone().into(),
+            capacity: disk.capacity,
+        }),
+    };
+    match ResponseCache::new(options) {
+        Ok(cache) => cache,
+        Err(err) => {
+            error!("Cannot open the response cache: {err}");
+            exit(1);
+        }
+    }
+}
+
+pub async fn parse_rate_limit_backend(config: &RateLimitBackend) -> LimiterBackend {
+    match config {
+        RateLimitBackend::Memory => LimiterBackend::Memory(Arc::new(Mutex::default())),
+        RateLimitBackend::Valkey { url } => {
+            let connection = match redis::Client::open(url.as_str()) {
+                Ok(client) => redis::aio::ConnectionManager::new(client).await,
+                Err(err) => Err(err),
+            };
+            match connection {
+                Ok(connection) => LimiterBackend::Valkey(connection),
+                Err(err) => {
+                    error!("Cannot connect to the rate limiting Valkey server: {err}");
+                    exit(1);
+                }
+            }
+        }
+    }
+}
+
 pub async fn parse_auth_config(config: AuthConfig) -> AuthContext {
     let mut auth_context = AuthContext::new("auth".to_owned());
 
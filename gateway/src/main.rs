@@ -13,7 +13,8 @@ use actix_web::{
 use actix_web::{web, HttpResponse};
 use actix_web_opentelemetry::RequestTracing;
 use config_parser::{
-    parse_auth_config, parse_files_config, parse_secret_key, parse_targets, Files,
+    parse_auth_config, parse_files_config, parse_rate_limit_backend, parse_secret_key,
+    parse_targets, Files,
 };
 use either::Either;
 use log::error;
@@ -22,6 +23,7 @@ use actix_auth::AuthMiddleware;
 
 mod config;
 mod config_parser;
+mod rate_limiter;
 mod request_modifier;
 
 #[actix_web::main]
@@ -44,8 +46,9 @@ async fn main() -> std::io::Result<()> {
         .collect::<Vec<_>>();
 
     let auth_context = parse_auth_config(config.auth.clone()).await;
+    let limiter_backend = parse_rate_limit_backend(&config.rate_limit_backend).await;
     let (proxy_targets, default_proxy_target) =
-        parse_targets(config.clone(), trusted_proxies.clone());
+        parse_targets(config.clone(), trusted_proxies.clone(), limiter_backend);
     let files_config = config.static_files.as_ref().map(parse_files_config);
 
     let default_service: Option<Either<Proxy, Files>> = match (default_proxy_target, files_config) {
@@ -0,0 +1,376 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_auth::{AuthStatus, RequestAuthExt};
+use actix_proxy::{IpNet, RequestLimiter, RequestPermit};
+use actix_web::{
+    http::{header, StatusCode},
+    HttpRequest, HttpResponse, ResponseError,
+};
+use futures_util::future::LocalBoxFuture;
+use log::error;
+use redis::{aio::ConnectionManager, AsyncCommands, RedisResult};
+
+use crate::config::RateLimit;
+
+/// Above this number of counters, expired in-memory counters are dropped
+const MAX_MEMORY_WINDOWS: usize = 10_000;
+
+/// Running request counters expire in Valkey after this delay,
+/// in case a gateway instance stops before decrementing them
+const RUNNING_COUNTER_TTL: i64 = 60 * 60;
+
+/// The delay clients are asked to wait when too many of their requests are running
+const CONCURRENCY_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub struct TooManyRequests {
+    retry_after: Duration,
+}
+
+impl TooManyRequests {
+    fn retry_after_secs(&self) -> u64 {
+        self.retry_after.as_secs_f64().ceil().max(1.) as u64
+    }
+}
+
+impl fmt::Display for TooManyRequests {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "too many requests, retry in {} seconds",
+            self.retry_after_secs()
+        )
+    }
+}
+
+impl ResponseError for TooManyRequests {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::TOO_MANY_REQUESTS
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header((header::RETRY_AFTER, self.retry_after_secs().to_string()))
+            .body(self.to_string())
+    }
+}
+
+/// Request counters of a single gateway instance
+#[derive(Default)]
+pub struct MemoryCounters {
+    /// The end of the current period and the number of requests in it, by key
+    windows: HashMap<String, (Instant, u32)>,
+    /// The number of running requests, by key
+    running: HashMap<String, u32>,
+}
+
+impl MemoryCounters {
+    /// Counts a request, or returns how long to wait before the next one is accepted
+    fn hit(
+        &mut self,
+        key: &str,
+        max_requests: u32,
+        period: Duration,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        if self.windows.len() >= MAX_MEMORY_WINDOWS {
+            self.windows.retain(|_, (window_end, _)| *window_end > now);
+        }
+
+        let (window_end, count) = self
+            .windows
+            .entry(key.to_owned())
+            .or_insert((now + period, 0));
+        if *window_end <= now {
+            *window_end = now + period;
+            *count = 0;
+        }
+        if *count >= max_requests {
+            return Err(*window_end - now);
+        }
+        *count += 1;
+        Ok(())
+    }
+
+    fn start(&mut self, key: &str, max_running: u32) -> bool {
+        let running = self.running.entry(key.to_owned()).or_insert(0);
+        if *running >= max_running {
+            return false;
+        }
+        *running += 1;
+        true
+    }
+
+    fn end(&mut self, key: &str) {
+        if let Some(running) = self.running.get_mut(key) {
+            *running = running.saturating_sub(1);
+            if *running == 0 {
+                self.running.remove(key);
+            }
+        }
+    }
+}
+
+/// The storage of request counters, shared by all rate limited targets
+#[derive(Clone)]
+pub enum LimiterBackend {
+    Memory(Arc<Mutex<MemoryCounters>>),
+    /// When Valkey can't be reached, requests are not limited
+    Valkey(ConnectionManager),
+}
+
+impl LimiterBackend {
+    async fn hit(&self, key: &str, limit: &RateLimit) -> Result<(), Duration> {
+        match self {
+            LimiterBackend::Memory(counters) => {
+                counters
+                    .lock()
+                    .unwrap()
+                    .hit(key, limit.max_requests, limit.period, Instant::now())
+            }
+            LimiterBackend::Valkey(conn) => match valkey_hit(&mut conn.clone(), key, limit).await {
+                Ok(res) => res,
+                Err(err) => {
+                    error!("rate limiting: cannot count request: {err}");
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    /// Counts a running request, returning `None` if it can't be tracked
+    async fn start(
+        &self,
+        key: String,
+        max_running: u32,
+    ) -> Result<Option<RunningRequest>, TooManyRequests> {
+        let started = match self {
+            LimiterBackend::Memory(counters) => counters.lock().unwrap().start(&key, max_running),
+            LimiterBackend::Valkey(conn) => {
+                match valkey_start(&mut conn.clone(), &key, max_running).await {
+                    Ok(started) => started,
+                    Err(err) => {
+                        error!("rate limiting: cannot count running request: {err}");
+                        return Ok(None);
+                    }
+                }
+            }
+        };
+        if !started {
+            return Err(TooManyRequests {
+                retry_after: CONCURRENCY_RETRY_AFTER,
+            });
+        }
+        Ok(Some(RunningRequest {
+            key,
+            backend: self.clone(),
+        }))
+    }
+}
+
+async fn valkey_hit(
+    conn: &mut ConnectionManager,
+    key: &str,
+    limit: &RateLimit,
+) -> RedisResult<Result<(), Duration>> {
+    let (count, ttl): (u32, i64) = redis::pipe()
+        .atomic()
+        .incr(key, 1)
+        // the period starts with its first request
+        .cmd("PEXPIRE")
+        .arg(key)
+        .arg(limit.period.as_millis() as u64)
+        .arg("NX")
+        .ignore()
+        .cmd("PTTL")
+        .arg(key)
+        .query_async(conn)
+        .await?;
+    if count > limit.max_requests {
+        return Ok(Err(Duration::from_millis(ttl.max(0) as u64)));
+    }
+    Ok(Ok(()))
+}
+
+async fn valkey_start(
+    conn: &mut ConnectionManager,
+    key: &str,
+    max_running: u32,
+) -> RedisResult<bool> {
+    let (running,): (u32,) = redis::pipe()
+        .atomic()
+        .incr(key, 1)
+        .expire(key, RUNNING_COUNTER_TTL)
+        .ignore()
+        .query_async(conn)
+        .await?;
+    if running > max_running {
+        conn.decr::<_, _, ()>(key, 1).await?;
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Decrements the running requests counter when dropped
+struct RunningRequest {
+    key: String,
+    backend: LimiterBackend,
+}
+
+impl Drop for RunningRequest {
+    fn drop(&mut self) {
+        match &self.backend {
+            LimiterBackend::Memory(counters) => counters.lock().unwrap().end(&self.key),
+            LimiterBackend::Valkey(conn) => {
+                let mut conn = conn.clone();
+                let key = std::mem::take(&mut self.key);
+                actix_web::rt::spawn(async move {
+                    if let Err(err) = conn.decr::<_, _, ()>(&key, 1).await {
+                        error!("rate limiting: cannot release running request: {err}");
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Limits the requests relayed to a target by each user
+#[derive(Clone)]
+pub struct TargetRateLimiter {
+    target_name: String,
+    limit: RateLimit,
+    backend: LimiterBackend,
+    trusted_proxies: Vec<IpNet>,
+}
+
+impl TargetRateLimiter {
+    pub fn new(
+        target_name: String,
+        limit: RateLimit,
+        backend: LimiterBackend,
+        trusted_proxies: Vec<IpNet>,
+    ) -> Self {
+        Self {
+            target_name,
+            limit,
+            backend,
+            trusted_proxies,
+        }
+    }
+
+    /// The client IP address, as reported by trusted proxies if any
+    fn client_ip(&self, req: &HttpRequest) -> Option<IpAddr> {
+        let peer_ip = req.peer_addr()?.ip();
+        if !self
+            .trusted_proxies
+            .iter()
+            .any(|trusted_proxy| trusted_proxy.contains(&peer_ip))
+        {
+            return Some(peer_ip);
+        }
+
+        // the last address was added by the trusted proxy
+        let forwarded_ip = req
+            .headers()
+            .get("X-Forwarded-For")
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse().ok());
+        Some(forwarded_ip.unwrap_or(peer_ip))
+    }
+
+    /// Identifies the user sending the request, or its IP address if unauthenticated
+    fn client_key(&self, req: &HttpRequest) -> Option<String> {
+        if let Some(request_auth) = req.get_request_auth() {
+            if let AuthStatus::Known {
+                provider_handler,
+                user_id,
+                ..
+            } = request_auth.status()
+            {
+                let provider_id = request_auth.context().get_provider_id(*provider_handler);
+                return Some(format!("user:{provider_id}/{user_id}"));
+            }
+        }
+        self.client_ip(req).map(|ip| format!("ip:{ip}"))
+    }
+}
+
+impl RequestLimiter for TargetRateLimiter {
+    fn acquire(
+        &self,
+        client_request: &HttpRequest,
+    ) -> LocalBoxFuture<'static, Result<RequestPermit, actix_web::Error>> {
+        let limiter = self.clone();
+        let client_key = self.client_key(client_request);
+        Box::pin(async move {
+            let Some(client_key) = client_key else {
+                return Ok(Box::new(()) as RequestPermit);
+            };
+            let key = format!("gateway:{}:{}", limiter.target_name, client_key);
+
+            limiter
+                .backend
+                .hit(&format!("{key}:requests"), &limiter.limit)
+                .await
+                .map_err(|retry_after| TooManyRequests { retry_after })?;
+
+            let Some(max_running) = limiter.limit.max_concurrent_requests else {
+                return Ok(Box::new(()) as RequestPermit);
+            };
+            let running_request = limiter
+                .backend
+                .start(format!("{key}:running"), max_running)
+                .await?;
+            Ok(Box::new(running_request) as RequestPermit)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_requests_per_period() {
+        let mut counters = MemoryCounters::default();
+        let period = Duration::from_secs(60);
+        let start = Instant::now();
+
+        assert!(counters.hit("a", 2, period, start).is_ok());
+        assert!(counters.hit("a", 2, period, start).is_ok());
+        assert_eq!(
+            counters.hit("a", 2, period, start + Duration::from_secs(20)),
+            Err(Duration::from_secs(40))
+        );
+        // other clients are counted separately
+        assert!(counters.hit("b", 2, period, start).is_ok());
+        // counters are reset when the period ends
+        assert!(counters.hit("a", 2, period, start + period).is_ok());
+    }
+
+    #[test]
+    fn limit_running_requests() {
+        let mut counters = MemoryCounters::default();
+        assert!(counters.start("a", 1));
+        assert!(!counters.start("a", 1));
+        counters.end("a");
+        assert!(counters.start("a", 1));
+    }
+
+    #[test]
+    fn too_many_requests_response() {
+        let error = TooManyRequests {
+            retry_after: Duration::from_millis(2500),
+        };
+        let response = error.error_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "3");
+    }
+}
@@ -0,0 +1,189 @@
+//! Scales the number of replicas of worker groups according to the load of their queue.
+//!
+//! The load of a queue is read from the RabbitMQ management API: the number of messages
+//! waiting or being processed, and the rate at which messages are published. The wanted
+//! number of replicas is the one needed to handle both, within the configured bounds.
+//!
+//! Scaling up happens as soon as the load requires it. Scaling down waits for the load
+//! to stay low for `scale_down_delay`, so that bursts of requests don't make workers
+//! (which can take a long time to load) come and go.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{management_client::QueueLoad, Key};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AutoscalingConfig {
+    /// The minimum number of replicas of a worker group
+    pub min_replicas: u32,
+    /// The maximum number of replicas of a worker group
+    pub max_replicas: u32,
+    /// The number of queued messages a single replica is expected to handle
+    pub messages_per_replica: u32,
+    /// The publish rate (in messages per second) a single replica is expected to handle
+    pub publish_rate_per_replica: Option<f64>,
+    /// How long the load must stay low before removing replicas
+    pub scale_down_delay: Duration,
+}
+
+impl Default for AutoscalingConfig {
+    fn default() -> Self {
+        Self {
+            min_replicas: 1,
+            max_replicas: 4,
+            messages_per_replica: 10,
+            publish_rate_per_replica: None,
+            scale_down_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl AutoscalingConfig {
+    /// Computes the number of replicas needed to handle the load of a queue
+    pub fn wanted_replicas(&self, load: &QueueLoad) -> u32 {
+        let min_replicas = self.min_replicas.max(1);
+        let max_replicas = self.max_replicas.max(min_replicas);
+
+        let by_depth = load
+            .messages
+            .div_ceil(self.messages_per_replica.max(1) as u64);
+        let by_rate = match self.publish_rate_per_replica {
+            Some(rate_per_replica) if rate_per_replica > 0. => {
+                (load.publish_rate() / rate_per_replica).ceil() as u64
+            }
+            _ => 0,
+        };
+
+        let wanted = by_depth.max(by_rate).min(max_replicas as u64) as u32;
+        wanted.clamp(min_replicas, max_replicas)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GroupScale {
+    /// The number of replicas the group should have
+    replicas: u32,
+    /// The last time the load required at least this number of replicas
+    last_needed: Instant,
+}
+
+/// Keeps track of the replicas of each worker group
+#[derive(Debug)]
+pub struct Autoscaler {
+    config: AutoscalingConfig,
+    groups: HashMap<Key, GroupScale>,
+}
+
+impl Autoscaler {
+    pub fn new(config: AutoscalingConfig) -> Self {
+        Self {
+            config,
+            groups: HashMap::new(),
+        }
+    }
+
+    /// Forgets about the groups which are not running anymore
+    pub fn retain(&mut self, worker_keys: &[Key]) {
+        self.groups.retain(|key, _| worker_keys.contains(key));
+    }
+
+    /// Returns the number of replicas the group should be scaled to
+    pub fn replicas(&mut self, worker_key: &Key, load: &QueueLoad, now: Instant) -> u32 {
+        let wanted = self.config.wanted_replicas(load);
+        let group = self.groups.entry(worker_key.clone()).or_insert(GroupScale {
+            replicas: wanted,
+            last_needed: now,
+        });
+
+        if wanted >= group.replicas
+            || now.duration_since(group.last_needed) >= self.config.scale_down_delay
+        {
+            group.replicas = wanted;
+            group.last_needed = now;
+        }
+        group.replicas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::management_client::{MessageStats, RateDetails};
+
+    fn load(messages: u64, publish_rate: f64) -> QueueLoad {
+        QueueLoad {
+            name: "core-req-1".into(),
+            messages,
+            message_stats: MessageStats {
+                publish_details: RateDetails { rate: publish_rate },
+            },
+        }
+    }
+
+    fn config() -> AutoscalingConfig {
+        AutoscalingConfig {
+            min_replicas: 1,
+            max_replicas: 5,
+            messages_per_replica: 10,
+            publish_rate_per_replica: Some(2.),
+            scale_down_delay: Duration::from_secs(30),
+        }
+    }
+
+    #[test]
+    fn wanted_replicas_from_load() {
+        let config = config();
+        assert_eq!(config.wanted_replicas(&load(0, 0.)), 1);
+        assert_eq!(config.wanted_replicas(&load(10, 0.)), 1);
+        assert_eq!(config.wanted_replicas(&load(11, 0.)), 2);
+        assert_eq!(config.wanted_replicas(&load(5, 6.5)), 4);
+        assert_eq!(config.wanted_replicas(&load(1000, 0.)), 5);
+    }
+
+    #[test]
+    fn wanted_replicas_with_invalid_bounds() {
+        let config = AutoscalingConfig {
+            min_replicas: 0,
+            max_replicas: 0,
+            ..config()
+        };
+        assert_eq!(config.wanted_replicas(&load(1000, 0.)), 1);
+    }
+
+    #[test]
+    fn scale_down_after_delay() {
+        let mut autoscaler = Autoscaler::new(config());
+        let key = Key::new("1");
+        let start = Instant::now();
+
+        assert_eq!(autoscaler.replicas(&key, &load(30, 0.), start), 3);
+
+        // Scaling up is immediate
+        let now = start + Duration::from_secs(1);
+        assert_eq!(autoscaler.replicas(&key, &load(40, 0.), now), 4);
+
+        // Scaling down waits for the load to stay low
+        let later = now + Duration::from_secs(10);
+        assert_eq!(autoscaler.replicas(&key, &load(0, 0.), later), 4);
+        let later = now + Duration::from_secs(30);
+        assert_eq!(autoscaler.replicas(&key, &load(0, 0.), later), 1);
+    }
+
+    #[test]
+    fn forget_stopped_groups() {
+        let mut autoscaler = Autoscaler::new(config());
+        let key = Key::new("1");
+        let now = Instant::now();
+        assert_eq!(autoscaler.replicas(&key, &load(20, 0.), now), 2);
+        assert_eq!(autoscaler.replicas(&key, &load(0, 0.), now), 2);
+
+        autoscaler.retain(&[]);
+        assert_eq!(autoscaler.replicas(&key, &load(0, 0.), now), 1);
+    }
+}
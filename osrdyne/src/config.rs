@@ -1,9 +1,11 @@
 use std::{path::PathBuf, time::Duration};
 
+use crate::autoscaler::AutoscalingConfig;
 use crate::drivers::{
     docker::DockerDriverOptions, kubernetes::KubernetesDriverOptions,
     process_compose::PCDriverOptions,
 };
+use crate::Key;
 use serde::{Deserialize, Serialize};
 
 use figment::{
@@ -35,6 +37,13 @@ pub struct OsrdyneConfig {
     pub max_length_bytes: Option<usize>,
     pub api_address: String,
     pub extra_lifetime: Option<Duration>,
+    /// Scale the number of workers of each group according to the load of its queue
+    pub autoscaling: Option<AutoscalingConfig>,
+    /// The maximum number of worker groups running at the same time.
+    /// When reached, the least recently active group is destroyed to make room for a new one.
+    pub max_worker_groups: Option<usize>,
+    /// Worker groups which are never destroyed to make room for others
+    pub pinned_keys: Vec<Key>,
     pub opentelemetry: Option<OpentelemetryConfig>,
 }
 
@@ -67,6 +76,9 @@ impl Default for OsrdyneConfig {
             max_length_bytes: None,
             api_address: "0.0.0.0:4242".into(), // TODO: decide on the port
             extra_lifetime: None,
+            autoscaling: None,
+            max_worker_groups: None,
+            pinned_keys: vec![],
             opentelemetry: None,
         }
     }
@@ -9,5 +9,6 @@ const LABEL_WORKER_ID: &str = "osrd/worker_id";
 const LABEL_WORKER_KEY: &str = "osrd/worker_key";
 const LABEL_VERSION_IDENTIFIER: &str = "osrd/version_identifier";
 const LABEL_QUEUE_NAME: &str = "osrd/queue_name";
+const LABEL_REPLICA: &str = "osrd/replica";
 
 const MANAGED_BY_VALUE: &str = "osrdyne";
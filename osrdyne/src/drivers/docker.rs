@@ -1,4 +1,9 @@
-use std::{collections::HashMap, fmt::Debug, future::Future, pin::Pin};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+};
 
 use bollard::{
     container::{
@@ -17,7 +22,7 @@ use crate::Key;
 
 use super::{
     worker_driver::{DriverError, WorkerDriver, WorkerMetadata},
-    LABEL_MANAGED_BY, LABEL_VERSION_IDENTIFIER, LABEL_WORKER_ID, LABEL_WORKER_KEY,
+    LABEL_MANAGED_BY, LABEL_REPLICA, LABEL_VERSION_IDENTIFIER, LABEL_WORKER_ID, LABEL_WORKER_KEY,
     MANAGED_BY_VALUE,
 };
 
@@ -78,6 +83,110 @@ impl DockerDriver {
             version_identifier: hashed,
         }
     }
+
+    /// The first replica of a group keeps the name of the groups without replicas
+    fn container_name(&self, worker_key: &Key, replica: u32) -> String {
+        let group_name = format!(
+            "{}-{}-{}",
+            self.options.container_prefix, self.worker_pool, worker_key
+        );
+        if replica == 0 {
+            group_name
+        } else {
+            format!("{group_name}-{replica}")
+        }
+    }
+
+    async fn create_worker_container(
+        &self,
+        worker_key: &Key,
+        worker_id: Uuid,
+        replica: u32,
+    ) -> Result<(), DriverError> {
+        let final_env = {
+            let mut env: Vec<String> = self.options.default_env.clone();
+            env.push(format!("WORKER_ID={}", worker_id));
+            env.push(format!("WORKER_KEY={}", worker_key));
+            env.push(format!("WORKER_AMQP_URI={}", self.amqp_uri));
+            env.push(format!("WORKER_MAX_MSG_SIZE={}", self.max_message_size));
+            env
+        };
+
+        let labels = HashMap::from([
+            (LABEL_MANAGED_BY.to_owned(), MANAGED_BY_VALUE.to_owned()),
+            (LABEL_WORKER_ID.to_owned(), worker_id.to_string()),
+            (LABEL_WORKER_KEY.to_owned(), worker_key.to_string()),
+            (
+                LABEL_VERSION_IDENTIFIER.to_owned(),
+                self.version_identifier.clone(),
+            ),
+            (LABEL_REPLICA.to_owned(), replica.to_string()),
+        ]);
+
+        let container_name = self.container_name(worker_key, replica);
+        info!(%container_name, "Creating container");
+        let options = CreateContainerOptions {
+            name: container_name.clone(),
+            platform: None,
+        };
+
+        let mut networking_config = Some(NetworkingConfig {
+            endpoints_config: HashMap::from([(
+                self.options.network.clone(),
+                bollard::models::EndpointSettings {
+                    ..Default::default()
+                },
+            )]),
+        });
+
+        let mut host_config = HostConfig {
+            auto_remove: Some(true),
+            ..Default::default()
+        };
+
+        if self.options.host_networking == Some(true) {
+            networking_config = None;
+            host_config.network_mode = Some("host".to_string());
+        }
+
+        let config = Config {
+            image: Some(self.options.worker_image.clone()),
+            env: Some(final_env),
+            labels: Some(labels),
+            cmd: self.options.container_command.clone(),
+            networking_config,
+            host_config: Some(host_config),
+            ..Default::default()
+        };
+
+        self.client
+            .create_container(Some(options), config)
+            .await
+            .map_err(DriverError::DockerError)?;
+
+        self.client
+            .start_container(
+                container_name.as_str(),
+                None::<StartContainerOptions<String>>,
+            )
+            .await
+            .map_err(DriverError::DockerError)?;
+
+        Ok(())
+    }
+
+    async fn remove_container(&self, external_id: &str) -> Result<(), DriverError> {
+        self.client
+            .remove_container(
+                external_id,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await
+            .map_err(DriverError::DockerError)
+    }
 }
 
 impl WorkerDriver for DockerDriver {
@@ -103,92 +212,12 @@ impl WorkerDriver for DockerDriver {
                 } else if worker.worker_key == worker_key {
                     current_worker_id = Some(worker.worker_id);
 
-                    self.client
-                        .remove_container(
-                            &worker.external_id,
-                            Some(RemoveContainerOptions {
-                                force: true,
-                                ..Default::default()
-                            }),
-                        )
-                        .await
-                        .map_err(DriverError::DockerError)?;
+                    self.remove_container(&worker.external_id).await?;
                 }
             }
 
             let new_id = current_worker_id.unwrap_or_else(Uuid::new_v4);
-
-            let final_env = {
-                let mut env: Vec<String> = self.options.default_env.clone();
-                env.push(format!("WORKER_ID={}", new_id));
-                env.push(format!("WORKER_KEY={}", worker_key));
-                env.push(format!("WORKER_AMQP_URI={}", self.amqp_uri));
-                env.push(format!("WORKER_MAX_MSG_SIZE={}", self.max_message_size));
-                env
-            };
-
-            let labels = HashMap::from([
-                (LABEL_MANAGED_BY.to_owned(), MANAGED_BY_VALUE.to_owned()),
-                (LABEL_WORKER_ID.to_owned(), new_id.to_string()),
-                (LABEL_WORKER_KEY.to_owned(), worker_key.to_string()),
-                (
-                    LABEL_VERSION_IDENTIFIER.to_owned(),
-                    self.version_identifier.clone(),
-                ),
-            ]);
-
-            let container_name = format!(
-                "{}-{}-{}",
-                self.options.container_prefix, self.worker_pool, worker_key
-            );
-            info!(%container_name, "Creating container");
-            let options = CreateContainerOptions {
-                name: container_name.clone(),
-                platform: None,
-            };
-
-            let mut networking_config = Some(NetworkingConfig {
-                endpoints_config: HashMap::from([(
-                    self.options.network.clone(),
-                    bollard::models::EndpointSettings {
-                        ..Default::default()
-                    },
-                )]),
-            });
-
-            let mut host_config = HostConfig {
-                auto_remove: Some(true),
-                ..Default::default()
-            };
-
-            if self.options.host_networking == Some(true) {
-                networking_config = None;
-                host_config.network_mode = Some("host".to_string());
-            }
-
-            let config = Config {
-                image: Some(self.options.worker_image.clone()),
-                env: Some(final_env),
-                labels: Some(labels),
-                cmd: self.options.container_command.clone(),
-                networking_config,
-                host_config: Some(host_config),
-                ..Default::default()
-            };
-
-            self.client
-                .create_container(Some(options), config)
-                .await
-                .map_err(DriverError::DockerError)?;
-
-            self.client
-                .start_container(
-                    container_name.as_str(),
-                    None::<StartContainerOptions<String>>,
-                )
-                .await
-                .map_err(DriverError::DockerError)?;
-
+            self.create_worker_container(&worker_key, new_id, 0).await?;
             Ok(new_id)
         })
     }
@@ -203,17 +232,47 @@ impl WorkerDriver for DockerDriver {
             for worker in current_workers {
                 if worker.worker_key == worker_key {
                     info!(%worker.external_id, "Removing container");
-                    self.client
-                        .remove_container(
-                            &worker.external_id,
-                            Some(RemoveContainerOptions {
-                                force: true,
-                                ..Default::default()
-                            }),
-                        )
-                        .await
-                        .map_err(DriverError::DockerError)?;
+                    self.remove_container(&worker.external_id).await?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    #[instrument]
+    fn scale_worker_group(
+        &mut self,
+        worker_key: Key,
+        replicas: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DriverError>> + Send + '_>> {
+        Box::pin(async move {
+            let mut running_replicas = HashSet::new();
+            let current_workers = self.list_worker_groups().await?;
+            for worker in current_workers {
+                if worker.worker_key != worker_key {
+                    continue;
                 }
+                let replica_label = &worker.metadata[LABEL_REPLICA];
+                let replica = replica_label.parse::<u32>().map_err(|_| {
+                    DriverError::InvalidWorkerMetadata(format!(
+                        "replica label '{replica_label}' of container {} is not a valid number",
+                        worker.external_id
+                    ))
+                })?;
+                let is_outdated =
+                    worker.metadata[LABEL_VERSION_IDENTIFIER] != self.version_identifier;
+                if is_outdated || replica >= replicas {
+                    info!(%worker.external_id, replica, "Removing replica container");
+                    self.remove_container(&worker.external_id).await?;
+                } else {
+                    running_replicas.insert(replica);
+                }
+            }
+
+            for replica in (0..replicas).filter(|r| !running_replicas.contains(r)) {
+                self.create_worker_container(&worker_key, Uuid::new_v4(), replica)
+                    .await?;
             }
 
             Ok(())
@@ -244,6 +303,14 @@ impl WorkerDriver for DockerDriver {
                                     .expect("version_identifier label missing")
                                     .clone(),
                             );
+                            // Containers created before replicas were supported have no label
+                            metadata.insert(
+                                LABEL_REPLICA.to_owned(),
+                                labels
+                                    .get(LABEL_REPLICA)
+                                    .cloned()
+                                    .unwrap_or_else(|| "0".to_owned()),
+                            );
 
                             Some(WorkerMetadata {
                                 external_id: container.id.clone().expect("container id missing"),
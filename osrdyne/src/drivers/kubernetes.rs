@@ -642,6 +642,54 @@ impl WorkerDriver for KubernetesDriver {
         })
     }
 
+    #[instrument]
+    fn scale_worker_group(
+        &mut self,
+        worker_key: Key,
+        replicas: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DriverError>> + Send + '_>> {
+        Box::pin(async move {
+            // Replicas are managed by the kubernetes autoscaler when there is one
+            if matches!(
+                self.options.autoscaling,
+                Some(AutoscalingOptions::Hpa(_)) | Some(AutoscalingOptions::Keda(_))
+            ) {
+                return Ok(());
+            }
+
+            let worker_deployment_name = format!(
+                "{}-{}-{}",
+                self.options.deployment_prefix, self.pool_id, worker_key
+            );
+            let deployments = kube::api::Api::<Deployment>::namespaced(
+                self.client.clone(),
+                &self.options.namespace,
+            );
+
+            let scale = deployments
+                .get_scale(&worker_deployment_name)
+                .await
+                .map_err(DriverError::KubernetesError)?;
+            let current_replicas = scale.spec.and_then(|spec| spec.replicas);
+            if current_replicas == Some(replicas as i32) {
+                return Ok(());
+            }
+
+            info!(%worker_deployment_name, ?current_replicas, replicas, "Scaling deployment");
+            let patch = serde_json::json!({ "spec": { "replicas": replicas } });
+            deployments
+                .patch_scale(
+                    &worker_deployment_name,
+                    &kube::api::PatchParams::default(),
+                    &kube::api::Patch::Merge(&patch),
+                )
+                .await
+                .map_err(DriverError::KubernetesError)?;
+
+            Ok(())
+        })
+    }
+
     #[instrument]
     fn list_worker_groups(
         &self,
@@ -36,6 +36,15 @@ impl WorkerDriver for NoopDriver {
         Box::pin(async move { Ok(()) })
     }
 
+    #[instrument]
+    fn scale_worker_group(
+        &mut self,
+        _worker_key: Key,
+        _replicas: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DriverError>> + Send + '_>> {
+        Box::pin(async move { Ok(()) })
+    }
+
     #[instrument]
     fn list_worker_groups(
         &self,
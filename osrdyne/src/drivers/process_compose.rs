@@ -28,6 +28,8 @@
 //! Unlike Docker or Kube, process-compose doesn't let us tag each process individually. We have to store in the driver
 //! a mapping {PID => (Key, Uuid)} to keep track of which process is assigned to which worker key.
 //!
+//! A worker group scaled to several replicas is simply made of several processes with the same key.
+//!
 //! ## Dealing with `process-compose process scale` behaviour
 //!
 //! process-compose scaling may rename the process being scale. For example, if when starting PC whe have:
@@ -95,7 +97,8 @@ pub struct CommFiles {
 pub struct PCDriver {
     options: PCDriverOptions,
     amqp_uri: String,
-    workers: HashMap<Key, (ProcessInfo, Uuid)>,
+    /// The processes running for each worker key, one per replica
+    workers: HashMap<Key, Vec<(ProcessInfo, Uuid)>>,
     /// A lock on the files from which are read the worker environment variables.
     /// Prevents races when spawning multiple workers rapidly (eg. when osrdyne is rescheduled).
     spawn_lock: tokio::sync::Mutex<()>,
@@ -109,7 +112,7 @@ impl WorkerDriver for PCDriver {
         worker_key: Key,
     ) -> Pin<Box<dyn Future<Output = Result<Uuid, DriverError>> + Send + '_>> {
         Box::pin(async move {
-            if let Some((_, id)) = self.workers.get(&worker_key) {
+            if let Some((_, id)) = self.workers.get(&worker_key).and_then(|w| w.first()) {
                 return Ok(*id);
             }
 
@@ -120,6 +123,7 @@ impl WorkerDriver for PCDriver {
             Ok(self
                 .workers
                 .get(&worker_key)
+                .and_then(|w| w.first())
                 .expect(
                     "process should have been created or an error should have been raised before",
                 )
@@ -132,12 +136,35 @@ impl WorkerDriver for PCDriver {
         worker_key: Key,
     ) -> Pin<Box<dyn Future<Output = Result<(), DriverError>> + Send + '_>> {
         Box::pin(async move {
-            if self.workers.contains_key(&worker_key) {
+            while self.workers.contains_key(&worker_key) {
+                self.stop_worker(worker_key.clone())
+                    .await
+                    .map_err(ProcessComposeError)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn scale_worker_group(
+        &mut self,
+        worker_key: Key,
+        replicas: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DriverError>> + Send + '_>> {
+        Box::pin(async move {
+            let running = |workers: &HashMap<Key, Vec<_>>| {
+                workers.get(&worker_key).map(Vec::len).unwrap_or(0)
+            };
+            while running(&self.workers) < replicas as usize {
+                self.start_worker(worker_key.clone())
+                    .await
+                    .map_err(ProcessComposeError)?;
+            }
+            // Never stop the last process, the group is destroyed with `destroy_worker_group`
+            while running(&self.workers) > (replicas as usize).max(1) {
                 self.stop_worker(worker_key.clone())
                     .await
                     .map_err(ProcessComposeError)?;
             }
-            assert!(!self.workers.contains_key(&worker_key));
             Ok(())
         })
     }
@@ -149,7 +176,11 @@ impl WorkerDriver for PCDriver {
             let workers = self
                 .workers
                 .iter()
-                .map(|(key, (info, id))| (info.pid, (key, id)))
+                .flat_map(|(key, processes)| {
+                    processes
+                        .iter()
+                        .map(move |(info, id)| (info.pid, (key, id)))
+                })
                 .collect::<HashMap<_, _>>();
 
             Ok(self
@@ -242,12 +273,18 @@ impl PCDriver {
             &processes[0].name
         };
 
-        let stop = self
+        let processes_of_key = self
             .workers
-            .remove(&key)
-            .expect("process to stop should exist")
+            .get_mut(&key)
+            .expect("process to stop should exist");
+        let stop = processes_of_key
+            .pop()
+            .expect("worker keys should have at least one process")
             .0
             .name;
+        if processes_of_key.is_empty() {
+            self.workers.remove(&key);
+        }
         self.pc.process_stop(&stop).await?;
         self.pc
             .process_scale(pc_proc_name_id, processes.len() - 1)
@@ -260,7 +297,11 @@ impl PCDriver {
         let processes = self.scan_workers().await?;
         let mut old_state = std::mem::replace(&mut self.workers, HashMap::new())
             .into_iter()
-            .map(|(key, (info, id))| (info.pid, (key, id)))
+            .flat_map(|(key, processes)| {
+                processes
+                    .into_iter()
+                    .map(move |(info, id)| (info.pid, (key.clone(), id)))
+            })
             .collect::<HashMap<_, _>>();
 
         for p @ ProcessInfo { pid, status, .. } in processes {
@@ -269,14 +310,14 @@ impl PCDriver {
                 ProcessStatus::Running => {
                     if let Some((key, id)) = old_state.remove(&pid) {
                         // yup, the process is still there
-                        self.workers.insert(key, (p, id));
+                        self.workers.entry(key).or_default().push((p, id));
                     } else {
                         let Some((key, id)) = new_worker.take() else {
                             warn!(%pid, ?status, "process cannot be given a worker key - was the process started manually?");
                             continue;
                         };
                         info!(%key, %p.name, %pid, "attached worker to process");
-                        self.workers.insert(key, (p, id));
+                        self.workers.entry(key).or_default().push((p, id));
                     }
                 }
                 status => {
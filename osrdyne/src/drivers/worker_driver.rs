@@ -31,6 +31,8 @@ pub enum DriverError {
     KubernetesError(kube::Error),
     /// Process-compose error
     ProcessComposeError(anyhow::Error),
+    /// A worker has metadata which can't be understood
+    InvalidWorkerMetadata(String),
 }
 
 impl Display for DriverError {
@@ -39,6 +41,7 @@ impl Display for DriverError {
             DriverError::DockerError(e) => write!(f, "Docker error: {}", e),
             DriverError::KubernetesError(e) => write!(f, "Kubernetes error: {}", e),
             DriverError::ProcessComposeError(e) => write!(f, "process-compose error: {}", e),
+            DriverError::InvalidWorkerMetadata(e) => write!(f, "Invalid worker metadata: {}", e),
         }
     }
 }
@@ -59,6 +62,14 @@ pub trait WorkerDriver: Send {
         worker_key: Key,
     ) -> Pin<Box<dyn Future<Output = Result<(), DriverError>> + Send + '_>>;
 
+    /// Sets the number of workers running for the given group.
+    /// The group must have been created before.
+    fn scale_worker_group(
+        &mut self,
+        worker_key: Key,
+        replicas: u32,
+    ) -> Pin<Box<dyn Future<Output = Result<(), DriverError>> + Send + '_>>;
+
     /// This method should check if any ressources are dangling and clean them up.
     fn cleanup_stalled(
         &mut self,
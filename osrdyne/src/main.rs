@@ -33,6 +33,7 @@ use crate::drivers::process_compose::PCDriver;
 use crate::drivers::worker_driver::WorkerDriver;
 
 mod api;
+mod autoscaler;
 mod config;
 mod drivers;
 mod key;
@@ -85,6 +86,7 @@ async fn main() -> Result<(), anyhow::Error> {
         request_queues_policy(&config),
         config.extra_lifetime.unwrap_or(Duration::from_secs(1)),
         config.worker_loop_interval,
+        config.autoscaling.clone(),
     ));
 
     // fetch the list of queues from the web API
@@ -101,7 +103,11 @@ async fn main() -> Result<(), anyhow::Error> {
     // it is kept running accross rabbitmq reconnects.
     let (sender, tracker_inbox) = mpsc::channel(100);
     let target_tracker_client = TargetTrackerClient::new(sender);
-    let target_tracker_config = TargetTrackerConfig::default();
+    let target_tracker_config = TargetTrackerConfig {
+        max_worker_groups: config.max_worker_groups,
+        pinned_keys: config.pinned_keys.iter().cloned().collect(),
+        ..Default::default()
+    };
     let mut target_tracker = spawn(target_tracker_actor(
         tracker_inbox,
         init_keys,
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::OsrdyneConfig;
 
+#[derive(Clone)]
 pub struct ManagementClient {
     client: reqwest::Client,
     base: url::Url,
@@ -34,6 +35,37 @@ pub struct Queue {
     pub vhost: String,
 }
 
+/// The load of a queue, as reported by the management API
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct QueueLoad {
+    pub name: String,
+    /// The number of messages ready to be delivered or being processed
+    #[serde(default)]
+    pub messages: u64,
+    /// Missing until messages are published to the queue
+    #[serde(default)]
+    pub message_stats: MessageStats,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct MessageStats {
+    #[serde(default)]
+    pub publish_details: RateDetails,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct RateDetails {
+    /// In messages per second
+    #[serde(default)]
+    pub rate: f64,
+}
+
+impl QueueLoad {
+    pub fn publish_rate(&self) -> f64 {
+        self.message_stats.publish_details.rate
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub enum PolicyScope {
     /// applies to exchanges only
@@ -105,6 +137,17 @@ impl ManagementClient {
         Ok(resp.json().await?)
     }
 
+    pub async fn list_queue_loads(&self) -> anyhow::Result<Vec<QueueLoad>> {
+        let path = format!(
+            "/api/queues/{}?columns=name,messages,message_stats.publish_details.rate",
+            self.vhost
+        );
+        let req = self.make_request(Method::GET, path)?;
+        let resp = self.client.execute(req).await?;
+        let resp = resp.error_for_status()?;
+        Ok(resp.json().await?)
+    }
+
     fn policy_path(&self, policy_id: String) -> String {
         let encoded_policy_id = utf8_percent_encode(&policy_id, NON_ALPHANUMERIC);
         format!("/api/policies/{}/{}", self.vhost, encoded_policy_id)
@@ -170,4 +213,20 @@ mod tests {
             client.base.as_str()
         );
     }
+
+    #[test]
+    fn parse_queue_loads() {
+        let queues: Vec<QueueLoad> = serde_json::from_str(
+            r#"[
+                {"name": "core-req-1", "messages": 12, "message_stats": {"publish_details": {"rate": 1.5}}},
+                {"name": "core-req-2", "messages": 0}
+            ]"#,
+        )
+        .expect("failed to parse queue loads");
+
+        assert_eq!(queues[0].messages, 12);
+        assert_eq!(queues[0].publish_rate(), 1.5);
+        assert_eq!(queues[1].messages, 0);
+        assert_eq!(queues[1].publish_rate(), 0.);
+    }
 }
@@ -1,7 +1,7 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use futures_lite::stream::StreamExt;
@@ -19,6 +19,7 @@ use tokio::{sync::oneshot, task::JoinSet};
 use tracing::{error, info, span, Level};
 
 use crate::{
+    autoscaler::{Autoscaler, AutoscalingConfig},
     drivers::worker_driver::WorkerMetadata,
     management_client::{ArgumentValue, ManagementClient, Policy, PolicyScope},
     queue_controller::{queues_control_loop, QueuesState},
@@ -45,6 +46,8 @@ pub struct Pool {
 
     pub worker_loop_interval: Duration,
 
+    pub autoscaling: Option<AutoscalingConfig>,
+
     pool_req_prefix: String,
     request_queue_policy: BTreeMap<String, ArgumentValue>,
 }
@@ -55,6 +58,7 @@ impl Pool {
         request_queue_policy: BTreeMap<String, ArgumentValue>,
         extra_lifetime: Duration,
         worker_loop_interval: Duration,
+        autoscaling: Option<AutoscalingConfig>,
     ) -> Self {
         let pool_id = utf8_percent_encode(&raw_pool_id, NON_ALPHANUMERIC).to_string();
 
@@ -88,6 +92,7 @@ impl Pool {
             pool_req_prefix,
             request_queue_policy,
             worker_loop_interval,
+            autoscaling,
         }
     }
 
@@ -251,12 +256,14 @@ impl Pool {
         {
             let arc_clone = self.clone();
             let expected_state = expected_state.clone();
+            let management_client = management_client.clone();
             tasks.spawn(async move {
                 worker_control_loop(
                     arc_clone,
                     expected_state,
                     running_worker_watch,
                     driver,
+                    management_client,
                     worker_loop_interval,
                 )
                 .await;
@@ -475,8 +482,10 @@ async fn worker_control_loop(
     expected_state: tokio::sync::watch::Receiver<TargetUpdate>,
     running_workers_watch: tokio::sync::watch::Sender<Arc<Vec<WorkerMetadata>>>,
     mut driver: Box<dyn WorkerDriver>,
+    management_client: ManagementClient,
     sleep_interval: Duration,
 ) {
+    let mut autoscaler = pool.autoscaling.clone().map(Autoscaler::new);
     loop {
         let span = span!(Level::INFO, "loop_iteration");
         let _enter = span.enter();
@@ -498,10 +507,11 @@ async fn worker_control_loop(
         let current_workers = Arc::new(current_workers);
         let _ = running_workers_watch.send(current_workers.clone());
 
+        // Groups with several replicas have one worker per replica
         let current_worker_keys = current_workers
             .iter()
             .map(|c| &c.worker_key)
-            .collect::<Vec<_>>();
+            .collect::<HashSet<_>>();
 
         let wanted_worker_keys = target
             .queues
@@ -524,10 +534,10 @@ async fn worker_control_loop(
         }
 
         // Add wanted groups
-        for worker_key in wanted_worker_keys {
-            let queue_name = pool.key_queue_name(&worker_key);
+        for worker_key in wanted_worker_keys.iter() {
+            let queue_name = pool.key_queue_name(worker_key);
             if let Err(e) = driver
-                .get_or_create_worker_group(queue_name, worker_key)
+                .get_or_create_worker_group(queue_name, worker_key.clone())
                 .await
             {
                 error!(
@@ -539,6 +549,22 @@ async fn worker_control_loop(
             }
         }
 
+        // Scale groups according to the load of their queue
+        if let Some(autoscaler) = autoscaler.as_mut() {
+            if let Err(e) = autoscale_worker_groups(
+                &pool,
+                autoscaler,
+                &management_client,
+                driver.as_mut(),
+                &wanted_worker_keys,
+            )
+            .await
+            {
+                // Stalled resources are cleaned up even when scaling failed
+                error!(?e, "Failed to scale worker groups.");
+            }
+        }
+
         // Refresh workers if needed
         if let Err(e) = driver.cleanup_stalled().await {
             error!(
@@ -554,6 +580,33 @@ async fn worker_control_loop(
     }
 }
 
+async fn autoscale_worker_groups(
+    pool: &Pool,
+    autoscaler: &mut Autoscaler,
+    management_client: &ManagementClient,
+    driver: &mut dyn WorkerDriver,
+    worker_keys: &[Key],
+) -> anyhow::Result<()> {
+    let queue_loads = management_client
+        .list_queue_loads()
+        .await?
+        .into_iter()
+        .filter_map(|load| Some((pool.parse_key(&load.name)?, load)))
+        .collect::<HashMap<_, _>>();
+
+    autoscaler.retain(worker_keys);
+    let now = Instant::now();
+    for worker_key in worker_keys {
+        let load = queue_loads.get(worker_key).cloned().unwrap_or_default();
+        let replicas = autoscaler.replicas(worker_key, &load, now);
+        driver
+            .scale_worker_group(worker_key.clone(), replicas)
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+    }
+    Ok(())
+}
+
 async fn deadletter_responder(pool: Arc<Pool>, chan: Channel) -> anyhow::Result<()> {
     chan.basic_qos(200, BasicQosOptions::default()).await?;
 
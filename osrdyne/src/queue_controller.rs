@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::sync::Arc;
+use std::time::Duration;
 
 use lapin::options::QueueBindOptions;
 use lapin::options::QueueDeclareOptions;
@@ -14,6 +15,8 @@ use tokio::sync::watch;
 use tokio::task::AbortHandle;
 use tokio::task::JoinSet;
 use tracing::debug;
+use tracing::error;
+use tracing::warn;
 
 use crate::target_tracker::GenerationId;
 use crate::target_tracker::QueueStatus;
@@ -21,6 +24,9 @@ use crate::target_tracker::TargetUpdate;
 use crate::Key;
 use crate::Pool;
 
+/// How long to wait before retrying a failed queue update
+const QUEUE_UPDATE_RETRY_DELAY: Duration = Duration::from_secs(10);
+
 #[derive(Debug)]
 pub struct QueuesState {
     pub target_generation: GenerationId,
@@ -51,11 +57,12 @@ pub async fn queues_control_loop(
 
         // remove queues which aren't supposed to be there
         if !target.queues.contains_key(&queue) {
-            let job = jobs.spawn(update_queue(
+            let job = jobs.spawn(update_queue_job(
                 pool.clone(),
                 chan.clone(),
                 queue.clone(),
                 None,
+                Duration::ZERO,
             ));
             jobs_by_key.insert(queue, job);
         }
@@ -63,11 +70,12 @@ pub async fn queues_control_loop(
 
     // set all queues to the target state
     for (queue, queue_status) in target.queues.iter() {
-        let job = jobs.spawn(update_queue(
+        let job = jobs.spawn(update_queue_job(
             pool.clone(),
             chan.clone(),
             queue.clone(),
             Some(*queue_status),
+            Duration::ZERO,
         ));
         jobs_by_key.insert(queue.clone(), job);
     }
@@ -89,10 +97,34 @@ pub async fn queues_control_loop(
         select! {
             // update the reported state on job completion
             Some(job_completion) = jobs.join_next(), if !jobs.is_empty() => {
-                // TODO: don't assume the join goes well
-                let job_completion = job_completion.unwrap();
-                // TODO: proper error handling on job failure (disconnect, non empty queue)
-                let (job_key, new_status) = job_completion.unwrap();
+                let (job_key, new_status, res) = match job_completion {
+                    Ok(completion) => completion,
+                    // jobs are cancelled when superseded by a new target state
+                    Err(err) if err.is_cancelled() => continue,
+                    Err(err) => {
+                        error!(?err, "queue update job failed");
+                        continue;
+                    }
+                };
+
+                if let Err(err) = res {
+                    warn!(?job_key, %err, "queue update failed, retrying later");
+                    // the queue was unbound before the deletion attempt
+                    if let QueueUpdateError::QueueNotEmpty(_) = err {
+                        tx.send_modify(|state| {
+                            state.queues.insert(job_key.clone(), QueueStatus::Unbound);
+                        });
+                    }
+                    let job = jobs.spawn(update_queue_job(
+                        pool.clone(),
+                        chan.clone(),
+                        job_key.clone(),
+                        new_status,
+                        QUEUE_UPDATE_RETRY_DELAY,
+                    ));
+                    jobs_by_key.insert(job_key, job);
+                    continue;
+                }
 
                 debug!(?job_key, ?new_status, "queue update job completed");
 
@@ -128,7 +160,7 @@ pub async fn queues_control_loop(
                     };
 
                     // TODO: I'm not confident that cancelling the previous job guarantees the absence of race
-                    let job = jobs.spawn(update_queue(pool.clone(), chan.clone(), key.clone(), new_status));
+                    let job = jobs.spawn(update_queue_job(pool.clone(), chan.clone(), key.clone(), new_status, Duration::ZERO));
                     if let Some(previous_job) = jobs_by_key.insert(key.clone(), job) {
                         previous_job.abort()
                     }
@@ -168,12 +200,25 @@ impl From<lapin::Error> for QueueUpdateError {
     }
 }
 
+/// Updates a queue after some delay, returning the outcome along with the key and target status
+async fn update_queue_job(
+    pool: Arc<Pool>,
+    chan: Arc<Channel>,
+    key: Key,
+    new_state: Option<QueueStatus>,
+    delay: Duration,
+) -> (Key, Option<QueueStatus>, Result<(), QueueUpdateError>) {
+    tokio::time::sleep(delay).await;
+    let res = update_queue(pool, chan, key.clone(), new_state).await;
+    (key, new_state, res)
+}
+
 async fn update_queue(
     pool: Arc<Pool>,
     chan: Arc<Channel>,
     key: Key,
     new_state: Option<QueueStatus>,
-) -> Result<(Key, Option<QueueStatus>), QueueUpdateError> {
+) -> Result<(), QueueUpdateError> {
     let queue_name = pool.key_queue_name(&key);
     match new_state {
         Some(QueueStatus::Active) => {
@@ -239,5 +284,5 @@ async fn update_queue(
             };
         }
     }
-    Ok((key, new_state))
+    Ok(())
 }
@@ -8,10 +8,13 @@ pub use actor::target_tracker_actor;
 pub use client::TargetTrackerClient;
 
 use priority_queue::PriorityQueue;
-use std::{cmp::Reverse, collections::HashMap};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
 use thunderdome::{Arena, Index};
 use tokio::time::{Duration, Instant};
-use tracing::{debug, error};
+use tracing::{debug, error, info, warn};
 
 use crate::Key;
 
@@ -37,6 +40,10 @@ struct Queue {
     // The reference time used for the spooldown schedule.
     // This is set to the last require time + extra lifetime
     schedule_start: Instant,
+    // The last require time, used to find the least recently used queue
+    last_required: Instant,
+    // When evicted, the time at which the queue was unbound
+    evicted_at: Option<Instant>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
@@ -57,6 +64,11 @@ pub struct TargetTracker {
     unbind_delay: Duration,
     /// Delay from unbinding to deletion
     delete_delay: Duration,
+    /// The maximum number of active worker groups, if any.
+    /// When reached, the least recently used idle group is evicted to make room for new ones.
+    max_queues: Option<usize>,
+    /// Worker groups which are never evicted
+    pinned_keys: HashSet<Key>,
 
     // a full description of all queues
     queues: Arena<Queue>,
@@ -88,10 +100,14 @@ impl TargetTracker {
         initial_worker_ids: Vec<Key>,
         unbind_delay: Duration,
         delete_delay: Duration,
+        max_queues: Option<usize>,
+        pinned_keys: HashSet<Key>,
     ) -> Self {
         let mut state = TargetTracker {
             unbind_delay,
             delete_delay,
+            max_queues,
+            pinned_keys,
             queues: Arena::new(),
             queues_by_key: HashMap::new(),
             planned_transitions: PriorityQueue::new(),
@@ -113,11 +129,11 @@ impl TargetTracker {
         self.planned_transitions.peek().map(|(_queue, at)| at.0)
     }
 
-    fn expected_status(&self, last_used_at: Instant, now: Instant) -> Option<QueueStatus> {
+    fn expected_status(&self, queue: &Queue, now: Instant) -> Option<QueueStatus> {
         let mut status = QueueStatus::Active;
         loop {
             let new_status = status.next();
-            let transition_time = self.event_time(last_used_at, new_status);
+            let transition_time = self.event_time(queue, new_status);
             if now < transition_time {
                 return Some(status);
             }
@@ -128,11 +144,17 @@ impl TargetTracker {
         }
     }
 
-    fn event_time(&self, last_used_at: Instant, status: Option<QueueStatus>) -> Instant {
+    fn event_time(&self, queue: &Queue, status: Option<QueueStatus>) -> Instant {
+        let last_used_at = queue.schedule_start;
+        // evicted queues are unbound early, and deleted after the usual delay
+        let unbind_at = match queue.evicted_at {
+            Some(evicted_at) => evicted_at,
+            None => last_used_at + self.unbind_delay,
+        };
         match status {
             Some(QueueStatus::Active) => last_used_at,
-            Some(QueueStatus::Unbound) => last_used_at + self.unbind_delay,
-            None => last_used_at + self.unbind_delay + self.delete_delay,
+            Some(QueueStatus::Unbound) => unbind_at,
+            None => unbind_at + self.delete_delay,
         }
     }
 
@@ -140,7 +162,7 @@ impl TargetTracker {
         let queue = &self.queues[queue_idx];
         let current_status = self.state[&queue.key];
         let next_status = current_status.next();
-        let transition_time = self.event_time(queue.schedule_start, next_status);
+        let transition_time = self.event_time(queue, next_status);
         self.planned_transitions
             .push(queue_idx, Reverse(transition_time));
     }
@@ -148,7 +170,7 @@ impl TargetTracker {
     fn queue_apply_transition(&mut self, at: Instant, queue_idx: Index) {
         let queue = &self.queues[queue_idx];
         let current = self.state[&queue.key];
-        let expected = self.expected_status(queue.schedule_start, at);
+        let expected = self.expected_status(queue, at);
 
         if Some(current) == expected {
             error!(?queue.key, ?current, "inconsistent state: no transition required");
@@ -170,6 +192,11 @@ impl TargetTracker {
         }
 
         // otherwise, remove the worker group
+        self.remove_queue(queue_idx);
+    }
+
+    fn remove_queue(&mut self, queue_idx: Index) {
+        let queue = &self.queues[queue_idx];
         self.planned_transitions.remove(&queue_idx);
         self.queues_by_key.remove(&queue.key);
         self.state.remove(&queue.key);
@@ -177,21 +204,69 @@ impl TargetTracker {
         self.queues.remove(queue_idx);
     }
 
+    /// Unbind the least recently used idle worker groups until there is room for a new one.
+    /// A group is idle once its extra lifetime is over. Evicted groups go through the
+    /// usual unbound state, so that queued requests can be processed before deletion.
+    fn make_room(&mut self, at: Instant) {
+        let Some(max_queues) = self.max_queues else {
+            return;
+        };
+        loop {
+            let active_queues = self
+                .state
+                .values()
+                .filter(|status| **status == QueueStatus::Active)
+                .count();
+            if active_queues < max_queues {
+                return;
+            }
+            let lru_queue = self
+                .queues
+                .iter()
+                .filter(|(_, queue)| {
+                    self.state[&queue.key] == QueueStatus::Active
+                        && queue.schedule_start <= at
+                        && !self.pinned_keys.contains(&queue.key)
+                })
+                .min_by_key(|(_, queue)| queue.last_required);
+            let Some((queue_idx, queue)) = lru_queue else {
+                warn!(
+                    max_queues,
+                    "no idle worker group to evict, exceeding the maximum number of worker groups"
+                );
+                return;
+            };
+            info!(?queue.key, "evicting least recently used worker group");
+            self.queues[queue_idx].evicted_at = Some(at);
+            self.state[&self.queues[queue_idx].key] = QueueStatus::Unbound;
+            self.generation.bump();
+            self.queue_update_transition(queue_idx);
+        }
+    }
+
     pub fn require_queue(&mut self, at: Instant, extra_lifetime: Duration, key: &Key) {
         let queue_idx = if let Some(queue_idx) = self.queues_by_key.get(key) {
             // update the worker group creation time
-            let queue = &mut self.queues[*queue_idx];
-            queue.schedule_start = at;
-            if self.state[&queue.key] != QueueStatus::Active {
-                self.state[&queue.key] = QueueStatus::Active;
+            let queue_idx = *queue_idx;
+            if self.state[key] != QueueStatus::Active {
+                // reactivating an unbound group may require evicting another one
+                self.make_room(at);
+                self.state[key] = QueueStatus::Active;
                 self.generation.bump();
             }
-            *queue_idx
+            let queue = &mut self.queues[queue_idx];
+            queue.schedule_start = at;
+            queue.last_required = at;
+            queue.evicted_at = None;
+            queue_idx
         } else {
             // create the worker group
+            self.make_room(at);
             let queue_idx = self.queues.insert(Queue {
                 key: key.clone(),
                 schedule_start: at + extra_lifetime,
+                last_required: at,
+                evicted_at: None,
             });
             self.queues_by_key.insert(key.clone(), queue_idx);
             self.state.insert(key.clone(), QueueStatus::Active);
@@ -211,3 +286,112 @@ impl TargetTracker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_tracker(max_queues: Option<usize>, pinned_keys: &[&str]) -> TargetTracker {
+        TargetTracker::new(
+            Instant::now(),
+            vec![],
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+            max_queues,
+            pinned_keys.iter().map(|key| Key::new(key)).collect(),
+        )
+    }
+
+    fn keys(tracker: &TargetTracker) -> Vec<Key> {
+        tracker.get_target_state().keys().cloned().collect()
+    }
+
+    #[test]
+    fn unlimited_worker_groups() {
+        let mut tracker = new_tracker(None, &[]);
+        let now = Instant::now();
+        for key in ["1", "2", "3"] {
+            tracker.require_queue(now, Duration::ZERO, &Key::new(key));
+        }
+        assert_eq!(keys(&tracker).len(), 3);
+    }
+
+    fn active_keys(tracker: &TargetTracker) -> Vec<Key> {
+        tracker
+            .get_target_state()
+            .into_iter()
+            .filter(|(_, status)| *status == QueueStatus::Active)
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let mut tracker = new_tracker(Some(2), &[]);
+        let start = Instant::now();
+        tracker.require_queue(start, Duration::ZERO, &Key::new("1"));
+        tracker.require_queue(
+            start + Duration::from_secs(1),
+            Duration::ZERO,
+            &Key::new("2"),
+        );
+        // Activity on the first group makes the second one the least recently used
+        tracker.require_queue(
+            start + Duration::from_secs(2),
+            Duration::ZERO,
+            &Key::new("1"),
+        );
+
+        let generation = tracker.get_generation();
+        let evicted_at = start + Duration::from_secs(3);
+        tracker.require_queue(evicted_at, Duration::ZERO, &Key::new("3"));
+        assert!(tracker.get_generation() > generation);
+        assert_eq!(active_keys(&tracker), vec![Key::new("1"), Key::new("3")]);
+
+        // The evicted group is unbound first, then deleted after the usual delay
+        assert_eq!(
+            tracker.get_target_state().get(&Key::new("2")),
+            Some(&QueueStatus::Unbound)
+        );
+        tracker.evolve_until(evicted_at + Duration::from_secs(60));
+        assert_eq!(keys(&tracker), vec![Key::new("1"), Key::new("3")]);
+    }
+
+    #[test]
+    fn busy_groups_are_not_evicted() {
+        let mut tracker = new_tracker(Some(1), &[]);
+        let start = Instant::now();
+        // The first group may still be processing a request for 30 seconds
+        tracker.require_queue(start, Duration::from_secs(30), &Key::new("1"));
+        tracker.require_queue(
+            start + Duration::from_secs(1),
+            Duration::ZERO,
+            &Key::new("2"),
+        );
+        assert_eq!(active_keys(&tracker), vec![Key::new("1"), Key::new("2")]);
+    }
+
+    #[test]
+    fn pinned_groups_are_not_evicted() {
+        let mut tracker = new_tracker(Some(2), &["1"]);
+        let start = Instant::now();
+        tracker.require_queue(start, Duration::ZERO, &Key::new("1"));
+        tracker.require_queue(
+            start + Duration::from_secs(1),
+            Duration::ZERO,
+            &Key::new("2"),
+        );
+        tracker.require_queue(
+            start + Duration::from_secs(2),
+            Duration::ZERO,
+            &Key::new("3"),
+        );
+        assert_eq!(active_keys(&tracker), vec![Key::new("1"), Key::new("3")]);
+
+        // When every group is pinned, the limit is exceeded
+        let mut tracker = new_tracker(Some(1), &["1"]);
+        tracker.require_queue(start, Duration::ZERO, &Key::new("1"));
+        tracker.require_queue(start, Duration::ZERO, &Key::new("2"));
+        assert_eq!(active_keys(&tracker).len(), 2);
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use tokio::sync::{mpsc, oneshot, watch};
 use tokio::time::{Duration, Instant};
 use tracing::trace;
@@ -10,6 +12,8 @@ pub struct TargetTrackerConfig {
     pub unbind_delay: Duration,
     pub delete_delay: Duration,
     pub timeout_allowance: Duration,
+    pub max_worker_groups: Option<usize>,
+    pub pinned_keys: HashSet<Key>,
 }
 
 impl Default for TargetTrackerConfig {
@@ -18,6 +22,8 @@ impl Default for TargetTrackerConfig {
             unbind_delay: Duration::from_secs(10 * 60),
             delete_delay: Duration::from_secs(10 * 60),
             timeout_allowance: Duration::from_secs(1),
+            max_worker_groups: None,
+            pinned_keys: HashSet::new(),
         }
     }
 }
@@ -84,6 +90,8 @@ pub async fn target_tracker_actor(
         initial_state,
         config.unbind_delay,
         config.delete_delay,
+        config.max_worker_groups,
+        config.pinned_keys,
     );
     let (stream, _) = watch::channel(TargetUpdate {
         generation: state.get_generation(),